@@ -0,0 +1,53 @@
+/*
+ * The C interface of scholar (see the `ffi` module), for loading saved networks and running them
+ * on raw buffers of doubles. Build the library with:
+ *
+ *     cargo rustc --release --features ffi --crate-type cdylib
+ */
+
+#ifndef SCHOLAR_H
+#define SCHOLAR_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* A loaded network, which must be freed with `scholar_net_free()`. */
+typedef struct ScholarNet ScholarNet;
+
+/* Loads a network from a saved file, returning NULL on failure. */
+ScholarNet *scholar_net_load(const char *path);
+
+/* Loads a network from `len` bytes in the bincode format, returning NULL on failure. */
+ScholarNet *scholar_net_from_bytes(const uint8_t *bytes, size_t len);
+
+/* Frees a network. Freeing NULL does nothing. */
+void scholar_net_free(ScholarNet *net);
+
+/* Returns the number of nodes in the network's input layer. */
+size_t scholar_net_inputs(const ScholarNet *net);
+
+/* Returns the number of nodes in the network's output layer. */
+size_t scholar_net_outputs(const ScholarNet *net);
+
+/*
+ * Guesses the outputs of `rows` rows, reading `rows * scholar_net_inputs(net)` values from
+ * `inputs` and writing `rows * scholar_net_outputs(net)` values to `outputs`. Returns 0 on
+ * success, or -1 on failure. The network isn't modified, so it can guess on many threads at once.
+ */
+int scholar_net_guess(const ScholarNet *net, const double *inputs, size_t rows, double *outputs);
+
+/*
+ * Returns a description of the last error on the calling thread (or NULL), which remains valid
+ * until the next failing call on the same thread.
+ */
+const char *scholar_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
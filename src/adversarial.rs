@@ -0,0 +1,104 @@
+//! Generation of adversarial examples, and evaluation of a network's robustness against them.
+//!
+//! Adversarial examples are inputs that have been perturbed very slightly, in the direction that
+//! most increases the network's cost, so as to fool it. To make a network more robust to them,
+//! they can be mixed into training with
+//! [`Trainer::adversarial()`](../struct.Trainer.html#method.adversarial).
+
+use crate::dataset::Dataset;
+use crate::metrics;
+use crate::network::{Activation, Float, NeuralNet};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Generates an adversarial example from the given inputs using the fast gradient sign method
+/// (FGSM): each input is moved by `epsilon` in whichever direction increases the network's cost
+/// on the given targets.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{adversarial, NeuralNet, Sigmoid};
+///
+/// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+/// let inputs = [0.0, 1.0];
+///
+/// let perturbed = adversarial::fgsm(&brain, &inputs, &[1.0], 0.1);
+/// for (x, p) in inputs.iter().zip(&perturbed) {
+///     assert!((x - p).abs() <= 0.1 + 1e-9);
+/// }
+/// ```
+///
+/// # Panics
+///
+/// This function panics if the number of given input (or target) values is not equal to the
+/// number of nodes in the network's input (or output) layer.
+pub fn fgsm<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    network: &NeuralNet<A, T>,
+    inputs: &[f64],
+    targets: &[f64],
+    epsilon: f64,
+) -> Vec<f64> {
+    network
+        .input_gradient(inputs, targets)
+        .iter()
+        .zip(inputs)
+        .map(|(gradient, x)| {
+            if *gradient == 0.0 {
+                *x
+            } else {
+                x + epsilon * gradient.signum()
+            }
+        })
+        .collect()
+}
+
+/// Creates a new `Dataset` in which the inputs of every row have been replaced by an adversarial
+/// example (see [`fgsm()`](fn.fgsm.html)), leaving the targets intact.
+pub fn perturb_dataset<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    network: &NeuralNet<A, T>,
+    dataset: &Dataset,
+    epsilon: f64,
+) -> Dataset {
+    let data: Vec<_> = dataset
+        .into_iter()
+        .map(|(inputs, targets)| (fgsm(network, inputs, targets, epsilon), targets.clone()))
+        .collect();
+
+    Dataset::from(data)
+}
+
+/// Calculates the proportion of rows in the `Dataset` that the network still classifies
+/// correctly after each row's inputs are replaced by an adversarial example (see
+/// [`fgsm()`](fn.fgsm.html)).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use scholar::{adversarial, metrics, Dataset, NeuralNet, Sigmoid, Trainer};
+///
+/// # fn main() -> Result<(), scholar::ParseCsvError> {
+/// let dataset = Dataset::from_csv("iris.csv", false, 4)?;
+/// let (training_data, testing_data) = dataset.split(0.75);
+///
+/// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[4, 10, 10, 3]);
+/// Trainer::new(10_000, 0.01)
+///     .adversarial(0.1, 0.5)
+///     .train(&mut brain, training_data);
+///
+/// println!("Accuracy: {:.2}", metrics::accuracy(&brain, &testing_data));
+/// println!(
+///     "Robust accuracy: {:.2}",
+///     adversarial::robust_accuracy(&brain, &testing_data, 0.1)
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn robust_accuracy<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    network: &NeuralNet<A, T>,
+    dataset: &Dataset,
+    epsilon: f64,
+) -> f64 {
+    let perturbed = perturb_dataset(network, dataset, epsilon);
+    metrics::accuracy(network, &perturbed)
+}
@@ -0,0 +1,177 @@
+//! Conversions between the library's types and `ndarray` arrays.
+
+use crate::dataset::{Batch, Dataset};
+use crate::network::{Activation, Float, NeuralNet};
+
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis};
+use serde::{de::DeserializeOwned, Serialize};
+
+impl Dataset {
+    /// Creates a `Dataset` from a 2D array of inputs and a 2D array of targets, where each row
+    /// of the arrays is a row of the dataset.
+    ///
+    /// This requires the `ndarray` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ndarray::array;
+    ///
+    /// let inputs = array![[0.0, 0.0], [0.0, 1.0], [1.0, 0.0], [1.0, 1.0]];
+    /// let targets = array![[0.0], [1.0], [1.0], [0.0]];
+    ///
+    /// let dataset = scholar::Dataset::from_ndarray(inputs.view(), targets.view());
+    /// assert_eq!(dataset.rows(), 4);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the arrays have differing numbers of rows.
+    pub fn from_ndarray(inputs: ArrayView2<f64>, targets: ArrayView2<f64>) -> Self {
+        if inputs.nrows() != targets.nrows() {
+            panic!(
+                "incorrect number of target rows supplied (expected {}, found {})",
+                inputs.nrows(),
+                targets.nrows()
+            );
+        }
+
+        inputs
+            .outer_iter()
+            .zip(targets.outer_iter())
+            .map(|(inputs, targets)| (inputs.to_vec(), targets.to_vec()))
+            .collect()
+    }
+
+    /// Converts the dataset into a 2D array of inputs and a 2D array of targets, where each row
+    /// of the arrays is a row of the dataset.
+    ///
+    /// This requires the `ndarray` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let dataset = scholar::Dataset::from(vec![
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 1.0], vec![0.0]),
+    /// ]);
+    ///
+    /// let (inputs, targets) = dataset.to_ndarray();
+    /// assert_eq!(inputs.shape(), &[2, 2]);
+    /// assert_eq!(targets.shape(), &[2, 1]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the rows have differing numbers of inputs or targets.
+    pub fn to_ndarray(&self) -> (Array2<f64>, Array2<f64>) {
+        rows_to_ndarray(self.into_iter())
+    }
+}
+
+impl<'a> Batch<'a> {
+    /// Converts the batch into a 2D array of inputs and a 2D array of targets, like
+    /// [`Dataset::to_ndarray()`](struct.Dataset.html#method.to_ndarray).
+    ///
+    /// This requires the `ndarray` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let dataset = scholar::Dataset::from(vec![
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 1.0], vec![0.0]),
+    ///     (vec![1.0, 0.0], vec![1.0]),
+    /// ]);
+    ///
+    /// for batch in dataset.batches(2) {
+    ///     let (inputs, targets) = batch.to_ndarray();
+    ///     assert_eq!(inputs.nrows(), targets.nrows());
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the rows have differing numbers of inputs or targets.
+    pub fn to_ndarray(&self) -> (Array2<f64>, Array2<f64>) {
+        rows_to_ndarray(self.iter())
+    }
+}
+
+impl<A: Activation + Serialize + DeserializeOwned, T: Float> NeuralNet<A, T> {
+    /// Calculates the network's output for a 1D array of inputs, like
+    /// [`NeuralNet::guess()`](#method.guess).
+    ///
+    /// This requires the `ndarray` feature.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of given input values is not equal to the number of nodes
+    /// in the network's input layer.
+    pub fn guess_array(&self, inputs: ArrayView1<f64>) -> Array1<f64> {
+        let guesses = match inputs.as_slice() {
+            Some(inputs) => self.guess(inputs),
+            None => self.guess(&inputs.to_vec()),
+        };
+
+        Array1::from(guesses)
+    }
+
+    /// Calculates the network's outputs for a 2D array of inputs, returning a 2D array whose rows
+    /// are the outputs for each row of inputs.
+    ///
+    /// This requires the `ndarray` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ndarray::array;
+    /// use scholar::{NeuralNet, Sigmoid};
+    ///
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 3]);
+    /// let guesses = brain.guess_batch(array![[0.0, 1.0], [1.0, 0.0]].view());
+    ///
+    /// assert_eq!(guesses.shape(), &[2, 3]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of columns in the given array is not equal to the number
+    /// of nodes in the network's input layer.
+    pub fn guess_batch(&self, inputs: ArrayView2<f64>) -> Array2<f64> {
+        let guesses: Vec<Array1<f64>> = inputs
+            .outer_iter()
+            .map(|inputs| self.guess_array(inputs))
+            .collect();
+        let views: Vec<_> = guesses.iter().map(Array1::view).collect();
+
+        if views.is_empty() {
+            Array2::zeros((0, 0))
+        } else {
+            ndarray::stack(Axis(0), &views).expect("outputs have the same length")
+        }
+    }
+}
+
+/// Collects the inputs and targets of the given rows into two 2D arrays.
+fn rows_to_ndarray<'a>(
+    rows: impl Iterator<Item = &'a (Vec<f64>, Vec<f64>)>,
+) -> (Array2<f64>, Array2<f64>) {
+    let mut rows = rows.peekable();
+    let (num_inputs, num_targets) = rows
+        .peek()
+        .map_or((0, 0), |(inputs, targets)| (inputs.len(), targets.len()));
+
+    let mut input_array = Array2::zeros((0, num_inputs));
+    let mut target_array = Array2::zeros((0, num_targets));
+    for (inputs, targets) in rows {
+        input_array
+            .push_row(ArrayView1::from(inputs.as_slice()))
+            .expect("rows must have the same number of inputs");
+        target_array
+            .push_row(ArrayView1::from(targets.as_slice()))
+            .expect("rows must have the same number of targets");
+    }
+
+    (input_array, target_array)
+}
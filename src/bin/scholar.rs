@@ -0,0 +1,365 @@
+//! The `scholar` command-line tool, for training, testing and running networks on CSV files
+//! without writing a Rust program.
+//!
+//! This requires the `cli` feature: install it with `cargo install scholar --features cli`.
+
+use scholar::config::{ExperimentConfig, Scale};
+use scholar::metrics::{Predictions, Report};
+use scholar::preprocessing::MinMaxScaler;
+use scholar::serve::Server;
+use scholar::{Dataset, NetworkConfig, NeuralNet, Sigmoid};
+
+use std::{
+    collections::HashMap,
+    env,
+    error::Error,
+    fmt::Display,
+    io::{self, BufWriter, Write},
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process,
+    str::FromStr,
+};
+
+const USAGE: &str = "\
+Usage:
+    scholar train [CONFIG] [OPTIONS]
+    scholar test <MODEL> <DATA> --inputs <N> [--headers] [--scaler <PATH>]
+    scholar predict <MODEL> <DATA> [--headers] [--scaler <PATH>]
+    scholar serve <MODEL> [--address <ADDRESS>] [--max-batch-size <N>] [--scaler <PATH>]
+
+Commands:
+    train      Trains a network on a CSV file, saves it, and prints a report of its performance
+               on the testing data. The experiment is described by a TOML configuration file
+               (see the documentation of the `config` module), whose settings are overridden by
+               any options given. Without one, --data, --inputs, --layers, --iterations and
+               --learning-rate are required.
+    test       Prints a report of a saved network's performance on a CSV file of inputs followed
+               by targets.
+    predict    Prints the outputs of a saved network for each row of a CSV file of inputs.
+    serve      Answers POST /predict requests with the outputs of a saved network for each row
+               of a JSON batch of inputs (see the documentation of the `serve` module).
+
+Options:
+    --data <PATH>             The CSV file to train and test on
+    --headers                 Skips the CSV's header row
+    --inputs <N>              The number of (leading) columns of the CSV that are inputs
+    --layers <N,N,...>        The number of nodes in each layer, such as 4,10,3
+    --iterations <N>          The number of times to iterate over the training data
+    --learning-rate <RATE>    How quickly the network adapts to the training data
+    --train-portion <P>       The proportion of rows to train on, with the rest tested on
+    --stratify                Preserves the proportion of each class when splitting the rows
+    --scale <none|min_max>    How the inputs are scaled
+    --output <PATH>           Where the trained network is saved (by default, the name of the
+                              data file with the .network extension)
+    --scaler <PATH>           Where the fitted scaler is saved, or loaded from when testing,
+                              predicting or serving
+    --address <ADDRESS>       The address to serve on [default: 127.0.0.1:8080]
+    --max-batch-size <N>      The maximum number of rows in a single request [default: 1024]
+";
+
+/// The options of each command that take a value.
+const TRAIN_OPTIONS: &[&str] = &[
+    "data",
+    "inputs",
+    "layers",
+    "iterations",
+    "learning-rate",
+    "train-portion",
+    "scale",
+    "output",
+    "scaler",
+];
+const TEST_OPTIONS: &[&str] = &["inputs", "scaler"];
+const PREDICT_OPTIONS: &[&str] = &["scaler"];
+const SERVE_OPTIONS: &[&str] = &["address", "max-batch-size", "scaler"];
+
+/// The options that don't take a value.
+const FLAGS: &[&str] = &["headers", "stratify"];
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let result = match args.next().as_deref() {
+        Some("train") => Args::parse(args, TRAIN_OPTIONS).and_then(train),
+        Some("test") => Args::parse(args, TEST_OPTIONS).and_then(test),
+        Some("predict") => Args::parse(args, PREDICT_OPTIONS).and_then(predict),
+        Some("serve") => Args::parse(args, SERVE_OPTIONS).and_then(serve),
+        None | Some("help") | Some("-h") | Some("--help") => {
+            print!("{}", USAGE);
+            return;
+        }
+        Some(command) => Err(format!("unknown command '{}'", command).into()),
+    };
+
+    if let Err(err) = result {
+        // Includes the underlying errors, such as the reason a file couldn't be read
+        eprint!("error: {}", err);
+        let mut source = err.source();
+        while let Some(err) = source {
+            eprint!(": {}", err);
+            source = err.source();
+        }
+        eprintln!("\n\nRun 'scholar --help' for usage.");
+        process::exit(1);
+    }
+}
+
+/// Trains a network as described by a configuration file and the given options.
+fn train(args: Args) -> Result<(), Box<dyn Error>> {
+    let mut config = match args.positional.as_slice() {
+        [] => ExperimentConfig::new(
+            args.required::<PathBuf>("data")?,
+            args.required("inputs")?,
+            NetworkConfig::new(
+                &args.required::<Layers>("layers")?.0,
+                args.required("iterations")?,
+                args.required("learning-rate")?,
+            ),
+        ),
+        [path] => {
+            let path = Path::new(path);
+            let mut config = ExperimentConfig::from_file(path)?;
+            if let Some(dir) = path.parent() {
+                config.resolve_paths(dir);
+            }
+            config
+        }
+        _ => return Err("expected at most one configuration file".into()),
+    };
+
+    // Overrides the configuration with the given options
+    let data = &mut config.data;
+    let network = &mut config.network.config;
+    if let Some(path) = args.option("data")? {
+        data.path = path;
+    }
+    if let Some(inputs) = args.option("inputs")? {
+        data.inputs = inputs;
+    }
+    if let Some(Layers(node_counts)) = args.option("layers")? {
+        network.node_counts = node_counts;
+    }
+    if let Some(iterations) = args.option("iterations")? {
+        network.iterations = iterations;
+    }
+    if let Some(learning_rate) = args.option("learning-rate")? {
+        network.learning_rate = learning_rate;
+    }
+    if let Some(train_portion) = args.option("train-portion")? {
+        data.train_portion = train_portion;
+    }
+    data.headers |= args.flag("headers");
+    data.stratify |= args.flag("stratify");
+    if let Some(ScaleArg(scale)) = args.option("scale")? {
+        config.preprocessing.scale = scale;
+    }
+    if let Some(scaler) = args.option("scaler")? {
+        config.output.scaler = Some(scaler);
+    }
+    if let Some(model) = args.option("output")? {
+        config.output.model = Some(model);
+    }
+    let data_name = config.data.path.file_name().unwrap_or_default();
+    let model = config
+        .output
+        .model
+        .get_or_insert_with(|| Path::new(data_name).with_extension("network"))
+        .clone();
+
+    let report = config.run()?;
+    println!("{}", report);
+    println!("Saved the network to {}", model.display());
+
+    Ok(())
+}
+
+/// Prints a report of a saved network's performance on a CSV file.
+fn test(args: Args) -> Result<(), Box<dyn Error>> {
+    let (model, data) = args.model_and_data()?;
+    let network: NeuralNet<Sigmoid> = NeuralNet::from_file(model)?;
+    let mut dataset = Dataset::from_csv(data, args.flag("headers"), args.required("inputs")?)?;
+    if let Some(scaler) = args.option::<PathBuf>("scaler")? {
+        dataset = MinMaxScaler::from_file(scaler)?.transform(&dataset);
+    }
+
+    println!("{}", Report::new(&Predictions::new(&network, &dataset)));
+
+    Ok(())
+}
+
+/// Prints the outputs of a saved network for each row of a CSV file, as CSV.
+fn predict(args: Args) -> Result<(), Box<dyn Error>> {
+    let (model, data) = args.model_and_data()?;
+    let network: NeuralNet<Sigmoid> = NeuralNet::from_file(model)?;
+    let scaler = match args.option::<PathBuf>("scaler")? {
+        Some(path) => Some(MinMaxScaler::from_file(path)?),
+        None => None,
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(args.flag("headers"))
+        .from_path(data)?;
+    let input_count = network.layer_sizes()[0];
+    let mut output = BufWriter::new(io::stdout().lock());
+    for record in reader.records() {
+        let record = record?;
+        if record.len() != input_count {
+            let line = record.position().map_or(0, |position| position.line());
+            return Err(format!(
+                "line {} has an incorrect number of inputs (expected {}, found {})",
+                line,
+                input_count,
+                record.len()
+            )
+            .into());
+        }
+        let inputs = record
+            .iter()
+            .map(|value| value.trim().parse())
+            .collect::<Result<Vec<f64>, _>>()?;
+        let inputs = match &scaler {
+            Some(scaler) => scaler.transform_inputs(&inputs),
+            None => inputs,
+        };
+
+        let outputs: Vec<String> = network.guess(&inputs).iter().map(f64::to_string).collect();
+        writeln!(output, "{}", outputs.join(","))?;
+    }
+    output.flush()?;
+
+    Ok(())
+}
+
+/// Serves a saved network over HTTP.
+fn serve(args: Args) -> Result<(), Box<dyn Error>> {
+    let model = match args.positional.as_slice() {
+        [model] => model,
+        _ => return Err("expected the path of a model".into()),
+    };
+    let network: NeuralNet<Sigmoid> = NeuralNet::from_file(model)?;
+    let mut server = Server::new(network);
+    if let Some(scaler) = args.option::<PathBuf>("scaler")? {
+        server = server.scaler(MinMaxScaler::from_file(scaler)?);
+    }
+    if let Some(max_batch_size) = args.option("max-batch-size")? {
+        server = server.max_batch_size(max_batch_size);
+    }
+
+    let address = args
+        .option("address")?
+        .unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    let listener = TcpListener::bind(&address)?;
+    let address = listener.local_addr()?;
+    println!("Serving {} on http://{}/predict", model, address);
+    server.serve_listener(listener)?;
+
+    Ok(())
+}
+
+/// The positional arguments and options given to a command.
+struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    /// Parses the arguments of a command that accepts the given options (and any flags).
+    fn parse(
+        mut args: impl Iterator<Item = String>,
+        options: &[&str],
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut parsed = Args {
+            positional: Vec::new(),
+            options: HashMap::new(),
+            flags: Vec::new(),
+        };
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(name) if FLAGS.contains(&name) => parsed.flags.push(name.to_string()),
+                Some(name) if options.contains(&name) => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("missing value for --{}", name))?;
+                    parsed.options.insert(name.to_string(), value);
+                }
+                Some(name) => return Err(format!("unknown option --{}", name).into()),
+                None => parsed.positional.push(arg),
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    /// Returns whether the given flag was given.
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+
+    /// Parses the value of the given option, if it was given.
+    fn option<T: FromStr>(&self, name: &str) -> Result<Option<T>, Box<dyn Error>>
+    where
+        T::Err: Display,
+    {
+        self.options
+            .get(name)
+            .map(|value| {
+                value.parse().map_err(|err| {
+                    format!("invalid value '{}' for --{}: {}", value, name, err).into()
+                })
+            })
+            .transpose()
+    }
+
+    /// Parses the value of the given option, which must have been given.
+    fn required<T: FromStr>(&self, name: &str) -> Result<T, Box<dyn Error>>
+    where
+        T::Err: Display,
+    {
+        self.option(name)?
+            .ok_or_else(|| format!("missing required option --{}", name).into())
+    }
+
+    /// Returns the paths of the model and data file, given as the only positional arguments.
+    fn model_and_data(&self) -> Result<(&str, &str), Box<dyn Error>> {
+        match self.positional.as_slice() {
+            [model, data] => Ok((model, data)),
+            _ => Err("expected the paths of a model and a data file".into()),
+        }
+    }
+}
+
+/// The node counts of a network's layers, given as a comma-separated list.
+struct Layers(Vec<usize>);
+
+impl FromStr for Layers {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let node_counts = s
+            .split(',')
+            .map(|count| count.trim().parse())
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|err| err.to_string())?;
+        if node_counts.len() < 2 {
+            return Err("a network needs at least 2 layers".to_string());
+        }
+
+        Ok(Layers(node_counts))
+    }
+}
+
+/// The scaling applied to the inputs, given by its name in configuration files.
+struct ScaleArg(Scale);
+
+impl FromStr for ScaleArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(ScaleArg(Scale::None)),
+            "min_max" => Ok(ScaleArg(Scale::MinMax)),
+            _ => Err("expected 'none' or 'min_max'".to_string()),
+        }
+    }
+}
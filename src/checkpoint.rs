@@ -0,0 +1,262 @@
+//! Periodic checkpointing of a network during training, with a policy for which checkpoints are
+//! retained so that long runs don't fill the disk.
+
+use crate::dataset::Dataset;
+use crate::metrics::OnlineEvaluator;
+use crate::network::{Activation, Float, LoadErr, NeuralNet, SaveErr};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Saves a network to a directory every few epochs whilst training with
+/// [`Trainer::train_with_checkpoints()`](../struct.Trainer.html#method.train_with_checkpoints),
+/// deleting the checkpoints that aren't retained.
+///
+/// A checkpoint is retained if it satisfies any of the retention rules: being one of the last
+/// `N` saved, being one of the best `K` (those with the lowest loss), or being saved on a
+/// multiple of `M` epochs. If no rules are given, every checkpoint is retained.
+///
+/// The loss of each checkpoint is its mean absolute error on the validation data, or on the
+/// training data if no validation data is given.
+///
+/// Alongside each network, the state of training at the checkpoint (the epoch, and the seed of
+/// the random number generator from then on) is saved with the extension `.state`, so that
+/// training can carry on from any checkpoint with
+/// [`Trainer::resume()`](../struct.Trainer.html#method.resume). Training uses plain stochastic
+/// gradient descent, which has no other state (such as momentum) to save.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{checkpoint::Checkpoints, Dataset, NeuralNet, Sigmoid, Trainer};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let dataset = Dataset::from(vec![
+///     (vec![0.0, 0.0], vec![0.0]),
+///     (vec![0.0, 1.0], vec![1.0]),
+///     (vec![1.0, 0.0], vec![1.0]),
+///     (vec![1.0, 1.0], vec![0.0]),
+/// ]);
+///
+/// let dir = std::env::temp_dir().join("scholar_checkpoints_example");
+/// # let _ = std::fs::remove_dir_all(&dir);
+///
+/// // Saves the network every 10 epochs, keeping the last 2 and best 1 checkpoints
+/// let mut checkpoints = Checkpoints::new(&dir, 10).keep_last(2).keep_best(1);
+///
+/// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+/// Trainer::new(100, 0.1).train_with_checkpoints(&mut brain, dataset, &mut checkpoints)?;
+///
+/// assert!(checkpoints.saved().len() <= 3);
+/// let best: NeuralNet<Sigmoid> = NeuralNet::from_file(&checkpoints.best().unwrap().path)?;
+/// # std::fs::remove_dir_all(&dir)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Checkpoints {
+    dir: PathBuf,
+    every: u64,
+    keep_last: Option<usize>,
+    keep_best: Option<usize>,
+    keep_every: Option<u64>,
+    validation: Option<Dataset>,
+    saved: Vec<Checkpoint>,
+}
+
+/// A checkpoint saved during training.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Checkpoint {
+    /// The epoch after which the checkpoint was saved.
+    pub epoch: u64,
+    /// The loss of the network at the checkpoint.
+    pub loss: f64,
+    /// The path of the saved network.
+    pub path: PathBuf,
+}
+
+/// The state of training saved alongside each checkpoint, so that training can be resumed from
+/// it.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct TrainingState {
+    /// The epoch after which the checkpoint was saved.
+    pub(crate) epoch: u64,
+    /// The seed that the random number generator was reseeded with after the epoch.
+    pub(crate) seed: u64,
+}
+
+impl TrainingState {
+    /// Loads the state of training saved alongside the network at the given path.
+    pub(crate) fn from_checkpoint(path: impl AsRef<Path>) -> Result<Self, LoadErr> {
+        let file = fs::File::open(state_path(path.as_ref()))?;
+        let decoded: TrainingState = bincode::deserialize_from(file)?;
+
+        Ok(decoded)
+    }
+}
+
+impl Checkpoints {
+    /// Creates a new checkpointing policy that saves the network to the given directory (which
+    /// is created if it doesn't exist) every `every` epochs, retaining every checkpoint.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `every` is 0.
+    pub fn new(dir: impl AsRef<Path>, every: u64) -> Self {
+        if every == 0 {
+            panic!("checkpoint interval must be at least 1 epoch");
+        }
+
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            every,
+            keep_last: None,
+            keep_best: None,
+            keep_every: None,
+            validation: None,
+            saved: Vec::new(),
+        }
+    }
+
+    /// Retains the last `n` checkpoints saved.
+    pub fn keep_last(mut self, n: usize) -> Self {
+        self.keep_last = Some(n);
+        self
+    }
+
+    /// Retains the `k` checkpoints with the lowest loss, ranking a loss of `NaN` (as when
+    /// training diverges) as the worst.
+    pub fn keep_best(mut self, k: usize) -> Self {
+        self.keep_best = Some(k);
+        self
+    }
+
+    /// Retains the checkpoints saved on every multiple of `m` epochs.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `m` is 0.
+    pub fn keep_every(mut self, m: u64) -> Self {
+        if m == 0 {
+            panic!("retention interval must be at least 1 epoch");
+        }
+
+        self.keep_every = Some(m);
+        self
+    }
+
+    /// Measures the loss of each checkpoint on the given validation data, rather than on the
+    /// training data.
+    pub fn validation(mut self, dataset: Dataset) -> Self {
+        self.validation = Some(dataset);
+        self
+    }
+
+    /// Returns the checkpoints that are currently retained, in the order they were saved.
+    pub fn saved(&self) -> &[Checkpoint] {
+        &self.saved
+    }
+
+    /// Returns the retained checkpoint with the lowest loss, if any have been saved.
+    pub fn best(&self) -> Option<&Checkpoint> {
+        self.saved
+            .iter()
+            .min_by(|a, b| compare_losses(a.loss, b.loss))
+    }
+
+    /// Saves a checkpoint of the network if one is due after the given epoch, and then deletes
+    /// the checkpoints that are no longer retained.
+    ///
+    /// The random number generator of training is reseeded with a seed from itself when saving
+    /// a checkpoint, so that training resumed from the checkpoint can use the same numbers.
+    pub(crate) fn update<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &mut self,
+        network: &mut NeuralNet<A, T>,
+        epoch: u64,
+        training_dataset: &Dataset,
+        rng: &mut StdRng,
+    ) -> Result<(), SaveErr> {
+        if !epoch.is_multiple_of(self.every) {
+            return Ok(());
+        }
+
+        let mut evaluator = OnlineEvaluator::new();
+        for (inputs, targets) in self.validation.as_ref().unwrap_or(training_dataset) {
+            evaluator.update(&network.guess(inputs), targets);
+        }
+
+        fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!("epoch-{}.network", epoch));
+        network.save(&path)?;
+        let state = TrainingState {
+            epoch,
+            seed: rng.gen(),
+        };
+        fs::write(state_path(&path), bincode::serialize(&state)?)?;
+        *rng = StdRng::seed_from_u64(state.seed);
+        self.saved.push(Checkpoint {
+            epoch,
+            loss: evaluator.loss(),
+            path,
+        });
+
+        self.rotate()
+    }
+
+    /// Deletes every saved checkpoint that isn't retained by any of the retention rules.
+    fn rotate(&mut self) -> Result<(), SaveErr> {
+        if self.keep_last.is_none() && self.keep_best.is_none() && self.keep_every.is_none() {
+            return Ok(());
+        }
+
+        let mut retained = vec![false; self.saved.len()];
+        if let Some(n) = self.keep_last {
+            let start = self.saved.len().saturating_sub(n);
+            retained[start..].iter_mut().for_each(|r| *r = true);
+        }
+        if let Some(k) = self.keep_best {
+            let mut ranked: Vec<_> = (0..self.saved.len()).collect();
+            ranked.sort_by(|&a, &b| compare_losses(self.saved[a].loss, self.saved[b].loss));
+            ranked.into_iter().take(k).for_each(|i| retained[i] = true);
+        }
+        if let Some(m) = self.keep_every {
+            for (checkpoint, retained) in self.saved.iter().zip(&mut retained) {
+                *retained |= checkpoint.epoch.is_multiple_of(m);
+            }
+        }
+
+        let mut kept = Vec::with_capacity(self.saved.len());
+        for (checkpoint, retained) in self.saved.drain(..).zip(retained) {
+            if retained {
+                kept.push(checkpoint);
+            } else {
+                fs::remove_file(&checkpoint.path)?;
+                fs::remove_file(state_path(&checkpoint.path))?;
+            }
+        }
+        self.saved = kept;
+
+        Ok(())
+    }
+}
+
+/// Orders losses from best to worst, with `NaN` (of a network whose training diverged) being
+/// worse than any other loss.
+fn compare_losses(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.total_cmp(&b),
+    }
+}
+
+/// Returns the path of the state of training saved alongside the network at the given path.
+fn state_path(path: &Path) -> PathBuf {
+    path.with_extension("state")
+}
@@ -0,0 +1,343 @@
+//! Declarative, configuration-file-driven training.
+//!
+//! An experiment (the dataset, its preprocessing, the network architecture and the training
+//! hyperparameters) is described in a TOML file like the following:
+//!
+//! ```toml
+//! [data]
+//! path = "iris.csv"
+//! headers = false
+//! inputs = 4
+//! train_portion = 0.75
+//! stratify = true
+//!
+//! [preprocessing]
+//! scale = "min_max"
+//!
+//! [network]
+//! activation = "sigmoid"
+//! loss = "mae"
+//! node_counts = [4, 10, 10, 3]
+//! iterations = 10000
+//! learning_rate = 0.01
+//!
+//! [output]
+//! model = "iris.network"
+//! scaler = "iris.scaler"
+//! ```
+//!
+//! Only the `path` and `inputs` of the `[data]` section, and the `node_counts`, `iterations` and
+//! `learning_rate` of the `[network]` section are required; every other setting has a default.
+
+use crate::dataset::{Dataset, ParseCsvError};
+use crate::metrics::{Predictions, Report};
+use crate::network::{Activation, NetworkConfig, NeuralNet, SaveErr};
+use crate::preprocessing::MinMaxScaler;
+use crate::registry::Registry;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// A complete description of a training experiment.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExperimentConfig {
+    /// Where the dataset is loaded from and how it is split.
+    pub data: DataConfig,
+    /// How the dataset is transformed before training.
+    #[serde(default)]
+    pub preprocessing: PreprocessingConfig,
+    /// The network architecture and training hyperparameters.
+    pub network: NetworkSection,
+    /// Where the trained artifacts are saved.
+    #[serde(default)]
+    pub output: OutputConfig,
+}
+
+/// The `[data]` section of an `ExperimentConfig`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DataConfig {
+    /// The path to the CSV file.
+    pub path: PathBuf,
+    /// Whether the CSV has a header row or not.
+    #[serde(default)]
+    pub headers: bool,
+    /// The number of (leading) columns that are inputs.
+    pub inputs: usize,
+    /// The proportion of rows used for training, with the rest used for testing.
+    #[serde(default = "default_train_portion")]
+    pub train_portion: f64,
+    /// Whether the split preserves the proportion of each class.
+    #[serde(default)]
+    pub stratify: bool,
+}
+
+/// The `[preprocessing]` section of an `ExperimentConfig`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PreprocessingConfig {
+    /// How the inputs are scaled.
+    #[serde(default)]
+    pub scale: Scale,
+}
+
+/// The scaling applied to the inputs of an experiment.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scale {
+    /// The inputs are left as they are.
+    #[default]
+    None,
+    /// The inputs are scaled with a [`MinMaxScaler`](../preprocessing/struct.MinMaxScaler.html)
+    /// fitted on the training data.
+    MinMax,
+}
+
+/// The `[network]` section of an `ExperimentConfig`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NetworkSection {
+    /// The name of the activation (`"sigmoid"`, or any other registered in a
+    /// [`Registry`](../registry/struct.Registry.html)).
+    #[serde(default = "default_activation")]
+    pub activation: String,
+    /// The name of the loss reported for the testing data (`"mae"`, `"rmse"`, or any other
+    /// registered in a [`Registry`](../registry/struct.Registry.html)), instead of the average
+    /// cost (see [`Report::loss()`](../metrics/struct.Report.html#method.loss)).
+    #[serde(default)]
+    pub loss: Option<String>,
+    /// The architecture and training hyperparameters.
+    #[serde(flatten)]
+    pub config: NetworkConfig,
+}
+
+/// The `[output]` section of an `ExperimentConfig`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutputConfig {
+    /// Where the trained network is saved, if anywhere.
+    pub model: Option<PathBuf>,
+    /// Where the fitted scaler is saved, if anywhere (and if the inputs are scaled).
+    pub scaler: Option<PathBuf>,
+}
+
+impl ExperimentConfig {
+    /// Creates a new `ExperimentConfig` that trains a network (with the `"sigmoid"` activation)
+    /// on the CSV file at the given path, whose leading `inputs` columns are inputs, with the
+    /// default setting for everything else.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{config::ExperimentConfig, NetworkConfig};
+    ///
+    /// let config = ExperimentConfig::new("iris.csv", 4, NetworkConfig::new(&[4, 10, 3], 100, 0.01));
+    /// assert_eq!(config.data.train_portion, 0.75);
+    /// ```
+    pub fn new(path: impl Into<PathBuf>, inputs: usize, network: NetworkConfig) -> Self {
+        Self {
+            data: DataConfig {
+                path: path.into(),
+                headers: false,
+                inputs,
+                train_portion: default_train_portion(),
+                stratify: false,
+            },
+            preprocessing: PreprocessingConfig::default(),
+            network: NetworkSection {
+                activation: default_activation(),
+                loss: None,
+                config: network,
+            },
+            output: OutputConfig::default(),
+        }
+    }
+
+    /// Loads an `ExperimentConfig` from a TOML file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigErr> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Parses an `ExperimentConfig` from a TOML string.
+    pub fn from_toml(toml: &str) -> Result<Self, ConfigErr> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    /// Runs the experiment: loads and splits the dataset, preprocesses it, trains a network,
+    /// saves the configured outputs, and returns a `Report` of the network's performance on the
+    /// testing data.
+    ///
+    /// Relative paths are resolved against the current working directory.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::config::ExperimentConfig;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let path = std::env::temp_dir().join("scholar_config_example.csv");
+    /// std::fs::write(&path, "0,0,0\n0,1,1\n1,0,1\n1,1,0\n")?;
+    ///
+    /// let mut config = ExperimentConfig::from_toml(
+    ///     r#"
+    ///     [data]
+    ///     path = "xor.csv"
+    ///     inputs = 2
+    ///     train_portion = 0.5
+    ///
+    ///     [network]
+    ///     loss = "rmse"
+    ///     node_counts = [2, 4, 1]
+    ///     iterations = 100
+    ///     learning_rate = 0.1
+    ///     "#,
+    /// )?;
+    /// config.data.path = path.clone();
+    ///
+    /// let report = config.run()?;
+    /// assert_eq!(report.confusion_matrix().total(), 2);
+    /// # std::fs::remove_file(path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn run(&self) -> Result<Report, ConfigErr> {
+        self.run_with_registry(&Registry::default())
+    }
+
+    /// Runs the experiment like [`ExperimentConfig::run()`](#method.run), looking up the
+    /// network's activation and loss in the given `Registry`.
+    pub fn run_with_registry(&self, registry: &Registry) -> Result<Report, ConfigErr> {
+        registry.run(&self.network.activation, self)
+    }
+
+    /// Runs the experiment with a network using the given activation.
+    pub(crate) fn run_as<A: Activation + Serialize + DeserializeOwned>(
+        &self,
+        registry: &Registry,
+    ) -> Result<Report, ConfigErr> {
+        let loss = match &self.network.loss {
+            Some(name) => Some(
+                registry
+                    .loss(name)
+                    .ok_or_else(|| ConfigErr::UnknownLoss(name.clone()))?,
+            ),
+            None => None,
+        };
+
+        let data = &self.data;
+        let dataset = Dataset::from_csv(&data.path, data.headers, data.inputs)?;
+        let (training_data, testing_data) = if data.stratify {
+            dataset.stratified_split(data.train_portion)
+        } else {
+            dataset.split(data.train_portion)
+        };
+
+        let (training_data, testing_data) = match self.preprocessing.scale {
+            Scale::None => (training_data, testing_data),
+            Scale::MinMax => {
+                let scaler = MinMaxScaler::fit(&training_data);
+                if let Some(path) = &self.output.scaler {
+                    scaler.save(path)?;
+                }
+                (
+                    scaler.transform(&training_data),
+                    scaler.transform(&testing_data),
+                )
+            }
+        };
+
+        let network: NeuralNet<A> = self.network.config.train(training_data);
+        if let Some(path) = &self.output.model {
+            network.save(path)?;
+        }
+
+        let predictions = Predictions::new(&network, &testing_data);
+        let report = Report::new(&predictions);
+        Ok(match loss {
+            Some(loss) => report.with_loss(loss(&predictions)),
+            None => report,
+        })
+    }
+
+    /// Resolves every relative path in the configuration against the given directory (such as
+    /// the directory containing the configuration file).
+    pub fn resolve_paths(&mut self, dir: &Path) {
+        let resolve = |path: &mut PathBuf| {
+            if path.is_relative() {
+                *path = dir.join(&*path);
+            }
+        };
+
+        resolve(&mut self.data.path);
+        for path in self.output.model.iter_mut().chain(&mut self.output.scaler) {
+            resolve(path);
+        }
+    }
+}
+
+/// Loads an `ExperimentConfig` from a TOML file and runs it, returning a `Report` of the trained
+/// network's performance on the testing data.
+///
+/// Relative paths within the file are resolved against the directory containing it, so that
+/// experiments can be run from anywhere.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # fn main() -> Result<(), scholar::config::ConfigErr> {
+/// let report = scholar::config::run_from_config("experiments/iris.toml")?;
+/// println!("{}", report);
+/// # Ok(())
+/// # }
+/// ```
+pub fn run_from_config(path: impl AsRef<Path>) -> Result<Report, ConfigErr> {
+    run_from_config_with(path, &Registry::default())
+}
+
+/// Loads an `ExperimentConfig` from a TOML file and runs it like
+/// [`run_from_config()`](fn.run_from_config.html), looking up the network's activation and loss
+/// in the given `Registry`.
+pub fn run_from_config_with(
+    path: impl AsRef<Path>,
+    registry: &Registry,
+) -> Result<Report, ConfigErr> {
+    let path = path.as_ref();
+    let mut config = ExperimentConfig::from_file(path)?;
+    if let Some(dir) = path.parent() {
+        config.resolve_paths(dir);
+    }
+
+    config.run_with_registry(registry)
+}
+
+/// Returns the default proportion of rows used for training.
+fn default_train_portion() -> f64 {
+    0.75
+}
+
+/// Returns the name of the default activation.
+fn default_activation() -> String {
+    "sigmoid".to_string()
+}
+
+/// An enumeration over the possible errors when running an experiment from a configuration.
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigErr {
+    /// When reading the configuration file fails.
+    #[error("failed to read configuration file")]
+    Read(#[from] std::io::Error),
+    /// When parsing the configuration fails.
+    #[error("failed to parse configuration")]
+    Parse(#[from] toml::de::Error),
+    /// When the configuration names an activation that doesn't exist.
+    #[error("unknown activation '{0}'")]
+    UnknownActivation(String),
+    /// When the configuration names a loss that doesn't exist.
+    #[error("unknown loss '{0}'")]
+    UnknownLoss(String),
+    /// When loading the dataset fails.
+    #[error("failed to load dataset")]
+    Dataset(#[from] ParseCsvError),
+    /// When saving the trained network or scaler fails.
+    #[error("failed to save output")]
+    Save(#[from] SaveErr),
+}
@@ -1,8 +1,54 @@
 
-use rand::seq::SliceRandom;
+mod augment;
+mod batch;
+#[cfg(any(feature = "arrow", feature = "parquet"))]
+mod columnar;
+#[cfg(feature = "fs")]
+mod idx;
+#[cfg(feature = "image")]
+mod images;
+#[cfg(feature = "fs")]
+mod json;
+mod resample;
+mod sequence;
+mod sparse;
+mod stream;
+
+pub use augment::*;
+pub use batch::*;
+#[cfg(any(feature = "arrow", feature = "parquet"))]
+pub use columnar::*;
+#[cfg(feature = "fs")]
+pub use idx::*;
+#[cfg(feature = "image")]
+pub use images::*;
+#[cfg(feature = "fs")]
+pub use json::*;
+pub use resample::*;
+pub use sequence::*;
+pub use sparse::*;
+pub use stream::*;
+
+#[cfg(feature = "fs")]
+use crate::network::{LoadErr, SaveErr};
+#[cfg(feature = "fs")]
+use crate::preprocessing::LabelEncoder;
+use crate::utils::decode_class;
+
+use rand::{seq::SliceRandom, Rng};
+#[cfg(feature = "fs")]
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "fs")]
+use std::{fs, path::Path};
+use std::{
+    iter::FromIterator,
+    ops::{Index, RangeBounds},
+    slice::SliceIndex,
+};
 
 /// A tuple containing a vector of input values matched to a vector of their expected output values
-type Row = (Vec<f64>, Vec<f64>);
+pub(crate) type Row = (Vec<f64>, Vec<f64>);
 
 /// A collection of input vectors matched with their expected output.
 ///
@@ -20,7 +66,11 @@ type Row = (Vec<f64>, Vec<f64>);
 ///
 /// let dataset = scholar::Dataset::from(data);
 /// ```
-#[derive(Debug)]
+///
+/// A `Dataset` can be saved to (and loaded from) a compact binary file, so that the results of
+/// expensive parsing or preprocessing can be cached between runs. It also implements `Serialize`
+/// and `Deserialize` for use with other formats.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Dataset {
     data: Vec<Row>,
 }
@@ -28,12 +78,15 @@ pub struct Dataset {
 impl Dataset {
     /// Parses a `Dataset` from a CSV file.
     ///
+    /// `NaN` cells are parsed as `NaN` and empty cells are rejected with an error; use
+    /// [`Dataset::from_csv_with()`](#method.from_csv_with) to handle them otherwise.
+    ///
     /// # Arguments
     ///
     /// * `file_path` - The path to the CSV file
     /// * `includes_headers` - Whether the CSV has a header row or not
     /// * `num_inputs` - The number of columns in the CSV that are designated as inputs (to a
-    /// Machine Learning model)
+    ///   Machine Learning model)
     ///
     /// # Examples
     /// ```rust
@@ -41,11 +94,214 @@ impl Dataset {
     /// // target outputs
     /// let dataset = scholar::Dataset::from_csv("iris.csv", false, 4);
     /// ```
+    #[cfg(feature = "fs")]
     pub fn from_csv(
         file_path: impl AsRef<std::path::Path>,
         includes_headers: bool,
         num_inputs: usize,
     ) -> Result<Self, ParseCsvError> {
+        Self::from_csv_with(
+            file_path,
+            &CsvOptions::new(num_inputs).headers(includes_headers),
+        )
+    }
+
+    /// Parses a `Dataset` from a CSV file using the given `CsvOptions`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use scholar::{CsvOptions, Dataset};
+    ///
+    /// # fn main() -> Result<(), scholar::ParseCsvError> {
+    /// // Uses the 'species' column as the target, and every other column except for 'id' as an
+    /// // input
+    /// let options = CsvOptions::new(0)
+    ///     .headers(true)
+    ///     .targets(vec!["species"])
+    ///     .ignore(vec!["id"]);
+    /// let dataset = Dataset::from_csv_with("flowers.csv", &options)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ```rust,no_run
+    /// use scholar::{CsvOptions, Dataset, MissingValues};
+    ///
+    /// # fn main() -> Result<(), scholar::ParseCsvError> {
+    /// // Fills in blank cells with the median of their column
+    /// let options = CsvOptions::new(4)
+    ///     .headers(true)
+    ///     .missing_values(MissingValues::Median);
+    /// let dataset = Dataset::from_csv_with("iris.csv", &options)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn from_csv_with(
+        file_path: impl AsRef<std::path::Path>,
+        options: &CsvOptions,
+    ) -> Result<Self, ParseCsvError> {
+        let (data, _) = Self::parse_csv(file_path, options, None)?;
+        Ok(Dataset::from(data))
+    }
+
+    /// Parses the rows of a CSV file using the given `CsvOptions`, along with each row's cell in
+    /// the given `group` column (if any), which should be ignored by the options.
+    #[cfg(feature = "fs")]
+    pub(crate) fn parse_csv(
+        file_path: impl AsRef<std::path::Path>,
+        options: &CsvOptions,
+        group: Option<&Column>,
+    ) -> Result<(Vec<Row>, Vec<String>), ParseCsvError> {
+        let file = std::fs::File::open(file_path)?;
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(options.includes_headers)
+            .from_reader(file);
+        let headers = if options.includes_headers {
+            Some(reader.headers()?.clone())
+        } else {
+            None
+        };
+
+        // The indices of the input and target columns, which are resolved once the number of
+        // columns is known
+        let mut layout: Option<Layout> = None;
+
+        // Parses every selected cell, with `None` marking a missing value
+        let mut rows = Vec::new();
+        let mut groups = Vec::new();
+        let mut group_index = None;
+        for row in reader.records() {
+            // Catches a possible parsing error
+            let row = row?;
+            let layout = match &layout {
+                Some(layout) => layout,
+                None => layout.get_or_insert(options.layout(headers.as_ref(), row.len())?),
+            };
+            rows.push(options.parse_record(&row, layout)?);
+
+            if let Some(group) = group {
+                let index = match group_index {
+                    Some(index) => index,
+                    None => *group_index.get_or_insert(group.index(headers.as_ref(), row.len())?),
+                };
+                groups.push(row[index].trim().to_string());
+            }
+        }
+        let num_inputs = layout.map_or(0, |(inputs, _)| inputs.len());
+
+        let fill = options.missing_values.fill_values(&rows);
+        let keep = |row: &Vec<Option<f64>>| fill.is_some() || row.iter().all(Option::is_some);
+        if group.is_some() {
+            let mut kept = rows.iter().map(keep);
+            groups.retain(|_| kept.next().unwrap_or(false));
+        }
+        let data: Vec<Row> = rows
+            .into_iter()
+            .filter(keep)
+            .map(|row| {
+                let mut inputs: Vec<f64> = row
+                    .into_iter()
+                    .enumerate()
+                    .map(|(column, value)| {
+                        value.unwrap_or_else(|| fill.as_ref().map_or(f64::NAN, |f| f[column]))
+                    })
+                    .collect();
+
+                // Splits the row into input and output vectors
+                let outputs = inputs.split_off(num_inputs);
+                (inputs, outputs)
+            })
+            .collect();
+
+        Ok((data, groups))
+    }
+
+    /// Parses a `Dataset` from a CSV file by deserializing each row into a record type of your
+    /// own, which is then converted into a row of inputs and targets.
+    ///
+    /// The columns are matched to the record's fields by name if the CSV has a header row, or
+    /// by position otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct House {
+    ///     id: String,
+    ///     rooms: f64,
+    ///     area: f64,
+    ///     price: f64,
+    /// }
+    ///
+    /// impl From<House> for (Vec<f64>, Vec<f64>) {
+    ///     fn from(house: House) -> Self {
+    ///         (vec![house.rooms, house.area], vec![house.price])
+    ///     }
+    /// }
+    ///
+    /// # fn main() -> Result<(), scholar::ParseCsvError> {
+    /// let dataset = scholar::Dataset::from_csv_records::<House>("houses.csv", true)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn from_csv_records<R: DeserializeOwned + Into<Row>>(
+        file_path: impl AsRef<std::path::Path>,
+        includes_headers: bool,
+    ) -> Result<Self, ParseCsvError> {
+        let file = std::fs::File::open(file_path)?;
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(includes_headers)
+            .trim(csv::Trim::All)
+            .from_reader(file);
+
+        let records = reader.deserialize::<R>().collect::<Result<Vec<_>, _>>()?;
+        Ok(records.into_iter().collect())
+    }
+
+    /// Parses a `Dataset` from a CSV file whose target column contains class names (e.g.
+    /// `"setosa"`) rather than numbers, returning it alongside the `LabelEncoder` that maps the
+    /// class names to their indices.
+    ///
+    /// Each row's class name is encoded as a one-hot target vector, with the classes indexed in
+    /// alphabetical order. The encoder can be used to decode the network's guesses back into class
+    /// names.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - The path to the CSV file
+    /// * `includes_headers` - Whether the CSV has a header row or not
+    /// * `num_inputs` - The number of columns in the CSV that are designated as inputs, which
+    ///   must be followed by exactly one column of class names
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use scholar::{Dataset, NeuralNet, Sigmoid};
+    ///
+    /// # fn main() -> Result<(), scholar::ParseCsvError> {
+    /// // Each row of 'iris.csv' ends with one of "setosa", "versicolor" or "virginica"
+    /// let (dataset, labels) = Dataset::from_csv_with_labels("iris.csv", false, 4)?;
+    /// assert_eq!(labels.len(), 3);
+    ///
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[4, 10, 10, labels.len()]);
+    /// brain.train(dataset, 10_000, 0.01);
+    ///
+    /// let guesses = brain.guess(&[5.1, 3.5, 1.4, 0.2]);
+    /// println!("Species: {}", labels.decode_guesses(&guesses));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn from_csv_with_labels(
+        file_path: impl AsRef<std::path::Path>,
+        includes_headers: bool,
+        num_inputs: usize,
+    ) -> Result<(Self, LabelEncoder), ParseCsvError> {
         use std::str::FromStr;
 
         let file = std::fs::File::open(file_path)?;
@@ -53,26 +309,38 @@ impl Dataset {
             .has_headers(includes_headers)
             .from_reader(file);
 
-        let data: Result<Vec<Row>, ParseCsvError> = reader
+        let rows: Vec<(Vec<f64>, String)> = reader
             .records()
             .map(|row| {
-                // Catches a possible parsing error
                 let row = row?;
-                let row = row
+                if row.len() != num_inputs + 1 {
+                    return Err(ParseCsvError::Columns {
+                        expected: num_inputs + 1,
+                        found: row.len(),
+                    });
+                }
+
+                let inputs = row
                     .iter()
-                    .map(|val| {
-                        let val = val.trim();
-                        f64::from_str(val)
-                    })
+                    .take(num_inputs)
+                    .map(|val| f64::from_str(val.trim()))
                     .collect::<Result<Vec<_>, _>>()?;
+                Ok((inputs, row[num_inputs].trim().to_string()))
+            })
+            .collect::<Result<_, _>>()?;
 
-                let mut inputs = row;
-                // Splits the row into input and output vectors
-                let outputs = inputs.split_off(num_inputs);
-                Ok((inputs, outputs))
+        let encoder = LabelEncoder::fit(rows.iter().map(|(_, label)| label.as_str()));
+        let data: Vec<Row> = rows
+            .into_iter()
+            .map(|(inputs, label)| {
+                let targets = encoder
+                    .one_hot(&label)
+                    .expect("every label was seen when fitting the encoder");
+                (inputs, targets)
             })
             .collect();
-        Ok(Dataset::from(data?))
+
+        Ok((Dataset::from(data), encoder))
     }
 
     /// Splits the dataset into two, with the size of each determined by the given `train_portion`.
@@ -80,19 +348,22 @@ impl Dataset {
     ///
     /// # Examples
     ///
-    /// ```rust
+    /// ```rust,no_run
+    /// # fn main() -> Result<(), scholar::ParseCsvError> {
     /// let dataset = scholar::Dataset::from_csv("iris.csv", false, 4)?;
     ///
     /// // Randomly allocates 75% of the original dataset to `training_data`, and the rest
     /// // to `testing_data`
     /// let (training_data, testing_data) = dataset.split(0.75);
+    /// # Ok(())
+    /// # }
     /// ```
     ///
     /// # Panics
     ///
     /// This method panics if the given `train_portion` isn't between 0 and 1.
     pub fn split(mut self, train_portion: f64) -> (Self, Self) {
-        if train_portion < 0.0 || train_portion > 1.0 {
+        if !(0.0..=1.0).contains(&train_portion) {
             panic!(
                 "training portion must be between 0 and 1 (found {})",
                 train_portion
@@ -108,9 +379,526 @@ impl Dataset {
         (self, Self::from(test_split))
     }
 
-    /// Shuffles the rows in the dataset.
-    pub(crate) fn shuffle(&mut self) {
-        self.data.shuffle(&mut rand::thread_rng());
+    /// Randomly splits the dataset into three: a training segment of `train_portion` of the
+    /// rows, a validation segment of `validation_portion` of the rows, and a testing segment of
+    /// the rest.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let dataset = scholar::Dataset::from(vec![(vec![0.0], vec![0.0]); 10]);
+    ///
+    /// let (training_data, validation_data, testing_data) = dataset.split3(0.6, 0.2);
+    /// assert_eq!(training_data.rows(), 6);
+    /// assert_eq!(validation_data.rows(), 2);
+    /// assert_eq!(testing_data.rows(), 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if either portion isn't between 0 and 1, or if their sum is greater
+    /// than 1.
+    pub fn split3(mut self, train_portion: f64, validation_portion: f64) -> (Self, Self, Self) {
+        for portion in &[train_portion, validation_portion] {
+            if !(0.0..=1.0).contains(portion) {
+                panic!("portions must be between 0 and 1 (found {})", portion);
+            }
+        }
+        if train_portion + validation_portion > 1.0 {
+            panic!(
+                "portions must sum to at most 1 (found {})",
+                train_portion + validation_portion
+            );
+        }
+
+        self.shuffle();
+
+        // Both boundaries are rounded from the start, so that the segments always cover every row
+        let rows = self.data.len() as f64;
+        let validation_end = (rows * (train_portion + validation_portion)).round() as usize;
+        let train_end = (rows * train_portion).round() as usize;
+        let test_split = self.data.split_off(validation_end);
+        let validation_split = self.data.split_off(train_end);
+
+        (self, Self::from(validation_split), Self::from(test_split))
+    }
+
+    /// Randomly chooses which rows would be allocated to each segment of a split, without
+    /// actually splitting the dataset. The returned `SplitIndices` can be saved and later passed
+    /// to [`Dataset::split_by()`](#method.split_by) to reconstruct exactly the same split, which
+    /// ensures that different models are compared on identical partitions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let data = vec![
+    ///     (vec![0.0, 0.0], vec![0.0]),
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 0.0], vec![1.0]),
+    ///     (vec![1.0, 1.0], vec![0.0]),
+    /// ];
+    /// let dataset = scholar::Dataset::from(data);
+    ///
+    /// let indices = dataset.split_indices(0.75);
+    /// assert_eq!(indices.train.len(), 3);
+    /// assert_eq!(indices.test.len(), 1);
+    ///
+    /// // Splitting by the same indices always produces the same segments
+    /// let (training_data, testing_data) = dataset.split_by(&indices);
+    /// assert_eq!(training_data.rows(), 3);
+    /// assert_eq!(testing_data.rows(), 1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the given `train_portion` isn't between 0 and 1.
+    pub fn split_indices(&self, train_portion: f64) -> SplitIndices {
+        if !(0.0..=1.0).contains(&train_portion) {
+            panic!(
+                "training portion must be between 0 and 1 (found {})",
+                train_portion
+            );
+        }
+
+        let mut train: Vec<usize> = (0..self.rows()).collect();
+        train.shuffle(&mut rand::thread_rng());
+
+        let index = self.rows() as f64 * train_portion;
+        let test = train.split_off(index.round() as usize);
+
+        SplitIndices { train, test }
+    }
+
+    /// Splits the dataset into two according to the given `SplitIndices`, leaving the original
+    /// dataset intact.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if any of the indices are out of bounds.
+    pub fn split_by(&self, indices: &SplitIndices) -> (Self, Self) {
+        (self.select(&indices.train), self.select(&indices.test))
+    }
+
+    /// Splits the dataset into two like [`Dataset::split()`](#method.split), but preserves the
+    /// proportion of each class in both segments, so that small classes aren't left out of
+    /// either by chance.
+    ///
+    /// Each row's class is decoded from its targets: a single target value is treated as a
+    /// binary class (thresholded at 0.5), whereas multiple values are treated as one-hot encoded
+    /// classes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut data = vec![(vec![0.0], vec![1.0, 0.0]); 8];
+    /// data.extend(vec![(vec![1.0], vec![0.0, 1.0]); 4]);
+    /// let dataset = scholar::Dataset::from(data);
+    ///
+    /// let (training_data, testing_data) = dataset.stratified_split(0.75);
+    ///
+    /// // Both segments contain twice as many rows of the first class as of the second
+    /// let count = |d: &scholar::Dataset| d.into_iter().filter(|(_, t)| t[1] == 1.0).count();
+    /// assert_eq!((training_data.rows(), count(&training_data)), (9, 3));
+    /// assert_eq!((testing_data.rows(), count(&testing_data)), (3, 1));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the given `train_portion` isn't between 0 and 1.
+    pub fn stratified_split(self, train_portion: f64) -> (Self, Self) {
+        let indices = self.stratified_split_indices(train_portion);
+        self.split_by(&indices)
+    }
+
+    /// Randomly chooses which rows would be allocated to each segment of a stratified split (see
+    /// [`Dataset::stratified_split()`](#method.stratified_split)), without actually splitting
+    /// the dataset.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the given `train_portion` isn't between 0 and 1.
+    pub fn stratified_split_indices(&self, train_portion: f64) -> SplitIndices {
+        if !(0.0..=1.0).contains(&train_portion) {
+            panic!(
+                "training portion must be between 0 and 1 (found {})",
+                train_portion
+            );
+        }
+
+        // Groups the row indices by class
+        let mut classes: Vec<Vec<usize>> = Vec::new();
+        for (i, (_, targets)) in self.data.iter().enumerate() {
+            let class = decode_class(targets, 0.5);
+            if class >= classes.len() {
+                classes.resize(class + 1, Vec::new());
+            }
+            classes[class].push(i);
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut indices = SplitIndices {
+            train: Vec::new(),
+            test: Vec::new(),
+        };
+        for mut class in classes {
+            class.shuffle(&mut rng);
+            let index = class.len() as f64 * train_portion;
+            indices.test.extend(class.split_off(index.round() as usize));
+            indices.train.extend(class);
+        }
+
+        // Prevents the rows from being ordered by class
+        indices.train.shuffle(&mut rng);
+        indices.test.shuffle(&mut rng);
+
+        indices
+    }
+
+    /// Randomly partitions the rows into `k` folds (of as equal size as possible), returning one
+    /// `SplitIndices` per fold in which that fold is the testing segment and the remaining folds
+    /// form the training segment.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `k` is less than 2 or greater than the number of rows.
+    pub fn k_fold_indices(&self, k: usize) -> Vec<SplitIndices> {
+        if k < 2 || k > self.rows() {
+            panic!(
+                "number of folds must be between 2 and the number of rows ({}) (found {})",
+                self.rows(),
+                k
+            );
+        }
+
+        let mut indices: Vec<usize> = (0..self.rows()).collect();
+        indices.shuffle(&mut rand::thread_rng());
+
+        // The first `rows % k` folds each take one of the leftover rows
+        let (fold_size, leftover) = (self.rows() / k, self.rows() % k);
+        let bounds: Vec<usize> = (0..=k)
+            .map(|fold| fold * fold_size + fold.min(leftover))
+            .collect();
+
+        bounds
+            .windows(2)
+            .map(|fold| SplitIndices {
+                train: indices[..fold[0]]
+                    .iter()
+                    .chain(&indices[fold[1]..])
+                    .cloned()
+                    .collect(),
+                test: indices[fold[0]..fold[1]].to_vec(),
+            })
+            .collect()
+    }
+
+    /// Randomly partitions the rows into `k` folds, returning a `(training, testing)` pair of
+    /// datasets for each fold in which that fold is the testing dataset. This is the basis of
+    /// [k-fold cross-validation](validation/fn.cross_validate.html).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let data: Vec<_> = (0..10).map(|i| (vec![i as f64], vec![0.0])).collect();
+    /// let dataset = scholar::Dataset::from(data);
+    ///
+    /// let folds = dataset.k_folds(3);
+    /// assert_eq!(folds.len(), 3);
+    /// for (training_data, testing_data) in &folds {
+    ///     assert_eq!(training_data.rows() + testing_data.rows(), 10);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `k` is less than 2 or greater than the number of rows.
+    pub fn k_folds(&self, k: usize) -> Vec<(Self, Self)> {
+        self.k_fold_indices(k)
+            .iter()
+            .map(|indices| self.split_by(indices))
+            .collect()
+    }
+
+    /// Creates a new dataset with the same inputs, but only the target value at the given column
+    /// index (of the targets, not of the original CSV). This is useful for training a separate
+    /// single-output model on one target of a multi-target dataset.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let data = vec![
+    ///     (vec![0.0, 1.0], vec![10.0, 20.0]),
+    ///     (vec![1.0, 0.0], vec![30.0, 40.0]),
+    /// ];
+    /// let dataset = scholar::Dataset::from(data);
+    ///
+    /// let second = dataset.targets_column(1);
+    /// let targets: Vec<_> = second.into_iter().map(|(_, t)| t.clone()).collect();
+    /// assert_eq!(targets, vec![vec![20.0], vec![40.0]]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if any row has no target value at the given index.
+    pub fn targets_column(&self, index: usize) -> Self {
+        let data: Vec<Row> = self
+            .data
+            .iter()
+            .map(|(inputs, targets)| match targets.get(index) {
+                Some(target) => (inputs.clone(), vec![*target]),
+                None => panic!(
+                    "target index out of bounds (found {}, targets {})",
+                    index,
+                    targets.len()
+                ),
+            })
+            .collect();
+
+        Self::from(data)
+    }
+
+    /// Decomposes a multi-target dataset into one single-target dataset per target column (see
+    /// [`Dataset::targets_column()`](#method.targets_column)).
+    ///
+    /// The number of target columns is taken from the first row.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let data = vec![
+    ///     (vec![0.0, 1.0], vec![10.0, 20.0, 30.0]),
+    ///     (vec![1.0, 0.0], vec![40.0, 50.0, 60.0]),
+    /// ];
+    /// let dataset = scholar::Dataset::from(data);
+    ///
+    /// let datasets = dataset.split_targets();
+    /// assert_eq!(datasets.len(), 3);
+    /// assert_eq!(datasets[2].rows(), 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if any row has fewer target values than the first.
+    pub fn split_targets(&self) -> Vec<Self> {
+        let num_targets = self.data.first().map_or(0, |(_, targets)| targets.len());
+        (0..num_targets).map(|i| self.targets_column(i)).collect()
+    }
+
+    /// Moves every row of `other` onto the end of the dataset.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::Dataset;
+    ///
+    /// let mut dataset = Dataset::from(vec![(vec![0.0, 0.0], vec![0.0])]);
+    /// dataset.append(Dataset::from(vec![(vec![0.0, 1.0], vec![1.0])]));
+    ///
+    /// assert_eq!(dataset.rows(), 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if any row of `other` has a different number of inputs or targets to
+    /// the rows of the dataset.
+    pub fn append(&mut self, other: Dataset) {
+        if let Some((num_inputs, num_targets)) = self.dimensions().or_else(|| other.dimensions()) {
+            for (inputs, targets) in &other {
+                if inputs.len() != num_inputs {
+                    panic!(
+                        "incorrect number of inputs in appended row (expected {}, found {})",
+                        num_inputs,
+                        inputs.len()
+                    );
+                }
+                if targets.len() != num_targets {
+                    panic!(
+                        "incorrect number of targets in appended row (expected {}, found {})",
+                        num_targets,
+                        targets.len()
+                    );
+                }
+            }
+        }
+
+        self.data.extend(other.data);
+    }
+
+    /// Combines the given datasets (e.g. multiple CSV shards) into one, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use scholar::Dataset;
+    ///
+    /// # fn main() -> Result<(), scholar::ParseCsvError> {
+    /// let shards = (0..4)
+    ///     .map(|i| Dataset::from_csv(format!("shard-{}.csv", i), true, 8))
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    ///
+    /// let dataset = Dataset::concat(shards);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if any row has a different number of inputs or targets to the rows
+    /// of the first non-empty dataset.
+    pub fn concat(datasets: impl IntoIterator<Item = Dataset>) -> Self {
+        datasets
+            .into_iter()
+            .fold(Dataset::from(Vec::new()), |mut dataset, other| {
+                dataset.append(other);
+                dataset
+            })
+    }
+
+    /// Transforms the inputs and targets of every row in place.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut dataset = scholar::Dataset::from(vec![(vec![1.0, 250.0], vec![0.0])]);
+    ///
+    /// // Adds the product of the two inputs as a third input
+    /// dataset.map_rows(|inputs, _| inputs.push(inputs[0] * inputs[1]));
+    ///
+    /// let (inputs, _) = dataset.into_iter().next().unwrap();
+    /// assert_eq!(inputs, &vec![1.0, 250.0, 250.0]);
+    /// ```
+    pub fn map_rows(&mut self, mut f: impl FnMut(&mut Vec<f64>, &mut Vec<f64>)) {
+        for (inputs, targets) in &mut self.data {
+            f(inputs, targets);
+        }
+    }
+
+    /// Keeps only the rows whose inputs and targets satisfy the given predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut dataset = scholar::Dataset::from(vec![
+    ///     (vec![1.0, 2.0], vec![0.0]),
+    ///     (vec![-1.0, 4.0], vec![1.0]),
+    /// ]);
+    ///
+    /// // Drops the rows with negative inputs
+    /// dataset.filter_rows(|inputs, _| inputs.iter().all(|&x| x >= 0.0));
+    /// assert_eq!(dataset.rows(), 1);
+    /// ```
+    pub fn filter_rows(&mut self, mut f: impl FnMut(&[f64], &[f64]) -> bool) {
+        self.data.retain(|(inputs, targets)| f(inputs, targets));
+    }
+
+    /// Transforms the input at the given index (i.e. a feature column) of every row in place.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut dataset = scholar::Dataset::from(vec![
+    ///     (vec![1.0, 100.0], vec![0.0]),
+    ///     (vec![2.0, 10_000.0], vec![1.0]),
+    /// ]);
+    ///
+    /// // Log-transforms the second feature
+    /// dataset.map_features(1, f64::log10);
+    ///
+    /// let (inputs, _) = dataset.into_iter().nth(1).unwrap();
+    /// assert_eq!(inputs, &vec![2.0, 4.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if any row has fewer inputs than the given index.
+    pub fn map_features(&mut self, index: usize, mut f: impl FnMut(f64) -> f64) {
+        for (inputs, _) in &mut self.data {
+            if index >= inputs.len() {
+                panic!(
+                    "input index out of bounds (expected less than {}, found {})",
+                    inputs.len(),
+                    index
+                );
+            }
+            inputs[index] = f(inputs[index]);
+        }
+    }
+
+    /// Returns the number of inputs and targets of the first row, if there is one.
+    fn dimensions(&self) -> Option<(usize, usize)> {
+        self.data
+            .first()
+            .map(|(inputs, targets)| (inputs.len(), targets.len()))
+    }
+
+    /// Loads a `Dataset` from a file created using [`Dataset::save()`](#method.save).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # fn main() -> Result<(), scholar::LoadErr> {
+    /// let dataset = scholar::Dataset::from_file("iris.dataset")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, LoadErr> {
+        let file = fs::File::open(path)?;
+        let decoded: Dataset = bincode::deserialize_from(std::io::BufReader::new(file))?;
+
+        Ok(decoded)
+    }
+
+    /// Saves the dataset in a binary format to the specified path.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::Dataset;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let dataset = Dataset::from(vec![(vec![0.0, 1.0], vec![1.0]), (vec![1.0, 1.0], vec![0.0])]);
+    ///
+    /// let path = std::env::temp_dir().join("scholar_dataset_example.dataset");
+    /// dataset.save(&path)?;
+    ///
+    /// let loaded = Dataset::from_file(&path)?;
+    /// assert_eq!(loaded.rows(), 2);
+    /// # std::fs::remove_file(path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SaveErr> {
+        let encoded = bincode::serialize(&self)?;
+        fs::write(path, encoded)?;
+
+        Ok(())
+    }
+
+    /// Randomly shuffles the order of the rows in the dataset.
+    pub fn shuffle(&mut self) {
+        self.shuffle_with(&mut rand::thread_rng());
+    }
+
+    /// Shuffles the order of the rows in the dataset with the given random number generator, so
+    /// that a seeded generator always gives the same order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let data: Vec<_> = (0..10).map(|i| (vec![i as f64], vec![0.0])).collect();
+    /// let mut a = scholar::Dataset::from(data.clone());
+    /// let mut b = scholar::Dataset::from(data);
+    ///
+    /// a.shuffle_with(&mut StdRng::seed_from_u64(42));
+    /// b.shuffle_with(&mut StdRng::seed_from_u64(42));
+    /// assert!(a.into_iter().eq(b.into_iter()));
+    /// ```
+    pub fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.data.shuffle(rng);
     }
 
     /// Returns the number of rows in the dataset.
@@ -133,13 +921,439 @@ impl Dataset {
         self.data.len()
     }
 
-    /// Returns a reference to the row at the specified index.
-    fn get(&self, index: usize) -> Option<&Row> {
+    /// Returns the number of rows in the dataset, like [`Dataset::rows()`](#method.rows).
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the dataset has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns a reference to the inputs and targets of the row at the given index, or `None` if
+    /// it is out of bounds.
+    ///
+    /// Rows (and ranges of rows) can also be accessed by indexing the dataset directly.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let dataset = scholar::Dataset::from(vec![
+    ///     (vec![0.0, 0.0], vec![0.0]),
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 0.0], vec![1.0]),
+    /// ]);
+    ///
+    /// assert_eq!(dataset.get(1), Some(&(vec![0.0, 1.0], vec![1.0])));
+    /// assert_eq!(dataset.get(3), None);
+    ///
+    /// assert_eq!(dataset[2].0, vec![1.0, 0.0]);
+    /// assert_eq!(dataset[1..].len(), 2);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&Row> {
         self.data.get(index)
     }
+
+    /// Returns the rows of the dataset as a slice.
+    pub fn as_slice(&self) -> &[Row] {
+        &self.data
+    }
+
+    /// Creates a new `Dataset` from a copy of the rows in the given range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let data: Vec<_> = (0..10).map(|i| (vec![i as f64], vec![0.0])).collect();
+    /// let dataset = scholar::Dataset::from(data);
+    ///
+    /// let head = dataset.slice(..3);
+    /// assert_eq!(head.rows(), 3);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the range is out of bounds.
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> Self {
+        let range = (range.start_bound().cloned(), range.end_bound().cloned());
+        Self::from(self.data[range].to_vec())
+    }
+
+    /// Creates a new `Dataset` from a copy of the rows at the given indices, in order.
+    ///
+    /// This is useful for building custom samplers or cross-validation folds.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if any index is out of bounds.
+    pub fn select(&self, indices: &[usize]) -> Self {
+        let rows = self.rows();
+        let data: Vec<Row> = indices
+            .iter()
+            .map(|&i| match self.get(i) {
+                Some(row) => row.clone(),
+                None => panic!("row index out of bounds (found {}, rows {})", i, rows),
+            })
+            .collect();
+
+        Self::from(data)
+    }
+}
+
+/// The indices of the rows allocated to the training and testing segments of a split.
+///
+/// These can be persisted so that a split can be reproduced across runs:
+///
+/// ```rust,no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let dataset = scholar::Dataset::from_csv("iris.csv", false, 4)?;
+///
+/// let indices = dataset.split_indices(0.75);
+/// indices.save("iris.split")?;
+///
+/// // ... later on, possibly in a different program
+/// let indices = scholar::SplitIndices::from_file("iris.split")?;
+/// let (training_data, testing_data) = dataset.split_by(&indices);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SplitIndices {
+    /// The indices of the rows in the training segment.
+    pub train: Vec<usize>,
+    /// The indices of the rows in the testing segment.
+    pub test: Vec<usize>,
+}
+
+impl SplitIndices {
+    /// Loads `SplitIndices` from a file created using [`SplitIndices::save()`](#method.save).
+    #[cfg(feature = "fs")]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, LoadErr> {
+        let file = fs::File::open(path)?;
+        let decoded: SplitIndices = bincode::deserialize_from(file)?;
+
+        Ok(decoded)
+    }
+
+    /// Saves the indices in a binary format to the specified path.
+    #[cfg(feature = "fs")]
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SaveErr> {
+        let encoded = bincode::serialize(&self)?;
+        fs::write(path, encoded)?;
+
+        Ok(())
+    }
+}
+
+/// The options for parsing a `Dataset` from a CSV file with
+/// [`Dataset::from_csv_with()`](struct.Dataset.html#method.from_csv_with).
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{CsvOptions, Dataset};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let path = std::env::temp_dir().join("scholar_csv_options_example.csv");
+/// std::fs::write(&path, "label,id,x,y\n1,a7,0.5,0.25\n0,b3,0.75,1.0\n")?;
+///
+/// let options = CsvOptions::new(0)
+///     .headers(true)
+///     .targets(vec!["label"])
+///     .ignore(vec!["id"]);
+/// let dataset = Dataset::from_csv_with(&path, &options)?;
+///
+/// let (inputs, targets) = dataset.into_iter().next().unwrap();
+/// assert_eq!(inputs, &vec![0.5, 0.25]);
+/// assert_eq!(targets, &vec![1.0]);
+/// # std::fs::remove_file(path)?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "fs")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsvOptions {
+    num_inputs: usize,
+    includes_headers: bool,
+    missing_values: MissingValues,
+    inputs: Option<Vec<Column>>,
+    targets: Option<Vec<Column>>,
+    ignored: Vec<Column>,
+}
+
+#[cfg(feature = "fs")]
+impl CsvOptions {
+    /// Creates a new set of options for a CSV without headers, whose first `num_inputs` columns
+    /// (that aren't ignored) are inputs and remaining columns are targets, and where cells are
+    /// parsed as they are (see [`MissingValues::Keep`](enum.MissingValues.html#variant.Keep)).
+    pub fn new(num_inputs: usize) -> Self {
+        Self {
+            num_inputs,
+            includes_headers: false,
+            missing_values: MissingValues::Keep,
+            inputs: None,
+            targets: None,
+            ignored: Vec::new(),
+        }
+    }
+
+    /// Sets whether the CSV has a header row or not.
+    pub fn headers(mut self, includes_headers: bool) -> Self {
+        self.includes_headers = includes_headers;
+        self
+    }
+
+    /// Sets how empty and `NaN` cells are handled.
+    pub fn missing_values(mut self, missing_values: MissingValues) -> Self {
+        self.missing_values = missing_values;
+        self
+    }
+
+    /// Selects the input columns (in the given order), by name or index.
+    ///
+    /// This overrides the number of inputs given to [`CsvOptions::new()`](#method.new). Unless
+    /// the targets are also selected, every other column that isn't ignored is a target.
+    pub fn inputs<C: Into<Column>>(mut self, columns: impl IntoIterator<Item = C>) -> Self {
+        self.inputs = Some(columns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Selects the target columns (in the given order), by name or index.
+    ///
+    /// Unless the inputs are also selected, every other column that isn't ignored is an input.
+    pub fn targets<C: Into<Column>>(mut self, columns: impl IntoIterator<Item = C>) -> Self {
+        self.targets = Some(columns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Leaves the given columns (such as IDs or other metadata) out of the dataset, by name or
+    /// index. Ignored columns aren't parsed, so they needn't contain numbers.
+    pub fn ignore<C: Into<Column>>(mut self, columns: impl IntoIterator<Item = C>) -> Self {
+        self.ignored = columns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Resolves the indices of the input and target columns of a CSV with the given headers and
+    /// number of columns.
+    fn layout(
+        &self,
+        headers: Option<&csv::StringRecord>,
+        num_columns: usize,
+    ) -> Result<Layout, ParseCsvError> {
+        let resolve = |columns: &[Column]| {
+            columns
+                .iter()
+                .map(|column| column.index(headers, num_columns))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        let ignored = resolve(&self.ignored)?;
+        let explicit_inputs = self.inputs.as_deref().map(resolve).transpose()?;
+        let explicit_targets = self.targets.as_deref().map(resolve).transpose()?;
+
+        // Every column that isn't ignored or already selected
+        let remaining = |selected: &[usize]| -> Vec<usize> {
+            (0..num_columns)
+                .filter(|i| !ignored.contains(i) && !selected.contains(i))
+                .collect()
+        };
+
+        Ok(match (explicit_inputs, explicit_targets) {
+            (Some(inputs), Some(targets)) => (inputs, targets),
+            (Some(inputs), None) => {
+                let targets = remaining(&inputs);
+                (inputs, targets)
+            }
+            (None, Some(targets)) => (remaining(&targets), targets),
+            (None, None) => {
+                let mut inputs = remaining(&[]);
+                let targets = inputs.split_off(self.num_inputs.min(inputs.len()));
+                (inputs, targets)
+            }
+        })
+    }
+
+    /// Parses the selected cells of a CSV record (inputs first), with `None` marking a missing
+    /// value.
+    fn parse_record(
+        &self,
+        row: &csv::StringRecord,
+        (inputs, targets): &Layout,
+    ) -> Result<Vec<Option<f64>>, ParseCsvError> {
+        use std::str::FromStr;
+
+        let line = row.position().map_or(0, |p| p.line());
+        let mut values = Vec::with_capacity(inputs.len() + targets.len());
+        for &column in inputs.iter().chain(targets) {
+            let val = row[column].trim();
+            let value = if self.missing_values == MissingValues::Keep {
+                Some(f64::from_str(val)?)
+            } else if val.is_empty() {
+                None
+            } else {
+                Some(f64::from_str(val)?).filter(|x| !x.is_nan())
+            };
+
+            if value.is_none() && self.missing_values == MissingValues::Error {
+                return Err(ParseCsvError::Missing { line, column });
+            }
+            values.push(value);
+        }
+
+        Ok(values)
+    }
+}
+
+/// The indices of the input and target columns of a CSV.
+#[cfg(feature = "fs")]
+type Layout = (Vec<usize>, Vec<usize>);
+
+/// A reference to a column of a CSV, either by its name in the header row or by its index.
+///
+/// Columns are usually given as `&str`s or `usize`s, which convert into a `Column`.
+#[cfg(feature = "fs")]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Column {
+    /// The column at the given index (starting at 0).
+    Index(usize),
+    /// The column with the given header.
+    Name(String),
+}
+
+#[cfg(feature = "fs")]
+impl Column {
+    /// Returns the index of the column in a CSV with the given headers and number of columns.
+    fn index(
+        &self,
+        headers: Option<&csv::StringRecord>,
+        num_columns: usize,
+    ) -> Result<usize, ParseCsvError> {
+        match self {
+            Self::Index(index) if *index < num_columns => Ok(*index),
+            Self::Index(index) => Err(ParseCsvError::UnknownColumn(index.to_string())),
+            Self::Name(name) => headers
+                .and_then(|headers| headers.iter().position(|h| h.trim() == name))
+                .ok_or_else(|| ParseCsvError::UnknownColumn(name.clone())),
+        }
+    }
+}
+
+#[cfg(feature = "fs")]
+impl From<usize> for Column {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
+}
+
+#[cfg(feature = "fs")]
+impl From<&str> for Column {
+    fn from(name: &str) -> Self {
+        Self::Name(name.to_string())
+    }
+}
+
+#[cfg(feature = "fs")]
+impl From<String> for Column {
+    fn from(name: String) -> Self {
+        Self::Name(name)
+    }
+}
+
+/// The ways of handling missing (empty or `NaN`) cells when parsing a CSV.
+///
+/// By default, cells are parsed as they are ([`Keep`](#variant.Keep)), so handling missing values
+/// is opt-in.
+///
+/// The statistics used for imputation are calculated over the whole file, so impute the training
+/// data separately if no statistics should leak from the testing data.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{CsvOptions, Dataset, MissingValues};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let path = std::env::temp_dir().join("scholar_missing_values_example.csv");
+/// std::fs::write(&path, "1.0,2.0,0\n,4.0,1\n3.0,NaN,1\n")?;
+///
+/// // By default, the empty cell fails to parse
+/// assert!(Dataset::from_csv_with(&path, &CsvOptions::new(2)).is_err());
+///
+/// let options = CsvOptions::new(2).missing_values(MissingValues::SkipRow);
+/// assert_eq!(Dataset::from_csv_with(&path, &options)?.rows(), 1);
+///
+/// let options = CsvOptions::new(2).missing_values(MissingValues::Mean);
+/// let dataset = Dataset::from_csv_with(&path, &options)?;
+/// let (inputs, _) = dataset.into_iter().nth(1).unwrap();
+/// assert_eq!(inputs, &vec![2.0, 4.0]);
+/// # std::fs::remove_file(path)?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "fs")]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum MissingValues {
+    /// Cells are parsed as they are, so `NaN` cells are kept as `NaN` and empty cells are
+    /// rejected with a conversion error.
+    #[default]
+    Keep,
+    /// Missing values are rejected with an error.
+    Error,
+    /// Rows containing a missing value are left out.
+    SkipRow,
+    /// Missing values are replaced with the mean of the other values in their column.
+    Mean,
+    /// Missing values are replaced with the median of the other values in their column.
+    Median,
+    /// Missing values are replaced with the given constant.
+    Constant(f64),
+}
+
+#[cfg(feature = "fs")]
+impl MissingValues {
+    /// Returns the value that fills in a missing cell of each column, or `None` if rows with
+    /// missing cells are left out (or rejected).
+    ///
+    /// Columns without any values are filled with 0.
+    fn fill_values(self, rows: &[Vec<Option<f64>>]) -> Option<Vec<f64>> {
+        let num_columns = rows.first().map_or(0, Vec::len);
+        let column = |i: usize| -> Vec<f64> { rows.iter().filter_map(|row| row[i]).collect() };
+
+        let fill = |statistic: fn(Vec<f64>) -> f64| {
+            (0..num_columns)
+                .map(|i| {
+                    let values = column(i);
+                    if values.is_empty() {
+                        0.0
+                    } else {
+                        statistic(values)
+                    }
+                })
+                .collect()
+        };
+
+        match self {
+            Self::Keep | Self::Error | Self::SkipRow => None,
+            Self::Mean => Some(fill(|values| {
+                values.iter().sum::<f64>() / values.len() as f64
+            })),
+            Self::Median => Some(fill(|mut values| {
+                values.sort_by(|a, b| a.partial_cmp(b).expect("values are never NaN"));
+                let mid = values.len() / 2;
+                if values.len() % 2 == 0 {
+                    (values[mid - 1] + values[mid]) / 2.0
+                } else {
+                    values[mid]
+                }
+            })),
+            Self::Constant(value) => Some(vec![value; num_columns]),
+        }
+    }
 }
 
 /// An enumeration over the possible errors when parsing a `Dataset` from a CSV.
+#[cfg(feature = "fs")]
 #[derive(thiserror::Error, Debug)]
 pub enum ParseCsvError {
     /// When reading from a file fails.
@@ -151,6 +1365,25 @@ pub enum ParseCsvError {
     /// When converting CSV values to floats fails.
     #[error("failed to convert value into float")]
     Convert(#[from] std::num::ParseFloatError),
+    /// When a cell is empty or `NaN` and missing values are rejected.
+    #[error("missing value on line {line} in column {column}")]
+    Missing {
+        /// The line of the CSV containing the missing value.
+        line: u64,
+        /// The index of the column containing the missing value.
+        column: usize,
+    },
+    /// When a selected column doesn't exist.
+    #[error("unknown column '{0}'")]
+    UnknownColumn(String),
+    /// When a row has an unexpected number of columns.
+    #[error("incorrect number of columns (expected {expected}, found {found})")]
+    Columns {
+        /// The number of columns expected.
+        expected: usize,
+        /// The number of columns found.
+        found: usize,
+    },
 }
 
 impl From<Vec<Row>> for Dataset {
@@ -159,6 +1392,52 @@ impl From<Vec<Row>> for Dataset {
     }
 }
 
+/// Collects a `Dataset` from any records that convert into a row of inputs and targets, such as
+/// the output of an existing data pipeline.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::Dataset;
+///
+/// struct Reading {
+///     temperature: f64,
+///     humidity: f64,
+///     rained: bool,
+/// }
+///
+/// impl From<Reading> for (Vec<f64>, Vec<f64>) {
+///     fn from(reading: Reading) -> Self {
+///         let targets = vec![if reading.rained { 1.0 } else { 0.0 }];
+///         (vec![reading.temperature, reading.humidity], targets)
+///     }
+/// }
+///
+/// let readings = vec![
+///     Reading { temperature: 18.5, humidity: 0.9, rained: true },
+///     Reading { temperature: 24.0, humidity: 0.4, rained: false },
+/// ];
+///
+/// let dataset: Dataset = readings.into_iter().collect();
+/// assert_eq!(dataset.rows(), 2);
+/// ```
+impl<R: Into<Row>> FromIterator<R> for Dataset {
+    fn from_iter<I: IntoIterator<Item = R>>(records: I) -> Self {
+        Self {
+            data: records.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Indexes the rows of the dataset, either individually or by range.
+impl<I: SliceIndex<[Row]>> Index<I> for Dataset {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &Self::Output {
+        &self.data[index]
+    }
+}
+
 impl<'a> IntoIterator for &'a Dataset {
     type Item = &'a Row;
     type IntoIter = DatasetIterator<'a>;
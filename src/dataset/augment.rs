@@ -0,0 +1,110 @@
+use super::{DataSource, Dataset, Row, Rows};
+use crate::utils::standard_normal;
+
+use rand::RngCore;
+use std::borrow::Cow;
+
+impl Dataset {
+    /// Appends `copies` perturbed copies of every row to the dataset, where each input of a copy
+    /// has Gaussian noise with the given standard deviation added to it.
+    ///
+    /// This enlarges small datasets so that the network generalizes better, rather than
+    /// memorizing the exact inputs it was trained on. The targets are left intact. To perturb the
+    /// rows afresh every epoch instead, see [`Augmented`](struct.Augmented.html).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut dataset = scholar::Dataset::from(vec![
+    ///     (vec![0.0, 0.0], vec![0.0]),
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 0.0], vec![1.0]),
+    ///     (vec![1.0, 1.0], vec![0.0]),
+    /// ]);
+    ///
+    /// dataset.augment_noise(0.05, 9);
+    /// assert_eq!(dataset.rows(), 40);
+    /// ```
+    pub fn augment_noise(&mut self, std_dev: f64, copies: usize) {
+        let mut rng = rand::thread_rng();
+        let original = self.data.len();
+
+        self.data.reserve(original * copies);
+        for _ in 0..copies {
+            for index in 0..original {
+                let (inputs, targets) = &self.data[index];
+                let inputs = inputs
+                    .iter()
+                    .map(|x| x + std_dev * standard_normal(&mut rng))
+                    .collect();
+                let row = (inputs, targets.clone());
+                self.data.push(row);
+            }
+        }
+    }
+}
+
+/// A `DataSource` that transforms every row of another source as it is read, so that the rows
+/// are augmented afresh each epoch of training.
+///
+/// The augmentation is given the inputs and targets of a row, which it modifies in place.
+/// Randomized augmentations (such as adding noise or flipping images) thus produce a different
+/// version of the row every epoch, rather than the fixed copies of
+/// [`Dataset::augment_noise()`](struct.Dataset.html#method.augment_noise).
+///
+/// # Examples
+///
+/// ```rust
+/// use rand::Rng;
+/// use scholar::{Augmented, Dataset, NeuralNet, Sigmoid, Trainer};
+///
+/// let dataset = Dataset::from(vec![
+///     (vec![0.0, 0.0], vec![0.0]),
+///     (vec![0.0, 1.0], vec![1.0]),
+///     (vec![1.0, 0.0], vec![1.0]),
+///     (vec![1.0, 1.0], vec![0.0]),
+/// ]);
+///
+/// // Jitters every input by up to 0.05 in either direction
+/// let mut rng = rand::thread_rng();
+/// let mut source = Augmented::new(dataset, |inputs, _| {
+///     for x in inputs {
+///         *x += rng.gen_range(-0.05, 0.05);
+///     }
+/// });
+///
+/// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+/// Trainer::new(100, 0.1).train_from(&mut brain, &mut source).unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Augmented<S, F> {
+    source: S,
+    augment: F,
+}
+
+impl<S: DataSource, F: FnMut(&mut Vec<f64>, &mut Vec<f64>)> Augmented<S, F> {
+    /// Creates a new source that reads the rows of the given source, and transforms them with
+    /// the given augmentation.
+    pub fn new(source: S, augment: F) -> Self {
+        Self { source, augment }
+    }
+
+    /// Returns the source whose rows are augmented.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+/// Augments the rows of the underlying source each epoch.
+impl<S: DataSource, F: FnMut(&mut Vec<f64>, &mut Vec<f64>)> DataSource for Augmented<S, F> {
+    type Error = S::Error;
+
+    fn epoch(&mut self, rng: &mut dyn RngCore) -> Rows<'_, Self::Error> {
+        let augment = &mut self.augment;
+        Box::new(self.source.epoch(rng).map(move |row| {
+            let (mut inputs, mut targets): Row = row?.into_owned();
+            augment(&mut inputs, &mut targets);
+            Ok(Cow::Owned((inputs, targets)))
+        }))
+    }
+}
@@ -0,0 +1,125 @@
+use super::{Dataset, Row};
+
+use rand::seq::SliceRandom;
+
+impl Dataset {
+    /// Returns an iterator over the rows of the dataset in batches of the given size, for
+    /// mini-batch training or for evaluating large datasets a batch at a time.
+    ///
+    /// Rows are batched in order by default, and the last batch holds the remaining rows (so may
+    /// be smaller than the rest); see [`Batches::shuffle()`](struct.Batches.html#method.shuffle)
+    /// and [`Batches::drop_last()`](struct.Batches.html#method.drop_last) to change this.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let dataset = scholar::Dataset::from(vec![
+    ///     (vec![0.0, 0.0], vec![0.0]),
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 0.0], vec![1.0]),
+    ///     (vec![1.0, 1.0], vec![0.0]),
+    ///     (vec![0.5, 0.5], vec![1.0]),
+    /// ]);
+    ///
+    /// let sizes: Vec<usize> = dataset.batches(2).map(|batch| batch.len()).collect();
+    /// assert_eq!(sizes, vec![2, 2, 1]);
+    ///
+    /// let sizes: Vec<usize> = dataset.batches(2).shuffle().drop_last().map(|b| b.len()).collect();
+    /// assert_eq!(sizes, vec![2, 2]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the batch size is 0.
+    pub fn batches(&self, batch_size: usize) -> Batches<'_> {
+        if batch_size == 0 {
+            panic!("batch size must be positive (expected at least 1, found 0)");
+        }
+
+        Batches {
+            dataset: self,
+            order: (0..self.rows()).collect(),
+            batch_size,
+            drop_last: false,
+            position: 0,
+        }
+    }
+}
+
+/// An iterator over the rows of a `Dataset` in batches, created by
+/// [`Dataset::batches()`](struct.Dataset.html#method.batches).
+#[derive(Clone, Debug)]
+pub struct Batches<'a> {
+    dataset: &'a Dataset,
+    order: Vec<usize>,
+    batch_size: usize,
+    drop_last: bool,
+    position: usize,
+}
+
+impl<'a> Batches<'a> {
+    /// Shuffles the rows before they are batched, without modifying the dataset.
+    pub fn shuffle(mut self) -> Self {
+        self.order[self.position..].shuffle(&mut rand::thread_rng());
+        self
+    }
+
+    /// Skips the last batch if it holds fewer rows than the batch size.
+    pub fn drop_last(mut self) -> Self {
+        self.drop_last = true;
+        self
+    }
+}
+
+impl<'a> Iterator for Batches<'a> {
+    type Item = Batch<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let remaining = self.order.len() - self.position;
+        if remaining == 0 || (self.drop_last && remaining < self.batch_size) {
+            return None;
+        }
+
+        let end = self.position + remaining.min(self.batch_size);
+        let dataset = self.dataset;
+        let rows = self.order[self.position..end]
+            .iter()
+            .filter_map(|&index| dataset.get(index))
+            .collect();
+        self.position = end;
+
+        Some(Batch { rows })
+    }
+}
+
+/// A batch of rows borrowed from a `Dataset`.
+#[derive(Clone, Debug)]
+pub struct Batch<'a> {
+    rows: Vec<&'a Row>,
+}
+
+impl<'a> Batch<'a> {
+    /// Returns the number of rows in the batch.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns `true` if the batch holds no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Returns an iterator over the rows in the batch.
+    pub fn iter(&self) -> impl Iterator<Item = &'a Row> + '_ {
+        self.rows.iter().copied()
+    }
+}
+
+impl<'a, 'b> IntoIterator for &'b Batch<'a> {
+    type Item = &'a Row;
+    type IntoIter = std::iter::Copied<std::slice::Iter<'b, &'a Row>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.rows.iter().copied()
+    }
+}
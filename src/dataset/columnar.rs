@@ -0,0 +1,239 @@
+use super::{Dataset, Row};
+
+use std::{collections::HashMap, fs, path::Path};
+
+impl Dataset {
+    /// Parses a `Dataset` from a Parquet file, reading only the given input and target columns.
+    ///
+    /// Every numeric (or boolean) column type is supported, whilst null values are rejected.
+    ///
+    /// This requires the `parquet` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # fn main() -> Result<(), scholar::ParseColumnarError> {
+    /// // Only the three named columns are read from the file
+    /// let dataset =
+    ///     scholar::Dataset::from_parquet("houses.parquet", &["rooms", "area"], &["price"])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "parquet")]
+    pub fn from_parquet(
+        file_path: impl AsRef<Path>,
+        inputs: &[&str],
+        targets: &[&str],
+    ) -> Result<Self, ParseColumnarError> {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+        use parquet::record::Field;
+        use parquet::schema::types::Type;
+
+        let reader = SerializedFileReader::new(fs::File::open(file_path)?)?;
+        let schema = reader.metadata().file_metadata().schema();
+        let columns = projection(inputs, targets);
+
+        // Projects the schema onto the required columns, so that no others are materialized
+        let fields = columns
+            .iter()
+            .map(|&name| {
+                schema
+                    .get_fields()
+                    .iter()
+                    .find(|field| field.name() == name)
+                    .cloned()
+                    .ok_or_else(|| ParseColumnarError::UnknownColumn(name.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let projection = Type::group_type_builder(schema.name())
+            .with_fields(fields)
+            .build()?;
+
+        let mut data: Vec<Row> = Vec::new();
+        for row in reader.get_row_iter(Some(projection))? {
+            let row = row?;
+            let values = row
+                .get_column_iter()
+                .map(|(name, field)| {
+                    let value = match *field {
+                        Field::Bool(x) => Some(if x { 1.0 } else { 0.0 }),
+                        Field::Byte(x) => Some(x.into()),
+                        Field::Short(x) => Some(x.into()),
+                        Field::Int(x) => Some(x.into()),
+                        Field::Long(x) => Some(x as f64),
+                        Field::UByte(x) => Some(x.into()),
+                        Field::UShort(x) => Some(x.into()),
+                        Field::UInt(x) => Some(x.into()),
+                        Field::ULong(x) => Some(x as f64),
+                        Field::Float(x) => Some(x.into()),
+                        Field::Double(x) => Some(x),
+                        Field::Null => None,
+                        _ => return Err(ParseColumnarError::NotNumeric(name.clone())),
+                    };
+                    Ok((name.as_str(), value))
+                })
+                .collect::<Result<HashMap<_, _>, _>>()?;
+
+            data.push(select(&values, inputs, targets, data.len())?);
+        }
+
+        Ok(Dataset::from(data))
+    }
+
+    /// Parses a `Dataset` from an Arrow IPC file, reading only the given input and target
+    /// columns.
+    ///
+    /// Every numeric column type is supported, whilst null values are rejected.
+    ///
+    /// This requires the `arrow` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # fn main() -> Result<(), scholar::ParseColumnarError> {
+    /// let dataset =
+    ///     scholar::Dataset::from_arrow_ipc("houses.arrow", &["rooms", "area"], &["price"])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "arrow")]
+    pub fn from_arrow_ipc(
+        file_path: impl AsRef<Path>,
+        inputs: &[&str],
+        targets: &[&str],
+    ) -> Result<Self, ParseColumnarError> {
+        use arrow_ipc::reader::FileReader;
+
+        let file = fs::File::open(file_path)?;
+        let schema = FileReader::try_new(file.try_clone()?, None)?.schema();
+        let columns = projection(inputs, targets);
+        let indices = columns
+            .iter()
+            .map(|&name| {
+                schema
+                    .index_of(name)
+                    .map_err(|_| ParseColumnarError::UnknownColumn(name.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut data: Vec<Row> = Vec::new();
+        for batch in FileReader::try_new(file, Some(indices))? {
+            let batch = batch?;
+            let batch_columns = columns
+                .iter()
+                .zip(batch.columns())
+                .map(|(&name, array)| Ok((name, arrow_values(name, array.as_ref())?)))
+                .collect::<Result<Vec<_>, ParseColumnarError>>()?;
+
+            for i in 0..batch.num_rows() {
+                let values: HashMap<_, _> = batch_columns
+                    .iter()
+                    .map(|(name, values)| (*name, values[i]))
+                    .collect();
+                data.push(select(&values, inputs, targets, data.len())?);
+            }
+        }
+
+        Ok(Dataset::from(data))
+    }
+}
+
+/// Returns the distinct columns needed for the given inputs and targets.
+fn projection<'a>(inputs: &[&'a str], targets: &[&'a str]) -> Vec<&'a str> {
+    let mut columns: Vec<&str> = Vec::new();
+    for &column in inputs.iter().chain(targets) {
+        if !columns.contains(&column) {
+            columns.push(column);
+        }
+    }
+    columns
+}
+
+/// Selects the inputs and targets of the row with the given index from its column values.
+fn select(
+    values: &HashMap<&str, Option<f64>>,
+    inputs: &[&str],
+    targets: &[&str],
+    row: usize,
+) -> Result<Row, ParseColumnarError> {
+    let get =
+        |columns: &[&str]| {
+            columns
+                .iter()
+                .map(|&column| {
+                    values.get(column).copied().flatten().ok_or_else(|| {
+                        ParseColumnarError::Missing {
+                            row,
+                            column: column.to_string(),
+                        }
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+    Ok((get(inputs)?, get(targets)?))
+}
+
+/// Converts a numeric Arrow array into its values, with `None` marking a null value.
+#[cfg(feature = "arrow")]
+fn arrow_values(
+    name: &str,
+    array: &dyn arrow_array::Array,
+) -> Result<Vec<Option<f64>>, ParseColumnarError> {
+    use arrow_array::{cast::AsArray, types::*};
+
+    macro_rules! convert {
+        ($($t:ty),*) => {
+            $(
+                if let Some(array) = array.as_primitive_opt::<$t>() {
+                    return Ok(array.iter().map(|x| x.map(|x| x as f64)).collect());
+                }
+            )*
+        };
+    }
+    convert!(
+        Float64Type,
+        Float32Type,
+        Int8Type,
+        Int16Type,
+        Int32Type,
+        Int64Type,
+        UInt8Type,
+        UInt16Type,
+        UInt32Type,
+        UInt64Type
+    );
+
+    Err(ParseColumnarError::NotNumeric(name.to_string()))
+}
+
+/// An enumeration over the possible errors when parsing a `Dataset` from a columnar (Parquet or
+/// Arrow) file.
+#[derive(thiserror::Error, Debug)]
+pub enum ParseColumnarError {
+    /// When reading from a file fails.
+    #[error("failed to read file")]
+    Read(#[from] std::io::Error),
+    /// When parsing a Parquet file fails.
+    #[cfg(feature = "parquet")]
+    #[error("failed to parse Parquet")]
+    Parquet(#[from] parquet::errors::ParquetError),
+    /// When parsing an Arrow file fails.
+    #[cfg(feature = "arrow")]
+    #[error("failed to parse Arrow")]
+    Arrow(#[from] arrow_schema::ArrowError),
+    /// When a selected column doesn't exist.
+    #[error("unknown column '{0}'")]
+    UnknownColumn(String),
+    /// When a selected column doesn't hold numbers.
+    #[error("column '{0}' is not numeric")]
+    NotNumeric(String),
+    /// When a selected column holds a null value.
+    #[error("missing value in row {row} of column '{column}'")]
+    Missing {
+        /// The index of the row.
+        row: usize,
+        /// The name of the column.
+        column: String,
+    },
+}
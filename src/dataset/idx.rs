@@ -0,0 +1,162 @@
+use super::{Dataset, Row};
+
+use std::{
+    fs,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+/// The IDX type code of unsigned bytes, the only type used by the MNIST family of datasets.
+const UNSIGNED_BYTE: u8 = 0x08;
+
+impl Dataset {
+    /// Parses a `Dataset` from a pair of (uncompressed) IDX files, the format of the MNIST and
+    /// Fashion-MNIST datasets: one holding the images, and the other their class labels.
+    ///
+    /// Each image is flattened row by row into the inputs, with its pixels scaled from 0–255 to
+    /// 0–1, and each label is one-hot encoded into the targets (with as many classes as the
+    /// largest label plus one).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # fn main() -> Result<(), scholar::ParseIdxError> {
+    /// let dataset = scholar::Dataset::from_idx(
+    ///     "mnist/train-images-idx3-ubyte",
+    ///     "mnist/train-labels-idx1-ubyte",
+    /// )?;
+    ///
+    /// // 28x28 pixel images of the digits 0 to 9
+    /// assert_eq!(dataset.rows(), 60_000);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_idx(
+        images_path: impl AsRef<Path>,
+        labels_path: impl AsRef<Path>,
+    ) -> Result<Self, ParseIdxError> {
+        let (image_dims, pixels) = read_idx(images_path)?;
+        let (label_dims, labels) = read_idx(labels_path)?;
+
+        if image_dims.len() < 2 {
+            return Err(ParseIdxError::Dimensions {
+                expected: 2,
+                found: image_dims.len(),
+            });
+        }
+        if label_dims.len() != 1 {
+            return Err(ParseIdxError::Dimensions {
+                expected: 1,
+                found: label_dims.len(),
+            });
+        }
+        if image_dims[0] != label_dims[0] {
+            return Err(ParseIdxError::Count {
+                images: image_dims[0],
+                labels: label_dims[0],
+            });
+        }
+
+        let num_classes = labels.iter().max().map_or(0, |&max| usize::from(max) + 1);
+        let image_size = num_values(&image_dims[1..]).ok_or_else(|| ParseIdxError::Size {
+            dims: image_dims.clone(),
+            found: pixels.len() as u64,
+        })?;
+        let data: Vec<Row> = pixels
+            .chunks(image_size.max(1))
+            .zip(&labels)
+            .map(|(image, &label)| {
+                let inputs = image.iter().map(|&p| f64::from(p) / 255.0).collect();
+                let mut targets = vec![0.0; num_classes];
+                targets[usize::from(label)] = 1.0;
+                (inputs, targets)
+            })
+            .collect();
+
+        Ok(Dataset::from(data))
+    }
+}
+
+/// Reads the dimensions and (unsigned byte) values of an IDX file.
+fn read_idx(path: impl AsRef<Path>) -> Result<(Vec<usize>, Vec<u8>), ParseIdxError> {
+    let file = fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic)?;
+    if magic[..2] != [0, 0] {
+        return Err(ParseIdxError::Magic);
+    }
+    if magic[2] != UNSIGNED_BYTE {
+        return Err(ParseIdxError::Type(magic[2]));
+    }
+
+    let mut dims = Vec::with_capacity(usize::from(magic[3]));
+    for _ in 0..magic[3] {
+        let mut dim = [0; 4];
+        reader.read_exact(&mut dim)?;
+        dims.push(u32::from_be_bytes(dim) as usize);
+    }
+
+    // The dimensions are checked against the length of the file before allocating the values,
+    // so that a corrupt header can't exhaust the memory
+    let remaining = len.saturating_sub(4 + 4 * dims.len() as u64);
+    let count = match num_values(&dims) {
+        Some(count) if count as u64 <= remaining => count,
+        _ => {
+            return Err(ParseIdxError::Size {
+                dims,
+                found: remaining,
+            })
+        }
+    };
+
+    let mut values = vec![0; count];
+    reader.read_exact(&mut values)?;
+
+    Ok((dims, values))
+}
+
+/// Returns the number of values in an array of the given dimensions, or `None` if it overflows.
+fn num_values(dims: &[usize]) -> Option<usize> {
+    dims.iter().try_fold(1usize, |n, &dim| n.checked_mul(dim))
+}
+
+/// An enumeration over the possible errors when parsing a `Dataset` from IDX files.
+#[derive(thiserror::Error, Debug)]
+pub enum ParseIdxError {
+    /// When reading from a file fails.
+    #[error("failed to read file")]
+    Read(#[from] std::io::Error),
+    /// When a file doesn't start with the IDX magic number.
+    #[error("file is not in the IDX format")]
+    Magic,
+    /// When a file holds values of a type other than unsigned bytes.
+    #[error("unsupported IDX value type {0:#04x} (expected 0x08)")]
+    Type(u8),
+    /// When a file has the wrong number of dimensions.
+    #[error("incorrect number of dimensions (expected {expected}, found {found})")]
+    Dimensions {
+        /// The number of dimensions expected (at least, for images).
+        expected: usize,
+        /// The number of dimensions found.
+        found: usize,
+    },
+    /// When a file's dimensions describe more values than it holds.
+    #[error("dimensions {dims:?} describe more values than the file holds ({found})")]
+    Size {
+        /// The dimensions in the file's header.
+        dims: Vec<usize>,
+        /// The number of values the file holds.
+        found: u64,
+    },
+    /// When the numbers of images and labels differ.
+    #[error("found {images} images but {labels} labels")]
+    Count {
+        /// The number of images.
+        images: usize,
+        /// The number of labels.
+        labels: usize,
+    },
+}
@@ -0,0 +1,153 @@
+use super::{Dataset, Row};
+use crate::preprocessing::LabelEncoder;
+
+use image::{imageops::FilterType, ImageFormat};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+impl Dataset {
+    /// Loads a `Dataset` from a directory of images, with one subdirectory per class, returning
+    /// it alongside the `LabelEncoder` of the class (i.e. subdirectory) names:
+    ///
+    /// ```text
+    /// pets/
+    /// ├── cat/
+    /// │   ├── 001.png
+    /// │   └── 002.png
+    /// └── dog/
+    ///     └── 001.jpg
+    /// ```
+    ///
+    /// Each image is resized to the dimensions of the given `ImageOptions`, and its pixels are
+    /// flattened row by row into the inputs, scaled from 0–255 to 0–1. The targets are the
+    /// one-hot encoding of its class. PNG and JPEG files are read, whilst any other files are
+    /// skipped.
+    ///
+    /// This requires the `image` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use scholar::{Dataset, ImageOptions};
+    ///
+    /// # fn main() -> Result<(), scholar::ParseImageError> {
+    /// let (dataset, encoder) = Dataset::from_image_dir("pets", &ImageOptions::new(32, 32))?;
+    ///
+    /// // 32x32 RGB images of cats and dogs
+    /// assert_eq!(encoder.labels(), &["cat", "dog"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_image_dir(
+        dir_path: impl AsRef<Path>,
+        options: &ImageOptions,
+    ) -> Result<(Self, LabelEncoder), ParseImageError> {
+        let mut classes = Vec::new();
+        for entry in fs::read_dir(dir_path)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                classes.push((name, entry.path()));
+            }
+        }
+        classes.sort();
+
+        let encoder = LabelEncoder::fit(classes.iter().map(|(name, _)| name));
+        let mut data: Vec<Row> = Vec::new();
+        for (name, class_path) in &classes {
+            let targets = encoder
+                .one_hot(name)
+                .expect("encoder was fitted on every class");
+            for image_path in image_paths(class_path)? {
+                data.push((options.pixels(&image_path)?, targets.clone()));
+            }
+        }
+
+        Ok((Dataset::from(data), encoder))
+    }
+}
+
+/// Returns the paths of the readable images in the given directory, in alphabetical order.
+fn image_paths(dir_path: &Path) -> Result<Vec<PathBuf>, ParseImageError> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir_path)? {
+        let path = entry?.path();
+        let readable = ImageFormat::from_path(&path).is_ok_and(|f| f.reading_enabled());
+        if readable && path.is_file() {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// Settings for loading images with
+/// [`Dataset::from_image_dir()`](struct.Dataset.html#method.from_image_dir).
+///
+/// # Examples
+///
+/// ```rust
+/// // Images are resized to 28x28 and converted to grayscale, giving 784 inputs each
+/// let options = scholar::ImageOptions::new(28, 28).grayscale(true);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageOptions {
+    width: u32,
+    height: u32,
+    grayscale: bool,
+}
+
+impl ImageOptions {
+    /// Creates new options that resize images to the given width and height (in pixels), and
+    /// keep their red, green and blue channels.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            grayscale: false,
+        }
+    }
+
+    /// Sets whether images are converted to grayscale (giving one input per pixel, rather than
+    /// three).
+    pub fn grayscale(mut self, grayscale: bool) -> Self {
+        self.grayscale = grayscale;
+        self
+    }
+
+    /// Decodes, resizes and flattens the image at the given path into scaled pixel values.
+    fn pixels(&self, image_path: &Path) -> Result<Vec<f64>, ParseImageError> {
+        let image = image::open(image_path)
+            .map_err(|source| ParseImageError::Decode {
+                path: image_path.to_path_buf(),
+                source,
+            })?
+            .resize_exact(self.width, self.height, FilterType::Triangle);
+
+        let bytes = if self.grayscale {
+            image.to_luma8().into_raw()
+        } else {
+            image.to_rgb8().into_raw()
+        };
+        Ok(bytes.into_iter().map(|p| f64::from(p) / 255.0).collect())
+    }
+}
+
+/// An enumeration over the possible errors when loading a `Dataset` from a directory of images.
+#[derive(thiserror::Error, Debug)]
+pub enum ParseImageError {
+    /// When reading a directory fails.
+    #[error("failed to read directory")]
+    Read(#[from] std::io::Error),
+    /// When decoding an image fails.
+    #[error("failed to decode image '{}'", path.display())]
+    Decode {
+        /// The path of the image.
+        path: PathBuf,
+        /// The underlying error.
+        source: image::ImageError,
+    },
+}
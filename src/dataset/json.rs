@@ -0,0 +1,191 @@
+use super::{Dataset, Row};
+
+use serde_json::{Map, Value};
+use std::{
+    fs,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+/// A JSON object, as a record of a JSON (Lines) file.
+type Record = Map<String, Value>;
+
+impl Dataset {
+    /// Parses a `Dataset` from a JSON file containing an array of records of the form
+    /// `{ "inputs": [...], "targets": [...] }`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # fn main() -> Result<(), scholar::ParseJsonError> {
+    /// let dataset = scholar::Dataset::from_json("xor.json")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_json(file_path: impl AsRef<Path>) -> Result<Self, ParseJsonError> {
+        Self::from_json_with(file_path, &JsonFields::default())
+    }
+
+    /// Parses a `Dataset` from a JSON file containing an array of records, whose inputs and
+    /// targets are taken from the given `JsonFields`.
+    pub fn from_json_with(
+        file_path: impl AsRef<Path>,
+        fields: &JsonFields,
+    ) -> Result<Self, ParseJsonError> {
+        let file = fs::File::open(file_path)?;
+        let records: Vec<Record> = serde_json::from_reader(BufReader::new(file))?;
+
+        let data: Vec<Row> = records
+            .iter()
+            .enumerate()
+            .map(|(index, record)| fields.row(index, record))
+            .collect::<Result<_, _>>()?;
+        Ok(Dataset::from(data))
+    }
+
+    /// Parses a `Dataset` from a JSON Lines file, where each (non-blank) line is a record of the
+    /// form `{ "inputs": [...], "targets": [...] }`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # fn main() -> Result<(), scholar::ParseJsonError> {
+    /// let dataset = scholar::Dataset::from_jsonl("xor.jsonl")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_jsonl(file_path: impl AsRef<Path>) -> Result<Self, ParseJsonError> {
+        Self::from_jsonl_with(file_path, &JsonFields::default())
+    }
+
+    /// Parses a `Dataset` from a JSON Lines file, where each (non-blank) line is a record whose
+    /// inputs and targets are taken from the given `JsonFields`.
+    pub fn from_jsonl_with(
+        file_path: impl AsRef<Path>,
+        fields: &JsonFields,
+    ) -> Result<Self, ParseJsonError> {
+        let file = fs::File::open(file_path)?;
+
+        let mut data: Vec<Row> = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record: Record = serde_json::from_str(&line)?;
+            data.push(fields.row(data.len(), &record)?);
+        }
+        Ok(Dataset::from(data))
+    }
+}
+
+/// The fields of a JSON record that make up the inputs and targets of a row.
+///
+/// Each field holds either a number or an array of numbers, and the values of the fields are
+/// concatenated in the given order. By default, the inputs are taken from the `"inputs"` field
+/// and the targets from the `"targets"` field.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{Dataset, JsonFields};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let path = std::env::temp_dir().join("scholar_json_fields_example.jsonl");
+/// std::fs::write(
+///     &path,
+///     r#"{"id": "a7", "age": 31, "scores": [0.5, 0.25], "label": 1}
+/// {"id": "b3", "age": 58, "scores": [0.75, 1.0], "label": 0}"#,
+/// )?;
+///
+/// let fields = JsonFields::new(&["age", "scores"], &["label"]);
+/// let dataset = Dataset::from_jsonl_with(&path, &fields)?;
+///
+/// let (inputs, targets) = dataset.into_iter().next().unwrap();
+/// assert_eq!(inputs, &vec![31.0, 0.5, 0.25]);
+/// assert_eq!(targets, &vec![1.0]);
+/// # std::fs::remove_file(path)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonFields {
+    inputs: Vec<String>,
+    targets: Vec<String>,
+}
+
+impl JsonFields {
+    /// Creates a new mapping from the given input and target fields.
+    pub fn new(inputs: &[&str], targets: &[&str]) -> Self {
+        let to_strings = |fields: &[&str]| fields.iter().map(|f| f.to_string()).collect();
+        Self {
+            inputs: to_strings(inputs),
+            targets: to_strings(targets),
+        }
+    }
+
+    /// Extracts a row from the record with the given index.
+    fn row(&self, index: usize, record: &Record) -> Result<Row, ParseJsonError> {
+        let values = |fields: &[String]| -> Result<Vec<f64>, ParseJsonError> {
+            let mut values = Vec::new();
+            for field in fields {
+                let value = record
+                    .get(field)
+                    .ok_or_else(|| ParseJsonError::MissingField {
+                        record: index,
+                        field: field.clone(),
+                    })?;
+
+                let not_numeric = || ParseJsonError::NotNumeric {
+                    record: index,
+                    field: field.clone(),
+                };
+                match value {
+                    Value::Array(array) => {
+                        for value in array {
+                            values.push(value.as_f64().ok_or_else(not_numeric)?);
+                        }
+                    }
+                    value => values.push(value.as_f64().ok_or_else(not_numeric)?),
+                }
+            }
+            Ok(values)
+        };
+
+        Ok((values(&self.inputs)?, values(&self.targets)?))
+    }
+}
+
+impl Default for JsonFields {
+    fn default() -> Self {
+        Self::new(&["inputs"], &["targets"])
+    }
+}
+
+/// An enumeration over the possible errors when parsing a `Dataset` from JSON.
+#[derive(thiserror::Error, Debug)]
+pub enum ParseJsonError {
+    /// When reading from a file fails.
+    #[error("failed to read file")]
+    Read(#[from] std::io::Error),
+    /// When parsing the JSON fails.
+    #[error("failed to parse JSON")]
+    Parse(#[from] serde_json::Error),
+    /// When a record doesn't have one of the input or target fields.
+    #[error("record {record} is missing the field '{field}'")]
+    MissingField {
+        /// The index of the record.
+        record: usize,
+        /// The name of the missing field.
+        field: String,
+    },
+    /// When one of the input or target fields of a record isn't a number or array of numbers.
+    #[error("field '{field}' of record {record} is not numeric")]
+    NotNumeric {
+        /// The index of the record.
+        record: usize,
+        /// The name of the field.
+        field: String,
+    },
+}
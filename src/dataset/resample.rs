@@ -0,0 +1,247 @@
+use super::Dataset;
+use crate::utils::decode_class;
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+impl Dataset {
+    /// Balances the classes of the dataset by appending randomly chosen duplicates of the rows
+    /// of every class, until each class has as many rows as the largest.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{Dataset, TargetEncoding};
+    ///
+    /// let mut data = vec![(vec![0.0], vec![0.0]); 8];
+    /// data.extend(vec![(vec![1.0], vec![1.0]); 2]);
+    /// let mut dataset = Dataset::from(data);
+    ///
+    /// dataset.oversample(TargetEncoding::Binary { threshold: 0.5 });
+    /// assert_eq!(dataset.rows(), 16);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if any row's targets don't match the `encoding` (see
+    /// [`TargetEncoding`](enum.TargetEncoding.html)).
+    pub fn oversample(&mut self, encoding: TargetEncoding) {
+        let classes = self.classes(encoding);
+        let largest = classes.iter().map(Vec::len).max().unwrap_or(0);
+
+        let mut rng = rand::thread_rng();
+        for class in classes.iter().filter(|class| !class.is_empty()) {
+            for _ in class.len()..largest {
+                let index = *class.choose(&mut rng).expect("class has rows");
+                let row = self.data[index].clone();
+                self.data.push(row);
+            }
+        }
+    }
+
+    /// Balances the classes of the dataset with SMOTE (Synthetic Minority Oversampling
+    /// Technique), appending synthetic rows to every class until each has as many rows as the
+    /// largest.
+    ///
+    /// Each synthetic row lies at a random point on the line between the inputs of a randomly
+    /// chosen row of the class and one of its `neighbours` nearest rows (by Euclidean distance)
+    /// within the class, and shares the targets of the former. Unlike duplicating rows with
+    /// [`Dataset::oversample()`](#method.oversample), this fills in the region of the input
+    /// space occupied by a class, making the network less likely to overfit to its few rows.
+    ///
+    /// Finding the nearest rows takes time quadratic in the number of rows of each class.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{Dataset, TargetEncoding};
+    ///
+    /// let mut dataset = Dataset::from(vec![
+    ///     (vec![0.0, 0.0], vec![1.0, 0.0]),
+    ///     (vec![0.1, 0.0], vec![1.0, 0.0]),
+    ///     (vec![0.0, 0.1], vec![1.0, 0.0]),
+    ///     (vec![0.1, 0.1], vec![1.0, 0.0]),
+    ///     (vec![1.0, 1.0], vec![0.0, 1.0]),
+    ///     (vec![0.9, 1.0], vec![0.0, 1.0]),
+    /// ]);
+    ///
+    /// dataset.smote(TargetEncoding::OneHot, 5);
+    /// assert_eq!(dataset.rows(), 8);
+    ///
+    /// // The synthetic rows of the second class lie between its original rows
+    /// let (inputs, targets) = dataset.into_iter().last().unwrap();
+    /// assert_eq!(targets, &vec![0.0, 1.0]);
+    /// assert!(inputs[0] >= 0.9 && inputs[1] == 1.0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `neighbours` is 0, or if any row's targets don't match the
+    /// `encoding` (see [`TargetEncoding`](enum.TargetEncoding.html)).
+    pub fn smote(&mut self, encoding: TargetEncoding, neighbours: usize) {
+        if neighbours == 0 {
+            panic!("number of neighbours must be positive (expected at least 1, found 0)");
+        }
+
+        let classes = self.classes(encoding);
+        let largest = classes.iter().map(Vec::len).max().unwrap_or(0);
+
+        let mut rng = rand::thread_rng();
+        for class in classes.iter().filter(|class| !class.is_empty()) {
+            if class.len() == largest {
+                continue;
+            }
+
+            let nearest: Vec<Vec<usize>> = class
+                .iter()
+                .map(|&index| self.nearest(index, class, neighbours))
+                .collect();
+            for _ in class.len()..largest {
+                let position = rng.gen_range(0, class.len());
+                let (inputs, targets) = &self.data[class[position]];
+                let inputs = match nearest[position].choose(&mut rng) {
+                    Some(&neighbour) => {
+                        let gap: f64 = rng.gen();
+                        inputs
+                            .iter()
+                            .zip(&self.data[neighbour].0)
+                            .map(|(a, b)| a + gap * (b - a))
+                            .collect()
+                    }
+                    // A class with a single row has no neighbours to interpolate towards
+                    None => inputs.clone(),
+                };
+                let row = (inputs, targets.clone());
+                self.data.push(row);
+            }
+        }
+    }
+
+    /// Balances the classes of the dataset by removing randomly chosen rows from every class
+    /// with more than `ratio` times as many rows as the smallest class, until it has that many.
+    ///
+    /// A `ratio` of 1 leaves every class with the same number of rows. Unlike oversampling, this
+    /// shrinks the dataset, making it a cheap way of balancing very large datasets. The rows to
+    /// remove are chosen with the given seed, so that the same arguments always remove the same
+    /// rows, and the order of the remaining rows is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{Dataset, TargetEncoding};
+    ///
+    /// let mut data = vec![(vec![0.0], vec![0.0]); 100];
+    /// data.extend(vec![(vec![1.0], vec![1.0]); 10]);
+    /// let mut dataset = Dataset::from(data);
+    ///
+    /// // Keeps at most twice as many rows of the majority class as of the minority class
+    /// dataset.undersample(TargetEncoding::Binary { threshold: 0.5 }, 2.0, 42);
+    /// assert_eq!(dataset.rows(), 30);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `ratio` is less than 1, or if any row's targets don't match the
+    /// `encoding` (see [`TargetEncoding`](enum.TargetEncoding.html)).
+    pub fn undersample(&mut self, encoding: TargetEncoding, ratio: f64, seed: u64) {
+        if ratio < 1.0 {
+            panic!("undersampling ratio must be at least 1 (found {})", ratio);
+        }
+
+        let classes = self.classes(encoding);
+        let smallest = classes
+            .iter()
+            .map(Vec::len)
+            .filter(|&len| len > 0)
+            .min()
+            .unwrap_or(0);
+        let limit = (smallest as f64 * ratio).floor() as usize;
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut keep = vec![true; self.data.len()];
+        for mut class in classes {
+            if class.len() > limit {
+                class.shuffle(&mut rng);
+                for index in class.split_off(limit) {
+                    keep[index] = false;
+                }
+            }
+        }
+
+        let mut keep = keep.into_iter();
+        self.data.retain(|_| keep.next().unwrap_or(true));
+    }
+
+    /// Groups the row indices by class, where the index of each group is its class.
+    fn classes(&self, encoding: TargetEncoding) -> Vec<Vec<usize>> {
+        let mut classes: Vec<Vec<usize>> = Vec::new();
+        for (i, (_, targets)) in self.data.iter().enumerate() {
+            let class = encoding.class(targets);
+            if class >= classes.len() {
+                classes.resize(class + 1, Vec::new());
+            }
+            classes[class].push(i);
+        }
+
+        classes
+    }
+
+    /// Returns the indices of (at most) the `k` rows among `candidates` whose inputs are nearest
+    /// to those of the row at `index`, excluding the row itself.
+    fn nearest(&self, index: usize, candidates: &[usize], k: usize) -> Vec<usize> {
+        let inputs = &self.data[index].0;
+        let mut distances: Vec<(f64, usize)> = candidates
+            .iter()
+            .filter(|&&candidate| candidate != index)
+            .map(|&candidate| {
+                let distance = inputs
+                    .iter()
+                    .zip(&self.data[candidate].0)
+                    .map(|(a, b)| (a - b).powi(2))
+                    .sum();
+                (distance, candidate)
+            })
+            .collect();
+        distances.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        distances.into_iter().take(k).map(|(_, i)| i).collect()
+    }
+}
+
+/// How the class of a row is encoded in its targets, for resampling a `Dataset` by class.
+///
+/// Resampling panics if a row has a different number of targets to its encoding: exactly one
+/// with `Binary`, or at least two with `OneHot`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TargetEncoding {
+    /// A single target value, which is class 1 if it is at least the given threshold and class
+    /// 0 otherwise.
+    Binary {
+        /// The smallest target value of class 1.
+        threshold: f64,
+    },
+    /// One target value per class, the largest of which is the class.
+    OneHot,
+}
+
+impl TargetEncoding {
+    /// Decodes the class of the given targets.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if there isn't exactly one target with `Binary`, or if there are fewer
+    /// than two with `OneHot` (which would put every row in the same class).
+    fn class(self, targets: &[f64]) -> usize {
+        match self {
+            TargetEncoding::Binary { .. } if targets.len() != 1 => panic!(
+                "incorrect number of targets for binary classes (expected 1, found {})",
+                targets.len()
+            ),
+            TargetEncoding::Binary { threshold } => (targets[0] >= threshold) as usize,
+            TargetEncoding::OneHot if targets.len() < 2 => panic!(
+                "incorrect number of targets for one-hot classes (expected at least 2, found {})",
+                targets.len()
+            ),
+            TargetEncoding::OneHot => decode_class(targets, 0.5),
+        }
+    }
+}
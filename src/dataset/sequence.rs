@@ -0,0 +1,273 @@
+use super::Row;
+#[cfg(feature = "fs")]
+use super::{Column, CsvOptions, Dataset, ParseCsvError};
+
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "fs")]
+use std::{collections::HashMap, path::Path};
+use std::{iter::FromIterator, ops::Index, slice::SliceIndex};
+
+/// A collection of input sequences, each of any number of steps, matched with their expected
+/// output, for training recurrent models (see
+/// [`Trainer::train_sequences()`](struct.Trainer.html#method.train_sequences)).
+///
+/// Every step of every sequence has the same number of values. Each row's inputs are stored
+/// flat, as the values of each step in turn, which is how a
+/// [`Sequential`](layers/struct.Sequential.html) model takes them.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::SequenceDataset;
+///
+/// // Two sequences of (temperature, humidity) readings
+/// let dataset = SequenceDataset::from(vec![
+///     (vec![vec![0.2, 0.5], vec![0.3, 0.4], vec![0.5, 0.4]], vec![1.0]),
+///     (vec![vec![0.6, 0.8]], vec![0.0]),
+/// ]);
+///
+/// assert_eq!(dataset.step_len(), 2);
+/// assert_eq!(dataset.steps(0), 3);
+/// assert_eq!(dataset[1].0, vec![0.6, 0.8]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SequenceDataset {
+    step_len: usize,
+    data: Vec<Row>,
+}
+
+impl SequenceDataset {
+    /// Parses a `SequenceDataset` from a CSV file with a row per step, whose sequences are
+    /// identified by the (not necessarily numeric) IDs in the given `group` column.
+    ///
+    /// The input and target columns are selected by the given `CsvOptions` (leaving out the
+    /// group column), as when parsing a [`Dataset`](struct.Dataset.html). Each group's steps are
+    /// in the order of its rows, which needn't be next to each other, and its targets are those
+    /// of its last row. Sequences are in the order that their groups first appear in.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{CsvOptions, SequenceDataset};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let path = std::env::temp_dir().join("scholar_sequence_csv_example.csv");
+    /// std::fs::write(
+    ///     &path,
+    ///     "sensor,temperature,humidity,failed\n\
+    ///      a,0.2,0.5,0\n\
+    ///      b,0.6,0.8,0\n\
+    ///      a,0.3,0.4,0\n\
+    ///      a,0.5,0.4,1\n",
+    /// )?;
+    ///
+    /// let options = CsvOptions::new(2).headers(true);
+    /// let dataset = SequenceDataset::from_csv(&path, "sensor", &options)?;
+    /// assert_eq!(dataset.rows(), 2);
+    /// assert_eq!(dataset[0], (vec![0.2, 0.5, 0.3, 0.4, 0.5, 0.4], vec![1.0]));
+    /// assert_eq!(dataset.steps(1), 1);
+    /// # std::fs::remove_file(path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn from_csv(
+        file_path: impl AsRef<Path>,
+        group: impl Into<Column>,
+        options: &CsvOptions,
+    ) -> Result<Self, ParseCsvError> {
+        let group = group.into();
+        let mut options = options.clone();
+        options.ignored.push(group.clone());
+        let (rows, groups) = Dataset::parse_csv(file_path, &options, Some(&group))?;
+
+        let mut indices = HashMap::new();
+        let mut sequences: Vec<(Vec<Vec<f64>>, Vec<f64>)> = Vec::new();
+        for ((inputs, targets), group) in rows.into_iter().zip(groups) {
+            let index = *indices.entry(group).or_insert_with(|| {
+                sequences.push((Vec::new(), Vec::new()));
+                sequences.len() - 1
+            });
+            let (steps, last_targets) = &mut sequences[index];
+            steps.push(inputs);
+            *last_targets = targets;
+        }
+
+        Ok(sequences.into_iter().collect())
+    }
+
+    /// Shuffles the order of the rows in the dataset with the given random number generator, so
+    /// that a seeded generator always gives the same order.
+    pub fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.data.shuffle(rng);
+    }
+
+    /// Returns the number of values in each step of every sequence, which is 0 if the dataset is
+    /// empty.
+    pub fn step_len(&self) -> usize {
+        self.step_len
+    }
+
+    /// Returns the number of steps in the sequence of the row at the given index.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the index is out of bounds.
+    pub fn steps(&self, index: usize) -> usize {
+        self.data[index].0.len() / self.step_len
+    }
+
+    /// Returns the number of steps in the longest sequence, which is 0 if the dataset is empty.
+    pub fn max_steps(&self) -> usize {
+        (0..self.rows()).map(|i| self.steps(i)).max().unwrap_or(0)
+    }
+
+    /// Pads the start of every sequence with fewer than the given number of steps with steps of
+    /// the given value, and cuts the earliest steps off every sequence with more, so that every
+    /// sequence has exactly that many steps.
+    ///
+    /// This is what models of a fixed number of steps need (whose inputs have the shape
+    /// `[steps, values]`). Padding the start rather than the end keeps the final hidden state of
+    /// a recurrent layer closer to what it would be without padding.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::SequenceDataset;
+    ///
+    /// let mut dataset = SequenceDataset::from(vec![
+    ///     (vec![vec![1.0], vec![2.0], vec![3.0]], vec![1.0]),
+    ///     (vec![vec![4.0]], vec![0.0]),
+    /// ]);
+    ///
+    /// dataset.pad_to(2, 0.0);
+    /// assert_eq!(dataset[0].0, vec![2.0, 3.0]);
+    /// assert_eq!(dataset[1].0, vec![0.0, 4.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of steps is 0.
+    pub fn pad_to(&mut self, steps: usize, value: f64) {
+        if steps == 0 {
+            panic!("sequences must have at least one step (found 0)");
+        }
+
+        let len = steps * self.step_len;
+        for (inputs, _) in &mut self.data {
+            if inputs.len() > len {
+                inputs.drain(..inputs.len() - len);
+            } else {
+                inputs.splice(..0, vec![value; len - inputs.len()]);
+            }
+        }
+    }
+
+    /// Splits the dataset into buckets of (at most) the given number of rows, each of whose
+    /// sequences have similar numbers of steps, from the shortest to the longest.
+    ///
+    /// Padding each bucket to its longest sequence (see [`pad_to()`](#method.pad_to)) adds far
+    /// fewer steps than padding the whole dataset to its longest.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::SequenceDataset;
+    ///
+    /// let dataset: SequenceDataset = [5, 1, 4, 2, 3]
+    ///     .iter()
+    ///     .map(|&steps| (vec![vec![0.0]; steps], vec![0.0]))
+    ///     .collect();
+    ///
+    /// let buckets = dataset.into_buckets(2);
+    /// let steps: Vec<_> = buckets.iter().map(|bucket| bucket.max_steps()).collect();
+    /// assert_eq!(steps, vec![2, 4, 5]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of rows is 0.
+    pub fn into_buckets(mut self, rows: usize) -> Vec<Self> {
+        if rows == 0 {
+            panic!("buckets must have at least one row (found 0)");
+        }
+
+        let step_len = self.step_len;
+        self.data.sort_by_key(|(inputs, _)| inputs.len());
+        self.data
+            .chunks(rows)
+            .map(|data| Self {
+                step_len,
+                data: data.to_vec(),
+            })
+            .collect()
+    }
+
+    /// Returns the number of rows in the dataset.
+    pub fn rows(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the number of rows in the dataset, like
+    /// [`SequenceDataset::rows()`](#method.rows).
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the dataset has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the rows of the dataset as a slice, with each sequence stored flat.
+    pub fn as_slice(&self) -> &[Row] {
+        &self.data
+    }
+}
+
+/// Flattens the sequence of every row.
+///
+/// # Panics
+///
+/// This panics if a sequence has no steps (or values), or its steps don't all have the same number
+/// of values as every other's.
+impl From<Vec<(Vec<Vec<f64>>, Vec<f64>)>> for SequenceDataset {
+    fn from(data: Vec<(Vec<Vec<f64>>, Vec<f64>)>) -> Self {
+        data.into_iter().collect()
+    }
+}
+
+impl FromIterator<(Vec<Vec<f64>>, Vec<f64>)> for SequenceDataset {
+    fn from_iter<I: IntoIterator<Item = (Vec<Vec<f64>>, Vec<f64>)>>(rows: I) -> Self {
+        let mut dataset = Self::default();
+        for (steps, targets) in rows {
+            let step_len = steps.first().map_or(0, Vec::len);
+            if step_len == 0 {
+                panic!("sequences must have at least one step, of at least one value");
+            }
+            if dataset.data.is_empty() {
+                dataset.step_len = step_len;
+            }
+
+            if let Some(step) = steps.iter().find(|step| step.len() != dataset.step_len) {
+                panic!(
+                    "every step must have the same number of values (expected {}, found {})",
+                    dataset.step_len,
+                    step.len()
+                );
+            }
+            dataset.data.push((steps.concat(), targets));
+        }
+
+        dataset
+    }
+}
+
+impl<I: SliceIndex<[Row]>> Index<I> for SequenceDataset {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &Self::Output {
+        &self.data[index]
+    }
+}
@@ -0,0 +1,92 @@
+use super::Dataset;
+use crate::network::SparseVector;
+
+use rand::{seq::SliceRandom, Rng};
+use std::{iter::FromIterator, ops::Index, slice::SliceIndex};
+
+/// A collection of sparse input vectors matched with their expected output, for training on
+/// high-dimensional inputs that are mostly zeros (see
+/// [`Trainer::train_sparse()`](struct.Trainer.html#method.train_sparse)).
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{Dataset, SparseDataset, SparseVector};
+///
+/// let dataset = SparseDataset::from(vec![
+///     (SparseVector::new(50_000, vec![(7, 1.0), (4_012, 2.0)]), vec![1.0]),
+///     (SparseVector::new(50_000, vec![(12, 1.0)]), vec![0.0]),
+/// ]);
+/// assert_eq!(dataset.rows(), 2);
+///
+/// // Dense datasets can be converted too, dropping their zeros
+/// let dense = Dataset::from(vec![(vec![0.0, 3.0, 0.0], vec![1.0])]);
+/// let sparse = SparseDataset::from(dense);
+/// assert_eq!(sparse[0].0.nnz(), 1);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SparseDataset {
+    data: Vec<(SparseVector, Vec<f64>)>,
+}
+
+impl SparseDataset {
+    /// Shuffles the order of the rows in the dataset with the given random number generator, so
+    /// that a seeded generator always gives the same order.
+    pub fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.data.shuffle(rng);
+    }
+
+    /// Returns the number of rows in the dataset.
+    pub fn rows(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the number of rows in the dataset, like
+    /// [`SparseDataset::rows()`](#method.rows).
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the dataset has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the rows of the dataset as a slice.
+    pub fn as_slice(&self) -> &[(SparseVector, Vec<f64>)] {
+        &self.data
+    }
+}
+
+impl From<Vec<(SparseVector, Vec<f64>)>> for SparseDataset {
+    fn from(data: Vec<(SparseVector, Vec<f64>)>) -> Self {
+        Self { data }
+    }
+}
+
+/// Converts the inputs of every row of a dense `Dataset` into sparse vectors.
+impl From<Dataset> for SparseDataset {
+    fn from(dataset: Dataset) -> Self {
+        dataset
+            .data
+            .into_iter()
+            .map(|(inputs, targets)| (SparseVector::from_dense(&inputs), targets))
+            .collect()
+    }
+}
+
+impl FromIterator<(SparseVector, Vec<f64>)> for SparseDataset {
+    fn from_iter<I: IntoIterator<Item = (SparseVector, Vec<f64>)>>(rows: I) -> Self {
+        Self {
+            data: rows.into_iter().collect(),
+        }
+    }
+}
+
+impl<I: SliceIndex<[(SparseVector, Vec<f64>)]>> Index<I> for SparseDataset {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &Self::Output {
+        &self.data[index]
+    }
+}
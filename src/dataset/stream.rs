@@ -0,0 +1,213 @@
+#[cfg(feature = "fs")]
+use super::{CsvOptions, Layout, MissingValues, ParseCsvError};
+use super::{Dataset, Row};
+
+use rand::RngCore;
+#[cfg(feature = "fs")]
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{borrow::Cow, convert::Infallible};
+#[cfg(feature = "fs")]
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The rows of a single pass over a `DataSource`.
+pub type Rows<'a, E> = Box<dyn Iterator<Item = Result<Cow<'a, Row>, E>> + 'a>;
+
+/// A source of rows that can be trained on, with
+/// [`Trainer::train_from()`](struct.Trainer.html#method.train_from).
+///
+/// A `Dataset` holds every row in memory, whereas a `CsvStream` reads and parses its rows from
+/// a file on the fly, so that datasets larger than memory can be trained on.
+pub trait DataSource {
+    /// The error that can occur when reading a row.
+    type Error;
+
+    /// Returns an iterator over every row, in the order that they should be trained on in the
+    /// next epoch.
+    ///
+    /// Any randomness in the order (or the rows themselves) should come from the given random
+    /// number generator, so that training with a seeded generator can be reproduced exactly.
+    fn epoch(&mut self, rng: &mut dyn RngCore) -> Rows<'_, Self::Error>;
+
+    /// Returns every row as a `Dataset` if they're all held in memory, so that metrics can be
+    /// calculated on them between epochs (see
+    /// [`Trainer::metric()`](struct.Trainer.html#method.metric)). Returns `None` by default.
+    fn as_dataset(&self) -> Option<&Dataset> {
+        None
+    }
+}
+
+/// Shuffles the rows of the dataset each epoch.
+impl DataSource for Dataset {
+    type Error = Infallible;
+
+    fn epoch(&mut self, rng: &mut dyn RngCore) -> Rows<'_, Self::Error> {
+        self.shuffle_with(rng);
+        Box::new(self.data.iter().map(|row| Ok(Cow::Borrowed(row))))
+    }
+
+    fn as_dataset(&self) -> Option<&Dataset> {
+        Some(self)
+    }
+}
+
+/// A `DataSource` that reads and parses the rows of a CSV file on the fly, rather than loading
+/// them all into memory.
+///
+/// Since the file is read from start to finish each epoch, the rows can be shuffled
+/// approximately with a shuffle buffer of a given size: the larger the buffer, the better the
+/// shuffle, but the more memory it uses.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use scholar::{CsvOptions, CsvStream, NeuralNet, Sigmoid, Trainer};
+///
+/// # fn main() -> Result<(), scholar::ParseCsvError> {
+/// let mut stream = CsvStream::new("huge.csv", CsvOptions::new(4)).shuffle_buffer(10_000);
+///
+/// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[4, 10, 10, 3]);
+/// Trainer::new(10, 0.01).train_from(&mut brain, &mut stream)?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "fs")]
+#[derive(Clone, Debug)]
+pub struct CsvStream {
+    path: PathBuf,
+    options: CsvOptions,
+    shuffle_buffer: Option<usize>,
+}
+
+#[cfg(feature = "fs")]
+impl CsvStream {
+    /// Creates a new stream over the CSV file at the given path, which is parsed with the given
+    /// `CsvOptions`. Rows are streamed in the order they appear in the file.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the options impute missing values with their column's mean or
+    /// median, since these can't be calculated without reading the whole file.
+    pub fn new(file_path: impl AsRef<Path>, options: CsvOptions) -> Self {
+        if let MissingValues::Mean | MissingValues::Median = options.missing_values {
+            panic!("missing values can't be imputed with a column statistic when streaming");
+        }
+
+        Self {
+            path: file_path.as_ref().to_path_buf(),
+            options,
+            shuffle_buffer: None,
+        }
+    }
+
+    /// Shuffles the rows approximately, using a buffer holding the given number of rows.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `size` is 0.
+    pub fn shuffle_buffer(mut self, size: usize) -> Self {
+        if size == 0 {
+            panic!("shuffle buffer must hold at least 1 row");
+        }
+
+        self.shuffle_buffer = Some(size);
+        self
+    }
+
+    /// Opens the file and returns an iterator over its rows, in order.
+    fn rows(&self) -> Result<Rows<'_, ParseCsvError>, ParseCsvError> {
+        let file = fs::File::open(&self.path)?;
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(self.options.includes_headers)
+            .from_reader(file);
+        let headers = if self.options.includes_headers {
+            Some(reader.headers()?.clone())
+        } else {
+            None
+        };
+
+        let options = self.options.clone();
+        let mut layout: Option<Layout> = None;
+        let rows = reader.into_records().filter_map(move |row| {
+            let parse = |row: csv::Result<csv::StringRecord>, layout: &mut Option<Layout>| {
+                let row = row?;
+                let layout = match layout {
+                    Some(layout) => layout,
+                    None => layout.get_or_insert(options.layout(headers.as_ref(), row.len())?),
+                };
+
+                let values = options.parse_record(&row, layout)?;
+                let fill = match options.missing_values {
+                    MissingValues::Constant(value) => value,
+                    // Rows with missing values are skipped
+                    _ if values.iter().any(Option::is_none) => return Ok(None),
+                    _ => f64::NAN,
+                };
+
+                let mut inputs: Vec<f64> = values.into_iter().map(|v| v.unwrap_or(fill)).collect();
+                let targets = inputs.split_off(layout.0.len());
+                Ok(Some(Cow::Owned((inputs, targets))))
+            };
+
+            parse(row, &mut layout).transpose()
+        });
+
+        Ok(Box::new(rows))
+    }
+}
+
+#[cfg(feature = "fs")]
+impl DataSource for CsvStream {
+    type Error = ParseCsvError;
+
+    fn epoch(&mut self, rng: &mut dyn RngCore) -> Rows<'_, Self::Error> {
+        let rows = match self.rows() {
+            Ok(rows) => rows,
+            Err(error) => return Box::new(std::iter::once(Err(error))),
+        };
+
+        match self.shuffle_buffer {
+            Some(size) => Box::new(ShuffleBuffer {
+                rows,
+                buffer: Vec::with_capacity(size),
+                size,
+                // Seeding from the given generator keeps the shuffle reproducible
+                rng: StdRng::seed_from_u64(rng.next_u64()),
+            }),
+            None => rows,
+        }
+    }
+}
+
+/// An iterator that shuffles rows approximately by filling a buffer with them, and then
+/// repeatedly yielding a random row from the buffer and replacing it with the next one.
+#[cfg(feature = "fs")]
+struct ShuffleBuffer<'a, E> {
+    rows: Rows<'a, E>,
+    buffer: Vec<Cow<'a, Row>>,
+    size: usize,
+    rng: StdRng,
+}
+
+#[cfg(feature = "fs")]
+impl<'a, E> Iterator for ShuffleBuffer<'a, E> {
+    type Item = Result<Cow<'a, Row>, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.buffer.len() < self.size {
+            match self.rows.next() {
+                Some(Ok(row)) => self.buffer.push(row),
+                Some(Err(error)) => return Some(Err(error)),
+                None => break,
+            }
+        }
+
+        if self.buffer.is_empty() {
+            return None;
+        }
+        let index = self.rng.gen_range(0, self.buffer.len());
+        Some(Ok(self.buffer.swap_remove(index)))
+    }
+}
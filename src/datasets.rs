@@ -0,0 +1,197 @@
+//! Generators of small synthetic datasets, for examples, tests and benchmarks.
+//!
+//! Each generator takes the number of rows to generate, the standard deviation of the Gaussian
+//! noise added to the inputs, and a seed, so that the same arguments always give the same
+//! dataset. The rows of each class are interleaved, so that the classes are balanced.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scholar::{datasets, NeuralNet, Sigmoid};
+//!
+//! let dataset = datasets::moons(200, 0.1, 42);
+//! let (training_data, _) = dataset.split(0.75);
+//!
+//! let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 8, 1]);
+//! brain.train(training_data, 100, 0.1);
+//! ```
+
+use crate::dataset::Dataset;
+use crate::utils::standard_normal;
+
+use rand::{rngs::StdRng, SeedableRng};
+use std::f64::consts::PI;
+
+/// Generates the XOR problem: points scattered around the corners of the unit square, whose
+/// target is 1 if exactly one of their (unperturbed) inputs is 1.
+///
+/// # Examples
+///
+/// ```rust
+/// // Without noise, the rows repeat the XOR truth table
+/// let dataset = scholar::datasets::xor(4, 0.0, 0);
+///
+/// let rows: Vec<_> = dataset.into_iter().cloned().collect();
+/// assert_eq!(rows[1], (vec![0.0, 1.0], vec![1.0]));
+/// assert_eq!(rows[3], (vec![1.0, 1.0], vec![0.0]));
+/// ```
+pub fn xor(rows: usize, noise: f64, seed: u64) -> Dataset {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    (0..rows)
+        .map(|i| {
+            let (a, b) = ((i / 2) % 2, i % 2);
+            let inputs = vec![
+                a as f64 + noise * standard_normal(&mut rng),
+                b as f64 + noise * standard_normal(&mut rng),
+            ];
+            (inputs, vec![(a ^ b) as f64])
+        })
+        .collect()
+}
+
+/// Generates two interleaving half circles, with targets of 0 for the upper moon and 1 for the
+/// lower moon.
+///
+/// # Examples
+///
+/// ```rust
+/// let dataset = scholar::datasets::moons(100, 0.1, 0);
+/// assert_eq!(dataset.rows(), 100);
+/// ```
+pub fn moons(rows: usize, noise: f64, seed: u64) -> Dataset {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let per_class = rows.div_ceil(2).max(2) - 1;
+
+    (0..rows)
+        .map(|i| {
+            let class = i % 2;
+            let angle = PI * (i / 2) as f64 / per_class as f64;
+            let (x, y) = if class == 0 {
+                (angle.cos(), angle.sin())
+            } else {
+                (1.0 - angle.cos(), 0.5 - angle.sin())
+            };
+
+            // Centres the moons on the origin
+            let inputs = vec![
+                x - 0.5 + noise * standard_normal(&mut rng),
+                y - 0.25 + noise * standard_normal(&mut rng),
+            ];
+            (inputs, vec![class as f64])
+        })
+        .collect()
+}
+
+/// Generates two concentric circles, with targets of 0 for the outer circle (of radius 1) and 1
+/// for the inner circle (of radius `factor`).
+///
+/// # Examples
+///
+/// ```rust
+/// let dataset = scholar::datasets::circles(100, 0.5, 0.05, 0);
+/// assert_eq!(dataset.rows(), 100);
+/// ```
+///
+/// # Panics
+///
+/// This function panics if `factor` isn't between 0 and 1 (exclusive).
+pub fn circles(rows: usize, factor: f64, noise: f64, seed: u64) -> Dataset {
+    if factor <= 0.0 || factor >= 1.0 {
+        panic!("factor must be between 0 and 1 (found {})", factor);
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let per_class = rows.div_ceil(2).max(1);
+
+    (0..rows)
+        .map(|i| {
+            let class = i % 2;
+            let angle = 2.0 * PI * (i / 2) as f64 / per_class as f64;
+            let radius = if class == 0 { 1.0 } else { factor };
+
+            let inputs = vec![
+                radius * angle.cos() + noise * standard_normal(&mut rng),
+                radius * angle.sin() + noise * standard_normal(&mut rng),
+            ];
+            (inputs, vec![class as f64])
+        })
+        .collect()
+}
+
+/// Generates two intertwined spirals, each making one and a half turns outwards from the origin,
+/// with targets of 0 for one spiral and 1 for the other.
+///
+/// # Examples
+///
+/// ```rust
+/// let dataset = scholar::datasets::spirals(100, 0.02, 0);
+/// assert_eq!(dataset.rows(), 100);
+/// ```
+pub fn spirals(rows: usize, noise: f64, seed: u64) -> Dataset {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let per_class = rows.div_ceil(2).max(1);
+
+    (0..rows)
+        .map(|i| {
+            let class = i % 2;
+            let radius = (i / 2 + 1) as f64 / per_class as f64;
+            let angle = 3.0 * PI * radius + PI * class as f64;
+
+            let inputs = vec![
+                radius * angle.cos() + noise * standard_normal(&mut rng),
+                radius * angle.sin() + noise * standard_normal(&mut rng),
+            ];
+            (inputs, vec![class as f64])
+        })
+        .collect()
+}
+
+/// Generates isotropic Gaussian blobs around the given centres (which may have any number of
+/// dimensions), with the one-hot encoding of the blob as each row's targets.
+///
+/// # Examples
+///
+/// ```rust
+/// let dataset = scholar::datasets::blobs(90, &[&[0.0, 0.0], &[1.0, 1.0], &[-1.0, 1.0]], 0.2, 0);
+///
+/// let (inputs, targets) = dataset.into_iter().nth(2).unwrap();
+/// assert_eq!(inputs.len(), 2);
+/// assert_eq!(targets, &vec![0.0, 0.0, 1.0]);
+/// ```
+///
+/// # Panics
+///
+/// This function panics if no centres are given, or if the centres have differing numbers of
+/// dimensions.
+pub fn blobs(rows: usize, centres: &[&[f64]], std_dev: f64, seed: u64) -> Dataset {
+    let dimensions = match centres.first() {
+        Some(centre) => centre.len(),
+        None => panic!("at least one centre must be given"),
+    };
+    for centre in centres {
+        if centre.len() != dimensions {
+            panic!(
+                "incorrect number of centre dimensions supplied (expected {}, found {})",
+                dimensions,
+                centre.len()
+            );
+        }
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    (0..rows)
+        .map(|i| {
+            let class = i % centres.len();
+            let inputs = centres[class]
+                .iter()
+                .map(|x| x + std_dev * standard_normal(&mut rng))
+                .collect();
+
+            let mut targets = vec![0.0; centres.len()];
+            targets[class] = 1.0;
+            (inputs, targets)
+        })
+        .collect()
+}
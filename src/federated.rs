@@ -0,0 +1,86 @@
+//! Federated training, for learning from datasets that are kept separate (e.g. by different
+//! organisations) without pooling them together.
+//!
+//! In each round of federated averaging, a copy of the global network is trained locally on each
+//! dataset (or 'shard'), and the trained copies are merged into a new global network with
+//! [`NeuralNet::weighted_average()`](../struct.NeuralNet.html#method.weighted_average), weighted
+//! by the number of rows in their shard.
+
+use crate::dataset::Dataset;
+use crate::network::{Activation, Float, NeuralNet};
+use crate::trainer::Trainer;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Performs a single round of federated averaging, returning the new global network.
+///
+/// Each copy of the `global` network is trained on its shard with the given `Trainer`, whose
+/// iterations are the number of local epochs per round.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{federated, Dataset, NeuralNet, Sigmoid, Trainer};
+///
+/// let shards = vec![
+///     Dataset::from(vec![(vec![0.0, 0.0], vec![0.0]), (vec![0.0, 1.0], vec![1.0])]),
+///     Dataset::from(vec![(vec![1.0, 0.0], vec![1.0]), (vec![1.0, 1.0], vec![0.0])]),
+/// ];
+///
+/// let global: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+/// let global = federated::train_round(&global, &shards, &Trainer::new(10, 0.1));
+/// ```
+///
+/// # Panics
+///
+/// This function panics if no shards are given.
+pub fn train_round<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    global: &NeuralNet<A, T>,
+    shards: &[Dataset],
+    trainer: &Trainer,
+) -> NeuralNet<A, T> {
+    let networks: Vec<_> = shards
+        .iter()
+        .map(|shard| {
+            let mut network = global.clone();
+            trainer.train(&mut network, shard.clone());
+            network
+        })
+        .collect();
+    let weights: Vec<_> = shards.iter().map(|shard| shard.rows() as f64).collect();
+
+    NeuralNet::weighted_average(&networks, &weights)
+}
+
+/// Trains the `global` network with the given number of rounds of federated averaging (see
+/// [`train_round()`](fn.train_round.html)).
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{federated, Dataset, NeuralNet, Sigmoid, Trainer};
+///
+/// let shards = vec![
+///     Dataset::from(vec![(vec![0.0, 0.0], vec![0.0]), (vec![0.0, 1.0], vec![1.0])]),
+///     Dataset::from(vec![(vec![1.0, 0.0], vec![1.0]), (vec![1.0, 1.0], vec![0.0])]),
+/// ];
+///
+/// let mut global: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+///
+/// // 20 rounds of 5 local epochs each
+/// federated::train(&mut global, &shards, 20, &Trainer::new(5, 0.1));
+/// ```
+///
+/// # Panics
+///
+/// This function panics if no shards are given.
+pub fn train<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    global: &mut NeuralNet<A, T>,
+    shards: &[Dataset],
+    rounds: u64,
+    trainer: &Trainer,
+) {
+    for _ in 0..rounds {
+        *global = train_round(global, shards, trainer);
+    }
+}
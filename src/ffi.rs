@@ -0,0 +1,273 @@
+//! A C interface for loading saved networks and running them on raw buffers of floats, so that
+//! services written in C, C++, Go (with cgo) and other languages can embed scholar models.
+//!
+//! This requires the `ffi` feature. Build the crate as a C library with
+//! `cargo rustc --release --features ffi --crate-type cdylib` (or `--crate-type staticlib`), and
+//! declare its functions with the `include/scholar.h` header.
+//!
+//! Only networks with the [`Sigmoid`](../struct.Sigmoid.html) activation can be loaded, with
+//! either `f64` or `f32` precision; the interface takes and returns `double`s either way. When a
+//! function fails, it returns `NULL` (or `-1`), and
+//! [`scholar_last_error()`](fn.scholar_last_error.html) describes what went wrong.
+//!
+//! # Examples
+//!
+//! The functions can be called from Rust too, which is how the following (C-like) example
+//! checks them:
+//!
+//! ```rust
+//! use scholar::{ffi::*, NeuralNet, Sigmoid};
+//! use std::ffi::CString;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let path = std::env::temp_dir().join("scholar_ffi_example.network");
+//! let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 3, 1]);
+//! brain.save(&path)?;
+//!
+//! let c_path = CString::new(path.to_str().unwrap())?;
+//! unsafe {
+//!     let net = scholar_net_load(c_path.as_ptr());
+//!     assert!(!net.is_null());
+//!     assert_eq!(scholar_net_inputs(net), 2);
+//!     assert_eq!(scholar_net_outputs(net), 1);
+//!
+//!     // Guesses the outputs of two rows at once
+//!     let inputs = [1.0, 0.0, 0.0, 1.0];
+//!     let mut outputs = [0.0; 2];
+//!     assert_eq!(scholar_net_guess(net, inputs.as_ptr(), 2, outputs.as_mut_ptr()), 0);
+//!     assert_eq!(outputs[1], brain.guess(&[0.0, 1.0])[0]);
+//!
+//!     scholar_net_free(net);
+//! }
+//! # std::fs::remove_file(path)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::network::{LoadErr, ModelFormat, NeuralNet, Sigmoid};
+
+use std::{
+    cell::RefCell,
+    error::Error,
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_int},
+    ptr, slice,
+};
+
+thread_local! {
+    /// The description of the last error on this thread.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// A network loaded through the C interface, which C code only handles by pointer.
+pub struct ScholarNet {
+    network: Network,
+}
+
+/// A network of either supported precision.
+enum Network {
+    F64(NeuralNet<Sigmoid, f64>),
+    F32(NeuralNet<Sigmoid, f32>),
+}
+
+impl Network {
+    /// Loads a network with the given function, trying `f32` precision if the network doesn't
+    /// have `f64` precision.
+    fn load(
+        load_f64: impl FnOnce() -> Result<NeuralNet<Sigmoid, f64>, LoadErr>,
+        load_f32: impl FnOnce() -> Result<NeuralNet<Sigmoid, f32>, LoadErr>,
+    ) -> Result<Self, LoadErr> {
+        match load_f64() {
+            Ok(network) => Ok(Network::F64(network)),
+            Err(LoadErr::Precision { .. }) => load_f32().map(Network::F32),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn layer_sizes(&self) -> Vec<usize> {
+        match self {
+            Network::F64(network) => network.layer_sizes(),
+            Network::F32(network) => network.layer_sizes(),
+        }
+    }
+
+    fn guess(&self, inputs: &[f64]) -> Vec<f64> {
+        match self {
+            Network::F64(network) => network.guess(inputs),
+            Network::F32(network) => network.guess(inputs),
+        }
+    }
+}
+
+/// Loads a network from a file saved by
+/// [`NeuralNet::save()`](../struct.NeuralNet.html#method.save), whose format is chosen from its
+/// extension.
+///
+/// Returns `NULL` if the network can't be loaded. Otherwise, the network must be freed with
+/// [`scholar_net_free()`](fn.scholar_net_free.html).
+///
+/// # Safety
+///
+/// `path` must be a valid pointer to a null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn scholar_net_load(path: *const c_char) -> *mut ScholarNet {
+    if path.is_null() {
+        return fail("path is null");
+    }
+    let path = match CStr::from_ptr(path).to_str() {
+        Ok(path) => path,
+        Err(_) => return fail("path isn't valid UTF-8"),
+    };
+
+    let network = Network::load(|| NeuralNet::from_file(path), || NeuralNet::from_file(path));
+    into_raw(network)
+}
+
+/// Loads a network from bytes in the bincode format (as saved by
+/// [`NeuralNet::save()`](../struct.NeuralNet.html#method.save) to a file with any extension
+/// besides `.msgpack`, `.mpk` and `.cbor`), for services that store their models elsewhere.
+///
+/// Returns `NULL` if the network can't be loaded. Otherwise, the network must be freed with
+/// [`scholar_net_free()`](fn.scholar_net_free.html).
+///
+/// # Safety
+///
+/// `bytes` must be a valid pointer to `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn scholar_net_from_bytes(bytes: *const u8, len: usize) -> *mut ScholarNet {
+    if bytes.is_null() {
+        return fail("bytes are null");
+    }
+    let bytes = slice::from_raw_parts(bytes, len);
+
+    let network = Network::load(
+        || NeuralNet::from_bytes(bytes, ModelFormat::Bincode),
+        || NeuralNet::from_bytes(bytes, ModelFormat::Bincode),
+    );
+    into_raw(network)
+}
+
+/// Frees a network loaded by [`scholar_net_load()`](fn.scholar_net_load.html) or
+/// [`scholar_net_from_bytes()`](fn.scholar_net_from_bytes.html). Freeing `NULL` does nothing.
+///
+/// # Safety
+///
+/// `net` must be `NULL` or a network that hasn't already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn scholar_net_free(net: *mut ScholarNet) {
+    if !net.is_null() {
+        drop(Box::from_raw(net));
+    }
+}
+
+/// Returns the number of nodes in the network's input layer, or 0 if `net` is `NULL`.
+///
+/// # Safety
+///
+/// `net` must be `NULL` or a valid network.
+#[no_mangle]
+pub unsafe extern "C" fn scholar_net_inputs(net: *const ScholarNet) -> usize {
+    match net.as_ref() {
+        Some(net) => net.network.layer_sizes()[0],
+        None => 0,
+    }
+}
+
+/// Returns the number of nodes in the network's output layer, or 0 if `net` is `NULL`.
+///
+/// # Safety
+///
+/// `net` must be `NULL` or a valid network.
+#[no_mangle]
+pub unsafe extern "C" fn scholar_net_outputs(net: *const ScholarNet) -> usize {
+    match net.as_ref() {
+        Some(net) => net.network.layer_sizes().last().copied().unwrap_or(0),
+        None => 0,
+    }
+}
+
+/// Performs the feedforward algorithm on each of the given number of rows, writing the values of
+/// the output layer for each row into `outputs`.
+///
+/// `inputs` holds the rows' input values one row after another, and `outputs` receives the
+/// output values in the same way. Returns 0 on success, or -1 if any pointer is `NULL`.
+///
+/// The network isn't modified, so it can guess on many threads at once.
+///
+/// # Safety
+///
+/// `net` must be a valid network, `inputs` must point to `rows * scholar_net_inputs(net)`
+/// values, and `outputs` must point to space for `rows * scholar_net_outputs(net)` values.
+#[no_mangle]
+pub unsafe extern "C" fn scholar_net_guess(
+    net: *const ScholarNet,
+    inputs: *const f64,
+    rows: usize,
+    outputs: *mut f64,
+) -> c_int {
+    let net = match net.as_ref() {
+        Some(net) => net,
+        None => {
+            set_last_error("network is null");
+            return -1;
+        }
+    };
+    if inputs.is_null() || outputs.is_null() {
+        set_last_error("inputs or outputs are null");
+        return -1;
+    }
+
+    let sizes = net.network.layer_sizes();
+    let (num_inputs, num_outputs) = (sizes[0], sizes[sizes.len() - 1]);
+    let inputs = slice::from_raw_parts(inputs, rows * num_inputs);
+    let outputs = slice::from_raw_parts_mut(outputs, rows * num_outputs);
+    for (row, output) in inputs
+        .chunks_exact(num_inputs)
+        .zip(outputs.chunks_exact_mut(num_outputs))
+    {
+        output.copy_from_slice(&net.network.guess(row));
+    }
+
+    0
+}
+
+/// Returns a description of the last error on the calling thread, or `NULL` if there hasn't
+/// been one.
+///
+/// The string remains valid until the next failing call on the same thread, and mustn't be freed.
+#[no_mangle]
+pub extern "C" fn scholar_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| match &*last.borrow() {
+        Some(message) => message.as_ptr(),
+        None => ptr::null(),
+    })
+}
+
+/// Boxes a loaded network for C, or records the error and returns `NULL`.
+fn into_raw(network: Result<Network, LoadErr>) -> *mut ScholarNet {
+    match network {
+        Ok(network) => Box::into_raw(Box::new(ScholarNet { network })),
+        Err(err) => {
+            // Includes the underlying errors, such as the reason a file couldn't be read
+            let mut message = err.to_string();
+            let mut source = err.source();
+            while let Some(err) = source {
+                message += &format!(": {}", err);
+                source = err.source();
+            }
+            fail(&message)
+        }
+    }
+}
+
+/// Records the error and returns `NULL`.
+fn fail(message: &str) -> *mut ScholarNet {
+    set_last_error(message);
+    ptr::null_mut()
+}
+
+fn set_last_error(message: &str) {
+    // Interior null bytes would truncate the message, so they are removed
+    let message = CString::new(message.replace('\0', "")).expect("null bytes were removed");
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
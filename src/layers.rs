@@ -0,0 +1,1015 @@
+//! Models built from layers of different kinds, such as the convolutional layers of image and
+//! signal models, rather than only the fully-connected layers of a
+//! [`NeuralNet`](../struct.NeuralNet.html).
+//!
+//! A [`Sequential`](struct.Sequential.html) model feeds its inputs through each of its layers in
+//! turn, applying its activation to the outputs of every layer with weights. Layers are added
+//! with builder methods, which work out the shape of each layer's inputs from the layers before
+//! it, so only the shape of the model's inputs needs to be given.
+//!
+//! Multi-dimensional inputs and outputs are stored as flat slices, in row-major order: a 2D
+//! image of several channels holds each channel in turn, each of which holds each row of pixels
+//! in turn.
+//!
+//! Recurrent layers read sequences one step at a time, whether of a fixed number of steps (with
+//! the shape `[steps, values]`) or of any number, in a model created with
+//! [`Sequential::sequences()`](struct.Sequential.html#method.sequences). Sequences are stored
+//! step by step.
+//!
+//! Layers of kinds defined outside the crate implement [`CustomLayer`](trait.CustomLayer.html),
+//! and are added with [`Sequential::custom()`](struct.Sequential.html#method.custom).
+//!
+//! Models with several inputs or outputs, or whose layers branch and merge, are built as a
+//! [`Graph`](struct.Graph.html) of nodes instead, each of which holds layers added with the same
+//! builder methods.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scholar::layers::{ConvOptions, Sequential};
+//! use scholar::{Dataset, Sigmoid, Trainer};
+//!
+//! // Whether a 6x6 image has a vertical or a horizontal line through it
+//! let line = |vertical: bool, offset: usize| {
+//!     let pixels = (0..36)
+//!         .map(|i| if (if vertical { i % 6 } else { i / 6 }) == offset { 1.0 } else { 0.0 })
+//!         .collect();
+//!     (pixels, vec![vertical as u8 as f64])
+//! };
+//! let dataset: Dataset = (0..6).flat_map(|i| vec![line(true, i), line(false, i)]).collect();
+//!
+//! let mut model: Sequential<Sigmoid> = Sequential::new(&[1, 6, 6])
+//!     .conv2d(4, (3, 3))
+//!     .conv2d_with(4, (3, 3), ConvOptions::new().stride(2).padding(1))
+//!     .dense(1);
+//! assert_eq!(model.layers()[1].output_shape(), &[4, 2, 2]);
+//!
+//! Trainer::new(500, 0.5).train_sequential(&mut model, dataset);
+//! assert!(model.guess(&line(true, 2).0)[0] > 0.5);
+//! assert!(model.guess(&line(false, 4).0)[0] < 0.5);
+//! ```
+
+mod attention;
+mod conv;
+mod custom;
+mod dense;
+mod graph;
+mod pool;
+mod recurrent;
+mod residual;
+
+pub use attention::*;
+pub use conv::*;
+pub use custom::*;
+pub use dense::*;
+pub use graph::*;
+pub use pool::*;
+pub use recurrent::*;
+pub use residual::*;
+
+use crate::network::{Activation, Float};
+use crate::utils::convert_slice_to_matrix;
+
+use nalgebra::DMatrix;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{fmt, marker::PhantomData};
+
+/// A layer of a [`Sequential`](struct.Sequential.html) model, along with the shape of its
+/// outputs.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Layer<T: Float = f64> {
+    kind: LayerKind<T>,
+    output_shape: Vec<usize>,
+}
+
+/// The kind of a [`Layer`](struct.Layer.html), which holds its parameters.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub enum LayerKind<T: Float = f64> {
+    /// A fully-connected layer, which flattens its inputs (see
+    /// [`Sequential::dense()`](struct.Sequential.html#method.dense)).
+    Dense(Dense<T>),
+    /// A 1D convolutional layer (see
+    /// [`Sequential::conv1d()`](struct.Sequential.html#method.conv1d)).
+    Conv1d(Conv<T>),
+    /// A 2D convolutional layer (see
+    /// [`Sequential::conv2d()`](struct.Sequential.html#method.conv2d)).
+    Conv2d(Conv<T>),
+    /// A 1D max pooling layer (see
+    /// [`Sequential::max_pool1d()`](struct.Sequential.html#method.max_pool1d)).
+    MaxPool1d(Pool),
+    /// A 2D max pooling layer (see
+    /// [`Sequential::max_pool2d()`](struct.Sequential.html#method.max_pool2d)).
+    MaxPool2d(Pool),
+    /// A 1D average pooling layer (see
+    /// [`Sequential::avg_pool1d()`](struct.Sequential.html#method.avg_pool1d)).
+    AvgPool1d(Pool),
+    /// A 2D average pooling layer (see
+    /// [`Sequential::avg_pool2d()`](struct.Sequential.html#method.avg_pool2d)).
+    AvgPool2d(Pool),
+    /// A simple recurrent layer (see [`Sequential::rnn()`](struct.Sequential.html#method.rnn)).
+    Rnn(Rnn<T>),
+    /// A long short-term memory layer (see
+    /// [`Sequential::lstm()`](struct.Sequential.html#method.lstm)).
+    Lstm(Lstm<T>),
+    /// A gated recurrent unit layer (see
+    /// [`Sequential::gru()`](struct.Sequential.html#method.gru)).
+    Gru(Gru<T>),
+    /// A multi-head self-attention layer (see
+    /// [`Sequential::self_attention()`](struct.Sequential.html#method.self_attention)).
+    SelfAttention(SelfAttention<T>),
+    /// A sinusoidal positional encoding (see
+    /// [`Sequential::positional_encoding()`](struct.Sequential.html#method.positional_encoding)).
+    PositionalEncoding(PositionalEncoding),
+    /// A block of layers with a skip connection around it (see
+    /// [`Sequential::residual()`](struct.Sequential.html#method.residual)).
+    Residual(Residual<T>),
+    /// A layer of a kind defined outside the crate (see
+    /// [`Sequential::custom()`](struct.Sequential.html#method.custom)).
+    Custom(Custom),
+}
+
+impl<T: Float> Layer<T> {
+    /// Returns the kind of the layer, which holds its parameters.
+    pub fn kind(&self) -> &LayerKind<T> {
+        &self.kind
+    }
+
+    /// Returns the shape of the layer's outputs.
+    pub fn output_shape(&self) -> &[usize] {
+        &self.output_shape
+    }
+
+    /// Returns whether the model's activation is applied to the layer's outputs.
+    fn is_activated(&self) -> bool {
+        match self.kind {
+            LayerKind::Dense(_) | LayerKind::Conv1d(_) | LayerKind::Conv2d(_) => true,
+            LayerKind::MaxPool1d(_)
+            | LayerKind::MaxPool2d(_)
+            | LayerKind::AvgPool1d(_)
+            | LayerKind::AvgPool2d(_)
+            | LayerKind::Rnn(_)
+            | LayerKind::Lstm(_)
+            | LayerKind::Gru(_)
+            | LayerKind::SelfAttention(_)
+            | LayerKind::PositionalEncoding(_)
+            | LayerKind::Residual(_)
+            | LayerKind::Custom(_) => false,
+        }
+    }
+
+    /// Returns whether the layer outputs a sequence of any number of steps.
+    fn returns_sequences(&self) -> bool {
+        match &self.kind {
+            LayerKind::Rnn(rnn) => rnn.returns_sequences(),
+            LayerKind::Lstm(lstm) => lstm.returns_sequences(),
+            LayerKind::Gru(gru) => gru.returns_sequences(),
+            LayerKind::SelfAttention(_) | LayerKind::PositionalEncoding(_) => true,
+            LayerKind::Residual(residual) => residual.returns_sequences(),
+            _ => false,
+        }
+    }
+
+    /// Returns every matrix of the layer's parameters (besides those of custom layers).
+    fn parameters_mut(&mut self) -> Vec<&mut DMatrix<T>> {
+        match &mut self.kind {
+            LayerKind::Dense(dense) => dense.parameters_mut(),
+            LayerKind::Conv1d(conv) | LayerKind::Conv2d(conv) => conv.parameters_mut(),
+            LayerKind::Rnn(rnn) => rnn.parameters_mut(),
+            LayerKind::Lstm(lstm) => lstm.parameters_mut(),
+            LayerKind::Gru(gru) => gru.parameters_mut(),
+            LayerKind::SelfAttention(attention) => attention.parameters_mut(),
+            LayerKind::Residual(residual) => residual.parameters_mut(),
+            LayerKind::MaxPool1d(_)
+            | LayerKind::MaxPool2d(_)
+            | LayerKind::AvgPool1d(_)
+            | LayerKind::AvgPool2d(_)
+            | LayerKind::PositionalEncoding(_)
+            | LayerKind::Custom(_) => Vec::new(),
+        }
+    }
+
+    /// Calculates the layer's outputs (before they are activated) from its inputs.
+    fn forward<A: Activation>(&self, inputs: &DMatrix<T>) -> DMatrix<T> {
+        match &self.kind {
+            LayerKind::Dense(dense) => dense.forward(inputs),
+            LayerKind::Conv1d(conv) | LayerKind::Conv2d(conv) => conv.forward(inputs),
+            LayerKind::MaxPool1d(pool) | LayerKind::MaxPool2d(pool) => pool.forward_max(inputs),
+            LayerKind::AvgPool1d(pool) | LayerKind::AvgPool2d(pool) => pool.forward_average(inputs),
+            LayerKind::Rnn(rnn) => rnn.forward(inputs),
+            LayerKind::Lstm(lstm) => lstm.forward(inputs),
+            LayerKind::Gru(gru) => gru.forward(inputs),
+            LayerKind::SelfAttention(attention) => attention.forward(inputs),
+            LayerKind::PositionalEncoding(encoding) => encoding.forward(inputs),
+            LayerKind::Residual(residual) => residual.forward::<A>(inputs),
+            LayerKind::Custom(custom) => custom.forward(inputs),
+        }
+    }
+
+    /// Updates the layer's parameters given its inputs and the gradients of the cost with
+    /// respect to its outputs (before they were activated), returning the gradients with respect
+    /// to its inputs.
+    fn backward<A: Activation>(
+        &mut self,
+        inputs: &DMatrix<T>,
+        gradients: &DMatrix<T>,
+        learning_rate: T,
+    ) -> DMatrix<T> {
+        match &mut self.kind {
+            LayerKind::Dense(dense) => dense.backward(inputs, gradients, learning_rate),
+            LayerKind::Conv1d(conv) | LayerKind::Conv2d(conv) => {
+                conv.backward(inputs, gradients, learning_rate)
+            }
+            LayerKind::MaxPool1d(pool) | LayerKind::MaxPool2d(pool) => {
+                pool.backward_max(inputs, gradients)
+            }
+            LayerKind::AvgPool1d(pool) | LayerKind::AvgPool2d(pool) => {
+                pool.backward_average(inputs, gradients)
+            }
+            LayerKind::Rnn(rnn) => rnn.backward(inputs, gradients, learning_rate),
+            LayerKind::Lstm(lstm) => lstm.backward(inputs, gradients, learning_rate),
+            LayerKind::Gru(gru) => gru.backward(inputs, gradients, learning_rate),
+            LayerKind::SelfAttention(attention) => {
+                attention.backward(inputs, gradients, learning_rate)
+            }
+            LayerKind::PositionalEncoding(_) => gradients.clone(),
+            LayerKind::Residual(residual) => {
+                residual.backward::<A>(inputs, gradients, learning_rate)
+            }
+            LayerKind::Custom(custom) => custom.backward(inputs, gradients, learning_rate),
+        }
+    }
+}
+
+/// A model that feeds its inputs through each of its layers in turn (see the
+/// [module documentation](index.html)).
+///
+/// Like a [`NeuralNet`](../struct.NeuralNet.html), its calculations use the `Float` type `T`,
+/// and it is trained to minimise the squared error of its outputs (see
+/// [`Trainer::train_sequential()`](../struct.Trainer.html#method.train_sequential)).
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Sequential<A: Activation, T: Float = f64> {
+    input_shape: Vec<usize>,
+    /// Whether the inputs are sequences of any number of steps, each of the input shape.
+    #[serde(default)]
+    sequences: bool,
+    layers: Vec<Layer<T>>,
+    activation: PhantomData<fn() -> A>,
+}
+
+impl<A: Activation + Serialize + DeserializeOwned, T: Float> Sequential<A, T> {
+    /// Creates a model without any layers, which takes inputs of the given shape.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the shape is empty or has a dimension of size 0.
+    pub fn new(input_shape: &[usize]) -> Self {
+        if input_shape.is_empty() || input_shape.contains(&0) {
+            panic!("invalid input shape {:?}", input_shape);
+        }
+
+        Self {
+            input_shape: input_shape.to_vec(),
+            sequences: false,
+            layers: Vec::new(),
+            activation: PhantomData,
+        }
+    }
+
+    /// Creates a model without any layers, which takes sequences of any number of steps, each of
+    /// the given number of values.
+    ///
+    /// Until a recurrent layer reduces them to its final hidden state, the model's outputs are
+    /// sequences too, whose shapes are those of each step, so only recurrent layers can be added
+    /// to them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::layers::{RecurrentOptions, Sequential};
+    /// use scholar::{SequenceDataset, Sigmoid, Trainer};
+    ///
+    /// // Whether a sequence of bits (of any length) has an odd number of ones
+    /// let dataset: SequenceDataset = (1..=6_usize)
+    ///     .flat_map(|len| (0..1 << len).map(move |bits: usize| (len, bits)))
+    ///     .map(|(len, bits)| {
+    ///         let steps = (0..len).map(|i| vec![(bits >> i & 1) as f64]).collect();
+    ///         (steps, vec![(bits.count_ones() % 2) as f64])
+    ///     })
+    ///     .collect();
+    ///
+    /// let mut model: Sequential<Sigmoid> = Sequential::sequences(1)
+    ///     .rnn_with(8, RecurrentOptions::new().return_sequences(true))
+    ///     .rnn(8)
+    ///     .dense(1);
+    /// assert_eq!(model.output_shape(), &[1]);
+    ///
+    /// Trainer::new(10, 0.1).train_sequences(&mut model, dataset);
+    /// assert_eq!(model.guess(&[1.0, 0.0, 1.0, 1.0]).len(), 1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the number of values is 0.
+    pub fn sequences(num_inputs: usize) -> Self {
+        let mut model = Self::new(&[num_inputs]);
+        model.sequences = true;
+        model
+    }
+
+    /// Adds a fully-connected layer with the given number of nodes, which flattens its inputs.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the model's outputs are still sequences.
+    pub fn dense(mut self, nodes: usize) -> Self {
+        self.check_fixed("dense layers");
+        let num_inputs = self.output_shape().iter().product();
+        self.layers.push(Layer {
+            kind: LayerKind::Dense(Dense::new(num_inputs, nodes)),
+            output_shape: vec![nodes],
+        });
+        self
+    }
+
+    /// Adds a 1D convolutional layer with the given number of filters and size of kernel, which
+    /// moves one value at a time over inputs without padding.
+    ///
+    /// The inputs must have the shape `[channels, length]`, or `[length]` for a single channel.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the inputs have another shape, or the kernel is larger than them.
+    pub fn conv1d(self, filters: usize, kernel_size: usize) -> Self {
+        self.conv1d_with(filters, kernel_size, ConvOptions::new())
+    }
+
+    /// Adds a 1D convolutional layer like [`conv1d()`](#method.conv1d), with the given stride
+    /// and padding.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the inputs have the wrong shape, or the kernel is larger than the
+    /// padded inputs.
+    pub fn conv1d_with(mut self, filters: usize, kernel_size: usize, options: ConvOptions) -> Self {
+        let (channels, length) = self.shape_1d("convolutions");
+
+        let window = Window {
+            channels,
+            height: 1,
+            width: length,
+            kernel: (1, kernel_size),
+            stride: (1, options.stride),
+            padding: (0, options.padding),
+        };
+        let conv = Conv::new(window, filters);
+        let (filters, _, length) = conv.output_shape();
+        self.layers.push(Layer {
+            kind: LayerKind::Conv1d(conv),
+            output_shape: vec![filters, length],
+        });
+        self
+    }
+
+    /// Adds a 2D convolutional layer with the given number of filters and kernel size (its
+    /// height and width), which moves one value at a time over inputs without padding.
+    ///
+    /// The inputs must have the shape `[channels, height, width]`, or `[height, width]` for a
+    /// single channel.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the inputs have another shape, or the kernel is larger than them.
+    pub fn conv2d(self, filters: usize, kernel_size: (usize, usize)) -> Self {
+        self.conv2d_with(filters, kernel_size, ConvOptions::new())
+    }
+
+    /// Adds a 2D convolutional layer like [`conv2d()`](#method.conv2d), with the given stride
+    /// and padding (which are the same in both dimensions).
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the inputs have the wrong shape, or the kernel is larger than the
+    /// padded inputs.
+    pub fn conv2d_with(
+        mut self,
+        filters: usize,
+        kernel_size: (usize, usize),
+        options: ConvOptions,
+    ) -> Self {
+        let (channels, height, width) = self.shape_2d("convolutions");
+        let window = Window {
+            channels,
+            height,
+            width,
+            kernel: kernel_size,
+            stride: (options.stride, options.stride),
+            padding: (options.padding, options.padding),
+        };
+        let conv = Conv::new(window, filters);
+        let (filters, height, width) = conv.output_shape();
+        self.layers.push(Layer {
+            kind: LayerKind::Conv2d(conv),
+            output_shape: vec![filters, height, width],
+        });
+        self
+    }
+
+    /// Adds a 1D max pooling layer, which reduces each channel of its inputs to the maximum of
+    /// each non-overlapping window of the given size.
+    ///
+    /// The inputs must have the shape `[channels, length]`, or `[length]` for a single channel.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the inputs have another shape, or the window is larger than them.
+    pub fn max_pool1d(self, size: usize) -> Self {
+        self.pool1d(size, LayerKind::MaxPool1d)
+    }
+
+    /// Adds a 2D max pooling layer, which reduces each channel of its inputs to the maximum of
+    /// each non-overlapping window of the given size (its height and width).
+    ///
+    /// The inputs must have the shape `[channels, height, width]`, or `[height, width]` for a
+    /// single channel.
+    ///
+    /// # Examples
+    ///
+    /// A LeNet-5 model for 28x28 images of handwritten digits:
+    ///
+    /// ```rust
+    /// use scholar::layers::{ConvOptions, Sequential};
+    /// use scholar::Sigmoid;
+    ///
+    /// let model: Sequential<Sigmoid> = Sequential::new(&[1, 28, 28])
+    ///     .conv2d_with(6, (5, 5), ConvOptions::new().padding(2))
+    ///     .max_pool2d((2, 2))
+    ///     .conv2d(16, (5, 5))
+    ///     .max_pool2d((2, 2))
+    ///     .dense(120)
+    ///     .dense(84)
+    ///     .dense(10);
+    ///
+    /// let shapes: Vec<_> = model.layers().iter().map(|layer| layer.output_shape()).collect();
+    /// assert_eq!(shapes[1], &[6, 14, 14]);
+    /// assert_eq!(shapes[3], &[16, 5, 5]);
+    /// assert_eq!(model.guess(&[0.0; 28 * 28]).len(), 10);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the inputs have another shape, or the window is larger than them.
+    pub fn max_pool2d(self, size: (usize, usize)) -> Self {
+        self.pool2d(size, LayerKind::MaxPool2d)
+    }
+
+    /// Adds a 1D average pooling layer, which reduces each channel of its inputs to the average
+    /// of each non-overlapping window of the given size.
+    ///
+    /// The inputs must have the shape `[channels, length]`, or `[length]` for a single channel.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the inputs have another shape, or the window is larger than them.
+    pub fn avg_pool1d(self, size: usize) -> Self {
+        self.pool1d(size, LayerKind::AvgPool1d)
+    }
+
+    /// Adds a 2D average pooling layer, which reduces each channel of its inputs to the average
+    /// of each non-overlapping window of the given size (its height and width).
+    ///
+    /// The inputs must have the shape `[channels, height, width]`, or `[height, width]` for a
+    /// single channel.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the inputs have another shape, or the window is larger than them.
+    pub fn avg_pool2d(self, size: (usize, usize)) -> Self {
+        self.pool2d(size, LayerKind::AvgPool2d)
+    }
+
+    /// Adds a simple (Elman) recurrent layer with the given number of hidden nodes, which
+    /// outputs its final hidden state (see [`Rnn`](struct.Rnn.html)).
+    ///
+    /// The inputs must be sequences (see [`sequences()`](#method.sequences)), or have the shape
+    /// `[steps, values]` for sequences of a fixed number of steps.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the inputs have another shape.
+    pub fn rnn(self, hidden: usize) -> Self {
+        self.rnn_with(hidden, RecurrentOptions::new())
+    }
+
+    /// Adds a simple recurrent layer like [`rnn()`](#method.rnn), with the given options.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the inputs have the wrong shape.
+    pub fn rnn_with(self, hidden: usize, options: RecurrentOptions) -> Self {
+        self.recurrent(hidden, options, |num_inputs, hidden, options| {
+            LayerKind::Rnn(Rnn::new(num_inputs, hidden, options))
+        })
+    }
+
+    /// Adds a long short-term memory layer with the given number of hidden nodes, which outputs
+    /// its final hidden state (see [`Lstm`](struct.Lstm.html)).
+    ///
+    /// The inputs must be sequences (see [`sequences()`](#method.sequences)), or have the shape
+    /// `[steps, values]` for sequences of a fixed number of steps.
+    ///
+    /// # Examples
+    ///
+    /// Forecasting the next reading of a sensor from the readings of the last 24 hours:
+    ///
+    /// ```rust
+    /// use scholar::layers::Sequential;
+    /// use scholar::{Dataset, Sigmoid, Trainer};
+    ///
+    /// let readings: Vec<f64> = (0..200).map(|i| 0.5 + 0.4 * (i as f64 / 6.0).sin()).collect();
+    /// let dataset: Dataset = readings
+    ///     .windows(25)
+    ///     .map(|window| (window[..24].to_vec(), vec![window[24]]))
+    ///     .collect();
+    ///
+    /// let mut model: Sequential<Sigmoid> = Sequential::new(&[24, 1]).lstm(8).dense(1);
+    /// Trainer::new(5, 0.1).train_sequential(&mut model, dataset);
+    /// assert_eq!(model.guess(&readings[..24]).len(), 1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the inputs have another shape.
+    pub fn lstm(self, hidden: usize) -> Self {
+        self.lstm_with(hidden, RecurrentOptions::new())
+    }
+
+    /// Adds a long short-term memory layer like [`lstm()`](#method.lstm), with the given
+    /// options.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the inputs have the wrong shape.
+    pub fn lstm_with(self, hidden: usize, options: RecurrentOptions) -> Self {
+        self.recurrent(hidden, options, |num_inputs, hidden, options| {
+            LayerKind::Lstm(Lstm::new(num_inputs, hidden, options))
+        })
+    }
+
+    /// Adds a gated recurrent unit layer with the given number of hidden nodes, which outputs
+    /// its final hidden state (see [`Gru`](struct.Gru.html)).
+    ///
+    /// The inputs must be sequences (see [`sequences()`](#method.sequences)), or have the shape
+    /// `[steps, values]` for sequences of a fixed number of steps.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the inputs have another shape.
+    pub fn gru(self, hidden: usize) -> Self {
+        self.gru_with(hidden, RecurrentOptions::new())
+    }
+
+    /// Adds a gated recurrent unit layer like [`gru()`](#method.gru), with the given options.
+    ///
+    /// # Examples
+    ///
+    /// Classifying long sequences, backpropagating through at most 20 steps at a time:
+    ///
+    /// ```rust
+    /// use scholar::layers::{RecurrentOptions, Sequential};
+    /// use scholar::Sigmoid;
+    ///
+    /// let model: Sequential<Sigmoid> = Sequential::sequences(3)
+    ///     .gru_with(16, RecurrentOptions::new().truncate(20))
+    ///     .dense(2);
+    /// assert_eq!(model.guess(&[0.5; 3 * 500]).len(), 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the inputs have the wrong shape.
+    pub fn gru_with(self, hidden: usize, options: RecurrentOptions) -> Self {
+        self.recurrent(hidden, options, |num_inputs, hidden, options| {
+            LayerKind::Gru(Gru::new(num_inputs, hidden, options))
+        })
+    }
+
+    /// Adds a recurrent layer with the given number of hidden nodes and options, created by
+    /// `new` from its number of inputs (per step), hidden nodes and options.
+    fn recurrent(
+        mut self,
+        hidden: usize,
+        options: RecurrentOptions,
+        new: fn(usize, usize, RecurrentOptions) -> LayerKind<T>,
+    ) -> Self {
+        let (steps, num_inputs) = self.shape_sequence("recurrent layers");
+        let output_shape = match steps {
+            Some(steps) if options.return_sequences => vec![steps, hidden],
+            _ => vec![hidden],
+        };
+        self.layers.push(Layer {
+            kind: new(num_inputs, hidden, options),
+            output_shape,
+        });
+        self
+    }
+
+    /// Adds a multi-head self-attention layer with the given number of heads, which mixes the
+    /// values of every step of a sequence into every other (see
+    /// [`SelfAttention`](struct.SelfAttention.html)), keeping the shape of its inputs.
+    ///
+    /// The inputs must be sequences (see [`sequences()`](#method.sequences)), or have the shape
+    /// `[steps, values]` for sequences of a fixed number of steps.
+    ///
+    /// # Examples
+    ///
+    /// A small transformer encoder, whose attention layers are followed by a recurrent layer
+    /// that summarises sequences of any length:
+    ///
+    /// ```rust
+    /// use scholar::layers::{RecurrentOptions, Sequential};
+    /// use scholar::{SequenceDataset, Sigmoid, Trainer};
+    ///
+    /// // Whether the first step of a sequence is repeated later on
+    /// let dataset: SequenceDataset = (0..40_u32)
+    ///     .map(|i| {
+    ///         let steps: Vec<Vec<f64>> = (0..3 + i % 4)
+    ///             .map(|t| (0..8).map(|j| ((i * 7 + t * 3 + j) % 5 == 0) as u8 as f64).collect())
+    ///             .collect();
+    ///         let repeated = steps[1..].contains(&steps[0]);
+    ///         (steps, vec![repeated as u8 as f64])
+    ///     })
+    ///     .collect();
+    ///
+    /// let mut model: Sequential<Sigmoid> = Sequential::sequences(8)
+    ///     .positional_encoding()
+    ///     .self_attention(2)
+    ///     .self_attention(2)
+    ///     .gru(8)
+    ///     .dense(1);
+    /// Trainer::new(5, 0.05).train_sequences(&mut model, dataset);
+    /// assert_eq!(model.guess(&[0.0; 8 * 6]).len(), 1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the inputs have another shape, or the number of heads doesn't
+    /// divide the number of values in each step.
+    pub fn self_attention(mut self, heads: usize) -> Self {
+        let (_, num_inputs) = self.shape_sequence("attention layers");
+        let output_shape = self.output_shape().to_vec();
+        self.layers.push(Layer {
+            kind: LayerKind::SelfAttention(SelfAttention::new(num_inputs, heads)),
+            output_shape,
+        });
+        self
+    }
+
+    /// Adds a sinusoidal positional encoding, which adds a different pattern to each step of a
+    /// sequence (see [`PositionalEncoding`](struct.PositionalEncoding.html)), keeping the shape
+    /// of its inputs.
+    ///
+    /// The inputs must be sequences (see [`sequences()`](#method.sequences)), or have the shape
+    /// `[steps, values]` for sequences of a fixed number of steps.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the inputs have another shape.
+    pub fn positional_encoding(mut self) -> Self {
+        let (_, num_inputs) = self.shape_sequence("positional encodings");
+        let output_shape = self.output_shape().to_vec();
+        self.layers.push(Layer {
+            kind: LayerKind::PositionalEncoding(PositionalEncoding::new(num_inputs)),
+            output_shape,
+        });
+        self
+    }
+
+    /// Adds a block of layers with a skip connection around it, whose outputs are the block's
+    /// outputs plus its inputs (see [`Residual`](struct.Residual.html)).
+    ///
+    /// The block's layers are added by `block` to a model without any layers, which takes the
+    /// outputs of this one, and must output the same shape as it takes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::layers::Sequential;
+    /// use scholar::Sigmoid;
+    ///
+    /// let model: Sequential<Sigmoid> = Sequential::new(&[16])
+    ///     .dense(8)
+    ///     .residual(|block| block.dense(8).dense(8))
+    ///     .residual(|block| block.dense(4).dense(8))
+    ///     .dense(1);
+    /// assert_eq!(model.layers().len(), 4);
+    ///
+    /// // A transformer encoder block, with a skip connection around its attention
+    /// let model: Sequential<Sigmoid> = Sequential::sequences(8)
+    ///     .positional_encoding()
+    ///     .residual(|block| block.self_attention(2))
+    ///     .gru(4);
+    /// assert_eq!(model.guess(&[0.5; 8 * 3]).len(), 4);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the block has no layers, or outputs a different shape than it takes
+    /// (or reduces sequences to a single step).
+    pub fn residual(mut self, block: impl FnOnce(Self) -> Self) -> Self {
+        let input_shape = self.output_shape().to_vec();
+        let sequences = self.outputs_sequences();
+        let block = block(Self {
+            input_shape: input_shape.clone(),
+            sequences,
+            layers: Vec::new(),
+            activation: PhantomData,
+        });
+
+        if block.layers.is_empty() {
+            panic!("residual blocks need at least one layer");
+        }
+        if block.output_shape() != input_shape || block.outputs_sequences() != sequences {
+            panic!(
+                "residual blocks must output the shape that they take (expected {:?}, found {:?})",
+                input_shape,
+                block.output_shape()
+            );
+        }
+
+        self.layers.push(Layer {
+            kind: LayerKind::Residual(Residual::new(block.layers)),
+            output_shape: input_shape,
+        });
+        self
+    }
+
+    /// Adds a layer of a kind defined outside the crate (see
+    /// [`CustomLayer`](trait.CustomLayer.html)), whose output shape it works out from the
+    /// model's.
+    ///
+    /// A model with custom layers can only be loaded through a
+    /// [`Registry`](../registry/struct.Registry.html) that they are registered in.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the model's outputs are still sequences, or the layer can't take
+    /// them.
+    pub fn custom<L: CustomLayer + Clone + Serialize + 'static>(mut self, layer: L) -> Self {
+        self.check_fixed("custom layers");
+        let output_shape = layer.output_shape(self.output_shape());
+        self.layers.push(Layer {
+            kind: LayerKind::Custom(Custom::new(layer)),
+            output_shape,
+        });
+        self
+    }
+
+    /// Adds a 1D pooling layer of the given kind.
+    fn pool1d(mut self, size: usize, kind: fn(Pool) -> LayerKind<T>) -> Self {
+        let (channels, length) = self.shape_1d("pooling");
+        let pool = Pool::new(channels, 1, length, (1, size));
+        let (channels, _, length) = pool.output_shape();
+        self.layers.push(Layer {
+            kind: kind(pool),
+            output_shape: vec![channels, length],
+        });
+        self
+    }
+
+    /// Adds a 2D pooling layer of the given kind.
+    fn pool2d(mut self, size: (usize, usize), kind: fn(Pool) -> LayerKind<T>) -> Self {
+        let (channels, height, width) = self.shape_2d("pooling");
+        let pool = Pool::new(channels, height, width, size);
+        let (channels, height, width) = pool.output_shape();
+        self.layers.push(Layer {
+            kind: kind(pool),
+            output_shape: vec![channels, height, width],
+        });
+        self
+    }
+
+    /// Returns the number of channels and length of the model's current outputs, panicking
+    /// (saying that 1D `what` needs them) unless they are 1D.
+    fn shape_1d(&self, what: &str) -> (usize, usize) {
+        self.check_fixed(&format!("1D {}", what));
+        match *self.output_shape() {
+            [length] => (1, length),
+            [channels, length] => (channels, length),
+            ref shape => panic!(
+                "1D {} need inputs of shape [channels, length] (found {:?})",
+                what, shape
+            ),
+        }
+    }
+
+    /// Returns the number of channels, height and width of the model's current outputs,
+    /// panicking (saying that 2D `what` needs them) unless they are 2D.
+    fn shape_2d(&self, what: &str) -> (usize, usize, usize) {
+        self.check_fixed(&format!("2D {}", what));
+        match *self.output_shape() {
+            [height, width] => (1, height, width),
+            [channels, height, width] => (channels, height, width),
+            ref shape => panic!(
+                "2D {} need inputs of shape [channels, height, width] (found {:?})",
+                what, shape
+            ),
+        }
+    }
+
+    /// Returns the number of steps (if it is fixed) and of values in each step of the model's
+    /// current outputs, panicking (saying that `what` needs them) unless they are sequences.
+    fn shape_sequence(&self, what: &str) -> (Option<usize>, usize) {
+        match *self.output_shape() {
+            [num_inputs] if self.outputs_sequences() => (None, num_inputs),
+            [steps, num_inputs] if !self.outputs_sequences() => (Some(steps), num_inputs),
+            ref shape => panic!(
+                "{} need sequences, or inputs of shape [steps, values] (found {:?})",
+                what, shape
+            ),
+        }
+    }
+
+    /// Panics (saying that `what` needs fixed-size inputs) if the model's current outputs are
+    /// sequences of any number of steps.
+    fn check_fixed(&self, what: &str) {
+        if self.outputs_sequences() {
+            panic!(
+                "{} need inputs of a fixed size (end the sequences with a recurrent layer that \
+                 outputs its final hidden state)",
+                what
+            );
+        }
+    }
+
+    /// Returns whether the model's current outputs are sequences of any number of steps.
+    fn outputs_sequences(&self) -> bool {
+        self.sequences && self.layers.iter().all(Layer::returns_sequences)
+    }
+
+    /// Returns whether the model takes sequences of any number of steps (see
+    /// [`sequences()`](#method.sequences)).
+    pub fn takes_sequences(&self) -> bool {
+        self.sequences
+    }
+
+    /// Returns the shape of the model's inputs, which is the shape of each step if it takes
+    /// sequences.
+    pub fn input_shape(&self) -> &[usize] {
+        &self.input_shape
+    }
+
+    /// Returns the shape of the model's outputs, which is the shape of its inputs if it has no
+    /// layers (and the shape of each step if they are sequences).
+    pub fn output_shape(&self) -> &[usize] {
+        self.layers
+            .last()
+            .map_or(&self.input_shape, |layer| &layer.output_shape)
+    }
+
+    /// Returns the model's layers.
+    pub fn layers(&self) -> &[Layer<T>] {
+        &self.layers
+    }
+
+    /// Returns a mutable iterator over every parameter of every layer (besides those of custom
+    /// layers), in order.
+    pub(crate) fn parameters_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.layers
+            .iter_mut()
+            .flat_map(Layer::parameters_mut)
+            .flat_map(|m| m.iter_mut())
+    }
+
+    /// Feeds the given inputs through the model, returning its outputs.
+    ///
+    /// Sequences are given (and output) as the values of each step in turn.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of given input values doesn't match the model's input
+    /// shape (or isn't a positive multiple of it, if the model takes sequences).
+    pub fn guess(&self, inputs: &[f64]) -> Vec<f64> {
+        let values = self.feedforward(inputs);
+        values[values.len() - 1]
+            .iter()
+            .map(|x| x.as_f64())
+            .collect()
+    }
+
+    /// Feeds the given inputs through the model, returning the inputs and outputs of every
+    /// layer.
+    pub(crate) fn feedforward(&self, inputs: &[f64]) -> Vec<DMatrix<T>> {
+        let num_inputs: usize = self.input_shape.iter().product();
+        if self.sequences {
+            if inputs.is_empty() || !inputs.len().is_multiple_of(num_inputs) {
+                panic!(
+                    "incorrect number of inputs supplied (expected a positive multiple of {}, \
+                     found {})",
+                    num_inputs,
+                    inputs.len()
+                );
+            }
+        } else if inputs.len() != num_inputs {
+            panic!(
+                "incorrect number of inputs supplied (expected {}, found {})",
+                num_inputs,
+                inputs.len()
+            );
+        }
+
+        feedforward_layers::<A, T>(&self.layers, convert_slice_to_matrix(inputs))
+    }
+
+    /// Updates every layer's parameters by backpropagating the error of the outputs fed forward
+    /// by [`feedforward()`](#method.feedforward) (given with the inputs of every layer).
+    pub(crate) fn backpropagate(
+        &mut self,
+        values: &[DMatrix<T>],
+        targets: &[f64],
+        learning_rate: f64,
+    ) {
+        let outputs = &values[values.len() - 1];
+        if targets.len() != outputs.len() {
+            panic!(
+                "incorrect number of targets supplied (expected {}, found {})",
+                outputs.len(),
+                targets.len()
+            );
+        }
+
+        // The gradient of the (halved) squared error with respect to the outputs
+        let gradients = outputs - convert_slice_to_matrix::<T>(targets);
+        backpropagate_layers::<A, T>(
+            &mut self.layers,
+            values,
+            gradients,
+            T::convert(learning_rate),
+        );
+    }
+}
+
+/// Feeds the given inputs through each of the given layers in turn, applying the activation `A`
+/// to the outputs of every activated layer, and returns the inputs and outputs of every layer.
+fn feedforward_layers<A: Activation, T: Float>(
+    layers: &[Layer<T>],
+    inputs: DMatrix<T>,
+) -> Vec<DMatrix<T>> {
+    let mut values = Vec::with_capacity(layers.len() + 1);
+    values.push(inputs);
+    for layer in layers {
+        let mut outputs = layer.forward::<A>(&values[values.len() - 1]);
+        if layer.is_activated() {
+            A::activate_slice(outputs.as_mut_slice());
+        }
+        values.push(outputs);
+    }
+
+    values
+}
+
+/// Updates the parameters of each of the given layers in reverse, by backpropagating the given
+/// gradients of the cost with respect to the outputs of the last (given the values fed forward by
+/// `feedforward_layers()`), and returns the gradients with respect to the inputs of the first.
+fn backpropagate_layers<A: Activation, T: Float>(
+    layers: &mut [Layer<T>],
+    values: &[DMatrix<T>],
+    mut gradients: DMatrix<T>,
+    learning_rate: T,
+) -> DMatrix<T> {
+    for (i, layer) in layers.iter_mut().enumerate().rev() {
+        if layer.is_activated() {
+            let mut derivatives = values[i + 1].clone();
+            A::derivative_slice(derivatives.as_mut_slice());
+            gradients.component_mul_assign(&derivatives);
+        }
+
+        gradients = layer.backward::<A>(&values[i], &gradients, learning_rate);
+    }
+
+    gradients
+}
+
+impl<A: Activation, T: Float> Clone for Sequential<A, T> {
+    fn clone(&self) -> Self {
+        Self {
+            input_shape: self.input_shape.clone(),
+            sequences: self.sequences,
+            layers: self.layers.clone(),
+            activation: PhantomData,
+        }
+    }
+}
+
+impl<A: Activation, T: Float> fmt::Debug for Sequential<A, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sequential")
+            .field("activation", &A::name())
+            .field("input_shape", &self.input_shape)
+            .field("sequences", &self.sequences)
+            .field("layers", &self.layers)
+            .finish()
+    }
+}
@@ -0,0 +1,293 @@
+use super::recurrent::{flatten, steps};
+use crate::network::{matrices::matrix, Float};
+use crate::utils::gen_random_matrix;
+
+use nalgebra::DMatrix;
+use serde::{Deserialize, Serialize};
+
+/// A multi-head self-attention layer, which replaces every step of a sequence with a mix of
+/// every step's values, weighted by how relevant each step is to it.
+///
+/// Each step is projected into a query, a key and a value, which are split evenly between the
+/// heads. Each head weights the values of every step by the softmax of the dot products of the
+/// step's query and every step's key (scaled by the square root of their size), and the mixed
+/// values of every head are projected back into the layer's outputs:
+///
+/// ```text
+/// attention[h] = softmax(keys[h]ᵀ * queries[h] / √size)
+/// outputs = output_weights * [values[h] * attention[h] for each head h] + biases
+/// ```
+///
+/// Steps have the same number of values in the layer's outputs as in its inputs, so layers can
+/// be stacked, and the outputs aren't activated. The layer has no sense of the order of the
+/// steps by itself, so it is usually preceded by a
+/// [`PositionalEncoding`](struct.PositionalEncoding.html).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct SelfAttention<T: Float = f64> {
+    heads: usize,
+    /// A row per value of the queries (each head's in turn), and a column per input.
+    #[serde(with = "matrix")]
+    query_weights: DMatrix<T>,
+    /// A row per value of the keys (each head's in turn), and a column per input.
+    #[serde(with = "matrix")]
+    key_weights: DMatrix<T>,
+    /// A row per value of the values (each head's in turn), and a column per input.
+    #[serde(with = "matrix")]
+    value_weights: DMatrix<T>,
+    /// A row per output, and a column per mixed value (each head's in turn).
+    #[serde(with = "matrix")]
+    output_weights: DMatrix<T>,
+    #[serde(with = "matrix")]
+    biases: DMatrix<T>,
+}
+
+/// The intermediate values of a `SelfAttention` layer, each with a column per step.
+struct Attended<T: Float> {
+    queries: DMatrix<T>,
+    keys: DMatrix<T>,
+    values: DMatrix<T>,
+    /// A matrix per head, with a row per key and a column per query.
+    attention: Vec<DMatrix<T>>,
+    mixed: DMatrix<T>,
+}
+
+impl<T: Float> SelfAttention<T> {
+    /// Creates a layer with the given number of inputs (per step) and heads, and random weights
+    /// and biases.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the number of heads doesn't divide the number of inputs.
+    pub(super) fn new(num_inputs: usize, heads: usize) -> Self {
+        if heads == 0 || !num_inputs.is_multiple_of(heads) {
+            panic!(
+                "number of heads must divide the number of values in each step (expected a \
+                 divisor of {}, found {})",
+                num_inputs, heads
+            );
+        }
+
+        // Scaled down, so that the softmax doesn't start out saturated by large dot products
+        let scale = T::convert(1.0 / (num_inputs as f64).sqrt());
+        let weights = || gen_random_matrix::<T>(num_inputs, num_inputs) * scale;
+        Self {
+            heads,
+            query_weights: weights(),
+            key_weights: weights(),
+            value_weights: weights(),
+            output_weights: weights(),
+            biases: gen_random_matrix(num_inputs, 1),
+        }
+    }
+
+    /// Returns the number of heads.
+    pub fn heads(&self) -> usize {
+        self.heads
+    }
+
+    /// Returns the weights of the queries, which have a row per value of the queries (each
+    /// head's in turn) and a column per input (of each step).
+    pub fn query_weights(&self) -> &DMatrix<T> {
+        &self.query_weights
+    }
+
+    /// Returns the weights of the keys, which have a row per value of the keys (each head's in
+    /// turn) and a column per input (of each step).
+    pub fn key_weights(&self) -> &DMatrix<T> {
+        &self.key_weights
+    }
+
+    /// Returns the weights of the values, which have a row per value (each head's in turn) and
+    /// a column per input (of each step).
+    pub fn value_weights(&self) -> &DMatrix<T> {
+        &self.value_weights
+    }
+
+    /// Returns the weights of the outputs, which have a row per output (of each step) and a
+    /// column per mixed value (each head's in turn).
+    pub fn output_weights(&self) -> &DMatrix<T> {
+        &self.output_weights
+    }
+
+    /// Returns the layer's biases, which have a row per output (of each step).
+    pub fn biases(&self) -> &DMatrix<T> {
+        &self.biases
+    }
+
+    /// Returns every matrix of the layer's parameters.
+    pub(super) fn parameters_mut(&mut self) -> Vec<&mut DMatrix<T>> {
+        vec![
+            &mut self.query_weights,
+            &mut self.key_weights,
+            &mut self.value_weights,
+            &mut self.output_weights,
+            &mut self.biases,
+        ]
+    }
+
+    /// Returns the number of values of each head's queries, keys and values.
+    fn head_size(&self) -> usize {
+        self.biases.nrows() / self.heads
+    }
+
+    /// Returns the factor that the dot products of the queries and keys are scaled by.
+    fn scale(&self) -> T {
+        T::one() / T::convert(self.head_size() as f64).sqrt()
+    }
+
+    /// Calculates the queries, keys and values of the given steps, and the values that each
+    /// head mixes from them.
+    fn attend(&self, steps: &DMatrix<T>) -> Attended<T> {
+        let size = self.head_size();
+        let queries = &self.query_weights * steps;
+        let keys = &self.key_weights * steps;
+        let values = &self.value_weights * steps;
+
+        let mut attention = Vec::with_capacity(self.heads);
+        let mut mixed = DMatrix::zeros(values.nrows(), values.ncols());
+        for head in 0..self.heads {
+            let rows = head * size;
+            let mut scores = keys.rows(rows, size).tr_mul(&queries.rows(rows, size)) * self.scale();
+            for mut column in scores.column_iter_mut() {
+                // Subtracting the maximum keeps the exponentials from overflowing
+                let max = column.iter().fold(T::neg_infinity(), |max, &x| max.max(x));
+                column.apply(|x| *x = (*x - max).exp());
+                let sum = column.sum();
+                column.apply(|x| *x = *x / sum);
+            }
+
+            mixed
+                .rows_mut(rows, size)
+                .copy_from(&(values.rows(rows, size) * &scores));
+            attention.push(scores);
+        }
+
+        Attended {
+            queries,
+            keys,
+            values,
+            attention,
+            mixed,
+        }
+    }
+
+    pub(super) fn forward(&self, inputs: &DMatrix<T>) -> DMatrix<T> {
+        let attended = self.attend(&steps(inputs, self.biases.nrows()));
+        let mut outputs = &self.output_weights * attended.mixed;
+        for mut step in outputs.column_iter_mut() {
+            step += self.biases.column(0);
+        }
+        flatten(outputs)
+    }
+
+    pub(super) fn backward(
+        &mut self,
+        inputs: &DMatrix<T>,
+        gradients: &DMatrix<T>,
+        learning_rate: T,
+    ) -> DMatrix<T> {
+        let size = self.head_size();
+        let gradients = steps(gradients, self.biases.nrows());
+        let inputs = steps(inputs, self.biases.nrows());
+        let Attended {
+            queries,
+            keys,
+            values,
+            attention,
+            mixed,
+        } = self.attend(&inputs);
+
+        let mixed_gradients = self.output_weights.tr_mul(&gradients);
+        let mut query_gradients = DMatrix::zeros(queries.nrows(), queries.ncols());
+        let mut key_gradients = DMatrix::zeros(keys.nrows(), keys.ncols());
+        let mut value_gradients = DMatrix::zeros(values.nrows(), values.ncols());
+        for (head, attention) in attention.iter().enumerate() {
+            let rows = head * size;
+            let mixed_gradients = mixed_gradients.rows(rows, size);
+            value_gradients
+                .rows_mut(rows, size)
+                .copy_from(&(mixed_gradients * attention.transpose()));
+
+            // Through the softmax of each query's scores
+            let mut scores = values.rows(rows, size).tr_mul(&mixed_gradients);
+            for (mut scores, attention) in scores.column_iter_mut().zip(attention.column_iter()) {
+                let dot = scores.dot(&attention);
+                scores.zip_apply(&attention, |x, a| *x = a * (*x - dot));
+            }
+            scores *= self.scale();
+
+            query_gradients
+                .rows_mut(rows, size)
+                .copy_from(&(keys.rows(rows, size) * &scores));
+            key_gradients
+                .rows_mut(rows, size)
+                .copy_from(&(queries.rows(rows, size) * scores.transpose()));
+        }
+
+        let input_gradients = self.query_weights.tr_mul(&query_gradients)
+            + self.key_weights.tr_mul(&key_gradients)
+            + self.value_weights.tr_mul(&value_gradients);
+
+        self.query_weights.gemm(
+            -learning_rate,
+            &query_gradients,
+            &inputs.transpose(),
+            T::one(),
+        );
+        self.key_weights.gemm(
+            -learning_rate,
+            &key_gradients,
+            &inputs.transpose(),
+            T::one(),
+        );
+        self.value_weights.gemm(
+            -learning_rate,
+            &value_gradients,
+            &inputs.transpose(),
+            T::one(),
+        );
+        self.output_weights
+            .gemm(-learning_rate, &gradients, &mixed.transpose(), T::one());
+        self.biases
+            .column_mut(0)
+            .axpy(-learning_rate, &gradients.column_sum(), T::one());
+
+        flatten(input_gradients)
+    }
+}
+
+/// A sinusoidal positional encoding, which adds a pattern of sines and cosines of different
+/// frequencies to every step of a sequence, so that the following layers (such as a
+/// [`SelfAttention`](struct.SelfAttention.html) layer) can tell the steps apart by their
+/// positions.
+///
+/// The layer has no parameters, and works with sequences of any number of steps:
+///
+/// ```text
+/// outputs[t][2i] = inputs[t][2i] + sin(t / 10000^(2i / values))
+/// outputs[t][2i + 1] = inputs[t][2i + 1] + cos(t / 10000^(2i / values))
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionalEncoding {
+    num_inputs: usize,
+}
+
+impl PositionalEncoding {
+    /// Creates an encoding of steps with the given number of values.
+    pub(super) fn new(num_inputs: usize) -> Self {
+        Self { num_inputs }
+    }
+
+    pub(super) fn forward<T: Float>(&self, inputs: &DMatrix<T>) -> DMatrix<T> {
+        let mut outputs = steps(inputs, self.num_inputs);
+        for (t, mut step) in outputs.column_iter_mut().enumerate() {
+            for (i, x) in step.iter_mut().enumerate() {
+                let divisor = 10_000_f64.powf((i - i % 2) as f64 / self.num_inputs as f64);
+                let angle = t as f64 / divisor;
+                *x += T::convert(if i % 2 == 0 { angle.sin() } else { angle.cos() });
+            }
+        }
+        flatten(outputs)
+    }
+}
@@ -0,0 +1,259 @@
+use crate::network::{matrices::matrix, Float};
+use crate::utils::gen_random_matrix;
+
+use nalgebra::{DMatrix, Dyn};
+use serde::{Deserialize, Serialize};
+
+/// The stride and padding of a convolutional layer (see
+/// [`Sequential::conv1d_with()`](struct.Sequential.html#method.conv1d_with) and
+/// [`Sequential::conv2d_with()`](struct.Sequential.html#method.conv2d_with)).
+///
+/// By default, the kernel moves one value at a time, and the inputs aren't padded.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::layers::ConvOptions;
+///
+/// // Halves the size of the inputs (with a kernel of size 3)
+/// let options = ConvOptions::new().stride(2).padding(1);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConvOptions {
+    pub(super) stride: usize,
+    pub(super) padding: usize,
+}
+
+impl ConvOptions {
+    /// Creates the default options: a stride of 1 and no padding.
+    pub fn new() -> Self {
+        Self {
+            stride: 1,
+            padding: 0,
+        }
+    }
+
+    /// Sets the number of values that the kernel moves by in each dimension between outputs.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the stride is 0.
+    pub fn stride(mut self, stride: usize) -> Self {
+        if stride == 0 {
+            panic!("stride must be positive (expected at least 1, found 0)");
+        }
+
+        self.stride = stride;
+        self
+    }
+
+    /// Sets the number of zeros that the inputs are padded with on each side of each dimension.
+    pub fn padding(mut self, padding: usize) -> Self {
+        self.padding = padding;
+        self
+    }
+}
+
+impl Default for ConvOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A window that slides over inputs of one or more channels (each a 2D grid of values, stored
+/// row by row, which is a single row for 1D inputs), reading a kernel-sized patch of every
+/// channel at each position.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct Window {
+    pub(super) channels: usize,
+    pub(super) height: usize,
+    pub(super) width: usize,
+    pub(super) kernel: (usize, usize),
+    pub(super) stride: (usize, usize),
+    pub(super) padding: (usize, usize),
+}
+
+impl Window {
+    /// Returns the height and width of the grid of the window's positions, i.e. of each output
+    /// channel.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the kernel is larger than the padded inputs.
+    pub(super) fn output_size(&self) -> (usize, usize) {
+        let size = |length: usize, kernel: usize, stride: usize, padding: usize| {
+            let padded = length + 2 * padding;
+            if kernel == 0 || kernel > padded {
+                panic!(
+                    "kernel doesn't fit the inputs (expected a size of 1 to {}, found {})",
+                    padded, kernel
+                );
+            }
+            (padded - kernel) / stride + 1
+        };
+
+        (
+            size(self.height, self.kernel.0, self.stride.0, self.padding.0),
+            size(self.width, self.kernel.1, self.stride.1, self.padding.1),
+        )
+    }
+
+    /// Returns the number of values that the window reads at each position.
+    pub(super) fn patch_len(&self) -> usize {
+        self.channels * self.kernel.0 * self.kernel.1
+    }
+
+    /// Calls `f` with each position of the window, each value of the patch read at that
+    /// position (channel by channel, then row by row), and the index of the input value there
+    /// (or `None` where the inputs are padded).
+    pub(super) fn for_each(&self, mut f: impl FnMut(usize, usize, Option<usize>)) {
+        let (output_height, output_width) = self.output_size();
+        let (kernel_height, kernel_width) = self.kernel;
+        for oy in 0..output_height {
+            for ox in 0..output_width {
+                let position = oy * output_width + ox;
+                let mut value = 0;
+                for channel in 0..self.channels {
+                    for ky in 0..kernel_height {
+                        for kx in 0..kernel_width {
+                            // Padding is subtracted last, so that these underflow to out of bounds
+                            let y = (oy * self.stride.0 + ky).wrapping_sub(self.padding.0);
+                            let x = (ox * self.stride.1 + kx).wrapping_sub(self.padding.1);
+                            let index = if y < self.height && x < self.width {
+                                Some((channel * self.height + y) * self.width + x)
+                            } else {
+                                None
+                            };
+
+                            f(position, value, index);
+                            value += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the patch read at each position as the columns of a matrix.
+    fn patches<T: Float>(&self, inputs: &DMatrix<T>) -> DMatrix<T> {
+        let (output_height, output_width) = self.output_size();
+        let mut patches = DMatrix::zeros(self.patch_len(), output_height * output_width);
+        self.for_each(|position, value, index| {
+            if let Some(index) = index {
+                patches[(value, position)] = inputs[index];
+            }
+        });
+        patches
+    }
+
+    /// Adds the gradients of each value of each patch to the gradient of the input value that
+    /// it was read from, the reverse of [`patches()`](#method.patches).
+    fn unpatch<T: Float>(&self, patch_gradients: &DMatrix<T>) -> DMatrix<T> {
+        let mut gradients = DMatrix::zeros(self.channels * self.height * self.width, 1);
+        self.for_each(|position, value, index| {
+            if let Some(index) = index {
+                gradients[index] += patch_gradients[(value, position)];
+            }
+        });
+        gradients
+    }
+}
+
+/// A convolutional layer, which slides a kernel of weights per filter over its inputs (of one
+/// or more channels), producing an output channel per filter.
+///
+/// Inputs and outputs are stored channel by channel, each channel being a 1D signal or the
+/// rows of a 2D image in turn. A 1D layer is a 2D layer whose inputs (and kernels) have a
+/// single row.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Conv<T: Float = f64> {
+    window: Window,
+    /// A column per filter, holding its kernel for each input channel in turn.
+    #[serde(with = "matrix")]
+    weights: DMatrix<T>,
+    #[serde(with = "matrix")]
+    biases: DMatrix<T>,
+}
+
+impl<T: Float> Conv<T> {
+    /// Creates a layer with the given window and number of filters, and random weights and
+    /// biases.
+    pub(super) fn new(window: Window, filters: usize) -> Self {
+        window.output_size();
+        Self {
+            weights: gen_random_matrix(window.patch_len(), filters),
+            biases: gen_random_matrix(filters, 1),
+            window,
+        }
+    }
+
+    /// Returns the number of filters, i.e. of output channels.
+    pub fn filters(&self) -> usize {
+        self.weights.ncols()
+    }
+
+    /// Returns the height and width of the kernel (whose height is 1 in a 1D layer).
+    pub fn kernel_size(&self) -> (usize, usize) {
+        self.window.kernel
+    }
+
+    /// Returns the layer's weights, which have a column per filter holding its kernel for each
+    /// input channel in turn (each stored row by row).
+    pub fn weights(&self) -> &DMatrix<T> {
+        &self.weights
+    }
+
+    /// Returns the layer's biases, which have a row per filter.
+    pub fn biases(&self) -> &DMatrix<T> {
+        &self.biases
+    }
+
+    /// Returns every matrix of the layer's parameters.
+    pub(super) fn parameters_mut(&mut self) -> Vec<&mut DMatrix<T>> {
+        vec![&mut self.weights, &mut self.biases]
+    }
+
+    /// Returns the shape of the layer's outputs: the number of channels, followed by their
+    /// height and width.
+    pub(super) fn output_shape(&self) -> (usize, usize, usize) {
+        let (height, width) = self.window.output_size();
+        (self.filters(), height, width)
+    }
+
+    pub(super) fn forward(&self, inputs: &DMatrix<T>) -> DMatrix<T> {
+        // A row per position and a column per filter, which is stored channel by channel
+        let mut outputs = self.window.patches(inputs).tr_mul(&self.weights);
+        for (mut channel, &bias) in outputs.column_iter_mut().zip(self.biases.iter()) {
+            channel.add_scalar_mut(bias);
+        }
+
+        let len = outputs.len();
+        outputs.reshape_generic(Dyn(len), Dyn(1))
+    }
+
+    pub(super) fn backward(
+        &mut self,
+        inputs: &DMatrix<T>,
+        gradients: &DMatrix<T>,
+        learning_rate: T,
+    ) -> DMatrix<T> {
+        let positions = gradients.len() / self.filters();
+        let gradients = gradients
+            .clone()
+            .reshape_generic(Dyn(positions), Dyn(self.filters()));
+
+        let patches = self.window.patches(inputs);
+        let input_gradients = self
+            .window
+            .unpatch(&(&self.weights * gradients.transpose()));
+
+        self.weights
+            .gemm(-learning_rate, &patches, &gradients, T::one());
+        for (bias, channel) in self.biases.iter_mut().zip(gradients.column_iter()) {
+            *bias -= learning_rate * channel.sum();
+        }
+
+        input_gradients
+    }
+}
@@ -0,0 +1,196 @@
+use crate::network::Float;
+
+use nalgebra::DMatrix;
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+use std::{cell::RefCell, collections::HashMap, fmt};
+
+/// A layer of a kind defined outside the crate, which can be added to a
+/// [`Sequential`](struct.Sequential.html) model with
+/// [`Sequential::custom()`](struct.Sequential.html#method.custom).
+///
+/// Its calculations use `f64`s, whatever the model's `Float` type, and its inputs and outputs are
+/// flat slices like the rest of the model's. The model's activation isn't applied to its outputs.
+///
+/// Models are saved with the layer's name and its serialized state (as JSON, whatever the format
+/// of the rest of the model), so a model with custom layers can only be loaded through a
+/// [`Registry`](../registry/struct.Registry.html) that they are registered in (see
+/// [`Registry::register_layer()`](../registry/struct.Registry.html#method.register_layer)).
+pub trait CustomLayer: fmt::Debug + Send + Sync {
+    /// Returns the name that the layer is saved and registered under.
+    fn name() -> &'static str
+    where
+        Self: Sized;
+
+    /// Returns the shape of the layer's outputs, given the shape of its inputs.
+    ///
+    /// This is called when the layer is added to a model, and should panic if the layer can't
+    /// take inputs of the given shape.
+    fn output_shape(&self, input_shape: &[usize]) -> Vec<usize>;
+
+    /// Calculates the layer's outputs from its inputs.
+    fn forward(&self, inputs: &[f64]) -> Vec<f64>;
+
+    /// Updates the layer's parameters (if it has any) given its inputs and the gradients of the
+    /// cost with respect to its outputs, returning the gradients with respect to its inputs.
+    fn backward(&mut self, inputs: &[f64], gradients: &[f64], learning_rate: f64) -> Vec<f64>;
+}
+
+/// A [`CustomLayer`](trait.CustomLayer.html) in a model, along with the name it is saved under.
+#[derive(Debug)]
+pub struct Custom {
+    name: &'static str,
+    layer: Box<dyn SavedLayer>,
+}
+
+impl Custom {
+    /// Wraps the given layer.
+    pub(super) fn new<L: CustomLayer + Clone + Serialize + 'static>(layer: L) -> Self {
+        Self {
+            name: L::name(),
+            layer: Box::new(layer),
+        }
+    }
+
+    /// Returns the name of the layer (see
+    /// [`CustomLayer::name()`](trait.CustomLayer.html#tymethod.name)).
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    /// Returns the layer.
+    pub fn layer(&self) -> &dyn CustomLayer {
+        self.layer.layer()
+    }
+
+    pub(super) fn forward<T: Float>(&self, inputs: &DMatrix<T>) -> DMatrix<T> {
+        to_matrix(self.layer().forward(&to_vec(inputs)))
+    }
+
+    pub(super) fn backward<T: Float>(
+        &mut self,
+        inputs: &DMatrix<T>,
+        gradients: &DMatrix<T>,
+        learning_rate: T,
+    ) -> DMatrix<T> {
+        let layer = self.layer.layer_mut();
+        to_matrix(layer.backward(&to_vec(inputs), &to_vec(gradients), learning_rate.as_f64()))
+    }
+}
+
+/// Converts a column of values to `f64`s.
+fn to_vec<T: Float>(values: &DMatrix<T>) -> Vec<f64> {
+    values.iter().map(|x| x.as_f64()).collect()
+}
+
+/// Converts `f64`s to a column of values.
+fn to_matrix<T: Float>(values: Vec<f64>) -> DMatrix<T> {
+    DMatrix::from_iterator(values.len(), 1, values.into_iter().map(T::convert))
+}
+
+/// A custom layer that can be cloned and saved, whatever its type.
+trait SavedLayer: fmt::Debug + Send + Sync {
+    fn layer(&self) -> &dyn CustomLayer;
+    fn layer_mut(&mut self) -> &mut dyn CustomLayer;
+    fn clone_box(&self) -> Box<dyn SavedLayer>;
+    /// Serializes the layer's state as JSON.
+    fn state(&self) -> serde_json::Result<String>;
+}
+
+impl<L: CustomLayer + Clone + Serialize + 'static> SavedLayer for L {
+    fn layer(&self) -> &dyn CustomLayer {
+        self
+    }
+
+    fn layer_mut(&mut self) -> &mut dyn CustomLayer {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn SavedLayer> {
+        Box::new(self.clone())
+    }
+
+    fn state(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+impl Clone for Custom {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name,
+            layer: self.layer.clone_box(),
+        }
+    }
+}
+
+/// Layers are equal if they have the same name and state.
+impl PartialEq for Custom {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.layer.state().ok() == other.layer.state().ok()
+    }
+}
+
+/// The saved form of a custom layer.
+#[derive(Serialize, Deserialize)]
+struct CustomState {
+    name: String,
+    /// The layer's state, as JSON.
+    state: String,
+}
+
+impl Serialize for Custom {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let state = self.layer.state().map_err(ser::Error::custom)?;
+        CustomState {
+            name: self.name.to_string(),
+            state,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Custom {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let saved = CustomState::deserialize(deserializer)?;
+        let load = LOADERS
+            .with(|loaders| loaders.borrow().get(saved.name.as_str()).copied())
+            .ok_or_else(|| {
+                de::Error::custom(format!(
+                    "unknown layer '{}' (models with custom layers must be loaded through a \
+                     `Registry` that they are registered in)",
+                    saved.name
+                ))
+            })?;
+        load(&saved.state).map_err(de::Error::custom)
+    }
+}
+
+/// Deserializes a custom layer of a particular type from its state.
+pub(crate) type LayerLoader = fn(&str) -> serde_json::Result<Custom>;
+
+/// Deserializes a custom layer of the type `L` from its state.
+#[cfg(feature = "fs")]
+pub(crate) fn load_layer<L>(state: &str) -> serde_json::Result<Custom>
+where
+    L: CustomLayer + Clone + Serialize + de::DeserializeOwned + 'static,
+{
+    serde_json::from_str::<L>(state).map(Custom::new)
+}
+
+thread_local! {
+    /// The custom layers that can be deserialized on this thread, by name, which are set whilst a
+    /// `Registry` deserializes a model.
+    static LOADERS: RefCell<HashMap<&'static str, LayerLoader>> = RefCell::new(HashMap::new());
+}
+
+/// Calls `f` whilst the given custom layers can be deserialized on this thread.
+#[cfg(feature = "fs")]
+pub(crate) fn with_loaders<R>(
+    loaders: &HashMap<&'static str, LayerLoader>,
+    f: impl FnOnce() -> R,
+) -> R {
+    let previous = LOADERS.with(|current| current.replace(loaders.clone()));
+    let result = f();
+    LOADERS.with(|current| current.replace(previous));
+    result
+}
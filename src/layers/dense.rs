@@ -0,0 +1,64 @@
+use crate::network::{matrices::matrix, Float};
+use crate::utils::gen_random_matrix;
+
+use nalgebra::DMatrix;
+use serde::{Deserialize, Serialize};
+
+/// A fully-connected layer, like each of the layers of a [`NeuralNet`](../struct.NeuralNet.html).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Dense<T: Float = f64> {
+    /// A row per output node and a column per input.
+    #[serde(with = "matrix")]
+    weights: DMatrix<T>,
+    #[serde(with = "matrix")]
+    biases: DMatrix<T>,
+}
+
+impl<T: Float> Dense<T> {
+    /// Creates a layer with the given numbers of inputs and output nodes, and random weights and
+    /// biases.
+    pub(super) fn new(num_inputs: usize, num_outputs: usize) -> Self {
+        Self {
+            weights: gen_random_matrix(num_outputs, num_inputs),
+            biases: gen_random_matrix(num_outputs, 1),
+        }
+    }
+
+    /// Returns the layer's weights, which have a row per output node and a column per input.
+    pub fn weights(&self) -> &DMatrix<T> {
+        &self.weights
+    }
+
+    /// Returns the layer's biases, which have a row per output node.
+    pub fn biases(&self) -> &DMatrix<T> {
+        &self.biases
+    }
+
+    /// Returns every matrix of the layer's parameters.
+    pub(super) fn parameters_mut(&mut self) -> Vec<&mut DMatrix<T>> {
+        vec![&mut self.weights, &mut self.biases]
+    }
+
+    pub(super) fn forward(&self, inputs: &DMatrix<T>) -> DMatrix<T> {
+        &self.weights * inputs + &self.biases
+    }
+
+    pub(super) fn backward(
+        &mut self,
+        inputs: &DMatrix<T>,
+        gradients: &DMatrix<T>,
+        learning_rate: T,
+    ) -> DMatrix<T> {
+        let input_gradients = self.weights.tr_mul(gradients);
+
+        let gradients = gradients.column(0);
+        self.weights
+            .ger(-learning_rate, &gradients, &inputs.column(0), T::one());
+        self.biases
+            .column_mut(0)
+            .axpy(-learning_rate, &gradients, T::one());
+
+        input_gradients
+    }
+}
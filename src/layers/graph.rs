@@ -0,0 +1,498 @@
+use super::{backpropagate_layers, feedforward_layers, Layer, Sequential};
+use crate::network::{Activation, Float};
+use crate::utils::convert_slice_to_matrix;
+
+use nalgebra::DMatrix;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{fmt, marker::PhantomData};
+
+/// A model whose layers are the nodes of a graph, which can branch (when several nodes take the
+/// same node's outputs) and merge (when a node takes several nodes' outputs), so that a model
+/// can have several inputs and outputs.
+///
+/// Nodes are added with methods that return a [`NodeId`](struct.NodeId.html) to add later nodes
+/// on top of, so nodes only ever take the outputs of nodes added before them, and the graph
+/// can't have cycles. The graph feeds its inputs through its nodes in the order that they were
+/// added, and backpropagates through them in reverse, adding up the gradients of every node
+/// whose outputs are taken by several others.
+///
+/// Like a [`Sequential`](struct.Sequential.html) model, the graph's calculations use the
+/// `Float` type `T`, its activation is applied to the outputs of every layer with weights, and
+/// it is trained to minimise the squared error of its outputs (see
+/// [`Trainer::train_graph()`](../struct.Trainer.html#method.train_graph)).
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::layers::Graph;
+/// use scholar::{Dataset, Sigmoid, Trainer};
+///
+/// // Whether a reading is high, and whether it is higher than the sensor's usual readings
+/// let mut graph: Graph<Sigmoid> = Graph::new();
+/// let reading = graph.input(&[1]);
+/// let history = graph.input(&[4]);
+/// let summary = graph.layers(history, |block| block.dense(2));
+/// let merged = graph.concat(&[reading, summary]);
+/// let high = graph.layers(reading, |block| block.dense(1));
+/// let unusual = graph.layers(merged, |block| block.dense(4).dense(1));
+/// graph.set_outputs(&[high, unusual]);
+/// assert_eq!(graph.node(merged).output_shape(), &[3]);
+///
+/// let row = |reading: f64, usual: f64| {
+///     let inputs = vec![reading, usual, usual, usual, usual];
+///     let targets = vec![(reading > 0.5) as u8 as f64, (reading > usual) as u8 as f64];
+///     (inputs, targets)
+/// };
+/// let dataset: Dataset = (0..=4)
+///     .flat_map(|i| (0..=4).map(move |j| (i as f64 / 4.0, j as f64 / 4.0 + 0.125)))
+///     .map(|(reading, usual)| row(reading, usual))
+///     .collect();
+///
+/// Trainer::new(1000, 0.5).train_graph(&mut graph, dataset);
+/// let outputs = graph.guess(&[&[1.0], &[0.1; 4]]);
+/// assert_eq!(outputs.len(), 2);
+/// assert!(outputs[0][0] > 0.5 && outputs[1][0] > 0.5);
+/// ```
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Graph<A: Activation, T: Float = f64> {
+    /// Every node, each after every node that it takes the outputs of.
+    nodes: Vec<Node<T>>,
+    inputs: Vec<NodeId>,
+    outputs: Vec<NodeId>,
+    activation: PhantomData<fn() -> A>,
+}
+
+/// The ID of a node of a [`Graph`](struct.Graph.html), which is its index in the graph's nodes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    /// Returns the index of the node in the graph's nodes.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// A node of a [`Graph`](struct.Graph.html), along with the nodes whose outputs it takes and the
+/// shape of its outputs.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Node<T: Float = f64> {
+    kind: NodeKind<T>,
+    inputs: Vec<NodeId>,
+    output_shape: Vec<usize>,
+}
+
+/// The kind of a [`Node`](struct.Node.html), which holds the parameters of its layers.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub enum NodeKind<T: Float = f64> {
+    /// One of the graph's inputs (see [`Graph::input()`](struct.Graph.html#method.input)).
+    Input,
+    /// Layers that each node's outputs are fed through in turn (see
+    /// [`Graph::layers()`](struct.Graph.html#method.layers)).
+    Layers(Vec<Layer<T>>),
+    /// The sum of several nodes' outputs (see [`Graph::add()`](struct.Graph.html#method.add)).
+    Add,
+    /// Several nodes' outputs, one after another (see
+    /// [`Graph::concat()`](struct.Graph.html#method.concat)).
+    Concat,
+}
+
+impl<T: Float> Node<T> {
+    /// Returns the kind of the node, which holds the parameters of its layers.
+    pub fn kind(&self) -> &NodeKind<T> {
+        &self.kind
+    }
+
+    /// Returns the nodes whose outputs the node takes.
+    pub fn inputs(&self) -> &[NodeId] {
+        &self.inputs
+    }
+
+    /// Returns the shape of the node's outputs.
+    pub fn output_shape(&self) -> &[usize] {
+        &self.output_shape
+    }
+}
+
+impl<A: Activation + Serialize + DeserializeOwned, T: Float> Graph<A, T> {
+    /// Creates a graph without any nodes.
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            activation: PhantomData,
+        }
+    }
+
+    /// Adds an input of the given shape to the graph, after every input added before it.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the shape is empty or has a dimension of size 0.
+    pub fn input(&mut self, shape: &[usize]) -> NodeId {
+        if shape.is_empty() || shape.contains(&0) {
+            panic!("invalid input shape {:?}", shape);
+        }
+
+        let id = self.push(NodeKind::Input, Vec::new(), shape.to_vec());
+        self.inputs.push(id);
+        id
+    }
+
+    /// Adds a node that feeds the outputs of the given node through layers, which are added to
+    /// the model given to `block` (taking the given node's outputs) with the builder methods of a
+    /// [`Sequential`](struct.Sequential.html) model.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the given node isn't in the graph, or no layers are added.
+    pub fn layers(
+        &mut self,
+        node: NodeId,
+        block: impl FnOnce(Sequential<A, T>) -> Sequential<A, T>,
+    ) -> NodeId {
+        let block = block(Sequential::new(self.node(node).output_shape()));
+        if block.layers.is_empty() {
+            panic!("layer nodes need at least one layer");
+        }
+
+        let output_shape = block.output_shape().to_vec();
+        self.push(NodeKind::Layers(block.layers), vec![node], output_shape)
+    }
+
+    /// Adds a node that adds up the outputs of the given nodes, which all have the same shape.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if no nodes are given, a node isn't in the graph, or the nodes have
+    /// outputs of different shapes.
+    pub fn add(&mut self, nodes: &[NodeId]) -> NodeId {
+        let shape = self.merged_shape(nodes, "added");
+        if let Some(&node) = nodes
+            .iter()
+            .find(|&&node| self.node(node).output_shape != shape)
+        {
+            panic!(
+                "added nodes must have outputs of the same shape (expected {:?}, found {:?})",
+                shape,
+                self.node(node).output_shape
+            );
+        }
+
+        self.push(NodeKind::Add, nodes.to_vec(), shape)
+    }
+
+    /// Adds a node that concatenates the outputs of the given nodes along their first
+    /// dimension, such as the channels of images of the same height and width.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if no nodes are given, a node isn't in the graph, or the nodes have
+    /// outputs of shapes that differ in anything but their first dimension.
+    pub fn concat(&mut self, nodes: &[NodeId]) -> NodeId {
+        let mut shape = self.merged_shape(nodes, "concatenated");
+        shape[0] = 0;
+        for &node in nodes {
+            let other = &self.node(node).output_shape;
+            if other[1..] != shape[1..] {
+                panic!(
+                    "concatenated nodes must have outputs of shapes that only differ in their \
+                     first dimension (expected [_, {:?}], found {:?})",
+                    &shape[1..],
+                    other
+                );
+            }
+            shape[0] += other[0];
+        }
+
+        self.push(NodeKind::Concat, nodes.to_vec(), shape)
+    }
+
+    /// Sets the nodes whose outputs are the graph's outputs, in order.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if a node isn't in the graph.
+    pub fn set_outputs(&mut self, nodes: &[NodeId]) {
+        for &node in nodes {
+            self.node(node);
+        }
+        self.outputs = nodes.to_vec();
+    }
+
+    /// Returns the shape of the first of the given nodes' outputs, panicking (saying that they
+    /// are being `what`) if no nodes are given.
+    fn merged_shape(&self, nodes: &[NodeId], what: &str) -> Vec<usize> {
+        match nodes.first() {
+            Some(&node) => self.node(node).output_shape.clone(),
+            None => panic!("at least one node must be {}", what),
+        }
+    }
+
+    /// Adds a node to the graph, returning its ID.
+    fn push(&mut self, kind: NodeKind<T>, inputs: Vec<NodeId>, output_shape: Vec<usize>) -> NodeId {
+        self.nodes.push(Node {
+            kind,
+            inputs,
+            output_shape,
+        });
+        NodeId(self.nodes.len() - 1)
+    }
+
+    /// Returns the node with the given ID.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the node isn't in the graph.
+    pub fn node(&self, id: NodeId) -> &Node<T> {
+        self.nodes
+            .get(id.0)
+            .unwrap_or_else(|| panic!("node {} isn't in the graph", id.0))
+    }
+
+    /// Returns the graph's nodes, each after every node that it takes the outputs of.
+    pub fn nodes(&self) -> &[Node<T>] {
+        &self.nodes
+    }
+
+    /// Returns the graph's input nodes, in order.
+    pub fn inputs(&self) -> &[NodeId] {
+        &self.inputs
+    }
+
+    /// Returns the nodes whose outputs are the graph's outputs, in order.
+    pub fn outputs(&self) -> &[NodeId] {
+        &self.outputs
+    }
+
+    /// Returns the total number of values of the graph's inputs.
+    pub fn num_inputs(&self) -> usize {
+        self.total_len(&self.inputs)
+    }
+
+    /// Returns the total number of values of the graph's outputs.
+    pub fn num_outputs(&self) -> usize {
+        self.total_len(&self.outputs)
+    }
+
+    /// Returns the total number of values of the given nodes' outputs.
+    fn total_len(&self, nodes: &[NodeId]) -> usize {
+        nodes
+            .iter()
+            .map(|&node| self.node(node).output_shape.iter().product::<usize>())
+            .sum()
+    }
+
+    /// Feeds the given values of each of the graph's inputs through the graph, returning the
+    /// values of each of its outputs.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the graph has no outputs, or the number of inputs or of any input's
+    /// values doesn't match the graph's inputs.
+    pub fn guess(&self, inputs: &[&[f64]]) -> Vec<Vec<f64>> {
+        let values = self.feedforward(inputs);
+        self.outputs
+            .iter()
+            .map(|output| {
+                output_of(&values[output.0])
+                    .iter()
+                    .map(|x| x.as_f64())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Splits the values of every input, one after another, into the values of each input.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of values doesn't match the graph's inputs.
+    pub(crate) fn split_inputs<'a>(&self, inputs: &'a [f64]) -> Vec<&'a [f64]> {
+        if inputs.len() != self.num_inputs() {
+            panic!(
+                "incorrect number of inputs supplied (expected {}, found {})",
+                self.num_inputs(),
+                inputs.len()
+            );
+        }
+
+        let mut rest = inputs;
+        self.inputs
+            .iter()
+            .map(|&input| {
+                let (values, others) =
+                    rest.split_at(self.node(input).output_shape.iter().product());
+                rest = others;
+                values
+            })
+            .collect()
+    }
+
+    /// Feeds the given inputs through the graph, returning the inputs and outputs of every layer
+    /// of every node (or just the outputs of any other node).
+    pub(crate) fn feedforward(&self, inputs: &[&[f64]]) -> Vec<Vec<DMatrix<T>>> {
+        if self.outputs.is_empty() {
+            panic!("graph has no outputs (set them with `Graph::set_outputs()`)");
+        }
+        if inputs.len() != self.inputs.len() {
+            panic!(
+                "incorrect number of inputs supplied (expected {}, found {})",
+                self.inputs.len(),
+                inputs.len()
+            );
+        }
+
+        let mut inputs = inputs.iter();
+        let mut values: Vec<Vec<DMatrix<T>>> = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            let taken = || node.inputs.iter().map(|input| output_of(&values[input.0]));
+            let outputs = match &node.kind {
+                NodeKind::Input => {
+                    let inputs = inputs.next().unwrap();
+                    let len: usize = node.output_shape.iter().product();
+                    if inputs.len() != len {
+                        panic!(
+                            "incorrect number of values supplied for an input (expected {}, \
+                             found {})",
+                            len,
+                            inputs.len()
+                        );
+                    }
+                    vec![convert_slice_to_matrix(inputs)]
+                }
+                NodeKind::Layers(layers) => {
+                    feedforward_layers::<A, T>(layers, output_of(&values[node.inputs[0].0]).clone())
+                }
+                NodeKind::Add => {
+                    let mut taken = taken();
+                    let first = taken.next().unwrap().clone();
+                    vec![taken.fold(first, |sum, outputs| sum + outputs)]
+                }
+                NodeKind::Concat => {
+                    let data: Vec<T> = taken()
+                        .flat_map(|outputs| outputs.iter().copied())
+                        .collect();
+                    vec![DMatrix::from_vec(data.len(), 1, data)]
+                }
+            };
+            values.push(outputs);
+        }
+
+        values
+    }
+
+    /// Updates every layer's parameters by backpropagating the error of the outputs fed forward
+    /// by [`feedforward()`](#method.feedforward), given the targets of every output one after
+    /// another.
+    pub(crate) fn backpropagate(
+        &mut self,
+        values: &[Vec<DMatrix<T>>],
+        targets: &[f64],
+        learning_rate: f64,
+    ) {
+        if targets.len() != self.num_outputs() {
+            panic!(
+                "incorrect number of targets supplied (expected {}, found {})",
+                self.num_outputs(),
+                targets.len()
+            );
+        }
+
+        // The gradient of the (halved) squared error with respect to each output
+        let mut gradients: Vec<Option<DMatrix<T>>> = vec![None; self.nodes.len()];
+        let mut rest = targets;
+        for output in &self.outputs {
+            let outputs = output_of(&values[output.0]);
+            let (targets, others) = rest.split_at(outputs.len());
+            rest = others;
+            accumulate(
+                &mut gradients[output.0],
+                outputs - convert_slice_to_matrix::<T>(targets),
+            );
+        }
+
+        let learning_rate = T::convert(learning_rate);
+        for (i, node) in self.nodes.iter_mut().enumerate().rev() {
+            // Nodes that no output depends on have nothing to learn
+            let node_gradients = match gradients[i].take() {
+                Some(node_gradients) => node_gradients,
+                None => continue,
+            };
+
+            match &mut node.kind {
+                NodeKind::Input => {}
+                NodeKind::Layers(layers) => {
+                    let input_gradients = backpropagate_layers::<A, T>(
+                        layers,
+                        &values[i],
+                        node_gradients,
+                        learning_rate,
+                    );
+                    accumulate(&mut gradients[node.inputs[0].0], input_gradients);
+                }
+                NodeKind::Add => {
+                    for input in &node.inputs {
+                        accumulate(&mut gradients[input.0], node_gradients.clone());
+                    }
+                }
+                NodeKind::Concat => {
+                    let mut start = 0;
+                    for input in &node.inputs {
+                        let len = output_of(&values[input.0]).len();
+                        accumulate(
+                            &mut gradients[input.0],
+                            node_gradients.rows(start, len).into_owned(),
+                        );
+                        start += len;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the outputs of a node, given the values that it fed forward.
+fn output_of<T: Float>(values: &[DMatrix<T>]) -> &DMatrix<T> {
+    &values[values.len() - 1]
+}
+
+/// Adds the given gradients to the gradients of a node (which it might not have yet).
+fn accumulate<T: Float>(node_gradients: &mut Option<DMatrix<T>>, gradients: DMatrix<T>) {
+    *node_gradients = Some(match node_gradients.take() {
+        Some(sum) => sum + gradients,
+        None => gradients,
+    });
+}
+
+impl<A: Activation + Serialize + DeserializeOwned, T: Float> Default for Graph<A, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Activation, T: Float> Clone for Graph<A, T> {
+    fn clone(&self) -> Self {
+        Self {
+            nodes: self.nodes.clone(),
+            inputs: self.inputs.clone(),
+            outputs: self.outputs.clone(),
+            activation: PhantomData,
+        }
+    }
+}
+
+impl<A: Activation, T: Float> fmt::Debug for Graph<A, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Graph")
+            .field("activation", &A::name())
+            .field("nodes", &self.nodes)
+            .field("inputs", &self.inputs)
+            .field("outputs", &self.outputs)
+            .finish()
+    }
+}
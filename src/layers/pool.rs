@@ -0,0 +1,130 @@
+use super::Window;
+use crate::network::Float;
+
+use nalgebra::DMatrix;
+use serde::{Deserialize, Serialize};
+
+/// A pooling layer, which slides a window over each channel of its inputs separately, without
+/// overlapping, and reduces the values in the window at each position to their maximum or
+/// average.
+///
+/// Any values left over at the ends of each dimension (when the size of the inputs isn't a
+/// multiple of the window's) are ignored.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pool {
+    /// The window over a single channel.
+    window: Window,
+    channels: usize,
+}
+
+impl Pool {
+    /// Creates a layer that pools each of the given number of channels (each of the given height
+    /// and width) with a window of the given size.
+    pub(super) fn new(channels: usize, height: usize, width: usize, size: (usize, usize)) -> Self {
+        let window = Window {
+            channels: 1,
+            height,
+            width,
+            kernel: size,
+            stride: size,
+            padding: (0, 0),
+        };
+        window.output_size();
+
+        Self { window, channels }
+    }
+
+    /// Returns the height and width of the window (whose height is 1 in a 1D layer).
+    pub fn size(&self) -> (usize, usize) {
+        self.window.kernel
+    }
+
+    /// Returns the shape of the layer's outputs: the number of channels, followed by their
+    /// height and width.
+    pub(super) fn output_shape(&self) -> (usize, usize, usize) {
+        let (height, width) = self.window.output_size();
+        (self.channels, height, width)
+    }
+
+    /// Calls `f` with each channel, position of the window in it, and the index of each input
+    /// value in the window there.
+    fn for_each(&self, mut f: impl FnMut(usize, usize, usize)) {
+        let channel_len = self.window.height * self.window.width;
+        for channel in 0..self.channels {
+            self.window.for_each(|position, _, index| {
+                // Without padding, the window never leaves the inputs
+                let index = index.expect("pooling windows should fit the inputs");
+                f(channel, position, channel * channel_len + index);
+            });
+        }
+    }
+
+    /// Returns the number of positions of the window in each channel.
+    fn positions(&self) -> usize {
+        let (height, width) = self.window.output_size();
+        height * width
+    }
+
+    pub(super) fn forward_max<T: Float>(&self, inputs: &DMatrix<T>) -> DMatrix<T> {
+        let positions = self.positions();
+        let mut outputs = DMatrix::from_element(self.channels * positions, 1, T::neg_infinity());
+        self.for_each(|channel, position, index| {
+            let output = &mut outputs[channel * positions + position];
+            *output = output.max(inputs[index]);
+        });
+        outputs
+    }
+
+    /// Routes the gradient of each output to the input that was its maximum (the first, if
+    /// several were).
+    pub(super) fn backward_max<T: Float>(
+        &self,
+        inputs: &DMatrix<T>,
+        gradients: &DMatrix<T>,
+    ) -> DMatrix<T> {
+        let positions = self.positions();
+        let mut maximums: Vec<Option<usize>> = vec![None; self.channels * positions];
+        self.for_each(|channel, position, index| {
+            let maximum = &mut maximums[channel * positions + position];
+            if maximum.is_none_or(|maximum| inputs[index] > inputs[maximum]) {
+                *maximum = Some(index);
+            }
+        });
+
+        let mut input_gradients = DMatrix::<T>::zeros(inputs.nrows(), 1);
+        for (maximum, &gradient) in maximums.into_iter().zip(gradients.iter()) {
+            if let Some(index) = maximum {
+                input_gradients[index] += gradient;
+            }
+        }
+        input_gradients
+    }
+
+    pub(super) fn forward_average<T: Float>(&self, inputs: &DMatrix<T>) -> DMatrix<T> {
+        let positions = self.positions();
+        let mut outputs = DMatrix::<T>::zeros(self.channels * positions, 1);
+        self.for_each(|channel, position, index| {
+            outputs[channel * positions + position] += inputs[index];
+        });
+        outputs * self.share::<T>()
+    }
+
+    /// Shares the gradient of each output equally between the inputs that it averaged.
+    pub(super) fn backward_average<T: Float>(
+        &self,
+        inputs: &DMatrix<T>,
+        gradients: &DMatrix<T>,
+    ) -> DMatrix<T> {
+        let positions = self.positions();
+        let mut input_gradients = DMatrix::<T>::zeros(inputs.nrows(), 1);
+        self.for_each(|channel, position, index| {
+            input_gradients[index] += gradients[channel * positions + position];
+        });
+        input_gradients * self.share::<T>()
+    }
+
+    /// Returns the share of an average that each value in the window contributes.
+    fn share<T: Float>(&self) -> T {
+        T::one() / T::convert((self.window.kernel.0 * self.window.kernel.1) as f64)
+    }
+}
@@ -0,0 +1,663 @@
+use crate::network::{matrices::matrix, Activation, Float, Sigmoid};
+use crate::utils::gen_random_matrix;
+
+use nalgebra::{DMatrix, DVector, Dyn};
+use serde::{Deserialize, Serialize};
+
+/// The options of a recurrent layer (see
+/// [`Sequential::rnn_with()`](struct.Sequential.html#method.rnn_with)).
+///
+/// By default, the layer outputs only its final hidden state, summarising the whole sequence,
+/// and backpropagates through every step of it.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::layers::RecurrentOptions;
+///
+/// // Outputs the hidden state after every step, for another recurrent layer to read
+/// let options = RecurrentOptions::new().return_sequences(true);
+///
+/// // Only carries gradients back through (at most) 50 steps of long sequences
+/// let options = RecurrentOptions::new().truncate(50);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecurrentOptions {
+    pub(super) return_sequences: bool,
+    #[serde(default)]
+    truncate: Option<usize>,
+}
+
+impl RecurrentOptions {
+    /// Creates the default options, which output only the final hidden state, and don't
+    /// truncate backpropagation through time.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the layer outputs its hidden state after every step (a sequence of the same
+    /// number of steps as its inputs), rather than only after the last.
+    pub fn return_sequences(mut self, return_sequences: bool) -> Self {
+        self.return_sequences = return_sequences;
+        self
+    }
+
+    /// Truncates backpropagation through time to chunks of the given number of steps.
+    ///
+    /// The hidden state is still carried forward through the whole sequence, but the gradients
+    /// of each chunk aren't carried back into the chunk before it, which keeps them from
+    /// vanishing or exploding over very long sequences (at the cost of learning dependencies
+    /// that span chunks).
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of steps is 0.
+    pub fn truncate(mut self, steps: usize) -> Self {
+        if steps == 0 {
+            panic!("truncated chunks must have at least 1 step (found 0)");
+        }
+
+        self.truncate = Some(steps);
+        self
+    }
+
+    /// Returns whether gradients are carried back from step `t` into the step before it.
+    fn carries_back(&self, t: usize) -> bool {
+        self.truncate.is_none_or(|steps| !t.is_multiple_of(steps))
+    }
+}
+
+/// A simple (Elman) recurrent layer, which reads a sequence one step at a time, updating a
+/// hidden state from each step's inputs and its previous value.
+///
+/// The hidden state starts at zero, and is activated with `tanh` (whatever the model's
+/// activation) at every step:
+///
+/// ```text
+/// h[t] = tanh(input_weights * x[t] + recurrent_weights * h[t - 1] + biases)
+/// ```
+///
+/// Inputs are stored step by step, and so are outputs when the layer returns sequences.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Rnn<T: Float = f64> {
+    /// A row per hidden node and a column per input.
+    #[serde(with = "matrix")]
+    input_weights: DMatrix<T>,
+    /// A row and a column per hidden node.
+    #[serde(with = "matrix")]
+    recurrent_weights: DMatrix<T>,
+    #[serde(with = "matrix")]
+    biases: DMatrix<T>,
+    options: RecurrentOptions,
+}
+
+impl<T: Float> Rnn<T> {
+    /// Creates a layer with the given numbers of inputs (per step) and hidden nodes, and random
+    /// weights and biases.
+    pub(super) fn new(num_inputs: usize, hidden: usize, options: RecurrentOptions) -> Self {
+        Self {
+            input_weights: gen_random_matrix(hidden, num_inputs),
+            recurrent_weights: gen_random_matrix(hidden, hidden),
+            biases: gen_random_matrix(hidden, 1),
+            options,
+        }
+    }
+
+    /// Returns the number of hidden nodes, i.e. of values in the hidden state.
+    pub fn hidden_size(&self) -> usize {
+        self.biases.nrows()
+    }
+
+    /// Returns whether the layer outputs its hidden state after every step, rather than only
+    /// after the last.
+    pub fn returns_sequences(&self) -> bool {
+        self.options.return_sequences
+    }
+
+    /// Returns the weights of the inputs, which have a row per hidden node and a column per
+    /// input (of each step).
+    pub fn input_weights(&self) -> &DMatrix<T> {
+        &self.input_weights
+    }
+
+    /// Returns the weights of the previous hidden state, which have a row and a column per
+    /// hidden node.
+    pub fn recurrent_weights(&self) -> &DMatrix<T> {
+        &self.recurrent_weights
+    }
+
+    /// Returns the layer's biases, which have a row per hidden node.
+    pub fn biases(&self) -> &DMatrix<T> {
+        &self.biases
+    }
+
+    /// Returns every matrix of the layer's parameters.
+    pub(super) fn parameters_mut(&mut self) -> Vec<&mut DMatrix<T>> {
+        vec![
+            &mut self.input_weights,
+            &mut self.recurrent_weights,
+            &mut self.biases,
+        ]
+    }
+
+    /// Returns the hidden state after every step, as a column per step.
+    fn states(&self, steps: &DMatrix<T>) -> DMatrix<T> {
+        let mut states = &self.input_weights * steps;
+        for t in 0..states.ncols() {
+            let recurrent = if t > 0 {
+                &self.recurrent_weights * states.column(t - 1) + self.biases.column(0)
+            } else {
+                self.biases.column(0).into_owned()
+            };
+            let mut state = states.column_mut(t);
+            state += recurrent;
+            state.apply(|x| *x = x.tanh());
+        }
+        states
+    }
+
+    pub(super) fn forward(&self, inputs: &DMatrix<T>) -> DMatrix<T> {
+        let states = self.states(&steps(inputs, self.input_weights.ncols()));
+        outputs(states, self.options.return_sequences)
+    }
+
+    /// Backpropagates through time, from the last step to the first.
+    pub(super) fn backward(
+        &mut self,
+        inputs: &DMatrix<T>,
+        gradients: &DMatrix<T>,
+        learning_rate: T,
+    ) -> DMatrix<T> {
+        let steps = steps(inputs, self.input_weights.ncols());
+        let states = self.states(&steps);
+        let mut totals = Gradients::zeros(&self.input_weights, &self.recurrent_weights, &steps);
+
+        // The gradient with respect to the hidden state, carried back from the following step
+        let mut carried = DMatrix::zeros(self.hidden_size(), 1);
+        for t in (0..steps.ncols()).rev() {
+            add_output_gradients(&mut carried, gradients, t, steps.ncols(), &self.options);
+
+            // Through the tanh, whose derivative is 1 - tanh^2
+            let mut deltas = carried;
+            for (delta, &state) in deltas.iter_mut().zip(states.column(t).iter()) {
+                *delta *= T::one() - state * state;
+            }
+
+            totals.add_step(t, &steps, &states, &deltas, &deltas, &self.input_weights);
+            carried = self.recurrent_weights.tr_mul(&deltas);
+            if !self.options.carries_back(t) {
+                carried.fill(T::zero());
+            }
+        }
+
+        totals.apply(
+            &mut self.input_weights,
+            &mut self.recurrent_weights,
+            &mut self.biases,
+            learning_rate,
+        )
+    }
+}
+
+/// A long short-term memory (LSTM) layer, which reads a sequence one step at a time like an
+/// [`Rnn`](struct.Rnn.html), but carries a cell state alongside its hidden state, which gates
+/// decide how much of to forget, update and output at each step.
+///
+/// Since the cell state is only ever scaled and added to, its gradients survive being carried
+/// back through many more steps than those of an `Rnn`'s hidden state, so it can learn
+/// dependencies between steps far apart.
+///
+/// The hidden and cell states start at zero, and the input (`i`), forget (`f`) and output
+/// (`o`) gates are activated with the sigmoid, and the cell's candidate values (`g`) with
+/// `tanh` (whatever the model's activation) at every step:
+///
+/// ```text
+/// c[t] = f[t] * c[t - 1] + i[t] * g[t]
+/// h[t] = o[t] * tanh(c[t])
+/// ```
+///
+/// The weights and biases of the four are stacked in that order (`i`, `f`, `g`, `o`), and the
+/// biases of the forget gate start 1 higher than the others, so that the cell remembers by
+/// default.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Lstm<T: Float = f64> {
+    /// A row per hidden node of each gate in turn, and a column per input.
+    #[serde(with = "matrix")]
+    input_weights: DMatrix<T>,
+    /// A row per hidden node of each gate in turn, and a column per hidden node.
+    #[serde(with = "matrix")]
+    recurrent_weights: DMatrix<T>,
+    #[serde(with = "matrix")]
+    biases: DMatrix<T>,
+    options: RecurrentOptions,
+}
+
+impl<T: Float> Lstm<T> {
+    /// Creates a layer with the given numbers of inputs (per step) and hidden nodes, and random
+    /// weights and biases.
+    pub(super) fn new(num_inputs: usize, hidden: usize, options: RecurrentOptions) -> Self {
+        let mut biases = gen_random_matrix(4 * hidden, 1);
+        biases.rows_mut(hidden, hidden).add_scalar_mut(T::one());
+
+        Self {
+            input_weights: gen_random_matrix(4 * hidden, num_inputs),
+            recurrent_weights: gen_random_matrix(4 * hidden, hidden),
+            biases,
+            options,
+        }
+    }
+
+    /// Returns the number of hidden nodes, i.e. of values in the hidden and cell states.
+    pub fn hidden_size(&self) -> usize {
+        self.biases.nrows() / 4
+    }
+
+    /// Returns whether the layer outputs its hidden state after every step, rather than only
+    /// after the last.
+    pub fn returns_sequences(&self) -> bool {
+        self.options.return_sequences
+    }
+
+    /// Returns the weights of the inputs, which have a row per hidden node of each gate (`i`,
+    /// `f`, `g` and `o`) in turn, and a column per input (of each step).
+    pub fn input_weights(&self) -> &DMatrix<T> {
+        &self.input_weights
+    }
+
+    /// Returns the weights of the previous hidden state, which have a row per hidden node of
+    /// each gate in turn, and a column per hidden node.
+    pub fn recurrent_weights(&self) -> &DMatrix<T> {
+        &self.recurrent_weights
+    }
+
+    /// Returns the layer's biases, which have a row per hidden node of each gate in turn.
+    pub fn biases(&self) -> &DMatrix<T> {
+        &self.biases
+    }
+
+    /// Returns every matrix of the layer's parameters.
+    pub(super) fn parameters_mut(&mut self) -> Vec<&mut DMatrix<T>> {
+        vec![
+            &mut self.input_weights,
+            &mut self.recurrent_weights,
+            &mut self.biases,
+        ]
+    }
+
+    /// Returns the activated gates, the cell state and the hidden state after every step, each
+    /// as a column per step.
+    fn states(&self, steps: &DMatrix<T>) -> (DMatrix<T>, DMatrix<T>, DMatrix<T>) {
+        let hidden = self.hidden_size();
+        let mut gates = &self.input_weights * steps;
+        let mut cells = DMatrix::zeros(hidden, steps.ncols());
+        let mut states = DMatrix::zeros(hidden, steps.ncols());
+        for t in 0..steps.ncols() {
+            let recurrent = if t > 0 {
+                &self.recurrent_weights * states.column(t - 1) + self.biases.column(0)
+            } else {
+                self.biases.column(0).into_owned()
+            };
+            let mut gate = gates.column_mut(t);
+            gate += recurrent;
+            for (i, x) in gate.iter_mut().enumerate() {
+                *x = if i / hidden == 2 {
+                    x.tanh()
+                } else {
+                    Sigmoid::activate_float(*x)
+                };
+            }
+
+            for j in 0..hidden {
+                let previous = if t > 0 { cells[(j, t - 1)] } else { T::zero() };
+                let cell = gate[hidden + j] * previous + gate[j] * gate[2 * hidden + j];
+                cells[(j, t)] = cell;
+                states[(j, t)] = gate[3 * hidden + j] * cell.tanh();
+            }
+        }
+        (gates, cells, states)
+    }
+
+    pub(super) fn forward(&self, inputs: &DMatrix<T>) -> DMatrix<T> {
+        let (_, _, states) = self.states(&steps(inputs, self.input_weights.ncols()));
+        outputs(states, self.options.return_sequences)
+    }
+
+    /// Backpropagates through time, from the last step to the first.
+    pub(super) fn backward(
+        &mut self,
+        inputs: &DMatrix<T>,
+        gradients: &DMatrix<T>,
+        learning_rate: T,
+    ) -> DMatrix<T> {
+        let hidden = self.hidden_size();
+        let steps = steps(inputs, self.input_weights.ncols());
+        let (gates, cells, states) = self.states(&steps);
+        let mut totals = Gradients::zeros(&self.input_weights, &self.recurrent_weights, &steps);
+
+        // The gradients with respect to the hidden and cell states, carried back from the
+        // following step
+        let mut carried = DMatrix::zeros(hidden, 1);
+        let mut carried_cells = DMatrix::<T>::zeros(hidden, 1);
+        let mut deltas = DMatrix::zeros(4 * hidden, 1);
+        for t in (0..steps.ncols()).rev() {
+            add_output_gradients(&mut carried, gradients, t, steps.ncols(), &self.options);
+
+            let gate = gates.column(t);
+            for j in 0..hidden {
+                let (input, forget) = (gate[j], gate[hidden + j]);
+                let (candidate, output) = (gate[2 * hidden + j], gate[3 * hidden + j]);
+                let cell = cells[(j, t)].tanh();
+                let previous = if t > 0 { cells[(j, t - 1)] } else { T::zero() };
+
+                let cell_gradient =
+                    carried_cells[j] + carried[j] * output * (T::one() - cell * cell);
+                deltas[j] = cell_gradient * candidate * input * (T::one() - input);
+                deltas[hidden + j] = cell_gradient * previous * forget * (T::one() - forget);
+                deltas[2 * hidden + j] = cell_gradient * input * (T::one() - candidate * candidate);
+                deltas[3 * hidden + j] = carried[j] * cell * output * (T::one() - output);
+                carried_cells[j] = cell_gradient * forget;
+            }
+
+            totals.add_step(t, &steps, &states, &deltas, &deltas, &self.input_weights);
+            carried = self.recurrent_weights.tr_mul(&deltas);
+            if !self.options.carries_back(t) {
+                carried.fill(T::zero());
+                carried_cells.fill(T::zero());
+            }
+        }
+
+        totals.apply(
+            &mut self.input_weights,
+            &mut self.recurrent_weights,
+            &mut self.biases,
+            learning_rate,
+        )
+    }
+}
+
+/// A gated recurrent unit (GRU) layer, a lighter alternative to an [`Lstm`](struct.Lstm.html)
+/// with three sets of weights rather than four, and no cell state.
+///
+/// Its update gate (`z`) decides how much of the hidden state to keep at each step, and how
+/// much to replace with candidate values (`n`), which its reset gate (`r`) decides how much of
+/// the previous hidden state to compute from. The hidden state starts at zero, and the gates
+/// are activated with the sigmoid, and the candidate values with `tanh` (whatever the model's
+/// activation) at every step:
+///
+/// ```text
+/// n[t] = tanh(input_weights_n * x[t] + biases_n + r[t] * (recurrent_weights_n * h[t - 1]))
+/// h[t] = z[t] * h[t - 1] + (1 - z[t]) * n[t]
+/// ```
+///
+/// The weights and biases of the three are stacked in that order (`z`, `r`, `n`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Gru<T: Float = f64> {
+    /// A row per hidden node of each gate in turn, and a column per input.
+    #[serde(with = "matrix")]
+    input_weights: DMatrix<T>,
+    /// A row per hidden node of each gate in turn, and a column per hidden node.
+    #[serde(with = "matrix")]
+    recurrent_weights: DMatrix<T>,
+    #[serde(with = "matrix")]
+    biases: DMatrix<T>,
+    options: RecurrentOptions,
+}
+
+impl<T: Float> Gru<T> {
+    /// Creates a layer with the given numbers of inputs (per step) and hidden nodes, and random
+    /// weights and biases.
+    pub(super) fn new(num_inputs: usize, hidden: usize, options: RecurrentOptions) -> Self {
+        Self {
+            input_weights: gen_random_matrix(3 * hidden, num_inputs),
+            recurrent_weights: gen_random_matrix(3 * hidden, hidden),
+            biases: gen_random_matrix(3 * hidden, 1),
+            options,
+        }
+    }
+
+    /// Returns the number of hidden nodes, i.e. of values in the hidden state.
+    pub fn hidden_size(&self) -> usize {
+        self.biases.nrows() / 3
+    }
+
+    /// Returns whether the layer outputs its hidden state after every step, rather than only
+    /// after the last.
+    pub fn returns_sequences(&self) -> bool {
+        self.options.return_sequences
+    }
+
+    /// Returns the weights of the inputs, which have a row per hidden node of each gate (`z`,
+    /// `r` and `n`) in turn, and a column per input (of each step).
+    pub fn input_weights(&self) -> &DMatrix<T> {
+        &self.input_weights
+    }
+
+    /// Returns the weights of the previous hidden state, which have a row per hidden node of
+    /// each gate in turn, and a column per hidden node.
+    pub fn recurrent_weights(&self) -> &DMatrix<T> {
+        &self.recurrent_weights
+    }
+
+    /// Returns the layer's biases, which have a row per hidden node of each gate in turn.
+    pub fn biases(&self) -> &DMatrix<T> {
+        &self.biases
+    }
+
+    /// Returns every matrix of the layer's parameters.
+    pub(super) fn parameters_mut(&mut self) -> Vec<&mut DMatrix<T>> {
+        vec![
+            &mut self.input_weights,
+            &mut self.recurrent_weights,
+            &mut self.biases,
+        ]
+    }
+
+    /// Returns the activated gates, the weighted previous hidden state that the candidate values
+    /// are reset from, and the hidden state after every step, each as a column per step.
+    fn states(&self, steps: &DMatrix<T>) -> (DMatrix<T>, DMatrix<T>, DMatrix<T>) {
+        let hidden = self.hidden_size();
+        let mut gates = &self.input_weights * steps;
+        let mut resets = DMatrix::zeros(hidden, steps.ncols());
+        let mut states = DMatrix::zeros(hidden, steps.ncols());
+        for t in 0..steps.ncols() {
+            let recurrent = if t > 0 {
+                &self.recurrent_weights * states.column(t - 1)
+            } else {
+                DVector::zeros(3 * hidden)
+            };
+            let mut gate = gates.column_mut(t);
+            gate += self.biases.column(0);
+            for j in 0..2 * hidden {
+                gate[j] = Sigmoid::activate_float(gate[j] + recurrent[j]);
+            }
+
+            for j in 0..hidden {
+                resets[(j, t)] = recurrent[2 * hidden + j];
+                let candidate = (gate[2 * hidden + j] + gate[hidden + j] * resets[(j, t)]).tanh();
+                gate[2 * hidden + j] = candidate;
+
+                let previous = if t > 0 { states[(j, t - 1)] } else { T::zero() };
+                let update = gate[j];
+                states[(j, t)] = update * previous + (T::one() - update) * candidate;
+            }
+        }
+        (gates, resets, states)
+    }
+
+    pub(super) fn forward(&self, inputs: &DMatrix<T>) -> DMatrix<T> {
+        let (_, _, states) = self.states(&steps(inputs, self.input_weights.ncols()));
+        outputs(states, self.options.return_sequences)
+    }
+
+    /// Backpropagates through time, from the last step to the first.
+    pub(super) fn backward(
+        &mut self,
+        inputs: &DMatrix<T>,
+        gradients: &DMatrix<T>,
+        learning_rate: T,
+    ) -> DMatrix<T> {
+        let hidden = self.hidden_size();
+        let steps = steps(inputs, self.input_weights.ncols());
+        let (gates, resets, states) = self.states(&steps);
+        let mut totals = Gradients::zeros(&self.input_weights, &self.recurrent_weights, &steps);
+
+        // The gradient with respect to the hidden state, carried back from the following step
+        let mut carried = DMatrix::zeros(hidden, 1);
+        let mut deltas = DMatrix::zeros(3 * hidden, 1);
+        for t in (0..steps.ncols()).rev() {
+            add_output_gradients(&mut carried, gradients, t, steps.ncols(), &self.options);
+
+            // The previous hidden state is reset before it is added to the candidates' sums
+            let mut recurrent_deltas = DMatrix::zeros(3 * hidden, 1);
+            let gate = gates.column(t);
+            for j in 0..hidden {
+                let (update, reset, candidate) = (gate[j], gate[hidden + j], gate[2 * hidden + j]);
+                let previous = if t > 0 { states[(j, t - 1)] } else { T::zero() };
+
+                let candidate_delta =
+                    carried[j] * (T::one() - update) * (T::one() - candidate * candidate);
+                deltas[j] = carried[j] * (previous - candidate) * update * (T::one() - update);
+                deltas[hidden + j] = candidate_delta * resets[(j, t)] * reset * (T::one() - reset);
+                deltas[2 * hidden + j] = candidate_delta;
+
+                recurrent_deltas[j] = deltas[j];
+                recurrent_deltas[hidden + j] = deltas[hidden + j];
+                recurrent_deltas[2 * hidden + j] = candidate_delta * reset;
+                carried[j] *= update;
+            }
+
+            totals.add_step(
+                t,
+                &steps,
+                &states,
+                &deltas,
+                &recurrent_deltas,
+                &self.input_weights,
+            );
+            carried += self.recurrent_weights.tr_mul(&recurrent_deltas);
+            if !self.options.carries_back(t) {
+                carried.fill(T::zero());
+            }
+        }
+
+        totals.apply(
+            &mut self.input_weights,
+            &mut self.recurrent_weights,
+            &mut self.biases,
+            learning_rate,
+        )
+    }
+}
+
+/// Returns a layer's inputs (a sequence) with a column per step of the given number of inputs.
+pub(super) fn steps<T: Float>(inputs: &DMatrix<T>, num_inputs: usize) -> DMatrix<T> {
+    inputs
+        .clone()
+        .reshape_generic(Dyn(num_inputs), Dyn(inputs.len() / num_inputs))
+}
+
+/// Returns a recurrent layer's outputs, given its hidden state after every step: all of them
+/// step by step if it returns sequences, or the last otherwise.
+fn outputs<T: Float>(states: DMatrix<T>, return_sequences: bool) -> DMatrix<T> {
+    if return_sequences {
+        flatten(states)
+    } else {
+        states.columns(states.ncols() - 1, 1).into_owned()
+    }
+}
+
+/// Adds the gradients of a recurrent layer's outputs with respect to its hidden state at step
+/// `t` (of the given number) to those carried back from the following step.
+fn add_output_gradients<T: Float>(
+    carried: &mut DMatrix<T>,
+    gradients: &DMatrix<T>,
+    t: usize,
+    num_steps: usize,
+    options: &RecurrentOptions,
+) {
+    if options.return_sequences {
+        *carried += gradients.rows(t * carried.nrows(), carried.nrows());
+    } else if t == num_steps - 1 {
+        *carried += gradients;
+    }
+}
+
+/// The gradients of a recurrent layer's weights, biases and inputs, accumulated step by step
+/// while backpropagating through time.
+struct Gradients<T: Float> {
+    input_weights: DMatrix<T>,
+    recurrent_weights: DMatrix<T>,
+    biases: DMatrix<T>,
+    steps: DMatrix<T>,
+}
+
+impl<T: Float> Gradients<T> {
+    /// Creates zero gradients for a layer with the given weights and inputs (a column per step).
+    fn zeros(
+        input_weights: &DMatrix<T>,
+        recurrent_weights: &DMatrix<T>,
+        steps: &DMatrix<T>,
+    ) -> Self {
+        Self {
+            input_weights: DMatrix::zeros(input_weights.nrows(), input_weights.ncols()),
+            recurrent_weights: DMatrix::zeros(recurrent_weights.nrows(), recurrent_weights.ncols()),
+            biases: DMatrix::zeros(input_weights.nrows(), 1),
+            steps: DMatrix::zeros(steps.nrows(), steps.ncols()),
+        }
+    }
+
+    /// Adds the gradients of step `t`, given the gradients with respect to the weighted sums of
+    /// the step's inputs (along with the biases) and of the previous hidden state, which are
+    /// the same unless the layer scales the latter before adding them.
+    fn add_step(
+        &mut self,
+        t: usize,
+        steps: &DMatrix<T>,
+        states: &DMatrix<T>,
+        input_deltas: &DMatrix<T>,
+        recurrent_deltas: &DMatrix<T>,
+        input_weights: &DMatrix<T>,
+    ) {
+        let input_deltas = input_deltas.column(0);
+        self.input_weights
+            .ger(T::one(), &input_deltas, &steps.column(t), T::one());
+        if t > 0 {
+            self.recurrent_weights.ger(
+                T::one(),
+                &recurrent_deltas.column(0),
+                &states.column(t - 1),
+                T::one(),
+            );
+        }
+        let mut biases = self.biases.column_mut(0);
+        biases += input_deltas;
+        self.steps
+            .column_mut(t)
+            .copy_from(&input_weights.tr_mul(&input_deltas));
+    }
+
+    /// Updates the given weights and biases by gradient descent, returning the gradients of the
+    /// inputs (step by step).
+    fn apply(
+        self,
+        input_weights: &mut DMatrix<T>,
+        recurrent_weights: &mut DMatrix<T>,
+        biases: &mut DMatrix<T>,
+        learning_rate: T,
+    ) -> DMatrix<T> {
+        *input_weights -= self.input_weights * learning_rate;
+        *recurrent_weights -= self.recurrent_weights * learning_rate;
+        *biases -= self.biases * learning_rate;
+
+        flatten(self.steps)
+    }
+}
+
+/// Returns a sequence with a column per step as a single column, step by step.
+pub(super) fn flatten<T: Float>(steps: DMatrix<T>) -> DMatrix<T> {
+    let len = steps.len();
+    steps.reshape_generic(Dyn(len), Dyn(1))
+}
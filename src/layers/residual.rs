@@ -0,0 +1,65 @@
+use super::{backpropagate_layers, feedforward_layers, Layer};
+use crate::network::{Activation, Float};
+
+use nalgebra::DMatrix;
+use serde::{Deserialize, Serialize};
+
+/// A block of layers with a skip connection around it, which adds the block's inputs to its
+/// outputs.
+///
+/// The block only has to learn how its outputs differ from its inputs, and the gradients of its
+/// outputs flow back to its inputs unchanged through the skip connection, which lets far deeper
+/// models be trained. Each of the block's layers is activated as in the rest of the model, but
+/// the sum isn't activated again.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Residual<T: Float = f64> {
+    layers: Vec<Layer<T>>,
+}
+
+impl<T: Float> Residual<T> {
+    /// Creates a skip connection around the given layers.
+    pub(super) fn new(layers: Vec<Layer<T>>) -> Self {
+        Self { layers }
+    }
+
+    /// Returns the layers of the block.
+    pub fn layers(&self) -> &[Layer<T>] {
+        &self.layers
+    }
+
+    /// Returns whether the block outputs a sequence of any number of steps.
+    pub(super) fn returns_sequences(&self) -> bool {
+        self.layers.iter().all(Layer::returns_sequences)
+    }
+
+    /// Returns every matrix of the parameters of the block's layers.
+    pub(super) fn parameters_mut(&mut self) -> Vec<&mut DMatrix<T>> {
+        self.layers
+            .iter_mut()
+            .flat_map(Layer::parameters_mut)
+            .collect()
+    }
+
+    pub(super) fn forward<A: Activation>(&self, inputs: &DMatrix<T>) -> DMatrix<T> {
+        let mut values = feedforward_layers::<A, T>(&self.layers, inputs.clone());
+        let outputs = values.pop().unwrap();
+        outputs + inputs
+    }
+
+    pub(super) fn backward<A: Activation>(
+        &mut self,
+        inputs: &DMatrix<T>,
+        gradients: &DMatrix<T>,
+        learning_rate: T,
+    ) -> DMatrix<T> {
+        let values = feedforward_layers::<A, T>(&self.layers, inputs.clone());
+        let block_gradients = backpropagate_layers::<A, T>(
+            &mut self.layers,
+            &values,
+            gradients.clone(),
+            learning_rate,
+        );
+        block_gradients + gradients
+    }
+}
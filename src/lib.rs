@@ -1,8 +1,72 @@
 //! A supervised machine learning library.
+//!
+//! Everything besides inference (i.e. training, datasets, metrics, and saving and loading networks)
+//! requires the default `std` feature. Without it, the crate is `no_std` (but requires `alloc`),
+//! so that trained networks can be deserialized (with any `serde` format) and run on embedded
+//! devices.
+//!
+//! Reading and writing files requires the default `fs` feature, and the progress bar shown whilst
+//! training requires the default `progress` feature. To train or run networks on
+//! `wasm32-unknown-unknown` (e.g. in the browser), disable the default features and enable `std`
+//! and `wasm` instead, then save and load networks in memory with
+//! [`NeuralNet::save_to_bytes()`](struct.NeuralNet.html#method.save_to_bytes) and
+//! [`NeuralNet::from_bytes()`](struct.NeuralNet.html#method.from_bytes).
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod adversarial;
+#[cfg(feature = "ndarray")]
+mod array;
+#[cfg(feature = "fs")]
+pub mod checkpoint;
+#[cfg(feature = "fs")]
+pub mod config;
+#[cfg(feature = "std")]
 mod dataset;
+#[cfg(feature = "std")]
+pub mod datasets;
+#[cfg(feature = "std")]
+pub mod federated;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod layers;
+#[cfg(feature = "std")]
+pub mod metrics;
 mod network;
+#[cfg(feature = "std")]
+pub mod preprocessing;
+#[cfg(feature = "std")]
+pub mod privacy;
+#[cfg(feature = "fs")]
+mod protobuf;
+#[cfg(feature = "fs")]
+pub mod registry;
+#[cfg(feature = "fs")]
+pub mod scaffold;
+#[cfg(feature = "serve")]
+pub mod serve;
+#[cfg(feature = "std")]
+mod trainer;
+#[cfg(feature = "fs")]
+pub mod training_log;
+#[cfg(feature = "std")]
+pub mod tuning;
 mod utils;
+#[cfg(feature = "std")]
+pub mod validation;
+#[cfg(feature = "std")]
+pub mod viz;
 
+#[cfg(feature = "std")]
 pub use dataset::*;
+/// The linear algebra library behind networks' weights and biases (see
+/// [`NeuralNet::weights()`](struct.NeuralNet.html#method.weights)), re-exported so that its
+/// version always matches.
+pub use nalgebra;
 pub use network::*;
+#[cfg(feature = "std")]
+pub use trainer::*;
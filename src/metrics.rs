@@ -0,0 +1,1096 @@
+//! Standard classification and regression metrics for evaluating a `NeuralNet` against a
+//! `Dataset`.
+//!
+//! For classification metrics, the predicted and actual class of each row are decoded from the
+//! network's outputs and the dataset's targets respectively: a single output is treated as a
+//! binary classification (thresholded at the network's
+//! [`threshold()`](../struct.NeuralNet.html#method.threshold), 0.5 by default), whereas multiple
+//! outputs are treated as one-hot encoded classes (the index of the largest value being the
+//! class).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scholar::{metrics, Dataset, NeuralNet, Sigmoid};
+//!
+//! let dataset = Dataset::from(vec![
+//!     (vec![0.0, 0.0], vec![0.0]),
+//!     (vec![0.0, 1.0], vec![1.0]),
+//!     (vec![1.0, 0.0], vec![1.0]),
+//!     (vec![1.0, 1.0], vec![0.0]),
+//! ]);
+//!
+//! let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 2, 1]);
+//!
+//! let accuracy = metrics::accuracy(&brain, &dataset);
+//! let f1 = metrics::f1(&brain, &dataset, metrics::Average::Macro);
+//! assert!(accuracy >= 0.0 && accuracy <= 1.0);
+//! assert!(f1 >= 0.0 && f1 <= 1.0);
+//! ```
+
+mod report;
+
+pub use report::*;
+
+use crate::dataset::Dataset;
+use crate::network::{Activation, Float, NeuralNet};
+use crate::utils::decode_class;
+
+use rand::Rng;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// The strategy used to combine per-class scores into a single score.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Average {
+    /// The unweighted mean of the per-class scores.
+    Macro,
+    /// The score calculated from the total true positives, false positives and false negatives
+    /// across all classes.
+    Micro,
+    /// The mean of the per-class scores, weighted by the number of rows belonging to each class.
+    Weighted,
+}
+
+/// A table counting how often each actual class was predicted as each class.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfusionMatrix {
+    /// Indexed first by actual class, then by predicted class.
+    counts: Vec<Vec<usize>>,
+}
+
+impl ConfusionMatrix {
+    /// Creates a `ConfusionMatrix` by comparing the network's guesses with the targets of every
+    /// row in the given `Dataset`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{metrics::ConfusionMatrix, Dataset, NeuralNet, Sigmoid};
+    ///
+    /// let dataset = Dataset::from(vec![
+    ///     (vec![0.0], vec![1.0, 0.0, 0.0]),
+    ///     (vec![1.0], vec![0.0, 0.0, 1.0]),
+    /// ]);
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[1, 3]);
+    ///
+    /// let matrix = ConfusionMatrix::new(&brain, &dataset);
+    /// assert_eq!(matrix.num_classes(), 3);
+    /// ```
+    pub fn new<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        network: &NeuralNet<A, T>,
+        dataset: &Dataset,
+    ) -> Self {
+        Predictions::new(network, dataset).confusion_matrix()
+    }
+
+    /// Creates a `ConfusionMatrix` from `(actual, predicted)` pairs of class indices.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::metrics::ConfusionMatrix;
+    ///
+    /// let matrix = ConfusionMatrix::from_pairs(2, vec![(0, 0), (0, 1), (1, 1), (1, 1)]);
+    /// assert_eq!(matrix.count(0, 1), 1);
+    /// assert_eq!(matrix.accuracy(), 0.75);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if any class index is not less than `num_classes`.
+    pub fn from_pairs(num_classes: usize, pairs: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        let mut counts = vec![vec![0; num_classes]; num_classes];
+        for (actual, predicted) in pairs {
+            if actual >= num_classes || predicted >= num_classes {
+                panic!(
+                    "class index out of range (expected less than {}, found {})",
+                    num_classes,
+                    actual.max(predicted)
+                );
+            }
+            counts[actual][predicted] += 1;
+        }
+
+        Self { counts }
+    }
+
+    /// Returns the number of classes in the matrix.
+    pub fn num_classes(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Counts a single classification, growing the matrix to at least `min_classes` classes (or
+    /// more if either class hasn't been seen).
+    fn record(&mut self, actual: usize, predicted: usize, min_classes: usize) {
+        let num_classes = min_classes
+            .max(self.num_classes())
+            .max(actual + 1)
+            .max(predicted + 1);
+        if num_classes > self.num_classes() {
+            for row in &mut self.counts {
+                row.resize(num_classes, 0);
+            }
+            self.counts.resize(num_classes, vec![0; num_classes]);
+        }
+        self.counts[actual][predicted] += 1;
+    }
+
+    /// Returns the number of rows of the `actual` class that were predicted as the `predicted`
+    /// class.
+    pub fn count(&self, actual: usize, predicted: usize) -> usize {
+        self.counts[actual][predicted]
+    }
+
+    /// Returns the total number of rows counted.
+    pub fn total(&self) -> usize {
+        self.counts.iter().flatten().sum()
+    }
+
+    /// Returns the number of rows whose actual class is the given `class`.
+    pub fn support(&self, class: usize) -> usize {
+        self.counts[class].iter().sum()
+    }
+
+    /// Returns the proportion of rows that were classified correctly.
+    pub fn accuracy(&self) -> f64 {
+        let correct: usize = (0..self.num_classes()).map(|c| self.counts[c][c]).sum();
+        ratio(correct, self.total())
+    }
+
+    /// Returns the precision of a single class: the proportion of rows predicted as the class
+    /// that actually belong to it.
+    pub fn class_precision(&self, class: usize) -> f64 {
+        let predicted: usize = self.counts.iter().map(|row| row[class]).sum();
+        ratio(self.counts[class][class], predicted)
+    }
+
+    /// Returns the recall of a single class: the proportion of rows belonging to the class that
+    /// were predicted as it.
+    pub fn class_recall(&self, class: usize) -> f64 {
+        ratio(self.counts[class][class], self.support(class))
+    }
+
+    /// Returns the F1 score (the harmonic mean of precision and recall) of a single class.
+    pub fn class_f1(&self, class: usize) -> f64 {
+        harmonic_mean(self.class_precision(class), self.class_recall(class))
+    }
+
+    /// Returns the precision across all classes, combined using the given `average`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::metrics::{Average, ConfusionMatrix};
+    ///
+    /// let matrix = ConfusionMatrix::from_pairs(2, vec![(0, 0), (0, 1), (1, 1), (1, 1)]);
+    /// assert_eq!(matrix.precision(Average::Macro), (1.0 + 2.0 / 3.0) / 2.0);
+    /// assert_eq!(matrix.precision(Average::Micro), 0.75);
+    /// ```
+    pub fn precision(&self, average: Average) -> f64 {
+        self.combine(average, Self::class_precision)
+    }
+
+    /// Returns the recall across all classes, combined using the given `average`.
+    pub fn recall(&self, average: Average) -> f64 {
+        self.combine(average, Self::class_recall)
+    }
+
+    /// Returns the F1 score across all classes, combined using the given `average`.
+    pub fn f1(&self, average: Average) -> f64 {
+        match average {
+            Average::Micro => harmonic_mean(self.precision(average), self.recall(average)),
+            _ => self.combine(average, Self::class_f1),
+        }
+    }
+
+    /// Combines a per-class score into a single score using the given `average`.
+    fn combine(&self, average: Average, score: fn(&Self, usize) -> f64) -> f64 {
+        let classes = 0..self.num_classes();
+        match average {
+            Average::Macro => {
+                classes.map(|c| score(self, c)).sum::<f64>() / self.num_classes() as f64
+            }
+            // Every misclassification is both a false positive (for the predicted class) and a
+            // false negative (for the actual class), so micro-averaged scores equal the accuracy
+            Average::Micro => self.accuracy(),
+            Average::Weighted => {
+                let total = self.total();
+                classes
+                    .map(|c| score(self, c) * ratio(self.support(c), total))
+                    .sum()
+            }
+        }
+    }
+}
+
+/// The network's guesses for each row of a `Dataset`, paired with the row's target values.
+///
+/// Collecting the guesses up front means that several metrics can be calculated (or a metric
+/// calculated several times, as when bootstrapping) without re-running the network.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Predictions {
+    /// Pairs of `(guesses, targets)`.
+    pairs: Vec<(Vec<f64>, Vec<f64>)>,
+    /// The threshold at which a single-output guess is classified as positive.
+    threshold: f64,
+}
+
+impl Predictions {
+    /// Collects the network's guesses for every row in the given `Dataset`.
+    pub fn new<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        network: &NeuralNet<A, T>,
+        dataset: &Dataset,
+    ) -> Self {
+        let pairs = dataset
+            .into_iter()
+            .map(|(inputs, targets)| (network.guess(inputs), targets.clone()))
+            .collect();
+
+        Self {
+            pairs,
+            threshold: network.threshold(),
+        }
+    }
+
+    /// Creates `Predictions` from pairs of `(guesses, targets)`, with a classification threshold
+    /// of 0.5.
+    pub fn from_pairs(pairs: Vec<(Vec<f64>, Vec<f64>)>) -> Self {
+        Self {
+            pairs,
+            threshold: 0.5,
+        }
+    }
+
+    /// Sets the threshold at which single-output guesses are classified as positive.
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Returns the threshold at which single-output guesses are classified as positive.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Returns the `(guesses, targets)` pairs.
+    pub fn pairs(&self) -> &[(Vec<f64>, Vec<f64>)] {
+        &self.pairs
+    }
+
+    /// Returns the number of predictions.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Returns `true` if there are no predictions.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Decodes the actual and predicted classes of each row into a `ConfusionMatrix`.
+    pub fn confusion_matrix(&self) -> ConfusionMatrix {
+        self.confusion_matrix_at(self.threshold)
+    }
+
+    /// Finds the classification threshold of a binary (single-output) classifier that maximizes
+    /// the given `metric`, by trying every distinct guess value as the threshold.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::metrics::Predictions;
+    ///
+    /// let predictions = Predictions::from_pairs(vec![
+    ///     (vec![0.45], vec![1.0]),
+    ///     (vec![0.4], vec![1.0]),
+    ///     (vec![0.3], vec![0.0]),
+    ///     (vec![0.1], vec![0.0]),
+    /// ]);
+    ///
+    /// // Maximizes the F1 score of the positive class
+    /// let threshold = predictions.best_threshold(|matrix| matrix.class_f1(1));
+    /// assert_eq!(threshold, 0.4);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if any of the guesses don't consist of exactly one value.
+    pub fn best_threshold(&self, metric: impl Fn(&ConfusionMatrix) -> f64) -> f64 {
+        let mut candidates: Vec<f64> = self
+            .pairs
+            .iter()
+            .map(|(guesses, _)| match guesses.as_slice() {
+                [guess] => *guess,
+                _ => panic!(
+                    "threshold tuning requires a single output (found {})",
+                    guesses.len()
+                ),
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+        candidates.dedup();
+
+        candidates
+            .into_iter()
+            .map(|threshold| (threshold, metric(&self.confusion_matrix_at(threshold))))
+            .fold((self.threshold, f64::NEG_INFINITY), |best, current| {
+                if current.1 > best.1 {
+                    current
+                } else {
+                    best
+                }
+            })
+            .0
+    }
+
+    /// Decodes the actual class of each row, and predicts its class as the one with the lowest
+    /// expected misclassification cost, into a `ConfusionMatrix`.
+    ///
+    /// See [`CostMatrix::min_cost_class()`](struct.CostMatrix.html#method.min_cost_class) for
+    /// details.
+    pub fn cost_sensitive_confusion_matrix(&self, costs: &CostMatrix) -> ConfusionMatrix {
+        self.confusion_matrix_by(|guesses| costs.min_cost_class(guesses))
+    }
+
+    /// Decodes the classes of each row into a `ConfusionMatrix`, using the given threshold for
+    /// single-output guesses.
+    fn confusion_matrix_at(&self, threshold: f64) -> ConfusionMatrix {
+        self.confusion_matrix_by(|guesses| decode_class(guesses, threshold))
+    }
+
+    /// Decodes the classes of each row into a `ConfusionMatrix`, using the given function to
+    /// predict a class from each row's guesses.
+    fn confusion_matrix_by(&self, predict: impl Fn(&[f64]) -> usize) -> ConfusionMatrix {
+        let num_classes = self
+            .pairs
+            .iter()
+            .map(|(guesses, targets)| guesses.len().max(targets.len()).max(2))
+            .max()
+            .unwrap_or(0);
+
+        ConfusionMatrix::from_pairs(
+            num_classes,
+            self.pairs
+                .iter()
+                .map(|(guesses, targets)| (decode_class(targets, 0.5), predict(guesses))),
+        )
+    }
+
+    /// Returns the proportion of rows that were classified correctly.
+    pub fn accuracy(&self) -> f64 {
+        self.confusion_matrix().accuracy()
+    }
+
+    /// Returns the root-mean-square error across every output value.
+    pub fn rmse(&self) -> f64 {
+        mean(self.errors().map(|e| e * e)).sqrt()
+    }
+
+    /// Returns the mean absolute error across every output value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::metrics::Predictions;
+    ///
+    /// let predictions = Predictions::from_pairs(vec![
+    ///     (vec![2.5], vec![3.0]),
+    ///     (vec![0.0], vec![-0.5]),
+    ///     (vec![2.0], vec![2.0]),
+    ///     (vec![8.0], vec![7.0]),
+    /// ]);
+    ///
+    /// assert_eq!(predictions.mae(), 0.5);
+    /// assert_eq!(predictions.rmse(), 0.375f64.sqrt());
+    /// ```
+    pub fn mae(&self) -> f64 {
+        mean(self.errors().map(f64::abs))
+    }
+
+    /// Returns the mean absolute percentage error across every output value, as a fraction
+    /// (i.e. 0.05 meaning 5%).
+    ///
+    /// Target values of 0 are skipped, since the percentage error is undefined for them.
+    pub fn mape(&self) -> f64 {
+        mean(self.pairs.iter().flat_map(|(guesses, targets)| {
+            guesses
+                .iter()
+                .zip(targets)
+                .filter(|(_, t)| **t != 0.0)
+                .map(|(g, t)| ((t - g) / t).abs())
+        }))
+    }
+
+    /// Returns the coefficient of determination (R²): the proportion of the variance in the
+    /// target values that is explained by the guesses. Multiple outputs are scored individually,
+    /// and their scores averaged.
+    ///
+    /// A perfect fit scores 1, whilst always guessing the mean of the targets scores 0.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::metrics::Predictions;
+    ///
+    /// let predictions = Predictions::from_pairs(vec![
+    ///     (vec![1.0], vec![1.0]),
+    ///     (vec![2.0], vec![2.0]),
+    ///     (vec![3.0], vec![3.0]),
+    /// ]);
+    /// assert_eq!(predictions.r2(), 1.0);
+    /// ```
+    pub fn r2(&self) -> f64 {
+        let num_outputs = self.pairs.first().map_or(0, |(_, targets)| targets.len());
+        mean((0..num_outputs).map(|i| {
+            let target_mean = mean(self.pairs.iter().map(|(_, targets)| targets[i]));
+            let (residual, total) =
+                self.pairs
+                    .iter()
+                    .fold((0.0, 0.0), |(residual, total), (guesses, targets)| {
+                        (
+                            residual + (targets[i] - guesses[i]).powi(2),
+                            total + (targets[i] - target_mean).powi(2),
+                        )
+                    });
+
+            if total == 0.0 {
+                // Constant targets can only be explained by guessing them exactly
+                if residual == 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            } else {
+                1.0 - residual / total
+            }
+        }))
+    }
+
+    /// Estimates a confidence interval for the given `metric` by recalculating it on `resamples`
+    /// bootstrap resamples (random samples, with replacement, of the same size as the original).
+    ///
+    /// The bounds of the interval are percentiles of the resampled scores, chosen so that the
+    /// given `confidence` (e.g. 0.95) of the scores lie between them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::metrics::Predictions;
+    ///
+    /// let predictions = Predictions::from_pairs(vec![
+    ///     (vec![0.9], vec![1.0]),
+    ///     (vec![0.2], vec![0.0]),
+    ///     (vec![0.7], vec![1.0]),
+    /// ]);
+    ///
+    /// let interval = predictions.bootstrap(1000, 0.95, Predictions::accuracy);
+    /// assert_eq!(interval.estimate, 1.0);
+    /// assert_eq!((interval.lower, interval.upper), (1.0, 1.0));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `resamples` is 0 or the given `confidence` isn't between 0 and 1.
+    pub fn bootstrap(
+        &self,
+        resamples: usize,
+        confidence: f64,
+        metric: impl Fn(&Predictions) -> f64,
+    ) -> ConfidenceInterval {
+        if resamples == 0 {
+            panic!("at least one resample is required");
+        }
+        if !(0.0..=1.0).contains(&confidence) {
+            panic!("confidence must be between 0 and 1 (found {})", confidence);
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut scores: Vec<f64> = (0..resamples)
+            .map(|_| {
+                let pairs = (0..self.len())
+                    .map(|_| self.pairs[rng.gen_range(0, self.len())].clone())
+                    .collect();
+                metric(&Self::from_pairs(pairs).with_threshold(self.threshold))
+            })
+            .collect();
+        scores.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        let tail = (1.0 - confidence) / 2.0;
+        ConfidenceInterval {
+            estimate: metric(self),
+            lower: percentile(&scores, tail),
+            upper: percentile(&scores, 1.0 - tail),
+        }
+    }
+
+    /// Calculates the receiver operating characteristic (ROC) curve of a binary classifier: the
+    /// true positive rate against the false positive rate at every distinct decision threshold.
+    ///
+    /// The positive-class score is the network's only output, or its second output when there
+    /// are two (i.e. one-hot encoded classes). The first point always lies at (0, 0) with an
+    /// infinite threshold, and the last at (1, 1).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::metrics::Predictions;
+    ///
+    /// let predictions = Predictions::from_pairs(vec![
+    ///     (vec![0.9], vec![1.0]),
+    ///     (vec![0.6], vec![0.0]),
+    ///     (vec![0.4], vec![1.0]),
+    ///     (vec![0.1], vec![0.0]),
+    /// ]);
+    ///
+    /// let curve = predictions.roc_curve();
+    /// assert_eq!(curve.len(), 5);
+    /// assert_eq!(curve[1].threshold, 0.9);
+    /// assert_eq!(curve[1].true_positive_rate, 0.5);
+    /// assert_eq!(predictions.auc(), 0.75);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the network has more than two outputs.
+    pub fn roc_curve(&self) -> Vec<RocPoint> {
+        let mut scored: Vec<(f64, bool)> = self
+            .pairs
+            .iter()
+            .map(|(guesses, targets)| (binary_score(guesses), decode_class(targets, 0.5) == 1))
+            .collect();
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        let positives = scored.iter().filter(|(_, positive)| *positive).count();
+        let negatives = scored.len() - positives;
+
+        let mut curve = vec![RocPoint {
+            threshold: f64::INFINITY,
+            false_positive_rate: 0.0,
+            true_positive_rate: 0.0,
+        }];
+        let (mut true_positives, mut false_positives) = (0, 0);
+        for (i, (score, positive)) in scored.iter().enumerate() {
+            if *positive {
+                true_positives += 1;
+            } else {
+                false_positives += 1;
+            }
+
+            // Only adds a point once every row sharing the same score has been counted
+            if scored.get(i + 1).is_none_or(|(next, _)| next != score) {
+                curve.push(RocPoint {
+                    threshold: *score,
+                    false_positive_rate: ratio(false_positives, negatives),
+                    true_positive_rate: ratio(true_positives, positives),
+                });
+            }
+        }
+
+        curve
+    }
+
+    /// Calculates the area under the ROC curve (AUC) of a binary classifier.
+    ///
+    /// See [`Predictions::roc_curve()`](#method.roc_curve) for how the scores are chosen.
+    pub fn auc(&self) -> f64 {
+        self.roc_curve()
+            .windows(2)
+            .map(|pair| {
+                let width = pair[1].false_positive_rate - pair[0].false_positive_rate;
+                width * (pair[0].true_positive_rate + pair[1].true_positive_rate) / 2.0
+            })
+            .sum()
+    }
+
+    /// Returns an iterator over the difference between every target value and its guess.
+    fn errors(&self) -> impl Iterator<Item = f64> + '_ {
+        self.pairs
+            .iter()
+            .flat_map(|(guesses, targets)| guesses.iter().zip(targets).map(|(g, t)| t - g))
+    }
+}
+
+/// A range of values within which a metric is expected to lie, alongside its point estimate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConfidenceInterval {
+    /// The metric calculated on the original (non-resampled) data.
+    pub estimate: f64,
+    /// The lower bound of the interval.
+    pub lower: f64,
+    /// The upper bound of the interval.
+    pub upper: f64,
+}
+
+/// Accumulates metrics from `(guesses, targets)` pairs as they arrive one at a time, for
+/// monitoring the live performance of a deployed network without storing every prediction.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::metrics::OnlineEvaluator;
+///
+/// let mut evaluator = OnlineEvaluator::new();
+///
+/// // e.g. as each ground truth becomes known for an earlier prediction
+/// evaluator.update(&[0.8], &[1.0]);
+/// evaluator.update(&[0.4], &[1.0]);
+///
+/// assert_eq!(evaluator.count(), 2);
+/// assert_eq!(evaluator.accuracy(), 0.5);
+/// assert!((evaluator.loss() - 0.4).abs() < 1e-9);
+/// assert_eq!(evaluator.confusion_matrix().count(1, 0), 1);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct OnlineEvaluator {
+    /// The threshold at which a single-output guess is classified as positive.
+    threshold: f64,
+    /// The number of pairs seen.
+    count: usize,
+    /// The sum of each pair's mean absolute error.
+    loss_sum: f64,
+    /// The classifications seen so far.
+    matrix: ConfusionMatrix,
+}
+
+impl OnlineEvaluator {
+    /// Creates a new `OnlineEvaluator`, with a classification threshold of 0.5.
+    pub fn new() -> Self {
+        Self {
+            threshold: 0.5,
+            count: 0,
+            loss_sum: 0.0,
+            matrix: ConfusionMatrix::from_pairs(0, None),
+        }
+    }
+
+    /// Sets the threshold at which single-output guesses are classified as positive (e.g. the
+    /// deployed network's [`threshold()`](../struct.NeuralNet.html#method.threshold)).
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Adds a single pair of guesses and their target values to the metrics.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of guesses and targets differ.
+    pub fn update(&mut self, guesses: &[f64], targets: &[f64]) {
+        if guesses.len() != targets.len() {
+            panic!(
+                "mismatched number of guesses and targets ({} and {})",
+                guesses.len(),
+                targets.len()
+            );
+        }
+
+        let error_sum: f64 = guesses
+            .iter()
+            .zip(targets)
+            .map(|(g, t)| (t - g).abs())
+            .sum();
+        self.loss_sum += error_sum / guesses.len() as f64;
+        self.count += 1;
+
+        self.matrix.record(
+            decode_class(targets, 0.5),
+            decode_class(guesses, self.threshold),
+            guesses.len().max(2),
+        );
+    }
+
+    /// Returns the number of pairs seen.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the average cost (mean absolute error) of the pairs seen, as calculated by
+    /// [`NeuralNet::test()`](../struct.NeuralNet.html#method.test).
+    pub fn loss(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.loss_sum / self.count as f64
+        }
+    }
+
+    /// Returns the proportion of the pairs seen that were classified correctly.
+    pub fn accuracy(&self) -> f64 {
+        self.matrix.accuracy()
+    }
+
+    /// Returns the classifications of the pairs seen.
+    pub fn confusion_matrix(&self) -> &ConfusionMatrix {
+        &self.matrix
+    }
+
+    /// Forgets every pair seen so far, e.g. at the start of a new monitoring window.
+    pub fn reset(&mut self) {
+        *self = Self::new().with_threshold(self.threshold);
+    }
+}
+
+impl Default for OnlineEvaluator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The cost of each possible misclassification, used both to evaluate a classifier and to choose
+/// the class with the lowest expected cost at prediction time.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::metrics::{ConfusionMatrix, CostMatrix};
+///
+/// // Missing a positive (e.g. a disease) is five times worse than a false alarm
+/// let costs = CostMatrix::new(vec![vec![0.0, 1.0], vec![5.0, 0.0]]);
+///
+/// let matrix = ConfusionMatrix::from_pairs(2, vec![(0, 0), (0, 1), (1, 0), (1, 1)]);
+/// assert_eq!(costs.total_cost(&matrix), 6.0);
+/// assert_eq!(costs.average_cost(&matrix), 1.5);
+///
+/// // A guess of 0.3 is classified as positive, since its expected cost (0.7) is lower than that
+/// // of classifying it as negative (1.5)
+/// assert_eq!(costs.min_cost_class(&[0.3]), 1);
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CostMatrix {
+    /// Indexed first by actual class, then by predicted class.
+    costs: Vec<Vec<f64>>,
+}
+
+impl CostMatrix {
+    /// Creates a `CostMatrix` from a square table of costs, indexed first by actual class, then
+    /// by predicted class. Correct classifications typically cost 0.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the given table is not square.
+    pub fn new(costs: Vec<Vec<f64>>) -> Self {
+        let num_classes = costs.len();
+        for row in &costs {
+            if row.len() != num_classes {
+                panic!(
+                    "cost matrix must be square (expected {} columns, found {})",
+                    num_classes,
+                    row.len()
+                );
+            }
+        }
+
+        Self { costs }
+    }
+
+    /// Returns the number of classes in the matrix.
+    pub fn num_classes(&self) -> usize {
+        self.costs.len()
+    }
+
+    /// Returns the cost of predicting the `predicted` class for a row of the `actual` class.
+    pub fn cost(&self, actual: usize, predicted: usize) -> f64 {
+        self.costs[actual][predicted]
+    }
+
+    /// Returns the total cost of the classifications counted by the given `ConfusionMatrix`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the matrices have a different number of classes.
+    pub fn total_cost(&self, matrix: &ConfusionMatrix) -> f64 {
+        if matrix.num_classes() != self.num_classes() {
+            panic!(
+                "mismatched number of classes (expected {}, found {})",
+                self.num_classes(),
+                matrix.num_classes()
+            );
+        }
+
+        (0..self.num_classes())
+            .flat_map(|a| (0..self.num_classes()).map(move |p| (a, p)))
+            .map(|(a, p)| self.costs[a][p] * matrix.count(a, p) as f64)
+            .sum()
+    }
+
+    /// Returns the average cost per row of the classifications counted by the given
+    /// `ConfusionMatrix`.
+    pub fn average_cost(&self, matrix: &ConfusionMatrix) -> f64 {
+        match matrix.total() {
+            0 => 0.0,
+            total => self.total_cost(matrix) / total as f64,
+        }
+    }
+
+    /// Returns the class with the lowest expected cost given a network's guesses.
+    ///
+    /// The guesses are interpreted as class probabilities: a single guess `p` as the probability
+    /// of class 1 (so class 0 has probability `1 - p`), and multiple guesses as the relative
+    /// likelihoods of each class (which are normalized to sum to 1). If multiple guesses sum to
+    /// 0, so that they can't be normalized, the class of the largest guess is returned instead.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the guesses imply a different number of classes to the matrix.
+    pub fn min_cost_class(&self, guesses: &[f64]) -> usize {
+        let num_classes = if guesses.len() == 1 { 2 } else { guesses.len() };
+        if num_classes != self.num_classes() {
+            panic!(
+                "mismatched number of classes (expected {}, found {})",
+                self.num_classes(),
+                num_classes
+            );
+        }
+        let probabilities = match guesses {
+            [p] => vec![1.0 - p, *p],
+            _ => {
+                let sum: f64 = guesses.iter().sum();
+                if sum == 0.0 {
+                    return decode_class(guesses, 0.5);
+                }
+                guesses.iter().map(|g| g / sum).collect()
+            }
+        };
+
+        (0..self.num_classes())
+            .map(|predicted| {
+                let expected_cost: f64 = probabilities
+                    .iter()
+                    .enumerate()
+                    .map(|(actual, p)| p * self.costs[actual][predicted])
+                    .sum();
+                (predicted, expected_cost)
+            })
+            .fold((0, f64::INFINITY), |best, current| {
+                if current.1 < best.1 {
+                    current
+                } else {
+                    best
+                }
+            })
+            .0
+    }
+}
+
+/// A point on a receiver operating characteristic (ROC) curve.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RocPoint {
+    /// The minimum score for which a row is classified as positive.
+    pub threshold: f64,
+    /// The proportion of negative rows classified as positive.
+    pub false_positive_rate: f64,
+    /// The proportion of positive rows classified as positive.
+    pub true_positive_rate: f64,
+}
+
+/// Estimates a confidence interval for a `metric` of the network on the `Dataset`.
+///
+/// See [`Predictions::bootstrap()`](struct.Predictions.html#method.bootstrap) for details.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use scholar::{metrics::{self, Predictions}, Dataset, NeuralNet, Sigmoid};
+///
+/// # fn main() -> Result<(), scholar::ParseCsvError> {
+/// let dataset = Dataset::from_csv("iris.csv", false, 4)?;
+/// let (training_data, testing_data) = dataset.split(0.75);
+///
+/// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[4, 10, 10, 3]);
+/// brain.train(training_data, 10_000, 0.01);
+///
+/// let interval = metrics::bootstrap(&brain, &testing_data, 1000, 0.95, Predictions::accuracy);
+/// println!(
+///     "Accuracy: {:.2} (95% CI {:.2}-{:.2})",
+///     interval.estimate, interval.lower, interval.upper
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn bootstrap<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    network: &NeuralNet<A, T>,
+    dataset: &Dataset,
+    resamples: usize,
+    confidence: f64,
+    metric: impl Fn(&Predictions) -> f64,
+) -> ConfidenceInterval {
+    Predictions::new(network, dataset).bootstrap(resamples, confidence, metric)
+}
+
+/// Calculates the proportion of rows in the `Dataset` that the network classifies correctly.
+pub fn accuracy<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    network: &NeuralNet<A, T>,
+    dataset: &Dataset,
+) -> f64 {
+    ConfusionMatrix::new(network, dataset).accuracy()
+}
+
+/// Calculates the network's precision on the `Dataset`, combined using the given `average`.
+pub fn precision<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    network: &NeuralNet<A, T>,
+    dataset: &Dataset,
+    average: Average,
+) -> f64 {
+    ConfusionMatrix::new(network, dataset).precision(average)
+}
+
+/// Calculates the network's recall on the `Dataset`, combined using the given `average`.
+pub fn recall<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    network: &NeuralNet<A, T>,
+    dataset: &Dataset,
+    average: Average,
+) -> f64 {
+    ConfusionMatrix::new(network, dataset).recall(average)
+}
+
+/// Calculates the network's F1 score on the `Dataset`, combined using the given `average`.
+pub fn f1<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    network: &NeuralNet<A, T>,
+    dataset: &Dataset,
+    average: Average,
+) -> f64 {
+    ConfusionMatrix::new(network, dataset).f1(average)
+}
+
+/// Calculates the network's root-mean-square error on the `Dataset`.
+pub fn rmse<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    network: &NeuralNet<A, T>,
+    dataset: &Dataset,
+) -> f64 {
+    Predictions::new(network, dataset).rmse()
+}
+
+/// Calculates the average misclassification cost per row of the network's predictions on the
+/// `Dataset`.
+///
+/// The network's ordinary predictions are evaluated; to evaluate its minimum-expected-cost
+/// predictions, use
+/// [`Predictions::cost_sensitive_confusion_matrix()`](struct.Predictions.html#method.cost_sensitive_confusion_matrix).
+pub fn average_cost<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    network: &NeuralNet<A, T>,
+    dataset: &Dataset,
+    costs: &CostMatrix,
+) -> f64 {
+    costs.average_cost(&ConfusionMatrix::new(network, dataset))
+}
+
+/// Calculates the network's mean absolute error on the `Dataset`.
+pub fn mae<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    network: &NeuralNet<A, T>,
+    dataset: &Dataset,
+) -> f64 {
+    Predictions::new(network, dataset).mae()
+}
+
+/// Calculates the network's mean absolute percentage error on the `Dataset`.
+pub fn mape<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    network: &NeuralNet<A, T>,
+    dataset: &Dataset,
+) -> f64 {
+    Predictions::new(network, dataset).mape()
+}
+
+/// Calculates the network's coefficient of determination (R²) on the `Dataset`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use scholar::{metrics, Dataset, NeuralNet, Sigmoid};
+///
+/// # fn main() -> Result<(), scholar::ParseCsvError> {
+/// let dataset = Dataset::from_csv("housing.csv", true, 8)?;
+/// let (training_data, testing_data) = dataset.split(0.75);
+///
+/// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[8, 16, 1]);
+/// brain.train(training_data, 1_000, 0.01);
+///
+/// println!("R²: {:.3}", metrics::r2(&brain, &testing_data));
+/// println!("RMSE: {:.3}", metrics::rmse(&brain, &testing_data));
+/// # Ok(())
+/// # }
+/// ```
+pub fn r2<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    network: &NeuralNet<A, T>,
+    dataset: &Dataset,
+) -> f64 {
+    Predictions::new(network, dataset).r2()
+}
+
+/// Calculates the ROC curve of a binary classifier on the `Dataset`.
+///
+/// See [`Predictions::roc_curve()`](struct.Predictions.html#method.roc_curve) for details.
+pub fn roc_curve<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    network: &NeuralNet<A, T>,
+    dataset: &Dataset,
+) -> Vec<RocPoint> {
+    Predictions::new(network, dataset).roc_curve()
+}
+
+/// Calculates the area under the ROC curve of a binary classifier on the `Dataset`.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{metrics, Dataset, NeuralNet, Sigmoid};
+///
+/// let dataset = Dataset::from(vec![
+///     (vec![0.0], vec![0.0]),
+///     (vec![1.0], vec![1.0]),
+/// ]);
+/// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[1, 1]);
+///
+/// let auc = metrics::auc(&brain, &dataset);
+/// assert!(auc >= 0.0 && auc <= 1.0);
+/// ```
+pub fn auc<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    network: &NeuralNet<A, T>,
+    dataset: &Dataset,
+) -> f64 {
+    Predictions::new(network, dataset).auc()
+}
+
+/// Returns the positive-class score from the guesses of a binary classifier.
+fn binary_score(guesses: &[f64]) -> f64 {
+    match guesses.len() {
+        1 => guesses[0],
+        2 => guesses[1],
+        n => panic!(
+            "binary classifier must have one or two outputs (found {})",
+            n
+        ),
+    }
+}
+
+/// Returns the value at the given quantile (between 0 and 1) of some sorted scores.
+fn percentile(sorted: &[f64], quantile: f64) -> f64 {
+    let index = (quantile * (sorted.len() - 1) as f64).round() as usize;
+    sorted[index]
+}
+
+/// Calculates the mean of some values, returning 0 if there are none.
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), v| (sum + v, count + 1));
+    if count == 0 {
+        0.0
+    } else {
+        sum / count as f64
+    }
+}
+
+/// Divides two counts, returning 0 if the denominator is 0.
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+/// Calculates the harmonic mean of two scores, returning 0 if both are 0.
+fn harmonic_mean(a: f64, b: f64) -> f64 {
+    if a + b == 0.0 {
+        0.0
+    } else {
+        2.0 * a * b / (a + b)
+    }
+}
@@ -0,0 +1,263 @@
+use super::{Average, ConfusionMatrix, Predictions};
+use crate::dataset::Dataset;
+use crate::network::{Activation, Float, NeuralNet};
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt;
+
+/// A summary of a network's performance on a `Dataset`, including its overall metrics, per-class
+/// scores, and confusion matrix, which is formatted as a readable table when displayed.
+///
+/// With the `color` feature enabled, scores and confusion matrix cells are colored according to
+/// how good they are (when the output is a terminal that supports it).
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::metrics::{Predictions, Report};
+///
+/// let predictions = Predictions::from_pairs(vec![
+///     (vec![0.9, 0.1], vec![1.0, 0.0]),
+///     (vec![0.2, 0.7], vec![0.0, 1.0]),
+///     (vec![0.6, 0.3], vec![0.0, 1.0]),
+/// ]);
+///
+/// let report = Report::new(&predictions);
+/// assert_eq!(report.confusion_matrix().count(1, 0), 1);
+/// println!("{}", report);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    matrix: ConfusionMatrix,
+    loss: f64,
+    rmse: f64,
+    mae: f64,
+    r2: f64,
+}
+
+impl Report {
+    /// Creates a `Report` from the given `Predictions`.
+    pub fn new(predictions: &Predictions) -> Self {
+        let loss = predictions
+            .pairs()
+            .iter()
+            .map(|(guesses, targets)| {
+                let cost_sum: f64 = guesses
+                    .iter()
+                    .zip(targets)
+                    .map(|(g, t)| (t - g).abs())
+                    .sum();
+                cost_sum / guesses.len() as f64
+            })
+            .sum::<f64>()
+            / predictions.len().max(1) as f64;
+
+        Self {
+            matrix: predictions.confusion_matrix(),
+            loss,
+            rmse: predictions.rmse(),
+            mae: predictions.mae(),
+            r2: predictions.r2(),
+        }
+    }
+
+    /// Replaces the report's loss with the given value, such as one calculated by a different
+    /// loss than the average cost.
+    #[cfg(feature = "fs")]
+    pub(crate) fn with_loss(mut self, loss: f64) -> Self {
+        self.loss = loss;
+        self
+    }
+
+    /// Returns the classifications counted for the report.
+    pub fn confusion_matrix(&self) -> &ConfusionMatrix {
+        &self.matrix
+    }
+
+    /// Returns the average cost, as calculated by
+    /// [`NeuralNet::test()`](../struct.NeuralNet.html#method.test), or the loss named by the
+    /// experiment's configuration (see [`config`](../config/index.html)) if it has one.
+    pub fn loss(&self) -> f64 {
+        self.loss
+    }
+
+    /// Returns the root-mean-square error.
+    pub fn rmse(&self) -> f64 {
+        self.rmse
+    }
+
+    /// Returns the mean absolute error.
+    pub fn mae(&self) -> f64 {
+        self.mae
+    }
+
+    /// Returns the coefficient of determination (R²).
+    pub fn r2(&self) -> f64 {
+        self.r2
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let m = &self.matrix;
+
+        writeln!(
+            f,
+            "{}",
+            paint_header(&format!("Evaluation ({} rows)", m.total()))
+        )?;
+        writeln!(f)?;
+        writeln!(f, "  Accuracy  {}", paint_score(m.accuracy(), 8))?;
+        writeln!(f, "  Loss      {:>8.4}", self.loss)?;
+        writeln!(f, "  RMSE      {:>8.4}", self.rmse)?;
+        writeln!(f, "  MAE       {:>8.4}", self.mae)?;
+        writeln!(f, "  R²        {:>8.4}", self.r2)?;
+        writeln!(f)?;
+
+        writeln!(
+            f,
+            "{}",
+            paint_header(&format!(
+                "  {:<12} {:>9} {:>9} {:>9} {:>9}",
+                "Class", "Precision", "Recall", "F1", "Support"
+            ))
+        )?;
+        for class in 0..m.num_classes() {
+            writeln!(
+                f,
+                "  {:<12} {} {} {} {:>9}",
+                class,
+                paint_score(m.class_precision(class), 9),
+                paint_score(m.class_recall(class), 9),
+                paint_score(m.class_f1(class), 9),
+                m.support(class)
+            )?;
+        }
+        for (name, average) in &[
+            ("Macro avg", Average::Macro),
+            ("Weighted avg", Average::Weighted),
+        ] {
+            writeln!(
+                f,
+                "  {:<12} {} {} {} {:>9}",
+                name,
+                paint_score(m.precision(*average), 9),
+                paint_score(m.recall(*average), 9),
+                paint_score(m.f1(*average), 9),
+                m.total()
+            )?;
+        }
+        writeln!(f)?;
+
+        // Every column is wide enough to fit the largest count
+        let width = m
+            .total()
+            .to_string()
+            .len()
+            .max(m.num_classes().to_string().len())
+            + 2;
+        writeln!(
+            f,
+            "{}",
+            paint_header("  Confusion matrix (rows: actual, columns: predicted)")
+        )?;
+        write!(f, "  {:>w$}", "", w = width)?;
+        for class in 0..m.num_classes() {
+            write!(f, "{}", paint_header(&format!("{:>w$}", class, w = width)))?;
+        }
+        writeln!(f)?;
+        for actual in 0..m.num_classes() {
+            write!(
+                f,
+                "  {}",
+                paint_header(&format!("{:>w$}", actual, w = width))
+            )?;
+            for predicted in 0..m.num_classes() {
+                let count = m.count(actual, predicted);
+                write!(f, "{}", paint_count(count, actual == predicted, width))?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates a `Report` of the network's performance on the `Dataset`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use scholar::{metrics, Dataset, NeuralNet, Sigmoid};
+///
+/// # fn main() -> Result<(), scholar::ParseCsvError> {
+/// let dataset = Dataset::from_csv("iris.csv", false, 4)?;
+/// let (training_data, testing_data) = dataset.split(0.75);
+///
+/// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[4, 10, 10, 3]);
+/// brain.train(training_data, 10_000, 0.01);
+///
+/// println!("{}", metrics::report(&brain, &testing_data));
+/// # Ok(())
+/// # }
+/// ```
+pub fn report<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    network: &NeuralNet<A, T>,
+    dataset: &Dataset,
+) -> Report {
+    Report::new(&Predictions::new(network, dataset))
+}
+
+/// Emphasizes a heading.
+fn paint_header(text: &str) -> String {
+    #[cfg(feature = "color")]
+    {
+        console::style(text).bold().to_string()
+    }
+    #[cfg(not(feature = "color"))]
+    {
+        text.to_string()
+    }
+}
+
+/// Formats a score (between 0 and 1) right-aligned to the given width, colored by how good it is.
+fn paint_score(score: f64, width: usize) -> String {
+    let text = format!("{:>w$.4}", score, w = width);
+    #[cfg(feature = "color")]
+    {
+        let style = console::Style::new();
+        let style = if score >= 0.9 {
+            style.green()
+        } else if score >= 0.7 {
+            style.yellow()
+        } else {
+            style.red()
+        };
+        style.apply_to(text).to_string()
+    }
+    #[cfg(not(feature = "color"))]
+    {
+        text
+    }
+}
+
+/// Formats a confusion matrix count right-aligned to the given width, highlighting correct
+/// classifications and misclassifications.
+fn paint_count(count: usize, correct: bool, width: usize) -> String {
+    let text = format!("{:>w$}", count, w = width);
+    #[cfg(feature = "color")]
+    {
+        let style = console::Style::new();
+        let style = match (count, correct) {
+            (0, _) => style.dim(),
+            (_, true) => style.green(),
+            (_, false) => style.red(),
+        };
+        style.apply_to(text).to_string()
+    }
+    #[cfg(not(feature = "color"))]
+    {
+        let _ = correct;
+        text
+    }
+}
@@ -1,23 +1,109 @@
+#[cfg(feature = "fs")]
+mod codegen;
+#[cfg(feature = "std")]
+mod device;
+mod embedding;
+#[cfg(feature = "std")]
+mod format;
+#[cfg(feature = "matrixmultiply")]
+mod gemm;
+#[cfg(feature = "std")]
+mod header;
+#[cfg(feature = "fs")]
+pub(crate) mod json;
+#[cfg(feature = "keras")]
+mod keras;
+pub(crate) mod matrices;
+#[cfg(feature = "fs")]
+mod onnx;
+mod scaler;
+mod simd;
+mod sparse;
 
+#[cfg(feature = "std")]
+pub use device::*;
+pub use embedding::*;
+#[cfg(feature = "std")]
+pub use format::*;
+pub use scaler::*;
+pub use sparse::*;
+
+#[cfg(feature = "std")]
 use crate::dataset::Dataset;
+#[cfg(feature = "std")]
+use crate::metrics::{ConfusionMatrix, CostMatrix, Predictions};
+#[cfg(feature = "std")]
+use crate::trainer::Trainer;
 use crate::utils::*;
+#[cfg(feature = "std")]
+use header::Header;
 
-use nalgebra::DMatrix;
-
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
+    any::TypeId,
+    fmt,
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+    slice,
+};
+use nalgebra::{ClosedAdd, ClosedMul, ClosedSub, DMatrix, Scalar};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use std::{fs, marker::PhantomData, path::Path};
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+#[cfg(feature = "fs")]
+use std::{fs, path::Path};
 
 /// A fully-connected neural network.
+///
+/// The network's weights and calculations use the `Float` type `T`, which is `f64` by default.
+/// Using `f32` instead halves the memory taken by the network and speeds up its matrix
+/// multiplications, at the cost of precision; its inputs, outputs and training data are `f64`
+/// either way.
+///
+/// ```rust
+/// use scholar::{NeuralNet, Sigmoid};
+///
+/// let brain: NeuralNet<Sigmoid, f32> = NeuralNet::new(&[2, 2, 1]);
+/// assert_eq!(brain.guess(&[1.0, 0.0]).len(), 1);
+/// ```
 #[derive(Serialize, Deserialize)]
-pub struct NeuralNet<A: Activation> {
-    layers: Vec<DMatrix<f64>>,
-    weights: Vec<DMatrix<f64>>,
-    biases: Vec<DMatrix<f64>>,
-    errors: Vec<DMatrix<f64>>,
-    activation: PhantomData<A>,
+#[serde(bound = "")]
+pub struct NeuralNet<A: Activation, T: Float = f64> {
+    /// A column vector per layer, whose number of rows is the layer's number of nodes. Its values
+    /// are unused, as guesses don't store them, but are kept so that saved networks still load.
+    #[serde(with = "matrices")]
+    layers: Vec<DMatrix<T>>,
+    #[serde(with = "matrices")]
+    weights: Vec<DMatrix<T>>,
+    #[serde(with = "matrices")]
+    biases: Vec<DMatrix<T>>,
+    #[serde(with = "matrices")]
+    errors: Vec<DMatrix<T>>,
+    threshold: f64,
+    /// Whether the output layer is left unactivated (see
+    /// [`set_linear_output()`](#method.set_linear_output)).
+    #[serde(default)]
+    linear_output: bool,
+    /// The scaler of the targets the network trains on, and so of its guesses (see
+    /// [`set_target_scaler()`](#method.set_target_scaler)).
+    #[serde(default)]
+    target_scaler: Option<TargetScaler>,
+    /// Whether each layer (besides the input layer) is frozen, which isn't saved. Layers past
+    /// the end are unfrozen.
+    #[serde(skip)]
+    frozen: Vec<bool>,
+    /// The network never holds an `A` (activations are only used through their functions), so
+    /// it is `Send` and `Sync` regardless of whether `A` is.
+    activation: PhantomData<fn() -> A>,
 }
 
-impl<A: Activation + Serialize + DeserializeOwned> NeuralNet<A> {
+impl<A: Activation + Serialize + DeserializeOwned, T: Float> NeuralNet<A, T> {
     /// Creates a new `NeuralNet` with the given node configuration.
     ///
     /// Note that you must supply a type annotation so that it knows which
@@ -37,6 +123,7 @@ impl<A: Activation + Serialize + DeserializeOwned> NeuralNet<A> {
     ///
     /// This function panics if the number of layers (i.e. the length of the given `node_counts`
     /// slice) is less than 2.
+    #[cfg(feature = "std")]
     pub fn new(node_counts: &[usize]) -> Self {
         let num_layers = node_counts.len();
         if num_layers < 2 {
@@ -61,34 +148,301 @@ impl<A: Activation + Serialize + DeserializeOwned> NeuralNet<A> {
                 .skip(1)
                 .map(|c| DMatrix::zeros(*c, 1))
                 .collect(),
+            threshold: 0.5,
+            linear_output: false,
+            target_scaler: None,
+            frozen: Vec::new(),
             activation: PhantomData,
         }
     }
 
-    /// Creates a new `NeuralNet` from a valid file (those created using
-    /// [`NeuralNet::save()`](#method.save)).
+    /// Creates a new `NeuralNet` with the given weights and biases (which must have consistent
+    /// shapes), and a threshold of 0.5.
+    #[cfg(feature = "fs")]
+    fn from_parameters(weights: Vec<DMatrix<T>>, biases: Vec<DMatrix<T>>) -> Self {
+        let mut node_counts = vec![weights[0].ncols()];
+        node_counts.extend(biases.iter().map(|b| b.nrows()));
+
+        Self {
+            layers: node_counts.iter().map(|c| DMatrix::zeros(*c, 1)).collect(),
+            weights,
+            biases,
+            errors: node_counts
+                .iter()
+                .skip(1)
+                .map(|c| DMatrix::zeros(*c, 1))
+                .collect(),
+            threshold: 0.5,
+            linear_output: false,
+            target_scaler: None,
+            frozen: Vec::new(),
+            activation: PhantomData,
+        }
+    }
+
+    /// Creates a new `NeuralNet` with the given node configuration, initialized from a (typically
+    /// smaller) pretrained network so that scaling up an architecture doesn't start training
+    /// from scratch.
+    ///
+    /// Wherever a weight or bias exists in both networks (i.e. the same connection between the
+    /// same nodes, or the same node), the pretrained value is copied; the rest are randomly
+    /// initialized as in [`NeuralNet::new()`](#method.new). The pretrained network's
+    /// [`threshold()`](#method.threshold) is also kept, as is its
+    /// [`target_scaler()`](#method.target_scaler) if the number of outputs is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{NeuralNet, Sigmoid};
+    ///
+    /// let small: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+    ///
+    /// // Widens the hidden layer and adds another
+    /// let large: NeuralNet<Sigmoid> =
+    ///     NeuralNet::from_pretrained_resized(&small, &[2, 8, 4, 1]);
+    /// assert_eq!(large.guess(&[1.0, 0.0]).len(), 1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the number of layers (i.e. the length of the given `node_counts`
+    /// slice) is less than 2.
+    #[cfg(feature = "std")]
+    pub fn from_pretrained_resized(pretrained: &NeuralNet<A, T>, node_counts: &[usize]) -> Self {
+        let mut network = Self::new(node_counts);
+        network.threshold = pretrained.threshold;
+        network.linear_output = pretrained.linear_output;
+        if node_counts.last() == pretrained.layer_sizes().last() {
+            network.target_scaler = pretrained.target_scaler.clone();
+        }
+
+        let layers = network.weights.iter_mut().zip(&mut network.biases);
+        let pretrained_layers = pretrained.weights.iter().zip(&pretrained.biases);
+        for ((weights, biases), (old_weights, old_biases)) in layers.zip(pretrained_layers) {
+            let rows = weights.nrows().min(old_weights.nrows());
+            let cols = weights.ncols().min(old_weights.ncols());
+            weights
+                .view_mut((0, 0), (rows, cols))
+                .copy_from(&old_weights.view((0, 0), (rows, cols)));
+            biases
+                .rows_mut(0, rows)
+                .copy_from(&old_biases.rows(0, rows));
+        }
+
+        network
+    }
+
+    /// Creates a new `NeuralNet` whose weights, biases and threshold are the average of those of
+    /// the given networks, as in federated averaging.
+    ///
+    /// This merges networks of the same architecture that were trained on separate datasets (see
+    /// the [`federated`](federated/index.html) module).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{NeuralNet, Sigmoid};
+    ///
+    /// let a: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+    /// let b: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+    ///
+    /// let merged = NeuralNet::average(&[a, b]);
+    /// assert_eq!(merged.guess(&[1.0, 0.0]).len(), 1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if no networks are given, or if their architectures differ.
+    pub fn average(networks: &[NeuralNet<A, T>]) -> Self {
+        Self::weighted_average(networks, &vec![1.0; networks.len()])
+    }
+
+    /// Creates a new `NeuralNet` whose weights, biases and threshold are the weighted average of
+    /// those of the given networks.
+    ///
+    /// In federated averaging, each network is usually weighted by the number of rows it was
+    /// trained on.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if no networks are given, if their architectures differ, if the
+    /// number of weights differs from the number of networks, or if the weights sum to 0.
+    pub fn weighted_average(networks: &[NeuralNet<A, T>], weights: &[f64]) -> Self {
+        let first = networks
+            .first()
+            .expect("at least one network must be given to average");
+        if weights.len() != networks.len() {
+            panic!(
+                "incorrect number of weights supplied (expected {}, found {})",
+                networks.len(),
+                weights.len()
+            );
+        }
+
+        let total: f64 = weights.iter().sum();
+        if total == 0.0 {
+            panic!("weights of the averaged networks must not sum to 0");
+        }
+
+        let mut average = first.clone();
+        average.threshold = 0.0;
+        for layer in average.weights.iter_mut().chain(&mut average.biases) {
+            layer.fill(T::zero());
+        }
+
+        for (network, weight) in networks.iter().zip(weights) {
+            let shapes = |n: &Self| n.weights.iter().map(|w| w.shape()).collect::<Vec<_>>();
+            if shapes(network) != shapes(first)
+                || network.linear_output != first.linear_output
+                || network.target_scaler != first.target_scaler
+            {
+                panic!("networks of differing architectures cannot be averaged");
+            }
+
+            let factor = weight / total;
+            let layers = average.weights.iter_mut().chain(&mut average.biases);
+            let network_layers = network.weights.iter().chain(&network.biases);
+            for (layer, network_layer) in layers.zip(network_layers) {
+                *layer += network_layer * T::convert(factor);
+            }
+            average.threshold += network.threshold * factor;
+        }
+
+        average
+    }
+
+    /// Creates a copy of the network that uses a different `Float` type, for example to train
+    /// a network with `f64` precision and then run it with `f32` precision.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use scholar::{NeuralNet, Sigmoid};
     ///
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+    /// let small: NeuralNet<Sigmoid, f32> = brain.cast();
+    ///
+    /// let difference = brain.guess(&[1.0, 0.0])[0] - small.guess(&[1.0, 0.0])[0];
+    /// assert!(difference.abs() < 1e-6);
+    /// ```
+    pub fn cast<U: Float>(&self) -> NeuralNet<A, U> {
+        let cast = |matrices: &[DMatrix<T>]| {
+            matrices
+                .iter()
+                .map(|m| m.map(|x| U::convert(x.as_f64())))
+                .collect()
+        };
+
+        NeuralNet {
+            layers: cast(&self.layers),
+            weights: cast(&self.weights),
+            biases: cast(&self.biases),
+            errors: cast(&self.errors),
+            threshold: self.threshold,
+            linear_output: self.linear_output,
+            target_scaler: self.target_scaler.clone(),
+            frozen: self.frozen.clone(),
+            activation: PhantomData,
+        }
+    }
+
+    /// Creates a new `NeuralNet` from a valid file (those created using
+    /// [`NeuralNet::save()`](#method.save)), whose format is chosen from its extension by
+    /// [`ModelFormat::from_path()`](enum.ModelFormat.html#method.from_path).
+    ///
+    /// Files saved by older versions of the library still load, including those saved before
+    /// networks had a header (whose activation and precision can't be checked, since they
+    /// weren't saved). Saving a loaded network again upgrades its file to the current format.
+    ///
+    /// # Errors
+    ///
+    /// Besides failing to read or deserialize the file, this function returns an error if the
+    /// file wasn't saved by a `NeuralNet` (or was saved by a newer version of the file format),
+    /// if the network was saved with a different `Activation` or `Float` type, or if its weights
+    /// don't match the architecture described in the file's header.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use scholar::{NeuralNet, Sigmoid};
+    ///
+    /// # fn main() -> Result<(), scholar::LoadErr> {
     /// let brain: NeuralNet<Sigmoid> = NeuralNet::from_file("brain.network")?;
+    /// # Ok(())
+    /// # }
     /// ```
+    #[cfg(feature = "fs")]
     pub fn from_file(path: impl AsRef<Path>) -> Result<Self, LoadErr> {
-        let file = fs::File::open(path)?;
-        let decoded: NeuralNet<A> = bincode::deserialize_from(file)?;
+        let format = ModelFormat::from_path(&path);
+        Self::from_file_as(path, format)
+    }
+
+    /// Creates a new `NeuralNet` from a file saved in the given format, regardless of its
+    /// extension.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use scholar::{ModelFormat, NeuralNet, Sigmoid};
+    ///
+    /// # fn main() -> Result<(), scholar::LoadErr> {
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::from_file_as("brain.bin", ModelFormat::Bincode)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn from_file_as(path: impl AsRef<Path>, format: ModelFormat) -> Result<Self, LoadErr> {
+        Self::read_from(io::BufReader::new(fs::File::open(path)?), format)
+    }
+
+    /// Creates a new `NeuralNet` from bytes in the given format (those returned by
+    /// [`NeuralNet::save_to_bytes()`](#method.save_to_bytes), or read from a file saved by
+    /// [`NeuralNet::save()`](#method.save)), for environments without a filesystem such as
+    /// WebAssembly in the browser.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [`NeuralNet::from_file()`](#method.from_file),
+    /// besides failing to read a file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{ModelFormat, NeuralNet, Sigmoid};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 2, 1]);
+    /// let bytes = brain.save_to_bytes(ModelFormat::Bincode)?;
+    ///
+    /// let loaded: NeuralNet<Sigmoid> = NeuralNet::from_bytes(&bytes, ModelFormat::Bincode)?;
+    /// assert_eq!(loaded.guess(&[1.0, 0.0]).len(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_bytes(bytes: &[u8], format: ModelFormat) -> Result<Self, LoadErr> {
+        Self::read_from(bytes, format)
+    }
 
-        Ok(decoded)
+    /// Reads a saved network (its header, then the network itself) in the given format.
+    #[cfg(feature = "std")]
+    fn read_from(reader: impl io::Read, format: ModelFormat) -> Result<Self, LoadErr> {
+        header::read_network(reader, format)
     }
 
     /// Trains the network on the given `Dataset` for the given number of `iterations`.
     ///
+    /// The network iterates over the dataset exactly `iterations` times, and the progress bar
+    /// advances after every epoch when training for fewer than 100 iterations.
+    ///
+    /// See [`Trainer`](struct.Trainer.html) for more training options.
+    ///
     /// # Examples
     ///
-    /// ```rust
+    /// ```rust,no_run
     /// use scholar::{Dataset, NeuralNet, Sigmoid};
     ///
+    /// # fn main() -> Result<(), scholar::ParseCsvError> {
     /// let dataset = Dataset::from_csv("iris.csv", false, 4)?;
     ///
     /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[4, 10, 10, 1]);
@@ -96,41 +450,22 @@ impl<A: Activation + Serialize + DeserializeOwned> NeuralNet<A> {
     /// // Trains the network by iterating over the entire dataset 10,000 times. The last parameter
     /// // (the 'learning rate') dictates how quickly the network 'adapts to the dataset'
     /// brain.train(dataset, 10_000, 0.01);
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn train(&mut self, mut training_dataset: Dataset, iterations: u64, learning_rate: f64) {
-        let progress_bar = indicatif::ProgressBar::new(iterations);
-        progress_bar.set_style(
-            indicatif::ProgressStyle::default_bar()
-                .template("Training [{bar:30}] {percent:>3}% ETA: {eta}")
-                .progress_chars("=> "),
-        );
-
-        // The progress bar is only updated every percentage progressed so as not to significantly
-        // impact the speed of training
-        let percentile = iterations / 100;
-
-        for i in 1..iterations {
-            training_dataset.shuffle();
-            for (inputs, targets) in &training_dataset {
-                let guesses = self.guess(inputs);
-                self.backpropagate(&guesses, targets, learning_rate);
-            }
-
-            if i % percentile == 0 {
-                progress_bar.inc(percentile);
-            }
-        }
-
-        progress_bar.finish_and_clear();
+    #[cfg(feature = "std")]
+    pub fn train(&mut self, training_dataset: Dataset, iterations: u64, learning_rate: f64) {
+        Trainer::new(iterations, learning_rate).train(self, training_dataset);
     }
 
     /// Calculates the average cost of the network.
     ///
     /// # Examples
     ///
-    /// ```rust
+    /// ```rust,no_run
     /// use scholar::{Dataset, NeuralNet, Sigmoid};
     ///
+    /// # fn main() -> Result<(), scholar::ParseCsvError> {
     /// let dataset = Dataset::from_csv("iris.csv", false, 4)?;
     /// let (training_data, testing_data) = dataset.split(0.75);
     ///
@@ -139,8 +474,11 @@ impl<A: Activation + Serialize + DeserializeOwned> NeuralNet<A> {
     ///
     /// let avg_cost = brain.test(testing_data);
     /// println!("Accuracy: {:.2}%", (1.0 - avg_cost) * 100.0);
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn test(&mut self, testing_dataset: Dataset) -> f64 {
+    #[cfg(feature = "std")]
+    pub fn test(&self, testing_dataset: Dataset) -> f64 {
         let mut avg_cost = 0.0;
         for (inputs, targets) in &testing_dataset {
             let guesses = self.guess(inputs);
@@ -160,140 +498,1528 @@ impl<A: Activation + Serialize + DeserializeOwned> NeuralNet<A> {
 
     /// Saves the network in a binary format to the specified path.
     ///
+    /// The format is chosen from the path's extension by
+    /// [`ModelFormat::from_path()`](enum.ModelFormat.html#method.from_path), so that (with the
+    /// `msgpack` or `cbor` features) a path ending in `.msgpack` or `.cbor` is saved in a format
+    /// that other languages can read. Any other extension uses the default bincode format.
+    ///
+    /// The file starts with a magic number and the version of the file format, followed by a
+    /// header describing the network's architecture, activation and precision, so that
+    /// [`NeuralNet::from_file()`](#method.from_file) can reject incompatible files with a
+    /// descriptive error rather than loading a broken network.
+    ///
     /// # Examples
     ///
-    /// ```rust
+    /// ```rust,no_run
     /// use scholar::{NeuralNet, Sigmoid};
     ///
+    /// # fn main() -> Result<(), scholar::SaveErr> {
     /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 2, 1]);
     ///
     /// // Note that the file doesn't have to use the '.network' extension; you can actually
     /// // choose anything you wish!
     /// brain.save("brain.network")?;
+    /// # Ok(())
+    /// # }
     /// ```
+    #[cfg(feature = "fs")]
     pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SaveErr> {
-        let encoded = bincode::serialize(&self)?;
-        fs::write(path, encoded)?;
+        let format = ModelFormat::from_path(&path);
+        self.save_as(path, format)
+    }
+
+    /// Saves the network to the specified path in the given format, regardless of its
+    /// extension.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use scholar::{ModelFormat, NeuralNet, Sigmoid};
+    ///
+    /// # fn main() -> Result<(), scholar::SaveErr> {
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 2, 1]);
+    /// brain.save_as("brain.bin", ModelFormat::Bincode)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn save_as(&self, path: impl AsRef<Path>, format: ModelFormat) -> Result<(), SaveErr> {
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        self.write_to(&mut file, format)?;
+        file.flush()?;
 
         Ok(())
     }
 
+    /// Saves the network in the given format to a vector of bytes, holding the same contents as
+    /// a file saved by [`NeuralNet::save_as()`](#method.save_as), so that it can be stored or
+    /// sent elsewhere without a filesystem (e.g. from WebAssembly in the browser).
+    ///
+    /// Load the network again with [`NeuralNet::from_bytes()`](#method.from_bytes).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{ModelFormat, NeuralNet, Sigmoid};
+    ///
+    /// # fn main() -> Result<(), scholar::SaveErr> {
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 2, 1]);
+    /// let bytes = brain.save_to_bytes(ModelFormat::Bincode)?;
+    ///
+    /// assert!(bytes.starts_with(b"SCHL"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn save_to_bytes(&self, format: ModelFormat) -> Result<Vec<u8>, SaveErr> {
+        let mut bytes = Vec::new();
+        self.write_to(&mut bytes, format)?;
+
+        Ok(bytes)
+    }
+
+    /// Writes the network's header, then the network itself, in the given format.
+    #[cfg(feature = "std")]
+    fn write_to(&self, writer: &mut impl Write, format: ModelFormat) -> Result<(), SaveErr> {
+        Header::of(self).write(writer, format)?;
+        format.serialize(writer, self)
+    }
+
     /// Performs the feedforward algorithm on the given input slice, returning the value of the
     /// output layer as a vector.
     ///
+    /// This doesn't modify the network, so a network shared between threads (such as in an `Arc`)
+    /// can guess on all of them at once. To guess without allocating, see
+    /// [`guess_into()`](#method.guess_into).
+    ///
     /// # Examples
     ///
     /// ```rust
     /// use scholar::{NeuralNet, Sigmoid};
     ///
-    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[3, 10, 2]);
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[3, 10, 2]);
     /// let result = brain.guess(&[1.0, 0.0, -0.5]);
     ///
     /// assert_eq!(result.len(), 2);
     /// ```
     ///
+    /// Guessing on several threads:
+    ///
+    /// ```rust
+    /// use scholar::{NeuralNet, Sigmoid};
+    /// use std::{sync::Arc, thread};
+    ///
+    /// let brain: Arc<NeuralNet<Sigmoid>> = Arc::new(NeuralNet::new(&[3, 10, 2]));
+    /// let handles: Vec<_> = (0..4)
+    ///     .map(|i| {
+    ///         let brain = Arc::clone(&brain);
+    ///         thread::spawn(move || brain.guess(&[i as f64, 0.0, -0.5]))
+    ///     })
+    ///     .collect();
+    ///
+    /// for handle in handles {
+    ///     assert_eq!(handle.join().unwrap().len(), 2);
+    /// }
+    /// ```
+    ///
     /// # Panics
     ///
     /// This method panics if the number of given input values is not equal to the number of nodes
     /// in the network's input layer.
-    pub fn guess(&mut self, inputs: &[f64]) -> Vec<f64> {
-        let num_inputs = inputs.len();
-        // The number of rows/values in the input layer of the network
-        let num_input_layer_rows = self.layers[0].row_iter().len();
-        if num_inputs != num_input_layer_rows {
+    pub fn guess(&self, inputs: &[f64]) -> Vec<f64> {
+        let layers = self.feedforward(inputs);
+        layers[layers.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| self.unscale_output(i, x))
+            .collect()
+    }
+
+    /// Performs the feedforward algorithm on the given input slice like
+    /// [`guess()`](#method.guess), but takes and returns values of the network's `Float` type
+    /// rather than `f64`s.
+    ///
+    /// The values are never converted to or from `f64`s (only the target scaler's statistics
+    /// are, if the network has one), so exotic `Float` types (such as dual numbers, which carry
+    /// derivatives alongside their values) flow through the whole network intact, as long as
+    /// the activation implements [`activate_float()`](trait.Activation.html#method.activate_float)
+    /// directly.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{NeuralNet, Sigmoid};
+    ///
+    /// let brain: NeuralNet<Sigmoid, f32> = NeuralNet::new(&[3, 10, 2]);
+    /// let result: Vec<f32> = brain.guess_float(&[1.0, 0.0, -0.5]);
+    ///
+    /// assert_eq!(result.len(), 2);
+    /// for (float, double) in result.iter().zip(brain.guess(&[1.0, 0.0, -0.5])) {
+    ///     assert!((f64::from(*float) - double).abs() < 1e-6);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of given input values is not equal to the number of nodes
+    /// in the network's input layer.
+    pub fn guess_float(&self, inputs: &[T]) -> Vec<T> {
+        self.check_inputs(inputs.len());
+
+        let inputs = DMatrix::from_column_slice(inputs.len(), 1, inputs);
+        let layers = self.feedforward_matrix(inputs);
+        layers[layers.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| match &self.target_scaler {
+                Some(scaler) => scaler.unscale_float(i, x),
+                None => x,
+            })
+            .collect()
+    }
+
+    /// Performs the feedforward algorithm on the given input slice like
+    /// [`guess()`](#method.guess), but writes the value of the output layer into `outputs`, and
+    /// keeps the values of every layer in the given `Scratch` rather than allocating them.
+    ///
+    /// Once the scratch space has been sized for the network (on its first use), guessing
+    /// doesn't allocate at all, which speeds up high-throughput inference. Each thread that
+    /// guesses at once needs its own `Scratch`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{NeuralNet, Scratch, Sigmoid};
+    ///
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[3, 10, 2]);
+    /// let mut scratch = Scratch::new();
+    /// let mut outputs = [0.0; 2];
+    ///
+    /// for inputs in &[[1.0, 0.0, -0.5], [0.0, 1.0, 0.5]] {
+    ///     brain.guess_into(inputs, &mut outputs, &mut scratch);
+    ///     assert_eq!(outputs.to_vec(), brain.guess(inputs));
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of given input (or output) values is not equal to the
+    /// number of nodes in the network's input (or output) layer.
+    pub fn guess_into(&self, inputs: &[f64], outputs: &mut [f64], scratch: &mut Scratch<T>) {
+        let num_output_layer_rows = self.layers[self.layers.len() - 1].nrows();
+        if outputs.len() != num_output_layer_rows {
             panic!(
-                "incorrect number of inputs supplied (expected {}, found {})",
-                num_input_layer_rows, num_inputs
+                "incorrect number of outputs supplied (expected {}, found {})",
+                num_output_layer_rows,
+                outputs.len()
             );
         }
 
-        let num_layers = self.layers.len();
-        // Stores the given inputs into the network's input layer
-        self.layers[0] = convert_slice_to_matrix(inputs);
+        self.feedforward_into(inputs, scratch);
+        let layers = &scratch.layers;
+        let guesses = layers[layers.len() - 1].iter();
+        for (i, (output, &x)) in outputs.iter_mut().zip(guesses).enumerate() {
+            *output = self.unscale_output(i, x);
+        }
+    }
 
-        for i in 0..num_layers - 1 {
-            let mut value = &self.weights[i] * &self.layers[i];
-            value += &self.biases[i];
+    /// Performs the feedforward algorithm on the given sparse input vector like
+    /// [`guess()`](#method.guess), returning the values of the output layer.
+    ///
+    /// Only the weights of the non-zero inputs are multiplied, so guessing from
+    /// high-dimensional inputs that are mostly zeros (such as bag-of-words vectors) takes a
+    /// fraction of the time that guessing from their dense equivalents would.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{NeuralNet, Sigmoid, SparseVector};
+    ///
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[1000, 10, 2]);
+    /// let inputs = SparseVector::new(1000, vec![(3, 1.0), (512, 2.0)]);
+    ///
+    /// let guesses = brain.guess_sparse(&inputs);
+    /// for (sparse, dense) in guesses.iter().zip(brain.guess(&inputs.to_dense())) {
+    ///     assert!((sparse - dense).abs() < 1e-12);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the length of the given input vector is not equal to the number of
+    /// nodes in the network's input layer.
+    pub fn guess_sparse(&self, inputs: &SparseVector) -> Vec<f64> {
+        self.check_inputs(inputs.len());
 
-            for x in value.iter_mut() {
-                *x = A::activate(*x);
-            }
+        let mut value = DMatrix::from_element(self.biases[0].nrows(), 1, T::zero());
+        self.feedforward_sparse_layer(inputs, &mut value);
+        let parameters = self.weights.iter().zip(&self.biases);
+        for (i, (weights, biases)) in parameters.enumerate().skip(1) {
+            value = weights * value;
+            value += biases;
 
-            // Feeds the value forward to the next layer
-            self.layers[i + 1] = value;
+            self.activate_layer(i, value.as_mut_slice());
         }
 
-        self.layers[num_layers - 1].iter().cloned().collect()
+        value
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| self.unscale_output(i, x))
+            .collect()
     }
 
-    /// Performs the backpropagation algorithm using the network's guessed values for a particular
-    /// input, and the real target values.
-    fn backpropagate(&mut self, guesses: &[f64], targets: &[f64], learning_rate: f64) {
-        let guesses = convert_slice_to_matrix(guesses);
-        let targets = convert_slice_to_matrix(targets);
+    /// Performs the feedforward algorithm on the given input slice, storing the values of every
+    /// layer in the given scratch space.
+    pub(crate) fn feedforward_into(&self, inputs: &[f64], scratch: &mut Scratch<T>) {
+        self.check_inputs(inputs.len());
 
-        let num_layers = self.layers.len();
-        // Calculates and sets the value of the last error matrix
-        self.errors[num_layers - 2] = targets - guesses;
+        scratch.fit(&self.layers);
+        let layers = &mut scratch.layers;
+        for (x, &input) in layers[0].iter_mut().zip(inputs) {
+            *x = T::convert(input);
+        }
 
-        // Iterates over each layer (except for the input layer) in reverse
-        for (i, layer) in self.layers.iter().enumerate().skip(1).rev() {
-            let mut gradients = layer.map(A::derivative);
-            gradients.component_mul_assign(&self.errors[i - 1]);
-            gradients *= learning_rate;
+        self.feedforward_layers(layers, 0);
+    }
 
-            let deltas = &gradients * self.layers[i - 1].transpose();
-            self.weights[i - 1] += deltas;
+    /// Performs the feedforward algorithm on the given sparse input vector, storing the values of
+    /// every layer in the given scratch space (except for the input layer's, which are left
+    /// as they were).
+    #[cfg(feature = "std")]
+    pub(crate) fn feedforward_sparse_into(&self, inputs: &SparseVector, scratch: &mut Scratch<T>) {
+        self.check_inputs(inputs.len());
 
-            self.biases[i - 1] += gradients;
+        scratch.fit(&self.layers);
+        let layers = &mut scratch.layers;
+        self.feedforward_sparse_layer(inputs, &mut layers[1]);
+        self.feedforward_layers(layers, 1);
+    }
 
-            // Calculates the errors for the next layer unless it is the last iteration
-            if i != 1 {
-                self.errors[i - 2] = self.weights[i - 1].transpose() * &self.errors[i - 1];
-            }
+    /// Calculates the values of the first hidden layer from a sparse input vector, adding only
+    /// the columns of the weights of its non-zero values to the biases.
+    fn feedforward_sparse_layer(&self, inputs: &SparseVector, value: &mut DMatrix<T>) {
+        value.copy_from(&self.biases[0]);
+        for (j, x) in inputs.iter() {
+            let weights = self.weights[0].column(j);
+            value.column_mut(0).axpy(T::convert(x), &weights, T::one());
         }
+
+        self.activate_layer(0, value.as_mut_slice());
     }
-}
 
-/// An activation for a `NeuralNet`, including a function and a 'derivative' function.
-///
-/// # Examples
-///
-/// The code below shows how to implement the
-/// [ReLU activation](https://en.wikipedia.org/wiki/Rectifier_(neural_networks)):
-///
-/// ```rust
-/// use serde::{Serialize, Deserialize};
-///
-/// // The activation must be serializable and deserializable so that the network can be
-/// // saved/loaded to/from files
-/// #[derive(Serialize, Deserialize)]
-/// struct Relu;
-///
-/// impl scholar::Activation for Relu {
-///     fn activate(x: f64) -> f64 {
-///         x.max(0.0)
-///     }
-///
-///     fn derivative(x: f64) -> f64 {
-///         if x > 0.0 {
-///             1.0
-///         } else {
-///             0.0
-///         }
-///     }
-/// }
-/// ```
-pub trait Activation {
-    /// The activation function.
-    fn activate(x: f64) -> f64;
-    /// The 'derivative' of the activation function.
-    ///
-    /// There is a small quirk regarding this function that occurs when it
-    /// 'references' the `activate` function of the same trait implementation.
-    /// For example, the real derivative of the sigmoid (σ) function is:
+    /// Feeds the values of the given layer forward through every layer after it.
+    fn feedforward_layers(&self, layers: &mut [DMatrix<T>], first: usize) {
+        let parameters = self.weights.iter().zip(&self.biases);
+        for (i, (weights, biases)) in parameters.enumerate().skip(first) {
+            let (previous, next) = layers.split_at_mut(i + 1);
+            let value = &mut next[0];
+            // Multiplying by a single column doesn't allocate (unlike multiplying by a matrix,
+            // which nalgebra does with `matrixmultiply`)
+            value.gemm(T::one(), weights, &previous[i], T::zero());
+            *value += biases;
+
+            self.activate_layer(i, value.as_mut_slice());
+        }
+    }
+
+    /// Panics if the given number of input values is not equal to the number of nodes in the
+    /// network's input layer.
+    fn check_inputs(&self, num_inputs: usize) {
+        // The number of rows/values in the input layer of the network
+        let num_input_layer_rows = self.layers[0].nrows();
+        if num_inputs != num_input_layer_rows {
+            panic!(
+                "incorrect number of inputs supplied (expected {}, found {})",
+                num_input_layer_rows, num_inputs
+            );
+        }
+    }
+
+    /// Performs the feedforward algorithm on the given input slice, returning the values of every
+    /// layer (starting with the input layer).
+    ///
+    /// The values are returned rather than stored in the network, so that guessing only needs a
+    /// shared reference and can happen on many threads at once.
+    pub(crate) fn feedforward(&self, inputs: &[f64]) -> Vec<DMatrix<T>> {
+        self.check_inputs(inputs.len());
+        self.feedforward_matrix(convert_slice_to_matrix(inputs))
+    }
+
+    /// Performs the feedforward algorithm on the given input column, which has already been
+    /// checked to fit the input layer, returning the values of every layer.
+    fn feedforward_matrix(&self, inputs: DMatrix<T>) -> Vec<DMatrix<T>> {
+        let mut layers = Vec::with_capacity(self.layers.len());
+        layers.push(inputs);
+
+        for (i, (weights, biases)) in self.weights.iter().zip(&self.biases).enumerate() {
+            let mut value = weights * &layers[layers.len() - 1];
+            value += biases;
+
+            self.activate_layer(i, value.as_mut_slice());
+
+            // Feeds the value forward to the next layer
+            layers.push(value);
+        }
+
+        layers
+    }
+
+    /// Predicts the class of the given input slice.
+    ///
+    /// A network with a single output node is treated as a binary classifier, predicting class 1
+    /// if the output is at least the network's [`threshold()`](#method.threshold) and class 0
+    /// otherwise. Networks with multiple output nodes predict the index of the largest output.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{NeuralNet, Sigmoid};
+    ///
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[4, 10, 3]);
+    /// let class = brain.predict_class(&[5.1, 3.5, 1.4, 0.2]);
+    ///
+    /// assert!(class < 3);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of given input values is not equal to the number of nodes
+    /// in the network's input layer.
+    pub fn predict_class(&self, inputs: &[f64]) -> usize {
+        decode_class(&self.guess(inputs), self.threshold)
+    }
+
+    /// Predicts the class of the given input slice with the lowest expected misclassification
+    /// cost, according to the given `CostMatrix`.
+    ///
+    /// See [`CostMatrix::min_cost_class()`](metrics/struct.CostMatrix.html#method.min_cost_class)
+    /// for how the network's guesses are interpreted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{metrics::CostMatrix, NeuralNet, Sigmoid};
+    ///
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+    ///
+    /// // Never predicting the positive class costs nothing, so it is never chosen
+    /// let costs = CostMatrix::new(vec![vec![0.0, 1.0], vec![0.0, 0.0]]);
+    /// assert_eq!(brain.predict_min_cost_class(&[0.0, 1.0], &costs), 0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of given input values is not equal to the number of nodes
+    /// in the network's input layer, or if the network's outputs imply a different number of
+    /// classes to the cost matrix.
+    #[cfg(feature = "std")]
+    pub fn predict_min_cost_class(&self, inputs: &[f64], costs: &CostMatrix) -> usize {
+        costs.min_cost_class(&self.guess(inputs))
+    }
+
+    /// Returns the threshold at which a single-output network classifies its input as positive.
+    ///
+    /// This is 0.5 unless changed with [`set_threshold()`](#method.set_threshold) or
+    /// [`tune_threshold()`](#method.tune_threshold).
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Sets the threshold at which a single-output network classifies its input as positive.
+    pub fn set_threshold(&mut self, threshold: f64) {
+        self.threshold = threshold;
+    }
+
+    /// Returns whether the network's output layer is left unactivated (see
+    /// [`set_linear_output()`](#method.set_linear_output)).
+    pub fn has_linear_output(&self) -> bool {
+        self.linear_output
+    }
+
+    /// Sets whether the network's output layer is left unactivated, as though its activation
+    /// were [`Linear`](struct.Linear.html), whilst its hidden layers keep the network's
+    /// activation.
+    ///
+    /// An activation such as the sigmoid squashes every output into a narrow range (0 to 1), so
+    /// regression targets outside of it can't be learned without rescaling them. A linear output
+    /// can take any value, and is trained to minimise the same squared error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{Dataset, NeuralNet, Sigmoid};
+    ///
+    /// // Converts temperatures from Celsius to Fahrenheit
+    /// let dataset: Dataset = (-10..=40)
+    ///     .step_by(5)
+    ///     .map(|c| (vec![c as f64 / 40.0], vec![c as f64 * 1.8 + 32.0]))
+    ///     .collect();
+    ///
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[1, 4, 1]);
+    /// brain.set_linear_output(true);
+    /// brain.train(dataset, 2_000, 0.001);
+    ///
+    /// let fahrenheit = brain.guess(&[20.0 / 40.0])[0];
+    /// assert!((fahrenheit - 68.0).abs() < 5.0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `linear` is `false` whilst the network has a target scaler (see
+    /// [`set_target_scaler()`](#method.set_target_scaler)).
+    pub fn set_linear_output(&mut self, linear: bool) {
+        if !linear && self.target_scaler.is_some() {
+            panic!("networks with a target scaler must have a linear output layer");
+        }
+
+        self.linear_output = linear;
+    }
+
+    /// Returns the scaler of the network's targets, if it has one (see
+    /// [`set_target_scaler()`](#method.set_target_scaler)).
+    pub fn target_scaler(&self) -> Option<&TargetScaler> {
+        self.target_scaler.as_ref()
+    }
+
+    /// Sets (or removes) the scaler of the network's targets.
+    ///
+    /// With a scaler, the targets of every row are scaled before the network trains on them,
+    /// and its guesses are inverse-transformed, so that it can be trained and used on raw values
+    /// (such as prices) as they are. The scaler is saved with the network.
+    ///
+    /// Scaled targets can take any value, which an activation such as the sigmoid can't output,
+    /// so the network must have a linear output layer (see
+    /// [`set_linear_output()`](#method.set_linear_output)) to have a scaler.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{Dataset, NeuralNet, Sigmoid, TargetScaler};
+    ///
+    /// // Prices of houses by their size in hundreds of square metres
+    /// let dataset: Dataset = (0..=10)
+    ///     .map(|i| (vec![i as f64 / 10.0], vec![150_000.0 + i as f64 * 20_000.0]))
+    ///     .collect();
+    ///
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[1, 4, 1]);
+    /// brain.set_linear_output(true);
+    /// brain.set_target_scaler(Some(TargetScaler::fit(&dataset)));
+    /// brain.train(dataset, 1_000, 0.05);
+    ///
+    /// let price = brain.guess(&[0.5])[0];
+    /// assert!((price - 250_000.0).abs() < 10_000.0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the network's output layer isn't linear, or if the number of
+    /// columns of the scaler is not equal to the number of nodes in the network's output layer.
+    pub fn set_target_scaler(&mut self, scaler: Option<TargetScaler>) {
+        if scaler.is_some() && !self.linear_output {
+            panic!("networks with a target scaler must have a linear output layer");
+        }
+
+        let num_output_layer_rows = self.layers[self.layers.len() - 1].nrows();
+        if let Some(scaler) = scaler.as_ref().filter(|s| s.len() != num_output_layer_rows) {
+            panic!(
+                "incorrect number of target columns supplied (expected {}, found {})",
+                num_output_layer_rows,
+                scaler.len()
+            );
+        }
+
+        self.target_scaler = scaler;
+    }
+
+    /// Returns the given target of the output node at the given index, as scaled by the
+    /// network's target scaler (if it has one).
+    fn scale_target(&self, index: usize, target: f64) -> T {
+        T::convert(match &self.target_scaler {
+            Some(scaler) => scaler.scale(index, target),
+            None => target,
+        })
+    }
+
+    /// Returns the value of the output node at the given index, with the scaling of the
+    /// network's target scaler (if it has one) reversed.
+    fn unscale_output(&self, index: usize, output: T) -> f64 {
+        match &self.target_scaler {
+            Some(scaler) => scaler.unscale(index, output.as_f64()),
+            None => output.as_f64(),
+        }
+    }
+
+    /// Returns whether the layer at the given index (excluding the input layer) is left
+    /// unactivated.
+    fn is_linear(&self, layer: usize) -> bool {
+        self.linear_output && layer + 1 == self.weights.len()
+    }
+
+    /// Applies the activation of the layer at the given index (excluding the input layer) to its
+    /// values.
+    fn activate_layer(&self, layer: usize, values: &mut [T]) {
+        if !self.is_linear(layer) {
+            A::activate_slice(values);
+        }
+    }
+
+    /// Replaces the (activated) values of the layer at the given index (excluding the input
+    /// layer) with the derivatives of its activation.
+    fn derivative_layer(&self, layer: usize, values: &mut [T]) {
+        if self.is_linear(layer) {
+            values.fill(T::one());
+        } else {
+            A::derivative_slice(values);
+        }
+    }
+
+    /// Sweeps the classification threshold over the network's guesses for the given validation
+    /// `Dataset`, and stores (and returns) the threshold that maximizes the given `metric`.
+    ///
+    /// The stored threshold is saved alongside the network, and is used by
+    /// [`predict_class()`](#method.predict_class) and the [`metrics`](metrics/index.html)
+    /// functions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use scholar::{Dataset, NeuralNet, Sigmoid};
+    ///
+    /// # fn main() -> Result<(), scholar::ParseCsvError> {
+    /// let dataset = Dataset::from_csv("diabetes.csv", true, 8)?;
+    /// let (training_data, validation_data) = dataset.split(0.75);
+    ///
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[8, 10, 1]);
+    /// brain.train(training_data, 1_000, 0.01);
+    ///
+    /// // Chooses the threshold with the best F1 score for the positive class
+    /// let threshold = brain.tune_threshold(&validation_data, |matrix| matrix.class_f1(1));
+    /// println!("Threshold: {:.2}", threshold);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the network has more than one output node.
+    #[cfg(feature = "std")]
+    pub fn tune_threshold(
+        &mut self,
+        validation_dataset: &Dataset,
+        metric: impl Fn(&ConfusionMatrix) -> f64,
+    ) -> f64 {
+        let threshold = Predictions::new(self, validation_dataset).best_threshold(metric);
+        self.threshold = threshold;
+
+        threshold
+    }
+
+    /// Calculates the gradient of the network's cost (half the squared error between its guesses
+    /// and the given targets) with respect to each of the given inputs. This indicates how
+    /// sensitive the network's performance is to a change in each input.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{NeuralNet, Sigmoid};
+    ///
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[3, 10, 2]);
+    /// let gradient = brain.input_gradient(&[1.0, 0.0, -0.5], &[1.0, 0.0]);
+    ///
+    /// assert_eq!(gradient.len(), 3);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of given input (or target) values is not equal to the
+    /// number of nodes in the network's input (or output) layer.
+    pub fn input_gradient(&self, inputs: &[f64], targets: &[f64]) -> Vec<f64> {
+        let layers = self.feedforward(inputs);
+        let guesses = &layers[layers.len() - 1];
+        if targets.len() != guesses.nrows() {
+            panic!(
+                "incorrect number of targets supplied (expected {}, found {})",
+                guesses.nrows(),
+                targets.len()
+            );
+        }
+
+        // The gradient of the cost with respect to the output layer
+        let targets = DMatrix::from_fn(targets.len(), 1, |i, _| self.scale_target(i, targets[i]));
+        let mut gradient = guesses - targets;
+
+        // Propagates the gradient back through each layer to the input layer
+        for (i, layer) in layers.iter().enumerate().skip(1).rev() {
+            let mut deltas = layer.clone();
+            self.derivative_layer(i - 1, deltas.as_mut_slice());
+            deltas.component_mul_assign(&gradient);
+            gradient = self.weights[i - 1].transpose() * deltas;
+        }
+
+        gradient.iter().map(|x| x.as_f64()).collect()
+    }
+
+    /// Calculates the gradients of the cost for a single row with respect to every weight and
+    /// bias, from the values of every layer (as returned by
+    /// [`feedforward()`](#method.feedforward)) and the row's targets, without updating the
+    /// network.
+    ///
+    /// The gradients point in the direction that decreases the cost, so that they can be applied
+    /// with [`apply_gradients()`](#method.apply_gradients) after being clipped, combined or
+    /// noised.
+    #[cfg(feature = "std")]
+    pub(crate) fn backward(&self, layers: &[DMatrix<T>], targets: &[f64]) -> Gradients<T> {
+        let num_layers = layers.len();
+        let targets = DMatrix::from_fn(targets.len(), 1, |i, _| self.scale_target(i, targets[i]));
+        let mut errors = targets - &layers[num_layers - 1];
+
+        let mut weights = Vec::with_capacity(num_layers - 1);
+        let mut biases = Vec::with_capacity(num_layers - 1);
+
+        // Iterates over each layer (except for the input layer) in reverse
+        for (i, layer) in layers.iter().enumerate().skip(1).rev() {
+            let mut deltas = layer.clone();
+            self.derivative_layer(i - 1, deltas.as_mut_slice());
+            deltas.component_mul_assign(&errors);
+
+            weights.push(&deltas * layers[i - 1].transpose());
+            errors = self.weights[i - 1].transpose() * &deltas;
+            biases.push(deltas);
+        }
+
+        weights.reverse();
+        biases.reverse();
+        Gradients { weights, biases }
+    }
+
+    /// Performs the feedforward algorithm on the inputs of the given rows (as pairs of inputs and
+    /// targets) at once, returning the values of every layer with a column for each row.
+    ///
+    /// The rows are fed through the network as the columns of a single matrix, so that each layer
+    /// takes one matrix-matrix multiplication (on the given device) rather than one per row.
+    #[cfg(any(feature = "matrixmultiply", feature = "gpu"))]
+    pub(crate) fn batch_feedforward(
+        &self,
+        rows: &[(&[f64], &[f64])],
+        device: &Device,
+    ) -> Vec<DMatrix<T>> {
+        let num_input_layer_rows = self.layers[0].nrows();
+        if let Some((inputs, _)) = rows.iter().find(|(x, _)| x.len() != num_input_layer_rows) {
+            panic!(
+                "incorrect number of inputs supplied (expected {}, found {})",
+                num_input_layer_rows,
+                inputs.len()
+            );
+        }
+
+        // Each layer's values, with a column for each row
+        let mut layers = Vec::with_capacity(self.layers.len());
+        layers.push(DMatrix::from_fn(
+            num_input_layer_rows,
+            rows.len(),
+            |i, j| T::convert(rows[j].0[i]),
+        ));
+        for (i, (weights, biases)) in self.weights.iter().zip(&self.biases).enumerate() {
+            let mut value = device.mul(weights, false, &layers[layers.len() - 1], false);
+            for mut column in value.column_iter_mut() {
+                column += biases;
+            }
+            self.activate_layer(i, value.as_mut_slice());
+            layers.push(value);
+        }
+
+        layers
+    }
+
+    /// Calculates the sum of the gradients of the given rows, from the values of every layer (as
+    /// returned by [`batch_feedforward()`](#method.batch_feedforward)), which is the same (besides
+    /// rounding) as summing the [`backward()`](#method.backward) gradients of each row.
+    #[cfg(any(feature = "matrixmultiply", feature = "gpu"))]
+    pub(crate) fn batch_backward(
+        &self,
+        layers: &[DMatrix<T>],
+        rows: &[(&[f64], &[f64])],
+        device: &Device,
+    ) -> Gradients<T> {
+        let num_layers = layers.len();
+        let num_outputs = layers[num_layers - 1].nrows();
+        let targets = DMatrix::from_fn(num_outputs, rows.len(), |i, j| {
+            self.scale_target(i, rows[j].1[i])
+        });
+        let mut errors = targets - &layers[num_layers - 1];
+
+        let mut weights = Vec::with_capacity(num_layers - 1);
+        let mut biases = Vec::with_capacity(num_layers - 1);
+
+        // Iterates over each layer (except for the input layer) in reverse
+        for (i, layer) in layers.iter().enumerate().skip(1).rev() {
+            let mut deltas = layer.clone();
+            self.derivative_layer(i - 1, deltas.as_mut_slice());
+            deltas.component_mul_assign(&errors);
+
+            weights.push(device.mul(&deltas, false, &layers[i - 1], true));
+            errors = device.mul(&self.weights[i - 1], true, &deltas, false);
+            let sums = deltas.column_sum();
+            biases.push(DMatrix::from_column_slice(sums.nrows(), 1, sums.as_slice()));
+        }
+
+        weights.reverse();
+        biases.reverse();
+        Gradients { weights, biases }
+    }
+
+    /// Updates the weights and biases of the network by the given gradients, scaled by the
+    /// learning rate.
+    #[cfg(feature = "std")]
+    pub(crate) fn apply_gradients(&mut self, gradients: &Gradients<T>, learning_rate: f64) {
+        let learning_rate = T::convert(learning_rate);
+        for (i, gradient) in gradients.weights.iter().enumerate() {
+            if !self.is_frozen(i) {
+                self.weights[i] += gradient * learning_rate;
+            }
+        }
+        for (i, gradient) in gradients.biases.iter().enumerate() {
+            if !self.is_frozen(i) {
+                self.biases[i] += gradient * learning_rate;
+            }
+        }
+    }
+
+    /// Performs the backpropagation algorithm using the network's guessed values for a particular
+    /// input (which [`feedforward_into()`](#method.feedforward_into) has stored in the given
+    /// scratch space), and the real target values.
+    ///
+    /// The gradients of each layer are also kept in the scratch space, and the errors in the
+    /// network's own, so that training row by row doesn't allocate.
+    #[cfg(feature = "std")]
+    pub(crate) fn backpropagate(
+        &mut self,
+        targets: &[f64],
+        learning_rate: f64,
+        scratch: &mut Scratch<T>,
+    ) {
+        self.backpropagate_from(None, targets, learning_rate, scratch);
+    }
+
+    /// Performs the backpropagation algorithm like [`backpropagate()`](#method.backpropagate),
+    /// after [`feedforward_sparse_into()`](#method.feedforward_sparse_into) has fed the given
+    /// sparse input vector forward, only updating the weights of its non-zero values.
+    #[cfg(feature = "std")]
+    pub(crate) fn backpropagate_sparse(
+        &mut self,
+        inputs: &SparseVector,
+        targets: &[f64],
+        learning_rate: f64,
+        scratch: &mut Scratch<T>,
+    ) {
+        self.backpropagate_from(Some(inputs), targets, learning_rate, scratch);
+    }
+
+    /// Performs the backpropagation algorithm, reading the inputs from the given sparse vector
+    /// if there is one, and otherwise from the input layer of the scratch space.
+    #[cfg(feature = "std")]
+    fn backpropagate_from(
+        &mut self,
+        sparse_inputs: Option<&SparseVector>,
+        targets: &[f64],
+        learning_rate: f64,
+        scratch: &mut Scratch<T>,
+    ) {
+        let num_layers = self.layers.len();
+        let num_output_layer_rows = self.layers[num_layers - 1].nrows();
+        if targets.len() != num_output_layer_rows {
+            panic!(
+                "incorrect number of targets supplied (expected {}, found {})",
+                num_output_layer_rows,
+                targets.len()
+            );
+        }
+
+        let Scratch { layers, deltas } = scratch;
+
+        // Calculates and sets the value of the last error matrix
+        let guesses = &layers[num_layers - 1];
+        for (j, &target) in targets.iter().enumerate() {
+            self.errors[num_layers - 2][j] = self.scale_target(j, target) - guesses[j];
+        }
+
+        // Iterates over each layer (except for the input layer) in reverse
+        for i in (1..num_layers).rev() {
+            let gradients = &mut deltas[i - 1];
+            gradients.copy_from(&layers[i]);
+            self.derivative_layer(i - 1, gradients.as_mut_slice());
+            gradients.component_mul_assign(&self.errors[i - 1]);
+            *gradients *= T::convert(learning_rate);
+
+            // Frozen layers still pass their errors back to the layers before them
+            if !self.is_frozen(i - 1) {
+                match sparse_inputs.filter(|_| i == 1) {
+                    // Only the columns of the non-zero inputs' weights change
+                    Some(inputs) => {
+                        let gradients = gradients.column(0);
+                        for (j, x) in inputs.iter() {
+                            let mut weights = self.weights[0].column_mut(j);
+                            weights.axpy(T::convert(x), &gradients, T::one());
+                        }
+                    }
+                    // Adds the outer product of the gradients and the previous layer's values
+                    None => self.weights[i - 1].ger(
+                        T::one(),
+                        &gradients.column(0),
+                        &layers[i - 1].column(0),
+                        T::one(),
+                    ),
+                }
+
+                self.biases[i - 1] += &*gradients;
+            }
+
+            // Calculates the errors for the next layer unless it is the last iteration
+            if i != 1 {
+                let (previous, next) = self.errors.split_at_mut(i - 1);
+                previous[i - 2].gemm_tr(T::one(), &self.weights[i - 1], &next[0], T::zero());
+            }
+        }
+    }
+}
+
+impl<A: Activation, T: Float> NeuralNet<A, T> {
+    /// Returns the number of nodes in each layer, including the input layer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{NeuralNet, Sigmoid};
+    ///
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[4, 10, 3]);
+    /// assert_eq!(brain.layer_sizes(), vec![4, 10, 3]);
+    /// ```
+    pub fn layer_sizes(&self) -> Vec<usize> {
+        self.layers.iter().map(|layer| layer.nrows()).collect()
+    }
+
+    /// Returns the weights between each pair of adjacent layers.
+    ///
+    /// The `i`th matrix holds the weights from layer `i` to layer `i + 1`, with a row for each
+    /// node in layer `i + 1` and a column for each node in layer `i` (so that multiplying it by
+    /// the values of layer `i` gives the weighted sums of layer `i + 1`). The matrices are from
+    /// [`nalgebra`](https://docs.rs/nalgebra/0.32), which is re-exported as `scholar::nalgebra`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{NeuralNet, Sigmoid};
+    ///
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[4, 10, 3]);
+    /// let shapes: Vec<_> = brain.weights().iter().map(|w| w.shape()).collect();
+    /// assert_eq!(shapes, vec![(10, 4), (3, 10)]);
+    ///
+    /// // The largest weight into the output layer
+    /// let largest = brain.weights()[1].max();
+    /// ```
+    pub fn weights(&self) -> &[DMatrix<T>] {
+        &self.weights
+    }
+
+    /// Returns the biases of each layer besides the input layer, as column vectors.
+    ///
+    /// The `i`th matrix holds the biases of layer `i + 1`, with a row for each of its nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{NeuralNet, Sigmoid};
+    ///
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[4, 10, 3]);
+    /// let shapes: Vec<_> = brain.biases().iter().map(|b| b.shape()).collect();
+    /// assert_eq!(shapes, vec![(10, 1), (3, 1)]);
+    /// ```
+    pub fn biases(&self) -> &[DMatrix<T>] {
+        &self.biases
+    }
+
+    /// Returns whether the network has the same architecture, threshold and target scaler as
+    /// another, with weights and biases that each differ by at most `epsilon`, such as after a
+    /// network is saved in a lossy format or converted to another precision.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{NeuralNet, Sigmoid};
+    ///
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 3, 1]);
+    /// let round_trip: NeuralNet<Sigmoid> = brain.cast::<f32>().cast();
+    ///
+    /// assert!(round_trip != brain);
+    /// assert!(round_trip.approx_eq(&brain, 1e-6));
+    /// ```
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        let close = |a: &[DMatrix<T>], b: &[DMatrix<T>]| {
+            a.len() == b.len()
+                && a.iter().zip(b).all(|(a, b)| {
+                    a.shape() == b.shape()
+                        && a.iter()
+                            .zip(b.iter())
+                            .all(|(x, y)| (x.as_f64() - y.as_f64()).abs() <= epsilon)
+                })
+        };
+
+        self.threshold == other.threshold
+            && self.linear_output == other.linear_output
+            && self.target_scaler == other.target_scaler
+            && close(&self.weights, &other.weights)
+            && close(&self.biases, &other.biases)
+    }
+
+    /// Replaces the weights between each pair of adjacent layers (in the same layout as
+    /// [`weights()`](#method.weights)), such as with weights trained by another framework or
+    /// evolved by a genetic algorithm.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{nalgebra::DMatrix, NeuralNet, Sigmoid};
+    ///
+    /// # fn main() -> Result<(), scholar::ShapeErr> {
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 1]);
+    /// brain.set_weights(vec![DMatrix::from_row_slice(1, 2, &[20.0, 20.0])])?;
+    /// brain.set_biases(vec![DMatrix::from_element(1, 1, -10.0)])?;
+    ///
+    /// // The network now computes OR
+    /// assert!(brain.guess(&[0.0, 1.0])[0] > 0.99);
+    /// assert!(brain.guess(&[0.0, 0.0])[0] < 0.01);
+    ///
+    /// // The weights must have the same shapes as the network's
+    /// assert!(brain.set_weights(vec![DMatrix::zeros(2, 1)]).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error (leaving the network unchanged) if the number of matrices
+    /// isn't one less than the number of layers, or if any matrix has a different shape to the
+    /// network's.
+    #[cfg(feature = "std")]
+    pub fn set_weights(&mut self, weights: Vec<DMatrix<T>>) -> Result<(), ShapeErr> {
+        check_shapes(&self.weights, &weights)?;
+        self.weights = weights;
+        Ok(())
+    }
+
+    /// Replaces the biases of each layer besides the input layer (in the same layout as
+    /// [`biases()`](#method.biases)).
+    ///
+    /// See [`set_weights()`](#method.set_weights) for an example.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error (leaving the network unchanged) if the number of matrices
+    /// isn't one less than the number of layers, or if any matrix has a different shape to the
+    /// network's.
+    #[cfg(feature = "std")]
+    pub fn set_biases(&mut self, biases: Vec<DMatrix<T>>) -> Result<(), ShapeErr> {
+        check_shapes(&self.biases, &biases)?;
+        self.biases = biases;
+        Ok(())
+    }
+
+    /// Replaces the output layer with a freshly initialized one of the given number of nodes,
+    /// keeping the earlier layers, such as to fine-tune a pretrained network for a task with a
+    /// different number of outputs.
+    ///
+    /// The new layer isn't frozen (see [`freeze_layers()`](#method.freeze_layers)), whilst the
+    /// earlier layers stay frozen or unfrozen as they were. The network's target scaler (which
+    /// scaled the old outputs) is removed. To resize the hidden layers too, use
+    /// [`from_pretrained_resized()`](#method.from_pretrained_resized).
+    ///
+    /// # Examples
     ///
+    /// ```rust
+    /// use scholar::{Dataset, NeuralNet, Sigmoid};
+    ///
+    /// // A network trained on a large dataset with 3 classes...
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[4, 8, 3]);
+    ///
+    /// // ...fine-tuned on a small one with 2 classes, reusing its hidden layer
+    /// brain.replace_output_layer(2);
+    /// brain.freeze_layers(..1);
+    /// brain.train(Dataset::from(vec![(vec![0.1, 0.2, 0.3, 0.4], vec![1.0, 0.0])]), 10, 0.1);
+    ///
+    /// assert_eq!(brain.layer_sizes(), vec![4, 8, 2]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the given number of nodes is 0.
+    #[cfg(feature = "std")]
+    pub fn replace_output_layer(&mut self, node_count: usize) {
+        if node_count == 0 {
+            panic!("output layer must have at least 1 node");
+        }
+
+        let last = self.weights.len() - 1;
+        let previous_count = self.weights[last].ncols();
+        self.weights[last] = gen_random_matrix(node_count, previous_count);
+        self.biases[last] = gen_random_matrix(node_count, 1);
+        self.layers[last + 1] = DMatrix::zeros(node_count, 1);
+        self.errors[last] = DMatrix::zeros(node_count, 1);
+        self.target_scaler = None;
+        if let Some(frozen) = self.frozen.get_mut(last) {
+            *frozen = false;
+        }
+    }
+
+    /// Freezes the given range of layers, so that training leaves their weights and biases
+    /// unchanged, such as to fine-tune only the output layer of a network trained on a larger
+    /// dataset.
+    ///
+    /// The layers are indexed as in [`weights()`](#method.weights), from 0 for the first layer
+    /// after the input layer. Whether layers are frozen isn't saved with the network.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{Dataset, NeuralNet, Sigmoid};
+    ///
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 4, 1]);
+    /// let hidden_weights = brain.weights()[..2].to_vec();
+    ///
+    /// // Freezes every layer but the output layer
+    /// brain.freeze_layers(..2);
+    /// brain.train(Dataset::from(vec![(vec![0.0, 1.0], vec![1.0])]), 10, 0.1);
+    /// assert_eq!(brain.weights()[..2], hidden_weights[..]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the range extends past the last layer.
+    pub fn freeze_layers(&mut self, layers: impl RangeBounds<usize>) {
+        self.set_frozen(layers, true);
+    }
+
+    /// Unfreezes the given range of layers (indexed as in
+    /// [`freeze_layers()`](#method.freeze_layers)), so that training updates them again.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the range extends past the last layer.
+    pub fn unfreeze_layers(&mut self, layers: impl RangeBounds<usize>) {
+        self.set_frozen(layers, false);
+    }
+
+    /// Returns whether the given layer (indexed as in [`freeze_layers()`](#method.freeze_layers))
+    /// is frozen.
+    pub fn is_frozen(&self, layer: usize) -> bool {
+        self.frozen.get(layer).copied().unwrap_or(false)
+    }
+
+    /// Sets whether each of the given range of layers is frozen.
+    fn set_frozen(&mut self, layers: impl RangeBounds<usize>, frozen: bool) {
+        let count = self.weights.len();
+        let start = match layers.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match layers.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => count,
+        };
+        if start > end || end > count {
+            panic!(
+                "layer range out of bounds (expected within 0..{}, found {}..{})",
+                count, start, end
+            );
+        }
+
+        self.frozen.resize(count, false);
+        self.frozen[start..end].fill(frozen);
+    }
+
+    /// Returns an iterator over every weight and then every bias, in the same order as the
+    /// values of the network's `Gradients`.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn parameters(&self) -> impl Iterator<Item = T> + '_ {
+        self.weights
+            .iter()
+            .chain(&self.biases)
+            .flat_map(|m| m.iter().cloned())
+    }
+
+    /// Returns a mutable iterator over every weight and then every bias.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn parameters_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.weights
+            .iter_mut()
+            .chain(&mut self.biases)
+            .flat_map(|m| m.iter_mut())
+    }
+
+    /// Returns whether each parameter (in the order of `parameters()`) is in a frozen layer.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn frozen_parameters(&self) -> Vec<bool> {
+        let weights = self.weights.iter().enumerate();
+        let biases = self.biases.iter().enumerate();
+        weights
+            .chain(biases)
+            .flat_map(|(i, m)| core::iter::repeat_n(self.is_frozen(i), m.len()))
+            .collect()
+    }
+
+    /// Returns a table of the network's layers, with their number of nodes and parameters (i.e.
+    /// weights and biases), along with its activation and precision, for debugging and reports.
+    ///
+    /// This is the same as formatting the network with `{}`. The number of parameters in
+    /// [frozen](#method.freeze_layers) layers is included too, if there are any.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{NeuralNet, Sigmoid};
+    ///
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[4, 10, 3]);
+    /// print!("{}", brain.summary());
+    /// // NeuralNet (sigmoid, f64)
+    /// //
+    /// //   Layer       Nodes   Weights   Biases   Parameters
+    /// //   Input           4
+    /// //   Hidden 1       10        40       10           50
+    /// //   Output          3        30        3           33
+    /// //
+    /// //   Total parameters: 83
+    /// assert!(brain.summary().contains("Total parameters: 83"));
     /// ```
+    pub fn summary(&self) -> String {
+        self.to_string()
+    }
+}
+
+// Implemented by hand, as deriving them would require the activation to implement them too
+impl<A: Activation, T: Float> Clone for NeuralNet<A, T> {
+    fn clone(&self) -> Self {
+        Self {
+            layers: self.layers.clone(),
+            weights: self.weights.clone(),
+            biases: self.biases.clone(),
+            errors: self.errors.clone(),
+            threshold: self.threshold,
+            linear_output: self.linear_output,
+            target_scaler: self.target_scaler.clone(),
+            frozen: self.frozen.clone(),
+            activation: PhantomData,
+        }
+    }
+}
+
+impl<A: Activation, T: Float> fmt::Debug for NeuralNet<A, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NeuralNet")
+            .field("activation", &A::name())
+            .field("weights", &self.weights)
+            .field("biases", &self.biases)
+            .field("threshold", &self.threshold)
+            .field("linear_output", &self.linear_output)
+            .field("target_scaler", &self.target_scaler)
+            .field("frozen", &self.frozen)
+            .finish()
+    }
+}
+
+/// Networks are equal if they have exactly the same weights, biases, threshold, output
+/// activation and target scaler, regardless of the values left in their layers by the last
+/// guess or whether any layers are frozen (see
+/// [`NeuralNet::approx_eq()`](struct.NeuralNet.html#method.approx_eq) to allow for rounding).
+impl<A: Activation, T: Float> PartialEq for NeuralNet<A, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.weights == other.weights
+            && self.biases == other.biases
+            && self.threshold == other.threshold
+            && self.linear_output == other.linear_output
+            && self.target_scaler == other.target_scaler
+    }
+}
+
+impl<A: Activation, T: Float> fmt::Display for NeuralNet<A, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let output = if self.linear_output {
+            ", linear output"
+        } else {
+            ""
+        };
+        writeln!(
+            f,
+            "NeuralNet ({}{}, {})",
+            A::name(),
+            output,
+            T::precision()
+        )?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "  {:<10} {:>6} {:>9} {:>8} {:>12}",
+            "Layer", "Nodes", "Weights", "Biases", "Parameters"
+        )?;
+        writeln!(f, "  {:<10} {:>6}", "Input", self.layers[0].nrows())?;
+
+        let (mut total, mut frozen) = (0, 0);
+        for (i, (weights, biases)) in self.weights.iter().zip(&self.biases).enumerate() {
+            let name = if i == self.weights.len() - 1 {
+                "Output".to_string()
+            } else {
+                format!("Hidden {}", i + 1)
+            };
+            let parameters = weights.len() + biases.len();
+            total += parameters;
+            if self.is_frozen(i) {
+                frozen += parameters;
+            }
+
+            writeln!(
+                f,
+                "  {:<10} {:>6} {:>9} {:>8} {:>12}",
+                name,
+                biases.nrows(),
+                weights.len(),
+                biases.len(),
+                parameters
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(f, "  Total parameters: {}", total)?;
+        if frozen > 0 {
+            writeln!(f, "  Frozen parameters: {}", frozen)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks that each of the given matrices has the same shape as the corresponding existing one.
+#[cfg(feature = "std")]
+fn check_shapes<T: Float>(existing: &[DMatrix<T>], given: &[DMatrix<T>]) -> Result<(), ShapeErr> {
+    if given.len() != existing.len() {
+        return Err(ShapeErr::Count {
+            expected: existing.len(),
+            found: given.len(),
+        });
+    }
+
+    match existing
+        .iter()
+        .zip(given)
+        .position(|(existing, given)| existing.shape() != given.shape())
+    {
+        Some(index) => Err(ShapeErr::Shape {
+            index,
+            expected: existing[index].shape(),
+            found: given[index].shape(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// The gradients of a network's weights and biases, in the same shapes as the network's own.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub(crate) struct Gradients<T: Float> {
+    weights: Vec<DMatrix<T>>,
+    biases: Vec<DMatrix<T>>,
+}
+
+#[cfg(feature = "std")]
+impl<T: Float> Gradients<T> {
+    /// Returns the L2 norm of every gradient combined.
+    pub(crate) fn norm(&self) -> f64 {
+        self.values()
+            .map(|x| x.as_f64() * x.as_f64())
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Scales every gradient by the given factor.
+    pub(crate) fn scale(&mut self, factor: f64) {
+        let factor = T::convert(factor);
+        self.values_mut().for_each(|x| *x *= factor);
+    }
+
+    /// Adds another set of gradients (of the same network) to these ones.
+    pub(crate) fn add(&mut self, other: &Gradients<T>) {
+        self.values_mut()
+            .zip(other.values())
+            .for_each(|(x, y)| *x += y);
+    }
+
+    /// Returns an iterator over every gradient value.
+    pub(crate) fn values(&self) -> impl Iterator<Item = T> + '_ {
+        self.weights
+            .iter()
+            .chain(&self.biases)
+            .flat_map(|m| m.iter().cloned())
+    }
+
+    /// Returns a mutable iterator over every gradient value.
+    pub(crate) fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.weights
+            .iter_mut()
+            .chain(&mut self.biases)
+            .flat_map(|m| m.iter_mut())
+    }
+}
+
+/// Scratch space for [`NeuralNet::guess_into()`](struct.NeuralNet.html#method.guess_into), which
+/// holds the values of each of a network's layers so that guessing doesn't allocate them.
+///
+/// A new `Scratch` is empty, and is sized for the network that it is first used with, after
+/// which it can be reused for any number of guesses (by networks of the same shape, as it is
+/// resized for others).
+#[derive(Clone, Debug)]
+pub struct Scratch<T: Float = f64> {
+    layers: Vec<DMatrix<T>>,
+    /// The gradients of each layer (except for the input layer), whilst training.
+    deltas: Vec<DMatrix<T>>,
+}
+
+impl<T: Float> Scratch<T> {
+    /// Creates an empty `Scratch`.
+    pub fn new() -> Self {
+        Self {
+            layers: Vec::new(),
+            deltas: Vec::new(),
+        }
+    }
+
+    /// Resizes the scratch space to match the given layers, unless it already does.
+    fn fit(&mut self, layers: &[DMatrix<T>]) {
+        let fits = self.layers.len() == layers.len()
+            && self
+                .layers
+                .iter()
+                .zip(layers)
+                .all(|(scratch, layer)| scratch.nrows() == layer.nrows());
+        if !fits {
+            self.layers = layers
+                .iter()
+                .map(|layer| DMatrix::from_element(layer.nrows(), 1, T::zero()))
+                .collect();
+            self.deltas = self.layers[1..].to_vec();
+        }
+    }
+}
+
+impl<T: Float> Default for Scratch<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A description of a `NeuralNet`'s architecture and training hyperparameters, from which fresh
+/// networks can be built and trained repeatedly (e.g. once per fold when cross-validating).
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{Dataset, NetworkConfig, NeuralNet, Sigmoid};
+///
+/// let dataset = Dataset::from(vec![
+///     (vec![0.0, 0.0], vec![0.0]),
+///     (vec![0.0, 1.0], vec![1.0]),
+///     (vec![1.0, 0.0], vec![1.0]),
+///     (vec![1.0, 1.0], vec![0.0]),
+/// ]);
+///
+/// let config = NetworkConfig::new(&[2, 4, 1], 100, 0.1);
+/// let brain: NeuralNet<Sigmoid> = config.train(dataset);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    /// The number of nodes in each layer (see
+    /// [`NeuralNet::new()`](struct.NeuralNet.html#method.new)).
+    pub node_counts: Vec<usize>,
+    /// The number of times to iterate over the training dataset.
+    pub iterations: u64,
+    /// How quickly the network adapts to the training dataset.
+    pub learning_rate: f64,
+}
+
+#[cfg(feature = "std")]
+impl NetworkConfig {
+    /// Creates a new `NetworkConfig`.
+    pub fn new(node_counts: &[usize], iterations: u64, learning_rate: f64) -> Self {
+        Self {
+            node_counts: node_counts.to_vec(),
+            iterations,
+            learning_rate,
+        }
+    }
+
+    /// Builds a new (untrained) network with the configured architecture.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if fewer than 2 layers are configured.
+    pub fn build<A: Activation + Serialize + DeserializeOwned, T: Float>(&self) -> NeuralNet<A, T> {
+        NeuralNet::new(&self.node_counts)
+    }
+
+    /// Builds a new network and trains it on the given `Dataset` with the configured
+    /// hyperparameters.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if fewer than 2 layers are configured.
+    pub fn train<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &self,
+        training_dataset: Dataset,
+    ) -> NeuralNet<A, T> {
+        let mut network = self.build();
+        network.train(training_dataset, self.iterations, self.learning_rate);
+
+        network
+    }
+}
+
+/// An activation for a `NeuralNet`, including a function and a 'derivative' function.
+///
+/// # Examples
+///
+/// The code below shows how to implement the
+/// [ReLU activation](https://en.wikipedia.org/wiki/Rectifier_(neural_networks)):
+///
+/// ```rust
+/// use serde::{Serialize, Deserialize};
+///
+/// // The activation must be serializable and deserializable so that the network can be
+/// // saved/loaded to/from files
+/// #[derive(Serialize, Deserialize)]
+/// struct Relu;
+///
+/// impl scholar::Activation for Relu {
+///     fn activate(x: f64) -> f64 {
+///         x.max(0.0)
+///     }
+///
+///     fn derivative(x: f64) -> f64 {
+///         if x > 0.0 {
+///             1.0
+///         } else {
+///             0.0
+///         }
+///     }
+/// }
+/// ```
+pub trait Activation {
+    /// The activation function.
+    fn activate(x: f64) -> f64;
+    /// The 'derivative' of the activation function.
+    ///
+    /// There is a small quirk regarding this function that occurs when it
+    /// 'references' the `activate` function of the same trait implementation.
+    /// For example, the real derivative of the sigmoid (σ) function is:
+    ///
+    /// ```text
     /// σ(x) * (1 - σ(x))
     /// ```
     ///
@@ -302,13 +2028,13 @@ pub trait Activation {
     /// function will have always been applied to the input of its derivative function, no matter
     /// the circumstances. The derivative of sigmoid thus becomes:
     ///
-    /// ```
+    /// ```text
     /// x * (1 - x)
     /// ```
     ///
     /// which matches what the real implementation looks like:
     ///
-    /// ```rust
+    /// ```rust,ignore
     /// impl Activation for Sigmoid {
     ///     ...
     ///
@@ -318,6 +2044,152 @@ pub trait Activation {
     /// }
     /// ```
     fn derivative(x: f64) -> f64;
+
+    /// The activation function for any `Float` type, used by networks whose calculations aren't
+    /// in `f64` precision.
+    ///
+    /// By default, this converts the value to an `f64` and back, so that only
+    /// [`activate()`](#tymethod.activate) needs implementing. Implementing it directly avoids the
+    /// conversions, and lets exotic `Float` types (which may hold more than a single number)
+    /// flow through the activation intact.
+    fn activate_float<T: Float>(x: T) -> T {
+        T::convert(Self::activate(x.as_f64()))
+    }
+
+    /// The 'derivative' of the activation function for any `Float` type (see
+    /// [`activate_float()`](#method.activate_float)).
+    fn derivative_float<T: Float>(x: T) -> T {
+        T::convert(Self::derivative(x.as_f64()))
+    }
+
+    /// Applies the activation function to every value of a slice (e.g. a whole layer), in place.
+    ///
+    /// By default, this calls [`activate_float()`](#method.activate_float) on each value in
+    /// turn. Implementing it directly lets the values be processed several at a time with SIMD
+    /// instructions, as `Sigmoid` does for `f32` and `f64`, which speeds up networks with wide
+    /// layers.
+    fn activate_slice<T: Float>(xs: &mut [T]) {
+        for x in xs {
+            *x = Self::activate_float(*x);
+        }
+    }
+
+    /// Applies the 'derivative' of the activation function to every value of a slice, in place
+    /// (see [`activate_slice()`](#method.activate_slice)).
+    fn derivative_slice<T: Float>(xs: &mut [T]) {
+        for x in xs {
+            *x = Self::derivative_float(*x);
+        }
+    }
+
+    /// The name identifying the activation in saved networks, so that a network can't be loaded
+    /// with a different activation than it was trained with.
+    ///
+    /// By default, this is the full path of the implementing type (e.g. `my_crate::Tanh`), which
+    /// changes if the type is moved or renamed. Override it with a fixed name to keep saved
+    /// networks loadable across such changes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{Activation, LoadErr, NeuralNet, Sigmoid};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Tanh;
+    ///
+    /// impl Activation for Tanh {
+    ///     fn activate(x: f64) -> f64 {
+    ///         x.tanh()
+    ///     }
+    ///
+    ///     fn derivative(x: f64) -> f64 {
+    ///         1.0 - x.powi(2)
+    ///     }
+    ///
+    ///     fn name() -> &'static str {
+    ///         "tanh"
+    ///     }
+    /// }
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let path = std::env::temp_dir().join("scholar_activation_example.network");
+    /// NeuralNet::<Sigmoid>::new(&[2, 3, 1]).save(&path)?;
+    ///
+    /// let result = NeuralNet::<Tanh>::from_file(&path);
+    /// assert!(matches!(result, Err(LoadErr::Activation { .. })));
+    /// # std::fs::remove_file(path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn name() -> &'static str {
+        core::any::type_name::<Self>()
+    }
+}
+
+/// A scalar type that a `NeuralNet`'s weights and calculations can use.
+///
+/// This is implemented for every (serializable and thread-safe) type that implements
+/// [`num_traits::Float`](https://docs.rs/num-traits/0.2/num_traits/float/trait.Float.html),
+/// including `f32` and `f64`, but also more exotic types such as fixed-point numbers for
+/// embedded devices, or dual numbers for analysing the sensitivity of a network's outputs.
+pub trait Float:
+    num_traits::Float
+    + Scalar
+    + ClosedAdd
+    + ClosedSub
+    + ClosedMul
+    + Serialize
+    + DeserializeOwned
+    + Send
+    + Sync
+{
+    /// Converts an `f64` to this type, rounding it if necessary.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the value can't be represented by this type.
+    fn convert(x: f64) -> Self {
+        <Self as num_traits::NumCast>::from(x).expect("value must be representable")
+    }
+
+    /// Converts a value of this type to an `f64`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the value can't be represented by an `f64`.
+    fn as_f64(self) -> f64 {
+        self.to_f64()
+            .expect("value must be representable as an f64")
+    }
+
+    /// The name identifying the type in saved networks, so that a network can't be loaded as a
+    /// different type than it was saved as.
+    ///
+    /// This is `"f32"` or `"f64"` for those types, and the full path of any other type (which
+    /// changes if the type is moved or renamed).
+    fn precision() -> &'static str {
+        if TypeId::of::<Self>() == TypeId::of::<f64>() {
+            "f64"
+        } else if TypeId::of::<Self>() == TypeId::of::<f32>() {
+            "f32"
+        } else {
+            core::any::type_name::<Self>()
+        }
+    }
+}
+
+impl<T> Float for T where
+    T: num_traits::Float
+        + Scalar
+        + ClosedAdd
+        + ClosedSub
+        + ClosedMul
+        + Serialize
+        + DeserializeOwned
+        + Send
+        + Sync
+{
 }
 
 /// The sigmoid activation.
@@ -326,32 +2198,181 @@ pub struct Sigmoid;
 
 impl Activation for Sigmoid {
     fn activate(x: f64) -> f64 {
-        1.0 / (1.0 + (-x).exp())
+        1.0 / (1.0 + num_traits::Float::exp(-x))
     }
 
     fn derivative(x: f64) -> f64 {
         x * (1.0 - x)
     }
+
+    fn activate_float<T: Float>(x: T) -> T {
+        T::one() / (T::one() + (-x).exp())
+    }
+
+    fn derivative_float<T: Float>(x: T) -> T {
+        x * (T::one() - x)
+    }
+
+    fn activate_slice<T: Float>(xs: &mut [T]) {
+        // SAFETY: the slice is only cast to the type that it has been checked to be
+        if TypeId::of::<T>() == TypeId::of::<f64>() {
+            let xs = unsafe { slice::from_raw_parts_mut(xs.as_mut_ptr() as *mut f64, xs.len()) };
+            simd::sigmoid_f64(xs);
+        } else if TypeId::of::<T>() == TypeId::of::<f32>() {
+            let xs = unsafe { slice::from_raw_parts_mut(xs.as_mut_ptr() as *mut f32, xs.len()) };
+            simd::sigmoid_f32(xs);
+        } else {
+            for x in xs {
+                *x = Self::activate_float(*x);
+            }
+        }
+    }
+
+    fn name() -> &'static str {
+        "sigmoid"
+    }
+}
+
+/// The identity (or linear) activation, which leaves values unchanged.
+///
+/// A network whose every layer is linear can only learn linear functions, so this is mostly
+/// useful for the output layer of a regression network (see
+/// [`NeuralNet::set_linear_output()`](struct.NeuralNet.html#method.set_linear_output)).
+#[derive(Serialize, Deserialize)]
+pub struct Linear;
+
+impl Activation for Linear {
+    fn activate(x: f64) -> f64 {
+        x
+    }
+
+    fn derivative(_: f64) -> f64 {
+        1.0
+    }
+
+    fn activate_float<T: Float>(x: T) -> T {
+        x
+    }
+
+    fn derivative_float<T: Float>(_: T) -> T {
+        T::one()
+    }
+
+    fn activate_slice<T: Float>(_: &mut [T]) {}
+
+    fn name() -> &'static str {
+        "linear"
+    }
 }
 
-/// An enumeration over the possible errors when saving a network to a file.
+/// An enumeration over the possible errors when saving a network (or other data) to a file.
+#[cfg(feature = "std")]
 #[derive(thiserror::Error, Debug)]
 pub enum SaveErr {
-    /// When serializing the network fails.
-    #[error("failed to serialize network")]
+    /// When serializing the data fails.
+    #[error("failed to serialize data")]
     Serialize(#[from] bincode::Error),
+    /// When serializing the data to JSON fails.
+    #[error("failed to serialize JSON")]
+    Json(#[from] serde_json::Error),
+    /// When serializing the data to MessagePack fails.
+    #[cfg(feature = "msgpack")]
+    #[error("failed to serialize MessagePack")]
+    MessagePack(#[from] rmp_serde::encode::Error),
+    /// When serializing the data to CBOR fails.
+    #[cfg(feature = "cbor")]
+    #[error("failed to serialize CBOR")]
+    Cbor(#[from] ciborium::ser::Error<std::io::Error>),
     /// When writing to the file fails.
     #[error("failed to write to file")]
     FileWrite(#[from] std::io::Error),
+    /// When exporting a network whose activation the format doesn't support.
+    #[error("activation '{0}' isn't supported by the format")]
+    UnsupportedActivation(String),
+    /// When exporting a network with a value that the format can't represent, such as NaN,
+    /// infinity or a number beyond the range of the format's floats.
+    #[error("value {0} can't be represented by the format")]
+    UnrepresentableValue(f64),
 }
 
-/// An enumeration over the possible errors when loading a network from a file.
+/// An enumeration over the possible errors when loading a network (or other data) from a file.
+#[cfg(feature = "std")]
 #[derive(thiserror::Error, Debug)]
 pub enum LoadErr {
-    /// When deserializing the network fails.
-    #[error("failed to deserialize network")]
+    /// When deserializing the data fails.
+    #[error("failed to deserialize data")]
     Deserialize(#[from] bincode::Error),
+    /// When deserializing the data from JSON fails.
+    #[error("failed to parse JSON")]
+    Json(#[from] serde_json::Error),
+    /// When deserializing the data from MessagePack fails.
+    #[cfg(feature = "msgpack")]
+    #[error("failed to deserialize MessagePack")]
+    MessagePack(#[from] rmp_serde::decode::Error),
+    /// When deserializing the data from CBOR fails.
+    #[cfg(feature = "cbor")]
+    #[error("failed to deserialize CBOR")]
+    Cbor(#[from] ciborium::de::Error<std::io::Error>),
+    /// When reading an HDF5 file fails.
+    #[cfg(feature = "keras")]
+    #[error("failed to read HDF5 file")]
+    Hdf5(#[from] hdf5_pure::Error),
+    /// When the file neither starts with the magic number of a saved network, nor holds a network
+    /// saved before files had one.
+    #[error("file isn't a saved network")]
+    Magic,
+    /// When the network was saved with an unsupported (newer) version of the file format.
+    #[error("unsupported file format version {0}")]
+    Version(u32),
+    /// When the network was saved with a different `Activation` than it is loaded with.
+    #[error("network has the wrong activation (expected '{expected}', found '{found}')")]
+    Activation {
+        /// The name of the activation of the network being loaded.
+        expected: String,
+        /// The name of the activation of the saved network.
+        found: String,
+    },
+    /// When the network's activation isn't registered in the
+    /// [`Registry`](registry/struct.Registry.html) that it is loaded with.
+    #[cfg(feature = "fs")]
+    #[error("unknown activation '{0}'")]
+    UnknownActivation(String),
+    /// When the network was saved with a different `Float` type than it is loaded as.
+    #[error("network has the wrong precision (expected {expected}, found {found})")]
+    Precision {
+        /// The `Float` type of the network being loaded.
+        expected: String,
+        /// The `Float` type of the saved network.
+        found: String,
+    },
+    /// When the loaded data doesn't describe a valid network.
+    #[error("invalid network: {0}")]
+    Invalid(String),
     /// When reading from the file fails.
     #[error("failed to read from file")]
     FileRead(#[from] std::io::Error),
+}
+
+/// An enumeration over the possible errors when replacing a network's weights or biases.
+#[cfg(feature = "std")]
+#[derive(thiserror::Error, Debug)]
+pub enum ShapeErr {
+    /// When the number of matrices isn't one less than the number of layers.
+    #[error("incorrect number of matrices supplied (expected {expected}, found {found})")]
+    Count {
+        /// The number of the network's matrices.
+        expected: usize,
+        /// The number of matrices supplied.
+        found: usize,
+    },
+    /// When a matrix has a different shape (in rows and columns) to the network's.
+    #[error("matrix {index} has the wrong shape (expected {expected:?}, found {found:?})")]
+    Shape {
+        /// The index of the matrix.
+        index: usize,
+        /// The shape of the network's matrix.
+        expected: (usize, usize),
+        /// The shape of the matrix supplied.
+        found: (usize, usize),
+    },
 }
\ No newline at end of file
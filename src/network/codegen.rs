@@ -0,0 +1,236 @@
+use super::{Activation, Float, NeuralNet, SaveErr};
+
+use nalgebra::DMatrix;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{fmt::Write, fs, path::Path};
+
+/// The generated layer function and approximation of `exp()`, which only use `core` so that the
+/// source compiles for `no_std` targets.
+const RUNTIME: &str = "
+fn layer<const I: usize, const O: usize>(
+    inputs: &[f32; I],
+    weights: &[[f32; I]; O],
+    biases: &[f32; O],
+    activate: fn(f32) -> f32,
+) -> [f32; O] {
+    let mut outputs = [0.0; O];
+    for ((output, row), bias) in outputs.iter_mut().zip(weights).zip(biases) {
+        let sum = row.iter().zip(inputs).fold(*bias, |sum, (w, x)| sum + w * x);
+        *output = activate(sum);
+    }
+    outputs
+}
+
+#[allow(dead_code)]
+fn exp(x: f32) -> f32 {
+    // Splits x into k * ln(2) + r (where |r| <= ln(2) / 2), so that exp(x) = 2^k * exp(r)
+    let x = x.clamp(-87.0, 88.0);
+    let k = (x * core::f32::consts::LOG2_E + if x < 0.0 { -0.5 } else { 0.5 }) as i32;
+    let r = x - k as f32 * core::f32::consts::LN_2;
+    let p = 1.0 / 120.0 + r / 720.0;
+    let p = 1.0 + r * (1.0 + r * (0.5 + r * (1.0 / 6.0 + r * (1.0 / 24.0 + r * p))));
+    p * f32::from_bits(((k + 127) as u32) << 23)
+}
+";
+
+/// The end of the generated `predict()` function of a network with a target scaler, which
+/// reverses the scaling of its outputs.
+const UNSCALE: &str = "    let scaling = TARGET_MEAN.iter().zip(&TARGET_STD_DEV);
+    for (output, (mean, std_dev)) in outputs.iter_mut().zip(scaling) {
+        *output = mean + *output * std_dev;
+    }
+    outputs
+}
+";
+
+impl<A: Activation + Serialize + DeserializeOwned, T: Float> NeuralNet<A, T> {
+    /// Exports the network to the specified path as Rust source code, holding its weights and
+    /// biases as constant arrays alongside a `predict()` function that performs the feedforward
+    /// algorithm.
+    ///
+    /// The generated code only uses `core` (with its own approximation of `exp()`), so that tiny
+    /// networks can run on microcontrollers and other `no_std` targets without depending on this
+    /// crate or deserializing a file. Include it in a crate as a module (e.g. `mod model;`) and
+    /// call `model::predict(&inputs)`, which takes and returns arrays of 32-bit floats with one
+    /// value per input and output node respectively.
+    ///
+    /// The activation is found from its [`name()`](trait.Activation.html#method.name):
+    /// `"sigmoid"`, `"tanh"`, `"relu"` and `"linear"` are supported. A network's target scaler is
+    /// held as constant arrays too, and reversed at the end of `predict()`.
+    ///
+    /// # Errors
+    ///
+    /// Besides failing to write the file, this method returns an error if the network's
+    /// activation isn't supported, or if any of its values isn't finite as a 32-bit float (i.e.
+    /// is NaN or infinite, or too large for an `f32`).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use scholar::{NeuralNet, Sigmoid};
+    ///
+    /// # fn main() -> Result<(), scholar::SaveErr> {
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 3, 1]);
+    ///
+    /// // Generates `pub fn predict(inputs: &[f32; 2]) -> [f32; 1]`
+    /// brain.export_rust("src/model.rs")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// The generated code compiles on its own, and predicts the same as the network (to within
+    /// the precision of 32-bit floats and the approximation of `exp()`):
+    ///
+    /// ```rust
+    /// use scholar::{NeuralNet, Sigmoid};
+    /// # use std::{fs, process::Command};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 3, 1]);
+    /// # let dir = std::env::temp_dir().join("scholar_export_rust_example");
+    /// # fs::create_dir_all(&dir)?;
+    /// brain.export_rust(dir.join("model.rs"))?;
+    ///
+    /// // In a crate with `mod model;`
+    /// let main = "mod model; fn main() { println!(\"{}\", model::predict(&[1.0, 0.0])[0]); }";
+    /// # fs::write(dir.join("main.rs"), main)?;
+    /// # let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    /// # let status = Command::new(rustc)
+    /// #     .args(&["--edition", "2018", "-o"])
+    /// #     .arg(dir.join("main"))
+    /// #     .arg(dir.join("main.rs"))
+    /// #     .status()?;
+    /// # assert!(status.success());
+    /// # let output = Command::new(dir.join("main")).output()?;
+    /// # let predicted: f64 = String::from_utf8(output.stdout)?.trim().parse()?;
+    /// assert!((predicted - brain.guess(&[1.0, 0.0])[0]).abs() < 1e-4);
+    /// # fs::remove_dir_all(&dir)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn export_rust(&self, path: impl AsRef<Path>) -> Result<(), SaveErr> {
+        let activation = match A::name() {
+            "sigmoid" => "1.0 / (1.0 + exp(-x))",
+            "tanh" => "2.0 / (1.0 + exp(-2.0 * x)) - 1.0",
+            "relu" => "if x > 0.0 { x } else { 0.0 }",
+            "linear" => "x",
+            name => return Err(SaveErr::UnsupportedActivation(name.to_string())),
+        };
+
+        let input_count = self.layers[0].nrows();
+        let output_count = self.layers[self.layers.len() - 1].nrows();
+
+        let mut source = format!(
+            "// Generated by scholar {} from a network with the {} activation.\n",
+            env!("CARGO_PKG_VERSION"),
+            A::name()
+        );
+        for (i, (weights, biases)) in self.weights.iter().zip(&self.biases).enumerate() {
+            source += &constants(i, weights, biases)?;
+        }
+        if let Some(scaler) = &self.target_scaler {
+            source += &vector_constant("TARGET_MEAN", scaler.mean())?;
+            source += &vector_constant("TARGET_STD_DEV", scaler.std_dev())?;
+        }
+
+        write!(
+            source,
+            "\npub fn predict(inputs: &[f32; {}]) -> [f32; {}] {{\n",
+            input_count, output_count
+        )
+        .expect("writing to a string cannot fail");
+        let mut previous = "inputs".to_string();
+        for i in 0..self.weights.len() {
+            let activate = if self.is_linear(i) {
+                "linear"
+            } else {
+                "activate"
+            };
+            let layer = format!(
+                "layer({}, &WEIGHTS_{1}, &BIASES_{1}, {2})",
+                previous, i, activate
+            );
+            if i + 1 == self.weights.len() && self.target_scaler.is_some() {
+                writeln!(source, "    let mut outputs = {};", layer)
+                    .expect("writing to a string cannot fail");
+                source += UNSCALE;
+            } else if i + 1 == self.weights.len() {
+                writeln!(source, "    {}\n}}", layer).expect("writing to a string cannot fail");
+            } else {
+                writeln!(source, "    let layer_{} = {};", i, layer)
+                    .expect("writing to a string cannot fail");
+                previous = format!("&layer_{}", i);
+            }
+        }
+
+        write!(
+            source,
+            "\nfn activate(x: f32) -> f32 {{\n    {}\n}}\n",
+            activation
+        )
+        .expect("writing to a string cannot fail");
+        if self.linear_output {
+            source += "\nfn linear(x: f32) -> f32 {\n    x\n}\n";
+        }
+        source += RUNTIME;
+        fs::write(path, source)?;
+
+        Ok(())
+    }
+}
+
+/// Generates the constant arrays of the weights and biases feeding into the layer at the given
+/// index (excluding the input layer).
+fn constants<T: Float>(
+    index: usize,
+    weights: &DMatrix<T>,
+    biases: &DMatrix<T>,
+) -> Result<String, SaveErr> {
+    let element = |x: &T| literal(x.as_f64());
+
+    let mut source = format!(
+        "\nconst WEIGHTS_{}: [[f32; {}]; {}] = [\n",
+        index,
+        weights.ncols(),
+        weights.nrows()
+    );
+    for row in weights.row_iter() {
+        let row = row.iter().map(element).collect::<Result<Vec<_>, _>>()?;
+        writeln!(source, "    [{}],", row.join(", ")).expect("writing to a string cannot fail");
+    }
+    let biases = biases.iter().map(element).collect::<Result<Vec<_>, _>>()?;
+    write!(
+        source,
+        "];\nconst BIASES_{}: [f32; {}] = [{}];\n",
+        index,
+        biases.len(),
+        biases.join(", ")
+    )
+    .expect("writing to a string cannot fail");
+
+    Ok(source)
+}
+
+/// Generates a constant array of the given values.
+fn vector_constant(name: &str, values: &[f64]) -> Result<String, SaveErr> {
+    let values = values
+        .iter()
+        .map(|&x| literal(x))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(format!(
+        "const {}: [f32; {}] = [{}];\n",
+        name,
+        values.len(),
+        values.join(", ")
+    ))
+}
+
+/// Generates an `f32` literal of the given value, which Rust only has for finite values.
+fn literal(x: f64) -> Result<String, SaveErr> {
+    let single = x as f32;
+    if single.is_finite() {
+        Ok(format!("{:?}", single))
+    } else {
+        Err(SaveErr::UnrepresentableValue(x))
+    }
+}
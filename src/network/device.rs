@@ -0,0 +1,171 @@
+#[cfg(feature = "gpu")]
+mod gpu;
+
+#[cfg(any(feature = "matrixmultiply", feature = "gpu"))]
+use super::Float;
+
+#[cfg(feature = "gpu")]
+pub use gpu::Gpu;
+#[cfg(any(feature = "matrixmultiply", feature = "gpu"))]
+use nalgebra::DMatrix;
+#[cfg(feature = "gpu")]
+use std::{any::TypeId, sync::Arc};
+
+/// The hardware that a network's matrices are multiplied on whilst training with mini-batches
+/// (see [`Trainer::device()`](struct.Trainer.html#method.device)).
+#[derive(Clone, Debug, Default)]
+pub enum Device {
+    /// The CPU, which multiplies matrices with nalgebra (or with the `matrixmultiply` crate's
+    /// kernels, with the `matrixmultiply` feature).
+    #[default]
+    Cpu,
+    /// A GPU, which multiplies matrices in wgpu compute shaders (requires the experimental `gpu`
+    /// feature).
+    ///
+    /// Shaders only calculate in `f32` precision, so only `f32` networks are trained on the GPU;
+    /// the matrices of any others are still multiplied on the CPU.
+    #[cfg(feature = "gpu")]
+    Gpu(Arc<Gpu>),
+}
+
+impl Device {
+    /// Opens the highest performance GPU available (which may be a software renderer when there
+    /// is no hardware GPU).
+    ///
+    /// Opening the GPU and compiling the shaders for it takes a while, so the returned `Device`
+    /// should be reused (clones of it share the same GPU).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use scholar::{Dataset, Device, NeuralNet, Sigmoid, Trainer};
+    ///
+    /// # fn main() -> Result<(), scholar::GpuErr> {
+    /// let dataset = Dataset::from(vec![
+    ///     (vec![0.0, 0.0], vec![0.0]),
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 0.0], vec![1.0]),
+    ///     (vec![1.0, 1.0], vec![0.0]),
+    /// ]);
+    ///
+    /// let mut brain: NeuralNet<Sigmoid, f32> = NeuralNet::new(&[2, 4, 1]);
+    /// Trainer::new(100, 0.5)
+    ///     .batch_size(4)
+    ///     .device(Device::gpu()?)
+    ///     .train(&mut brain, dataset);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "gpu")]
+    pub fn gpu() -> Result<Self, GpuErr> {
+        Gpu::new().map(|gpu| Device::Gpu(Arc::new(gpu)))
+    }
+
+    /// Returns the name of the device, e.g. `"CPU"` or the name of the GPU's adapter.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::Device;
+    ///
+    /// assert_eq!(Device::Cpu.name(), "CPU");
+    /// ```
+    pub fn name(&self) -> &str {
+        match self {
+            Device::Cpu => "CPU",
+            #[cfg(feature = "gpu")]
+            Device::Gpu(gpu) => gpu.name(),
+        }
+    }
+
+    /// Returns whether the device should be given whole batches of rows to multiply at once,
+    /// rather than rows one by one.
+    #[cfg(any(feature = "matrixmultiply", feature = "gpu"))]
+    pub(crate) fn multiplies_batches(&self) -> bool {
+        match self {
+            Device::Cpu => cfg!(feature = "matrixmultiply"),
+            #[cfg(feature = "gpu")]
+            Device::Gpu(_) => true,
+        }
+    }
+
+    /// Multiplies `a` (or its transpose) by `b` (or its transpose) on the device.
+    #[cfg(any(feature = "matrixmultiply", feature = "gpu"))]
+    pub(super) fn mul<T: Float>(
+        &self,
+        a: &DMatrix<T>,
+        transpose_a: bool,
+        b: &DMatrix<T>,
+        transpose_b: bool,
+    ) -> DMatrix<T> {
+        #[cfg(feature = "gpu")]
+        if let Device::Gpu(gpu) = self {
+            if TypeId::of::<T>() == TypeId::of::<f32>() {
+                // SAFETY: the matrices are only cast to the type that they have been checked to be
+                let (a, b) = unsafe {
+                    (
+                        &*(a as *const DMatrix<T> as *const DMatrix<f32>),
+                        &*(b as *const DMatrix<T> as *const DMatrix<f32>),
+                    )
+                };
+                if let Some(product) = gpu.mul(a, transpose_a, b, transpose_b) {
+                    let product = DMatrix::from_iterator(
+                        product.nrows(),
+                        product.ncols(),
+                        product.iter().map(|&x| T::convert(f64::from(x))),
+                    );
+                    return product;
+                }
+            }
+        }
+
+        cpu_mul(a, transpose_a, b, transpose_b)
+    }
+}
+
+impl PartialEq for Device {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Device::Cpu, Device::Cpu) => true,
+            #[cfg(feature = "gpu")]
+            (Device::Gpu(a), Device::Gpu(b)) => Arc::ptr_eq(a, b),
+            #[cfg(feature = "gpu")]
+            _ => false,
+        }
+    }
+}
+
+/// Multiplies `a` (or its transpose) by `b` (or its transpose) on the CPU.
+#[cfg(any(feature = "matrixmultiply", feature = "gpu"))]
+fn cpu_mul<T: Float>(
+    a: &DMatrix<T>,
+    transpose_a: bool,
+    b: &DMatrix<T>,
+    transpose_b: bool,
+) -> DMatrix<T> {
+    #[cfg(feature = "matrixmultiply")]
+    {
+        super::gemm::mul(a, transpose_a, b, transpose_b)
+    }
+    #[cfg(not(feature = "matrixmultiply"))]
+    {
+        match (transpose_a, transpose_b) {
+            (false, false) => a * b,
+            (true, false) => a.tr_mul(b),
+            (false, true) => a * b.transpose(),
+            (true, true) => a.transpose() * b.transpose(),
+        }
+    }
+}
+
+/// An error when opening a GPU.
+#[cfg(feature = "gpu")]
+#[derive(thiserror::Error, Debug)]
+pub enum GpuErr {
+    /// When no GPU adapter (or software renderer) is available.
+    #[error("no GPU adapter is available")]
+    Adapter(#[from] wgpu::RequestAdapterError),
+    /// When the GPU adapter can't be opened.
+    #[error("failed to open the GPU")]
+    Device(#[from] wgpu::RequestDeviceError),
+}
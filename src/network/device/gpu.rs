@@ -0,0 +1,222 @@
+//! Matrix multiplication in a wgpu compute shader.
+
+use super::GpuErr;
+
+use nalgebra::DMatrix;
+use std::fmt;
+use wgpu::util::DeviceExt;
+
+/// The compute shader, which calculates one value of the product per invocation.
+///
+/// The matrices are column-major, with the strides of `a` and `b` given so that either can be
+/// read as its transpose in place.
+const SHADER: &str = r#"
+struct Params {
+    m: u32,
+    k: u32,
+    n: u32,
+    rsa: u32,
+    csa: u32,
+    rsb: u32,
+    csb: u32,
+    padding: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> a: array<f32>;
+@group(0) @binding(2) var<storage, read> b: array<f32>;
+@group(0) @binding(3) var<storage, read_write> c: array<f32>;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    let j = id.y;
+    if (i >= params.m || j >= params.n) {
+        return;
+    }
+
+    var sum = 0.0;
+    for (var p = 0u; p < params.k; p++) {
+        sum += a[i * params.rsa + p * params.csa] * b[p * params.rsb + j * params.csb];
+    }
+    c[i + j * params.m] = sum;
+}
+"#;
+
+/// The size of the shader's workgroups in each dimension.
+const WORKGROUP_SIZE: u32 = 8;
+
+/// A GPU opened by [`Device::gpu()`](enum.Device.html#method.gpu), along with the compute
+/// pipeline that multiplies matrices on it.
+pub struct Gpu {
+    info: wgpu::AdapterInfo,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+}
+
+impl Gpu {
+    /// Opens the highest performance GPU available, and compiles the shader for it.
+    pub(super) fn new() -> Result<Self, GpuErr> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            ..Default::default()
+        }))?;
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default()))?;
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("scholar matmul"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("scholar matmul"),
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        Ok(Self {
+            info: adapter.get_info(),
+            device,
+            queue,
+            pipeline,
+        })
+    }
+
+    /// Returns the name of the GPU.
+    pub(super) fn name(&self) -> &str {
+        &self.info.name
+    }
+
+    /// Multiplies `a` (or its transpose) by `b` (or its transpose), or returns `None` if the
+    /// matrices are too large for the GPU's buffers.
+    pub(super) fn mul(
+        &self,
+        a: &DMatrix<f32>,
+        transpose_a: bool,
+        b: &DMatrix<f32>,
+        transpose_b: bool,
+    ) -> Option<DMatrix<f32>> {
+        let (m, k, rsa, csa) = if transpose_a {
+            (a.ncols(), a.nrows(), a.nrows(), 1)
+        } else {
+            (a.nrows(), a.ncols(), 1, a.nrows())
+        };
+        let (n, rsb, csb) = if transpose_b {
+            (b.nrows(), b.nrows(), 1)
+        } else {
+            (b.ncols(), 1, b.nrows())
+        };
+
+        // Empty buffers can't be bound, and the product is all zeros anyway
+        if m == 0 || n == 0 || k == 0 {
+            return Some(DMatrix::zeros(m, n));
+        }
+
+        let limits = self.device.limits();
+        let max_len = limits.max_storage_buffer_binding_size as usize / 4;
+        let max_workgroups = limits.max_compute_workgroups_per_dimension as usize;
+        let workgroups = |len: usize| len.div_ceil(WORKGROUP_SIZE as usize);
+        if a.len().max(b.len()).max(m * n) > max_len
+            || workgroups(m) > max_workgroups
+            || workgroups(n) > max_workgroups
+        {
+            return None;
+        }
+
+        let params = [m, k, n, rsa, csa, rsb, csb, 0].map(|x| x as u32);
+        let storage = |label, contents: &[f32]| {
+            self.device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some(label),
+                    contents: bytemuck::cast_slice(contents),
+                    usage: wgpu::BufferUsages::STORAGE,
+                })
+        };
+        let params = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("params"),
+                contents: bytemuck::cast_slice(&params),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+        let a = storage("a", a.as_slice());
+        let b = storage("b", b.as_slice());
+
+        let size = (m * n * 4) as u64;
+        let c = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("c"),
+            size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("staging"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: a.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: b.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: c.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(workgroups(m) as u32, workgroups(n) as u32, 1);
+        }
+        encoder.copy_buffer_to_buffer(&c, 0, &staging, 0, size);
+        self.queue.submit([encoder.finish()]);
+
+        let slice = staging.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| {
+            result.expect("failed to read the product from the GPU")
+        });
+        self.device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .expect("failed to wait for the GPU");
+        let data = slice
+            .get_mapped_range()
+            .expect("failed to read the product from the GPU");
+
+        Some(DMatrix::from_column_slice(
+            m,
+            n,
+            bytemuck::cast_slice(&data),
+        ))
+    }
+}
+
+impl fmt::Debug for Gpu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Gpu").field(&self.info.name).finish()
+    }
+}
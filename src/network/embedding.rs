@@ -0,0 +1,211 @@
+#[cfg(feature = "std")]
+use super::Scratch;
+use super::{matrices, Activation, Float, NeuralNet};
+#[cfg(feature = "std")]
+use crate::utils::gen_random_matrix;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::fmt;
+use nalgebra::DMatrix;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// The shape of an embedding, which maps each of a categorical input's categories to a learned
+/// dense vector (see [`EmbeddingNet`](struct.EmbeddingNet.html)).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Embedding {
+    /// The number of categories, whose IDs are `0..categories`.
+    pub categories: usize,
+    /// The number of values in each category's vector.
+    pub dimensions: usize,
+}
+
+impl Embedding {
+    /// Creates an embedding of the given number of categories into vectors of the given number
+    /// of dimensions.
+    pub fn new(categories: usize, dimensions: usize) -> Self {
+        Self {
+            categories,
+            dimensions,
+        }
+    }
+}
+
+/// A `NeuralNet` whose first inputs are category IDs, each of which is replaced by a learned
+/// dense vector (its embedding) before being fed into the network along with the remaining,
+/// numeric inputs.
+///
+/// Embeddings are trained jointly with the network (see
+/// [`Trainer::train_embedding()`](struct.Trainer.html#method.train_embedding)), and are far
+/// smaller than one-hot encodings of categorical inputs with many categories: a column of 10,000
+/// categories embedded in 8 dimensions adds 8 inputs to the network, rather than 10,000.
+///
+/// Category IDs are given as the first values of each row's inputs (as `f64`s, like the indices
+/// that a [`LabelEncoder`](preprocessing/struct.LabelEncoder.html) encodes labels into), in the
+/// same order as the embeddings.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{Dataset, Embedding, EmbeddingNet, Sigmoid, Trainer};
+///
+/// // A store ID (of 1,000 stores) and a day of the week, followed by a temperature
+/// let dataset = Dataset::from(vec![
+///     (vec![17.0, 5.0, 0.3], vec![1.0]),
+///     (vec![512.0, 1.0, 0.8], vec![0.0]),
+///     (vec![17.0, 2.0, 0.5], vec![0.0]),
+///     (vec![999.0, 6.0, 0.1], vec![1.0]),
+/// ]);
+///
+/// let embeddings = [Embedding::new(1_000, 8), Embedding::new(7, 3)];
+/// let mut model: EmbeddingNet<Sigmoid> = EmbeddingNet::new(&embeddings, &[1, 16, 1]);
+/// Trainer::new(100, 0.5).train_embedding(&mut model, dataset);
+///
+/// assert_eq!(model.network().layer_sizes()[0], 8 + 3 + 1);
+/// assert_eq!(model.guess(&[17.0, 5.0, 0.3]).len(), 1);
+/// ```
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct EmbeddingNet<A: Activation, T: Float = f64> {
+    /// A matrix per categorical input, whose columns are the vectors of its categories.
+    #[serde(with = "matrices")]
+    embeddings: Vec<DMatrix<T>>,
+    network: NeuralNet<A, T>,
+}
+
+impl<A: Activation + Serialize + DeserializeOwned, T: Float> EmbeddingNet<A, T> {
+    /// Creates a new `EmbeddingNet` with the given embeddings (one per categorical input) and
+    /// node configuration, whose first value is the number of numeric inputs (which may be 0).
+    ///
+    /// The network's input layer has a node for every dimension of every embedding, followed by
+    /// a node for each numeric input.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the number of layers (i.e. the length of the given `node_counts`
+    /// slice) is less than 2.
+    #[cfg(feature = "std")]
+    pub fn new(embeddings: &[Embedding], node_counts: &[usize]) -> Self {
+        let mut network_node_counts = node_counts.to_vec();
+        if let Some(num_inputs) = network_node_counts.first_mut() {
+            *num_inputs += embeddings.iter().map(|e| e.dimensions).sum::<usize>();
+        }
+
+        Self {
+            embeddings: embeddings
+                .iter()
+                .map(|e| gen_random_matrix(e.dimensions, e.categories))
+                .collect(),
+            network: NeuralNet::new(&network_node_counts),
+        }
+    }
+
+    /// Performs the feedforward algorithm on the given inputs (category IDs followed by numeric
+    /// values), returning the values of the output layer.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of given input values is incorrect, or a category ID
+    /// isn't the index of one of its embedding's categories.
+    pub fn guess(&self, inputs: &[f64]) -> Vec<f64> {
+        let mut embedded = Vec::with_capacity(self.network.layers[0].nrows());
+        self.embed(inputs, |x| embedded.push(x.as_f64()));
+        self.network.guess(&embedded)
+    }
+
+    /// Returns the embeddings, as a matrix per categorical input whose columns are the vectors
+    /// of its categories.
+    pub fn embeddings(&self) -> &[DMatrix<T>] {
+        &self.embeddings
+    }
+
+    /// Returns the network that the embedded inputs are fed into.
+    pub fn network(&self) -> &NeuralNet<A, T> {
+        &self.network
+    }
+
+    /// Performs the feedforward algorithm on the given inputs, storing the values of every layer
+    /// of the network in the given scratch space.
+    #[cfg(feature = "std")]
+    pub(crate) fn feedforward_into(&self, inputs: &[f64], scratch: &mut Scratch<T>) {
+        scratch.fit(&self.network.layers);
+        let mut values = scratch.layers[0].iter_mut();
+        self.embed(inputs, |x| *values.next().unwrap() = x);
+        self.network.feedforward_layers(&mut scratch.layers, 0);
+    }
+
+    /// Performs the backpropagation algorithm after
+    /// [`feedforward_into()`](#method.feedforward_into) has fed the given inputs forward,
+    /// updating the network and then the vectors of the inputs' categories.
+    #[cfg(feature = "std")]
+    pub(crate) fn backpropagate(
+        &mut self,
+        inputs: &[f64],
+        targets: &[f64],
+        learning_rate: f64,
+        scratch: &mut Scratch<T>,
+    ) {
+        self.network.backpropagate(targets, learning_rate, scratch);
+
+        // Propagates the (already scaled) gradients of the first hidden layer back to the inputs,
+        // whose values are no longer needed
+        let Scratch { layers, deltas } = scratch;
+        layers[0].gemm_tr(T::one(), &self.network.weights[0], &deltas[0], T::zero());
+
+        let mut offset = 0;
+        for (embedding, &id) in self.embeddings.iter_mut().zip(inputs) {
+            let dimensions = embedding.nrows();
+            let mut vector = embedding.column_mut(id as usize);
+            vector += layers[0].rows(offset, dimensions);
+            offset += dimensions;
+        }
+    }
+
+    /// Passes each of the network's input values to `push` in turn: the vectors of the given
+    /// category IDs, followed by the numeric inputs.
+    fn embed(&self, inputs: &[f64], mut push: impl FnMut(T)) {
+        let num_categorical = self.embeddings.len();
+        let num_inputs = num_categorical + self.network.layers[0].nrows()
+            - self.embeddings.iter().map(|e| e.nrows()).sum::<usize>();
+        if inputs.len() != num_inputs {
+            panic!(
+                "incorrect number of inputs supplied (expected {}, found {})",
+                num_inputs,
+                inputs.len()
+            );
+        }
+
+        for (embedding, &id) in self.embeddings.iter().zip(inputs) {
+            // Casting saturates, so negative and fractional IDs don't survive the round trip
+            if id as usize as f64 != id || id as usize >= embedding.ncols() {
+                panic!(
+                    "invalid category ID (expected an integer below {}, found {})",
+                    embedding.ncols(),
+                    id
+                );
+            }
+            embedding.column(id as usize).iter().for_each(|&x| push(x));
+        }
+        inputs[num_categorical..]
+            .iter()
+            .for_each(|&x| push(T::convert(x)));
+    }
+}
+
+impl<A: Activation, T: Float> Clone for EmbeddingNet<A, T> {
+    fn clone(&self) -> Self {
+        Self {
+            embeddings: self.embeddings.clone(),
+            network: self.network.clone(),
+        }
+    }
+}
+
+impl<A: Activation, T: Float> fmt::Debug for EmbeddingNet<A, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmbeddingNet")
+            .field("embeddings", &self.embeddings)
+            .field("network", &self.network)
+            .finish()
+    }
+}
@@ -0,0 +1,79 @@
+use super::{LoadErr, SaveErr};
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    io::{Read, Write},
+    path::Path,
+};
+
+/// The binary formats in which a `NeuralNet` can be saved.
+///
+/// Besides the default bincode format, the self-describing MessagePack and CBOR formats (which
+/// require the `msgpack` and `cbor` features respectively) can be read by libraries in most
+/// other languages.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::ModelFormat;
+///
+/// assert_eq!(ModelFormat::from_path("brain.network"), ModelFormat::Bincode);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ModelFormat {
+    /// The compact [bincode](https://github.com/bincode-org/bincode) format, which is only
+    /// readable by Rust programs.
+    #[default]
+    Bincode,
+    /// The [MessagePack](https://msgpack.org) format.
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+    /// The [CBOR](https://cbor.io) format.
+    #[cfg(feature = "cbor")]
+    Cbor,
+}
+
+impl ModelFormat {
+    /// Chooses the format from the extension of the given path: `.msgpack` or `.mpk` for
+    /// MessagePack and `.cbor` for CBOR (when their features are enabled), and bincode otherwise.
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        let extension = path.as_ref().extension().and_then(|e| e.to_str());
+        match extension.map(str::to_ascii_lowercase).as_deref() {
+            #[cfg(feature = "msgpack")]
+            Some("msgpack") | Some("mpk") => ModelFormat::MessagePack,
+            #[cfg(feature = "cbor")]
+            Some("cbor") => ModelFormat::Cbor,
+            _ => ModelFormat::Bincode,
+        }
+    }
+
+    /// Serializes the given value into the writer in this format.
+    pub(crate) fn serialize(
+        self,
+        writer: &mut impl Write,
+        value: &impl Serialize,
+    ) -> Result<(), SaveErr> {
+        match self {
+            ModelFormat::Bincode => bincode::serialize_into(writer, value)?,
+            #[cfg(feature = "msgpack")]
+            ModelFormat::MessagePack => rmp_serde::encode::write_named(writer, value)?,
+            #[cfg(feature = "cbor")]
+            ModelFormat::Cbor => ciborium::ser::into_writer(value, writer)?,
+        }
+
+        Ok(())
+    }
+
+    /// Deserializes a value from the reader in this format.
+    pub(crate) fn deserialize<V: DeserializeOwned>(self, reader: impl Read) -> Result<V, LoadErr> {
+        let value = match self {
+            ModelFormat::Bincode => bincode::deserialize_from(reader)?,
+            #[cfg(feature = "msgpack")]
+            ModelFormat::MessagePack => rmp_serde::from_read(reader)?,
+            #[cfg(feature = "cbor")]
+            ModelFormat::Cbor => ciborium::de::from_reader(reader)?,
+        };
+
+        Ok(value)
+    }
+}
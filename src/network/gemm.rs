@@ -0,0 +1,94 @@
+//! Matrix multiplication through the `matrixmultiply` crate's kernels, which are used to run a
+//! whole batch of rows through a network at once (see `NeuralNet::batch_feedforward()`).
+
+use super::Float;
+
+use core::any::TypeId;
+use nalgebra::DMatrix;
+
+/// Multiplies `a` (or its transpose) by `b` (or its transpose).
+///
+/// `f32` and `f64` matrices are multiplied with `matrixmultiply`'s `sgemm` and `dgemm`, which read
+/// the transposes in place rather than copying them. Any other type falls back to nalgebra.
+pub(super) fn mul<T: Float>(
+    a: &DMatrix<T>,
+    transpose_a: bool,
+    b: &DMatrix<T>,
+    transpose_b: bool,
+) -> DMatrix<T> {
+    // The shapes and strides of the (possibly transposed) matrices, which are column-major
+    let (m, k, rsa, csa) = if transpose_a {
+        (a.ncols(), a.nrows(), a.nrows(), 1)
+    } else {
+        (a.nrows(), a.ncols(), 1, a.nrows())
+    };
+    let (k2, n, rsb, csb) = if transpose_b {
+        (b.ncols(), b.nrows(), b.nrows(), 1)
+    } else {
+        (b.nrows(), b.ncols(), 1, b.nrows())
+    };
+    assert_eq!(
+        k, k2,
+        "matrix dimensions must be compatible for multiplication"
+    );
+
+    let mut c = DMatrix::from_element(m, n, T::zero());
+    if m == 0 || n == 0 {
+        return c;
+    }
+
+    let (rsa, csa, rsb, csb) = (rsa as isize, csa as isize, rsb as isize, csb as isize);
+    let (rsc, csc) = (1, m as isize);
+
+    // SAFETY: the pointers are valid for the shapes and strides given, which describe the
+    // column-major storage of `a`, `b` and `c` (`c` being distinct from both), and the casts are
+    // only made when `T` is the type being cast to
+    if TypeId::of::<T>() == TypeId::of::<f64>() {
+        unsafe {
+            matrixmultiply::dgemm(
+                m,
+                k,
+                n,
+                1.0,
+                a.as_ptr() as *const f64,
+                rsa,
+                csa,
+                b.as_ptr() as *const f64,
+                rsb,
+                csb,
+                0.0,
+                c.as_mut_ptr() as *mut f64,
+                rsc,
+                csc,
+            );
+        }
+    } else if TypeId::of::<T>() == TypeId::of::<f32>() {
+        unsafe {
+            matrixmultiply::sgemm(
+                m,
+                k,
+                n,
+                1.0,
+                a.as_ptr() as *const f32,
+                rsa,
+                csa,
+                b.as_ptr() as *const f32,
+                rsb,
+                csb,
+                0.0,
+                c.as_mut_ptr() as *mut f32,
+                rsc,
+                csc,
+            );
+        }
+    } else {
+        c = match (transpose_a, transpose_b) {
+            (false, false) => a * b,
+            (true, false) => a.tr_mul(b),
+            (false, true) => a * b.transpose(),
+            (true, true) => a.transpose() * b.transpose(),
+        };
+    }
+
+    c
+}
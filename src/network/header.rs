@@ -0,0 +1,258 @@
+use super::{matrices, Activation, Float, LoadErr, ModelFormat, NeuralNet, SaveErr};
+
+use bincode::Options;
+use nalgebra::DMatrix;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{Read, Write},
+    marker::PhantomData,
+};
+
+/// The bytes at the start of every saved network, identifying the file as one.
+const MAGIC: [u8; 4] = *b"SCHL";
+
+/// The version of the file format, incremented whenever the layout of saved networks changes.
+///
+/// Every version can still be read, as can networks saved before there was a header at all:
+///
+/// 1. The first version, whose header has no activation.
+/// 2. Adds the activation to the header.
+/// 3. Adds `linear_output` to the network.
+/// 4. Adds `target_scaler` to the network.
+const FORMAT_VERSION: u32 = 4;
+
+/// Reads a saved network in the given format: its header, and then the network itself.
+///
+/// Networks saved by older versions of the file format are migrated to the current one, and
+/// networks saved before there was a header are read (without checking their activation and
+/// precision, which weren't saved) if the bytes don't start with the magic number.
+pub(crate) fn read_network<A: Activation, T: Float>(
+    mut reader: impl Read,
+    format: ModelFormat,
+) -> Result<NeuralNet<A, T>, LoadErr> {
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        let mut bytes = magic.to_vec();
+        reader.read_to_end(&mut bytes)?;
+        let network = read_headerless(&bytes, format).ok_or(LoadErr::Magic)?;
+        Header::of(&network).validate(&network)?;
+        return Ok(network);
+    }
+
+    let mut version = [0; 4];
+    reader.read_exact(&mut version)?;
+    let version = u32::from_le_bytes(version);
+    if version == 0 || version > FORMAT_VERSION {
+        return Err(LoadErr::Version(version));
+    }
+
+    let header = Header::read::<A, T>(&mut reader, format, version)?;
+    let network = match format {
+        // Bincode isn't self-describing, so the fields that older versions leave out can't be
+        // told apart from the end of the file
+        ModelFormat::Bincode if version < 3 => read_parameters(&mut reader, false)?,
+        ModelFormat::Bincode if version < 4 => read_parameters(&mut reader, true)?,
+        // The self-describing formats fill in any fields left out with their defaults
+        _ => format.deserialize(&mut reader)?,
+    };
+    header.validate(&network)?;
+
+    Ok(network)
+}
+
+/// Reads the parameters and threshold of a network saved in bincode by a version of the file
+/// format before the target scaler was added, along with whether its output is linear if it
+/// was saved with it.
+fn read_parameters<A: Activation, T: Float>(
+    reader: &mut impl Read,
+    has_linear_output: bool,
+) -> Result<NeuralNet<A, T>, LoadErr> {
+    let parameters: Parameters<T> = bincode::deserialize_from(&mut *reader)?;
+    let threshold: f64 = bincode::deserialize_from(&mut *reader)?;
+    let linear_output = if has_linear_output {
+        bincode::deserialize_from(&mut *reader)?
+    } else {
+        false
+    };
+
+    Ok(parameters.into_network(threshold, linear_output))
+}
+
+/// Reads a network saved before there was a header, returning `None` if the bytes don't hold
+/// one.
+fn read_headerless<A: Activation, T: Float>(
+    bytes: &[u8],
+    format: ModelFormat,
+) -> Option<NeuralNet<A, T>> {
+    match format {
+        ModelFormat::Bincode => {
+            let mut rest = bytes;
+            let parameters: Parameters<T> = bincode::DefaultOptions::new()
+                .with_fixint_encoding()
+                .allow_trailing_bytes()
+                .deserialize_from(&mut rest)
+                .ok()?;
+
+            // Networks saved before the threshold was added end with their parameters
+            let threshold = match rest.len() {
+                0 => 0.5,
+                8 => bincode::deserialize(rest).ok()?,
+                _ => return None,
+            };
+            Some(parameters.into_network(threshold, false))
+        }
+        #[allow(unreachable_patterns)]
+        _ => format.deserialize(bytes).ok(),
+    }
+}
+
+/// The parameters of a network, as saved by every version of the file format (and before).
+#[derive(Deserialize)]
+#[serde(bound = "")]
+struct Parameters<T: Float> {
+    #[serde(with = "matrices")]
+    layers: Vec<DMatrix<T>>,
+    #[serde(with = "matrices")]
+    weights: Vec<DMatrix<T>>,
+    #[serde(with = "matrices")]
+    biases: Vec<DMatrix<T>>,
+    #[serde(with = "matrices")]
+    errors: Vec<DMatrix<T>>,
+}
+
+impl<T: Float> Parameters<T> {
+    /// Creates a network (without a target scaler) from the parameters.
+    fn into_network<A: Activation>(self, threshold: f64, linear_output: bool) -> NeuralNet<A, T> {
+        NeuralNet {
+            layers: self.layers,
+            weights: self.weights,
+            biases: self.biases,
+            errors: self.errors,
+            threshold,
+            linear_output,
+            target_scaler: None,
+            frozen: Vec::new(),
+            activation: PhantomData,
+        }
+    }
+}
+
+/// The metadata saved ahead of a network, so that a file can be checked before (and after) its
+/// network is deserialized.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Header {
+    /// The number of nodes in each layer of the network, including the input layer.
+    node_counts: Vec<usize>,
+    /// The name of the network's `Activation`.
+    activation: String,
+    /// The name of the `Float` type of the network's calculations (see `Float::precision()`).
+    precision: String,
+}
+
+impl Header {
+    /// Creates the header describing the given network.
+    pub(crate) fn of<A: Activation, T: Float>(network: &NeuralNet<A, T>) -> Self {
+        Self {
+            node_counts: network.layer_sizes(),
+            activation: A::name().to_string(),
+            precision: T::precision().to_string(),
+        }
+    }
+
+    /// Writes the magic number, the format version, and then the header in the given format.
+    pub(crate) fn write(
+        &self,
+        writer: &mut impl Write,
+        format: ModelFormat,
+    ) -> Result<(), SaveErr> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        format.serialize(writer, self)
+    }
+
+    /// Reads the header of the given version of the file format, checking that it describes a
+    /// network of the activation `A` (unless it is from the first version, which didn't save
+    /// it) and `Float` type `T`.
+    fn read<A: Activation, T: Float>(
+        reader: &mut impl Read,
+        format: ModelFormat,
+        version: u32,
+    ) -> Result<Self, LoadErr> {
+        let header = if version == 1 {
+            let header: FirstHeader = format.deserialize(reader)?;
+            Self {
+                node_counts: header.node_counts,
+                activation: A::name().to_string(),
+                precision: header.precision,
+            }
+        } else {
+            format.deserialize(reader)?
+        };
+
+        if header.activation != A::name() {
+            return Err(LoadErr::Activation {
+                expected: A::name().to_string(),
+                found: header.activation,
+            });
+        }
+
+        let expected = T::precision();
+        if header.precision != expected {
+            return Err(LoadErr::Precision {
+                expected: expected.to_string(),
+                found: header.precision,
+            });
+        }
+
+        Ok(header)
+    }
+
+    /// Checks that the given (deserialized) network has the architecture described by the header.
+    pub(crate) fn validate<A: Activation, T: Float>(
+        &self,
+        network: &NeuralNet<A, T>,
+    ) -> Result<(), LoadErr> {
+        let counts = &self.node_counts;
+        let consistent = network.layers.len() == counts.len()
+            && network.weights.len() + 1 == counts.len()
+            && network.biases.len() + 1 == counts.len()
+            && network.errors.len() + 1 == counts.len()
+            && network
+                .layers
+                .iter()
+                .zip(counts)
+                .all(|(l, &c)| l.shape() == (c, 1))
+            && network
+                .weights
+                .iter()
+                .zip(counts.windows(2))
+                .all(|(w, c)| w.shape() == (c[1], c[0]))
+            && network
+                .biases
+                .iter()
+                .zip(&counts[1..])
+                .all(|(b, &c)| b.shape() == (c, 1))
+            && network
+                .errors
+                .iter()
+                .zip(&counts[1..])
+                .all(|(e, &c)| e.shape() == (c, 1));
+
+        if consistent {
+            Ok(())
+        } else {
+            Err(LoadErr::Invalid(format!(
+                "weights don't match the architecture {:?} in the header",
+                counts
+            )))
+        }
+    }
+}
+
+/// The header of the first version of the file format, which didn't save the activation.
+#[derive(Deserialize)]
+struct FirstHeader {
+    node_counts: Vec<usize>,
+    precision: String,
+}
@@ -0,0 +1,179 @@
+use super::{Activation, Float, LoadErr, NeuralNet, SaveErr, TargetScaler};
+
+use nalgebra::DMatrix;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    fs,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+/// The JSON representation of a `NeuralNet`.
+#[derive(Serialize, Deserialize)]
+struct JsonNetwork {
+    activation: String,
+    threshold: f64,
+    /// Whether the output layer is left unactivated, which older files leave out.
+    #[serde(default)]
+    linear_output: bool,
+    /// The scaler of the network's targets, which older files leave out.
+    #[serde(default)]
+    target_scaler: Option<TargetScaler>,
+    layers: Vec<JsonLayer>,
+}
+
+/// The part of the JSON representation of a `NeuralNet` that names its activation.
+#[derive(Deserialize)]
+struct JsonActivation {
+    activation: String,
+}
+
+/// Reads the name of the activation of the network in a JSON file created using
+/// [`NeuralNet::save_json()`](../struct.NeuralNet.html#method.save_json), without loading the
+/// network.
+pub(crate) fn read_activation(path: &Path) -> Result<String, LoadErr> {
+    let file = fs::File::open(path)?;
+    let network: JsonActivation = serde_json::from_reader(BufReader::new(file))?;
+    Ok(network.activation)
+}
+
+/// The JSON representation of the weights and biases feeding into a layer of a `NeuralNet`.
+#[derive(Serialize, Deserialize)]
+struct JsonLayer {
+    /// One row per node of the layer, holding the weight of each node of the previous layer.
+    weights: Vec<Vec<f64>>,
+    biases: Vec<f64>,
+}
+
+impl<A: Activation + Serialize + DeserializeOwned, T: Float> NeuralNet<A, T> {
+    /// Creates a new `NeuralNet` from a JSON file created using
+    /// [`NeuralNet::save_json()`](#method.save_json).
+    ///
+    /// # Errors
+    ///
+    /// Besides failing to read or parse the file, this function returns an error if the network
+    /// was saved with a different `Activation`, or if the shapes of the weights and biases don't
+    /// form a valid network.
+    pub fn from_json(path: impl AsRef<Path>) -> Result<Self, LoadErr> {
+        let file = fs::File::open(path)?;
+        let network: JsonNetwork = serde_json::from_reader(BufReader::new(file))?;
+        if network.activation != A::name() {
+            return Err(LoadErr::Activation {
+                expected: A::name().to_string(),
+                found: network.activation,
+            });
+        }
+
+        let input_count = match network.layers.first() {
+            Some(layer) => layer.weights.first().map_or(0, Vec::len),
+            None => return Err(LoadErr::Invalid("network has no layers".to_string())),
+        };
+
+        let mut node_counts = vec![input_count];
+        let mut weights = Vec::with_capacity(network.layers.len());
+        let mut biases = Vec::with_capacity(network.layers.len());
+        for (i, layer) in network.layers.iter().enumerate() {
+            let (rows, cols) = (layer.weights.len(), node_counts[i]);
+            if layer.weights.iter().any(|row| row.len() != cols) || layer.biases.len() != rows {
+                return Err(LoadErr::Invalid(format!(
+                    "layer {} has inconsistent weights and biases",
+                    i + 1
+                )));
+            }
+
+            let values: Vec<T> = layer
+                .weights
+                .iter()
+                .flatten()
+                .map(|&x| T::convert(x))
+                .collect();
+            weights.push(DMatrix::from_row_slice(rows, cols, &values));
+            biases.push(DMatrix::from_iterator(
+                rows,
+                1,
+                layer.biases.iter().map(|&x| T::convert(x)),
+            ));
+            node_counts.push(rows);
+        }
+
+        if network.target_scaler.is_some() && !network.linear_output {
+            return Err(LoadErr::Invalid(
+                "network with a target scaler doesn't have a linear output".to_string(),
+            ));
+        }
+
+        let output_count = node_counts[node_counts.len() - 1];
+        if network
+            .target_scaler
+            .as_ref()
+            .is_some_and(|s| s.len() != output_count)
+        {
+            return Err(LoadErr::Invalid(
+                "target scaler doesn't match the output layer".to_string(),
+            ));
+        }
+
+        let mut loaded = Self::from_parameters(weights, biases);
+        loaded.threshold = network.threshold;
+        loaded.linear_output = network.linear_output;
+        loaded.target_scaler = network.target_scaler;
+
+        Ok(loaded)
+    }
+
+    /// Saves the network to the specified path in a human-readable JSON format, so that it can
+    /// be inspected, diffed, or used by code in other languages.
+    ///
+    /// The file holds the name of the network's `activation`, its `threshold`, whether it has a
+    /// `linear_output` (see [`set_linear_output()`](#method.set_linear_output)), its
+    /// `target_scaler` (see [`set_target_scaler()`](#method.set_target_scaler)) with the `mean`
+    /// and `std_dev` of each output, and a list of `layers` (excluding the input layer), each
+    /// with the `weights` of its nodes (one list per node, of the weights of its connections from
+    /// the previous layer) and their `biases`. Every value is written with enough digits to be
+    /// read back exactly by [`from_json()`](#method.from_json), so a loaded `f64` network guesses
+    /// exactly the same as the saved one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{NeuralNet, Sigmoid};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 3, 1]);
+    ///
+    /// let path = std::env::temp_dir().join("scholar_network_example.json");
+    /// brain.save_json(&path)?;
+    ///
+    /// let loaded: NeuralNet<Sigmoid> = NeuralNet::from_json(&path)?;
+    /// assert_eq!(loaded.guess(&[1.0, 0.0]), brain.guess(&[1.0, 0.0]));
+    /// # std::fs::remove_file(path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn save_json(&self, path: impl AsRef<Path>) -> Result<(), SaveErr> {
+        let layers = self
+            .weights
+            .iter()
+            .zip(&self.biases)
+            .map(|(weights, biases)| JsonLayer {
+                weights: weights
+                    .row_iter()
+                    .map(|row| row.iter().map(|x| x.as_f64()).collect())
+                    .collect(),
+                biases: biases.iter().map(|x| x.as_f64()).collect(),
+            })
+            .collect();
+        let network = JsonNetwork {
+            activation: A::name().to_string(),
+            threshold: self.threshold,
+            linear_output: self.linear_output,
+            target_scaler: self.target_scaler.clone(),
+            layers,
+        };
+
+        let file = BufWriter::new(fs::File::create(path)?);
+        serde_json::to_writer_pretty(file, &network)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,214 @@
+use super::{Activation, Float, LoadErr, NeuralNet};
+
+use hdf5_pure::{AttrValue, File, Group};
+use nalgebra::DMatrix;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{collections::HashMap, path::Path};
+
+impl<A: Activation + Serialize + DeserializeOwned, T: Float> NeuralNet<A, T> {
+    /// Creates a new `NeuralNet` from the weights of a Keras network of `Dense` layers, saved in
+    /// the HDF5 format by `model.save("model.h5")` or `model.save_weights("weights.h5")`.
+    ///
+    /// Layers without weights (such as `InputLayer` or `Dropout` layers) are skipped, whilst
+    /// layers with weights other than a kernel and a bias aren't supported. When the file holds
+    /// the model's configuration (as whole saved models do), every `Dense` layer must use the
+    /// network's activation (either directly, or through a following `Activation` layer), whose
+    /// [`name()`](trait.Activation.html#method.name) must match Keras' name for it (e.g.
+    /// `"sigmoid"`), besides an output layer with the `linear` activation, which makes the
+    /// network's output linear (see [`set_linear_output()`](#method.set_linear_output)). Files of
+    /// weights alone can't be checked, so it is up to the caller to load them with the right
+    /// activation.
+    ///
+    /// This requires the `keras` feature.
+    ///
+    /// # Errors
+    ///
+    /// Besides failing to read the file, this function returns an error if it doesn't hold the
+    /// weights of a Keras network, if the network has unsupported layers or a different
+    /// activation, or if the shapes of its weights don't form a valid network.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{NeuralNet, Sigmoid};
+    ///
+    /// # fn main() -> Result<(), scholar::LoadErr> {
+    /// // A Keras model with 2 sigmoid `Dense` layers of hand-picked weights that compute XOR
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::from_keras("examples/keras_xor.h5")?;
+    /// assert_eq!(brain.layer_sizes(), vec![2, 2, 1]);
+    ///
+    /// assert!(brain.guess(&[0.0, 0.0])[0] < 0.001);
+    /// assert!(brain.guess(&[0.0, 1.0])[0] > 0.999);
+    /// assert!(brain.guess(&[1.0, 0.0])[0] > 0.999);
+    /// assert!(brain.guess(&[1.0, 1.0])[0] < 0.001);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_keras(path: impl AsRef<Path>) -> Result<Self, LoadErr> {
+        let file = File::open(path)?;
+        let root = file.root();
+        let root_attrs = root.attrs()?;
+
+        // Whole saved models keep their weights in a group, whilst weight files keep them at the
+        // root of the file
+        let group = match file.group("model_weights") {
+            Ok(group) => group,
+            Err(_) => root,
+        };
+        let layer_names = strings(group.attrs()?.get("layer_names"))
+            .ok_or_else(|| invalid("file doesn't hold the weights of a Keras network"))?;
+
+        let activations = match strings(root_attrs.get("model_config")) {
+            Some(config) => {
+                let config: Value = serde_json::from_str(&config.concat())?;
+                dense_activations(&config)
+            }
+            None => HashMap::new(),
+        };
+
+        let mut weights: Vec<DMatrix<T>> = Vec::new();
+        let mut biases: Vec<DMatrix<T>> = Vec::new();
+        // The index of the first layer with the linear activation, if the network's isn't linear
+        let mut linear_layer = None;
+        for layer_name in &layer_names {
+            let layer = group.group(layer_name)?;
+            let weight_names = strings(layer.attrs()?.get("weight_names")).unwrap_or_default();
+            if weight_names.is_empty() {
+                continue;
+            }
+
+            let (kernel, bias) = read_dense(&layer, &weight_names).ok_or_else(|| {
+                LoadErr::Invalid(format!("layer '{}' isn't a Dense layer", layer_name))
+            })?;
+            if let Some(found) = activations.get(layer_name.as_str()) {
+                if found != A::name() && found == "linear" && linear_layer.is_none() {
+                    linear_layer = Some(weights.len());
+                } else if found != A::name() {
+                    return Err(LoadErr::Activation {
+                        expected: A::name().to_string(),
+                        found: found.clone(),
+                    });
+                }
+            }
+
+            // Keras kernels hold one column per node, rather than one row
+            let (inputs, nodes) = match kernel.shape()?[..] {
+                [inputs, nodes] => (inputs as usize, nodes as usize),
+                _ => return Err(invalid("kernels must be two-dimensional")),
+            };
+            let kernel = kernel.read_f64()?;
+            let bias = bias.read_f64()?;
+            let previous_count = biases.last().map(|b| b.nrows());
+            if kernel.len() != inputs * nodes
+                || bias.len() != nodes
+                || previous_count.is_some_and(|count| count != inputs)
+            {
+                return Err(invalid("layers have inconsistent shapes"));
+            }
+
+            weights.push(DMatrix::from_iterator(
+                nodes,
+                inputs,
+                kernel.into_iter().map(T::convert),
+            ));
+            biases.push(DMatrix::from_iterator(
+                nodes,
+                1,
+                bias.into_iter().map(T::convert),
+            ));
+        }
+
+        if weights.is_empty() {
+            return Err(invalid("network has no layers"));
+        }
+        // Only the output layer can be linear
+        if linear_layer.is_some_and(|layer| layer + 1 != weights.len()) {
+            return Err(LoadErr::Activation {
+                expected: A::name().to_string(),
+                found: "linear".to_string(),
+            });
+        }
+
+        let mut loaded = Self::from_parameters(weights, biases);
+        loaded.linear_output = linear_layer.is_some();
+        Ok(loaded)
+    }
+}
+
+/// Creates the error of an invalid Keras file.
+fn invalid(message: &str) -> LoadErr {
+    LoadErr::Invalid(message.to_string())
+}
+
+/// Returns the strings of a string (or string array) attribute.
+fn strings(value: Option<&AttrValue>) -> Option<Vec<String>> {
+    match value? {
+        AttrValue::String(value)
+        | AttrValue::StringSized { value, .. }
+        | AttrValue::AsciiString(value)
+        | AttrValue::AsciiStringSized { value, .. }
+        | AttrValue::VarLenString(value) => Some(vec![value.clone()]),
+        AttrValue::StringArray(values)
+        | AttrValue::StringArraySized { values, .. }
+        | AttrValue::AsciiStringArray(values)
+        | AttrValue::AsciiStringArraySized { values, .. }
+        | AttrValue::VarLenAsciiCharArray(values)
+        | AttrValue::VarLenStringArray(values) => Some(values.clone()),
+        _ => None,
+    }
+}
+
+/// Returns the kernel and bias datasets of a layer with the given weights, if it is a `Dense`
+/// layer.
+fn read_dense(
+    layer: &Group,
+    weight_names: &[String],
+) -> Option<(hdf5_pure::Dataset, hdf5_pure::Dataset)> {
+    let find = |suffix: &str| {
+        weight_names
+            .iter()
+            .find(|name| name.trim_end_matches(":0").ends_with(suffix))
+    };
+
+    match (weight_names.len(), find("kernel"), find("bias")) {
+        (2, Some(kernel), Some(bias)) => {
+            Some((layer.dataset(kernel).ok()?, layer.dataset(bias).ok()?))
+        }
+        _ => None,
+    }
+}
+
+/// Finds the activation of each `Dense` layer in a Keras model configuration, by the name of the
+/// layer.
+///
+/// A `Dense` layer with the `linear` activation takes the activation of an `Activation` layer
+/// directly following it.
+fn dense_activations(config: &Value) -> HashMap<String, String> {
+    let layers = config["config"]["layers"]
+        .as_array()
+        .map_or(&[][..], Vec::as_slice);
+
+    let mut activations = HashMap::new();
+    let mut previous_dense = None;
+    for layer in layers {
+        let (class_name, config) = (layer["class_name"].as_str(), &layer["config"]);
+        let (name, activation) = (config["name"].as_str(), config["activation"].as_str());
+        match (class_name, name, activation) {
+            (Some("Dense"), Some(name), Some(activation)) => {
+                activations.insert(name.to_string(), activation.to_string());
+                previous_dense = Some(name);
+            }
+            (Some("Activation"), _, Some(activation)) => {
+                if let Some(dense) = previous_dense.take() {
+                    if activations.get(dense).is_some_and(|a| a == "linear") {
+                        activations.insert(dense.to_string(), activation.to_string());
+                    }
+                }
+            }
+            _ => previous_dense = None,
+        }
+    }
+
+    activations
+}
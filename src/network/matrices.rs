@@ -0,0 +1,82 @@
+//! (De)serialization of a network's matrices, which doesn't rely on nalgebra's (`std`-only)
+//! implementations, but matches the layout they have always used.
+
+use super::Float;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use nalgebra::DMatrix;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+/// A matrix, whose values are stored in column-major order.
+#[derive(Serialize)]
+struct MatrixRef<'a, T> {
+    data: &'a [T],
+    nrows: usize,
+    ncols: usize,
+}
+
+/// An owned `MatrixRef`.
+#[derive(Deserialize)]
+struct Matrix<T> {
+    data: Vec<T>,
+    nrows: usize,
+    ncols: usize,
+}
+
+pub(crate) fn serialize<T: Float, S: Serializer>(
+    matrices: &[DMatrix<T>],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(matrices.iter().map(|matrix| MatrixRef {
+        data: matrix.as_slice(),
+        nrows: matrix.nrows(),
+        ncols: matrix.ncols(),
+    }))
+}
+
+pub(crate) fn deserialize<'de, T: Float, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<DMatrix<T>>, D::Error> {
+    let matrices: Vec<Matrix<T>> = Vec::deserialize(deserializer)?;
+    matrices
+        .into_iter()
+        .map(|Matrix { data, nrows, ncols }| {
+            if data.len() != nrows * ncols {
+                return Err(D::Error::custom("matrix has the wrong number of values"));
+            }
+            Ok(DMatrix::from_vec(nrows, ncols, data))
+        })
+        .collect()
+}
+
+/// (De)serialization of a single matrix, in the same layout as each of the matrices above.
+#[cfg(feature = "std")]
+pub(crate) mod matrix {
+    use super::{Float, Matrix, MatrixRef};
+
+    use nalgebra::DMatrix;
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(crate) fn serialize<T: Float, S: Serializer>(
+        matrix: &DMatrix<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        MatrixRef {
+            data: matrix.as_slice(),
+            nrows: matrix.nrows(),
+            ncols: matrix.ncols(),
+        }
+        .serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, T: Float, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DMatrix<T>, D::Error> {
+        let Matrix { data, nrows, ncols } = Matrix::deserialize(deserializer)?;
+        if data.len() != nrows * ncols {
+            return Err(D::Error::custom("matrix has the wrong number of values"));
+        }
+        Ok(DMatrix::from_vec(nrows, ncols, data))
+    }
+}
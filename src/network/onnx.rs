@@ -0,0 +1,544 @@
+use super::{Activation, Float, LoadErr, NeuralNet, SaveErr, TargetScaler};
+use crate::protobuf::{self, DecodeError, Message, Value};
+
+use nalgebra::DMatrix;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::HashMap, convert::TryFrom, fs, path::Path};
+
+/// The version of the ONNX intermediate representation that exported models use.
+const IR_VERSION: i64 = 7;
+/// The version of the default ONNX operator set that exported models use.
+const OPSET_VERSION: i64 = 13;
+/// The ONNX data type of 32-bit floats.
+const FLOAT: i64 = 1;
+/// The ONNX data type of 64-bit floats.
+const DOUBLE: i64 = 11;
+/// The ONNX attribute type of integers.
+const ATTRIBUTE_INT: i64 = 2;
+
+impl<A: Activation + Serialize + DeserializeOwned, T: Float> NeuralNet<A, T> {
+    /// Exports the network to the specified path as an [ONNX](https://onnx.ai) model, so that it
+    /// can be served by ONNX Runtime or converted for other frameworks.
+    ///
+    /// Each layer becomes a `Gemm` node followed by a node of its activation (besides a linear
+    /// output layer), which is found from the activation's
+    /// [`name()`](trait.Activation.html#method.name): `"sigmoid"`, `"tanh"`, `"relu"` and
+    /// `"linear"` are supported. The model has a single `input` of shape `[N, inputs]` and a single
+    /// `output` of shape `[N, outputs]` (where `N` is the number of rows in a batch), and its
+    /// weights are stored as 32-bit floats.
+    ///
+    /// Note that the model computes the raw outputs of the network, as returned by
+    /// [`NeuralNet::guess()`](#method.guess); the network's threshold isn't exported. A network's
+    /// target scaler is exported as a `Mul` node of the targets' standard deviations and an `Add`
+    /// node of their means after the output layer.
+    ///
+    /// # Errors
+    ///
+    /// Besides failing to write the file, this method returns an error if the network's
+    /// activation has no ONNX equivalent.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use scholar::{NeuralNet, Sigmoid};
+    ///
+    /// # fn main() -> Result<(), scholar::SaveErr> {
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 3, 1]);
+    /// brain.export_onnx("brain.onnx")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn export_onnx(&self, path: impl AsRef<Path>) -> Result<(), SaveErr> {
+        let op_type = match A::name() {
+            "sigmoid" => "Sigmoid",
+            "tanh" => "Tanh",
+            "relu" => "Relu",
+            "linear" => "Identity",
+            name => return Err(SaveErr::UnsupportedActivation(name.to_string())),
+        };
+
+        let layer_count = self.weights.len();
+        let mut nodes = Vec::with_capacity(layer_count * 2);
+        let mut initializers = Vec::with_capacity(layer_count * 2);
+        let mut previous = "input".to_string();
+        for (i, (weights, biases)) in self.weights.iter().zip(&self.biases).enumerate() {
+            let (weights_name, biases_name) = (format!("weights_{}", i), format!("biases_{}", i));
+            initializers.push(tensor(
+                &weights_name,
+                weights,
+                &[weights.nrows(), weights.ncols()],
+            ));
+            initializers.push(tensor(&biases_name, biases, &[biases.nrows()]));
+
+            let output = if i + 1 == layer_count && self.target_scaler.is_none() {
+                "output".to_string()
+            } else if i + 1 == layer_count {
+                "scaled_output".to_string()
+            } else {
+                format!("layer_{}", i)
+            };
+            // A linear output layer is only the `Gemm`
+            let gemm = if self.is_linear(i) {
+                output.clone()
+            } else {
+                format!("gemm_{}", i)
+            };
+
+            // Computes `previous * weightsᵀ + biases`, as the weights hold one row per node
+            nodes.push(
+                node(
+                    "Gemm",
+                    &gemm,
+                    &[&previous, &weights_name, &biases_name],
+                    &gemm,
+                )
+                .message(
+                    5,
+                    Message::new()
+                        .string(1, "transB")
+                        .int(3, 1)
+                        .int(20, ATTRIBUTE_INT),
+                ),
+            );
+            if !self.is_linear(i) {
+                nodes.push(node(
+                    op_type,
+                    &format!("{}_{}", op_type, i),
+                    &[&gemm],
+                    &output,
+                ));
+            }
+            previous = output;
+        }
+
+        let input_count = self.layers[0].nrows();
+        let output_count = self.layers[layer_count].nrows();
+        // Reverses the scaling of the outputs by multiplying them by the standard deviation of
+        // the targets and adding their mean
+        if let Some(scaler) = &self.target_scaler {
+            let std_dev = DMatrix::from_column_slice(output_count, 1, scaler.std_dev());
+            let mean = DMatrix::from_column_slice(output_count, 1, scaler.mean());
+            initializers.push(tensor("target_std_dev", &std_dev, &[output_count]));
+            initializers.push(tensor("target_mean", &mean, &[output_count]));
+            nodes.push(node(
+                "Mul",
+                "unscale",
+                &[&previous, "target_std_dev"],
+                "unscaled_output",
+            ));
+            nodes.push(node(
+                "Add",
+                "add_mean",
+                &["unscaled_output", "target_mean"],
+                "output",
+            ));
+        }
+        let graph = Message::new()
+            .messages(1, nodes)
+            .string(2, "scholar")
+            .messages(5, initializers)
+            .message(11, value_info("input", input_count))
+            .message(12, value_info("output", output_count));
+
+        let model = Message::new()
+            .int(1, IR_VERSION)
+            .string(2, "scholar")
+            .string(3, env!("CARGO_PKG_VERSION"))
+            .message(7, graph)
+            .message(8, Message::new().string(1, "").int(2, OPSET_VERSION));
+
+        fs::write(path, model.into_bytes())?;
+
+        Ok(())
+    }
+
+    /// Creates a new `NeuralNet` from an [ONNX](https://onnx.ai) model of a multilayer
+    /// perceptron, such as one exported from PyTorch or Keras (or by
+    /// [`NeuralNet::export_onnx()`](#method.export_onnx)).
+    ///
+    /// The model's graph must be a chain of layers, each of which is either a `Gemm` node, or a
+    /// `MatMul` node optionally followed by an `Add` node of its biases, and is then followed by
+    /// a node of the network's activation (`Sigmoid`, `Tanh`, `Relu` or `Identity`, matching the
+    /// activation's [`name()`](trait.Activation.html#method.name)). Every layer must use the same
+    /// activation, besides an output layer without one, which makes the network's output linear
+    /// (see [`set_linear_output()`](#method.set_linear_output)). The layers may be followed by a
+    /// `Mul` node and an `Add` node, which become the network's target scaler (see
+    /// [`set_target_scaler()`](#method.set_target_scaler)) with the standard deviations and
+    /// means they hold. The weights, biases and scaler must be stored in the graph's
+    /// initializers as 32-bit or 64-bit floats.
+    ///
+    /// # Errors
+    ///
+    /// Besides failing to read the file, this function returns an error if the file isn't a
+    /// valid ONNX model, if its graph contains any other nodes or a different activation, or if
+    /// the shapes of its weights and biases don't form a valid network.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{NeuralNet, Sigmoid};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 3, 1]);
+    ///
+    /// let path = std::env::temp_dir().join("scholar_onnx_example.onnx");
+    /// brain.export_onnx(&path)?;
+    ///
+    /// let imported: NeuralNet<Sigmoid> = NeuralNet::from_onnx(&path)?;
+    /// let difference = imported.guess(&[1.0, 0.0])[0] - brain.guess(&[1.0, 0.0])[0];
+    /// assert!(difference.abs() < 1e-6);
+    /// # std::fs::remove_file(path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_onnx(path: impl AsRef<Path>) -> Result<Self, LoadErr> {
+        let bytes = fs::read(path)?;
+        let graph = read_graph(&bytes).map_err(|_| invalid("malformed ONNX file"))?;
+
+        let mut weights = Vec::new();
+        let mut biases: Vec<DMatrix<T>> = Vec::new();
+        let mut push = |(layer_weights, layer_biases): (DMatrix<f64>, DMatrix<f64>)| {
+            let previous_count = biases.last().map(|b| b.nrows());
+            if previous_count.is_some_and(|count| count != layer_weights.ncols()) {
+                return Err(invalid("layers have inconsistent shapes"));
+            }
+            weights.push(layer_weights.map(T::convert));
+            biases.push(layer_biases.map(T::convert));
+            Ok(())
+        };
+        // The weights (with one row per node) and biases of the layer being read
+        let mut pending: Option<(DMatrix<f64>, DMatrix<f64>)> = None;
+        let mut output_count = 0;
+        let mut linear_output = false;
+        // The standard deviations of the targets from a `Mul` node, until its `Add` node
+        let mut std_dev: Option<DMatrix<f64>> = None;
+        let mut target_scaler = None;
+        let mut current = graph
+            .inputs
+            .iter()
+            .find(|input| !graph.initializers.contains_key(*input))
+            .ok_or_else(|| invalid("graph has no input"))?;
+
+        for node in &graph.nodes {
+            if !node.inputs.contains(current) {
+                return Err(invalid("graph isn't a chain of layers"));
+            }
+            if target_scaler.is_some() || (std_dev.is_some() && node.op_type != "Add") {
+                return Err(invalid("target scaler must end the graph"));
+            }
+            let other = node.inputs.iter().find(|&input| input != current);
+
+            match (node.op_type, pending.as_mut()) {
+                ("Gemm", None) => {
+                    if node.attribute("transA").is_some_and(|a| a.i != 0)
+                        || node.attribute("alpha").is_some_and(|a| a.f != 1.0)
+                        || node.attribute("beta").is_some_and(|a| a.f != 1.0)
+                    {
+                        return Err(invalid("'Gemm' nodes must not scale or transpose inputs"));
+                    }
+
+                    let matrix = graph.matrix(node.inputs.get(1))?;
+                    let layer_weights = match node.attribute("transB") {
+                        Some(a) if a.i != 0 => matrix,
+                        _ => matrix.transpose(),
+                    };
+                    let layer_biases = match node.inputs.get(2) {
+                        Some(name) => graph.vector(name, layer_weights.nrows())?,
+                        None => DMatrix::zeros(layer_weights.nrows(), 1),
+                    };
+                    pending = Some((layer_weights, layer_biases));
+                }
+                ("MatMul", None) => {
+                    let layer_weights = graph.matrix(node.inputs.get(1))?.transpose();
+                    let layer_biases = DMatrix::zeros(layer_weights.nrows(), 1);
+                    pending = Some((layer_weights, layer_biases));
+                }
+                ("Add", Some((layer_weights, layer_biases))) => {
+                    let name = other.ok_or_else(|| invalid("'Add' node has no biases"))?;
+                    *layer_biases += graph.vector(name, layer_weights.nrows())?;
+                }
+                ("Mul", layer) if layer.is_some() || output_count > 0 => {
+                    // A linear output layer is still pending
+                    if let Some(layer) = pending.take() {
+                        output_count = layer.0.nrows();
+                        linear_output = true;
+                        push(layer)?;
+                    }
+                    let name = other.ok_or_else(|| invalid("'Mul' node has no scale"))?;
+                    std_dev = Some(graph.vector(name, output_count)?);
+                }
+                ("Add", None) if std_dev.is_some() => {
+                    let name = other.ok_or_else(|| invalid("'Add' node has no offset"))?;
+                    let mean = graph.vector(name, output_count)?;
+                    let std_dev = std_dev.take().expect("'Mul' node has been read");
+                    target_scaler = Some(TargetScaler::new(
+                        mean.as_slice().to_vec(),
+                        std_dev.as_slice().to_vec(),
+                    ));
+                }
+                ("Sigmoid", Some(_))
+                | ("Tanh", Some(_))
+                | ("Relu", Some(_))
+                | ("Identity", Some(_)) => {
+                    let found = match node.op_type {
+                        "Identity" => "linear".to_string(),
+                        op_type => op_type.to_ascii_lowercase(),
+                    };
+                    if found != A::name() {
+                        return Err(LoadErr::Activation {
+                            expected: A::name().to_string(),
+                            found,
+                        });
+                    }
+
+                    let layer = pending.take().expect("layer is pending");
+                    output_count = layer.0.nrows();
+                    push(layer)?;
+                }
+                (op_type, _) => {
+                    return Err(invalid(&format!("unexpected '{}' node in graph", op_type)))
+                }
+            }
+            current = &node.output;
+        }
+
+        if std_dev.is_some() {
+            return Err(invalid("'Mul' node isn't followed by an 'Add' node"));
+        }
+        // An output layer without an activation is linear
+        if let Some(layer) = pending {
+            linear_output = true;
+            push(layer)?;
+        }
+        if weights.is_empty() {
+            return Err(invalid("graph has no layers"));
+        }
+        if target_scaler.is_some() && !linear_output {
+            return Err(invalid("target scaler follows an activated output layer"));
+        }
+
+        let mut imported = Self::from_parameters(weights, biases);
+        imported.linear_output = linear_output;
+        imported.target_scaler = target_scaler;
+        Ok(imported)
+    }
+}
+
+/// Creates the error of an invalid ONNX model.
+fn invalid(message: &str) -> LoadErr {
+    LoadErr::Invalid(message.to_string())
+}
+
+/// The parts of an ONNX graph needed to import a network.
+struct Graph<'a> {
+    nodes: Vec<Node<'a>>,
+    /// The dimensions and values of the graph's constant tensors, by name.
+    initializers: HashMap<&'a str, (Vec<i64>, Vec<f64>)>,
+    inputs: Vec<&'a str>,
+}
+
+impl Graph<'_> {
+    /// Returns the initializer with the given name as a matrix of the same shape.
+    fn matrix(&self, name: Option<&&str>) -> Result<DMatrix<f64>, LoadErr> {
+        let tensor = name.and_then(|name| self.initializers.get(name));
+        match tensor {
+            Some((dims, values)) if dims.len() == 2 => {
+                let (rows, cols) = match (usize::try_from(dims[0]), usize::try_from(dims[1])) {
+                    (Ok(rows), Ok(cols)) => (rows, cols),
+                    _ => return Err(invalid("tensor dimensions must be non-negative")),
+                };
+                if rows.checked_mul(cols) != Some(values.len()) {
+                    return Err(invalid("tensor has the wrong number of values"));
+                }
+                Ok(DMatrix::from_row_slice(rows, cols, values))
+            }
+            _ => Err(invalid("weights must be a two-dimensional initializer")),
+        }
+    }
+
+    /// Returns the initializer with the given name as a column vector of the given length.
+    fn vector(&self, name: &str, len: usize) -> Result<DMatrix<f64>, LoadErr> {
+        match self.initializers.get(name) {
+            Some((_, values)) if values.len() == len => {
+                Ok(DMatrix::from_column_slice(len, 1, values))
+            }
+            _ => Err(invalid(
+                "biases must be an initializer with one value per node",
+            )),
+        }
+    }
+}
+
+/// A node of an ONNX graph.
+struct Node<'a> {
+    op_type: &'a str,
+    inputs: Vec<&'a str>,
+    output: &'a str,
+    attributes: Vec<Attribute<'a>>,
+}
+
+impl Node<'_> {
+    /// Returns the attribute with the given name, if the node has it.
+    fn attribute(&self, name: &str) -> Option<&Attribute<'_>> {
+        self.attributes.iter().find(|a| a.name == name)
+    }
+}
+
+/// A numeric attribute of an ONNX node.
+#[derive(Default)]
+struct Attribute<'a> {
+    name: &'a str,
+    f: f32,
+    i: i64,
+}
+
+/// Decodes the graph of an encoded `ModelProto`.
+fn read_graph(model: &[u8]) -> Result<Graph<'_>, DecodeError> {
+    let mut graph = Graph {
+        nodes: Vec::new(),
+        initializers: HashMap::new(),
+        inputs: Vec::new(),
+    };
+
+    for field in protobuf::fields(model) {
+        if let (7, value) = field? {
+            for field in protobuf::fields(value.bytes()?) {
+                match field? {
+                    (1, value) => graph.nodes.push(read_node(value)?),
+                    (5, value) => {
+                        let (name, tensor) = read_tensor(value)?;
+                        graph.initializers.insert(name, tensor);
+                    }
+                    (11, value) => graph.inputs.push(read_name(value)?),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+/// Decodes a `NodeProto`.
+fn read_node(node: Value<'_>) -> Result<Node<'_>, DecodeError> {
+    let mut inputs = Vec::new();
+    let mut outputs = Vec::new();
+    let mut op_type = "";
+    let mut attributes = Vec::new();
+    for field in protobuf::fields(node.bytes()?) {
+        match field? {
+            (1, value) => inputs.push(value.string()?),
+            (2, value) => outputs.push(value.string()?),
+            (4, value) => op_type = value.string()?,
+            (5, value) => {
+                let mut attribute = Attribute::default();
+                for field in protobuf::fields(value.bytes()?) {
+                    match field? {
+                        (1, value) => attribute.name = value.string()?,
+                        (2, value) => attribute.f = value.float()?,
+                        (3, value) => attribute.i = value.int()?,
+                        _ => {}
+                    }
+                }
+                attributes.push(attribute);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(Node {
+        op_type,
+        // Optional inputs may be given as empty names
+        inputs: inputs.into_iter().filter(|i| !i.is_empty()).collect(),
+        output: outputs.first().ok_or(DecodeError)?,
+        attributes,
+    })
+}
+
+/// Decodes a `TensorProto` of 32-bit or 64-bit floats into its name, dimensions and values.
+#[allow(clippy::type_complexity)]
+fn read_tensor(tensor: Value<'_>) -> Result<(&str, (Vec<i64>, Vec<f64>)), DecodeError> {
+    let mut name = "";
+    let mut dims = Vec::new();
+    let mut data_type = 0;
+    let mut raw = None;
+    let mut floats = Vec::new();
+    let mut doubles = Vec::new();
+    for field in protobuf::fields(tensor.bytes()?) {
+        match field? {
+            (1, value) => value.extend_ints(&mut dims)?,
+            (2, value) => data_type = value.int()?,
+            (4, value) => value.extend_floats(&mut floats, false)?,
+            (8, value) => name = value.string()?,
+            (9, value) => raw = Some(value),
+            (10, value) => value.extend_floats(&mut doubles, true)?,
+            _ => {}
+        }
+    }
+
+    let values = match (data_type, raw) {
+        (FLOAT, Some(raw)) => {
+            let mut values = Vec::new();
+            raw.extend_floats(&mut values, false)?;
+            values
+        }
+        (DOUBLE, Some(raw)) => {
+            let mut values = Vec::new();
+            raw.extend_floats(&mut values, true)?;
+            values
+        }
+        (FLOAT, None) => floats,
+        (DOUBLE, None) => doubles,
+        _ => Vec::new(),
+    };
+
+    Ok((name, (dims, values)))
+}
+
+/// Decodes the name of a `ValueInfoProto`.
+fn read_name(value_info: Value<'_>) -> Result<&str, DecodeError> {
+    for field in protobuf::fields(value_info.bytes()?) {
+        if let (1, value) = field? {
+            return value.string();
+        }
+    }
+
+    Err(DecodeError)
+}
+
+/// Encodes a `NodeProto` of the given operator.
+fn node(op_type: &str, name: &str, inputs: &[&str], output: &str) -> Message {
+    let message = inputs
+        .iter()
+        .fold(Message::new(), |message, input| message.string(1, input));
+    message.string(2, output).string(3, name).string(4, op_type)
+}
+
+/// Encodes a `TensorProto` of 32-bit floats holding the given matrix (in row-major order) with
+/// the given dimensions.
+fn tensor<T: Float>(name: &str, matrix: &DMatrix<T>, dims: &[usize]) -> Message {
+    let raw: Vec<u8> = matrix
+        .transpose()
+        .iter()
+        .flat_map(|x| (x.as_f64() as f32).to_le_bytes())
+        .collect();
+    let dims: Vec<i64> = dims.iter().map(|&d| d as i64).collect();
+
+    Message::new()
+        .ints(1, &dims)
+        .int(2, FLOAT)
+        .string(8, name)
+        .bytes(9, &raw)
+}
+
+/// Encodes a `ValueInfoProto` of a tensor of 32-bit floats with shape `[N, columns]`.
+fn value_info(name: &str, columns: usize) -> Message {
+    let shape = Message::new()
+        .message(1, Message::new().string(2, "N"))
+        .message(1, Message::new().int(1, columns as i64));
+    let tensor_type = Message::new().int(1, FLOAT).message(2, shape);
+
+    Message::new()
+        .string(1, name)
+        .message(2, Message::new().message(1, tensor_type))
+}
@@ -0,0 +1,198 @@
+use super::Float;
+#[cfg(feature = "std")]
+use crate::dataset::Dataset;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// Standardizes each target column to have a mean of 0 and a standard deviation of 1, based on
+/// the targets of the dataset it was fitted on.
+///
+/// Networks learn targets of a similar scale to their initial outputs far more easily than raw
+/// values such as prices or temperatures. Giving a network a scaler (see
+/// [`NeuralNet::set_target_scaler()`](struct.NeuralNet.html#method.set_target_scaler)) scales
+/// the targets of every row it trains on, and inverse-transforms its guesses back into the
+/// original units. The scaler is saved with the network, so guesses of a loaded network are
+/// unchanged. Only networks with a linear output layer can have a scaler, since the scaled
+/// targets aren't confined to the range of an activation.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{Dataset, TargetScaler};
+///
+/// let dataset = Dataset::from(vec![
+///     (vec![0.0], vec![10.0, -1.0]),
+///     (vec![1.0], vec![30.0, -1.0]),
+/// ]);
+///
+/// let scaler = TargetScaler::fit(&dataset);
+/// assert_eq!(scaler.mean(), &[20.0, -1.0]);
+/// assert_eq!(scaler.std_dev(), &[10.0, 0.0]);
+///
+/// // Constant columns are scaled to 0
+/// assert_eq!(scaler.transform_targets(&[30.0, -1.0]), vec![1.0, 0.0]);
+/// assert_eq!(scaler.inverse_transform_targets(&[1.0, 0.0]), vec![30.0, -1.0]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TargetScaler {
+    mean: Vec<f64>,
+    std_dev: Vec<f64>,
+}
+
+impl TargetScaler {
+    /// Creates a scaler from the mean and standard deviation of each target column.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the lengths of `mean` and `std_dev` differ.
+    pub fn new(mean: Vec<f64>, std_dev: Vec<f64>) -> Self {
+        if mean.len() != std_dev.len() {
+            panic!(
+                "incorrect number of standard deviations supplied (expected {}, found {})",
+                mean.len(),
+                std_dev.len()
+            );
+        }
+
+        Self { mean, std_dev }
+    }
+
+    /// Fits a scaler to the mean and (population) standard deviation of each target column in
+    /// the `Dataset`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the rows have differing numbers of targets.
+    #[cfg(feature = "std")]
+    pub fn fit(dataset: &Dataset) -> Self {
+        let num_targets = dataset
+            .into_iter()
+            .next()
+            .map_or(0, |(_, targets)| targets.len());
+        let mut scaler = Self::new(vec![0.0; num_targets], vec![0.0; num_targets]);
+
+        for (_, targets) in dataset {
+            scaler.check_len(targets.len());
+            for (sum, t) in scaler.mean.iter_mut().zip(targets) {
+                *sum += t;
+            }
+        }
+        let rows = dataset.rows().max(1) as f64;
+        for mean in &mut scaler.mean {
+            *mean /= rows;
+        }
+
+        for (_, targets) in dataset {
+            let columns = scaler.std_dev.iter_mut().zip(&scaler.mean);
+            for ((sum, mean), t) in columns.zip(targets) {
+                *sum += (t - mean) * (t - mean);
+            }
+        }
+        for std_dev in &mut scaler.std_dev {
+            *std_dev = (*std_dev / rows).sqrt();
+        }
+
+        scaler
+    }
+
+    /// Returns the number of target columns.
+    pub fn len(&self) -> usize {
+        self.mean.len()
+    }
+
+    /// Returns `true` if there are no target columns.
+    pub fn is_empty(&self) -> bool {
+        self.mean.is_empty()
+    }
+
+    /// Returns the mean of each target column that the scaler was fitted on.
+    pub fn mean(&self) -> &[f64] {
+        &self.mean
+    }
+
+    /// Returns the standard deviation of each target column that the scaler was fitted on.
+    pub fn std_dev(&self) -> &[f64] {
+        &self.std_dev
+    }
+
+    /// Creates a new `Dataset` with the targets of every row scaled, leaving the inputs intact.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if any row has a different number of targets to the dataset the
+    /// scaler was fitted on.
+    #[cfg(feature = "std")]
+    pub fn transform(&self, dataset: &Dataset) -> Dataset {
+        let data: Vec<_> = dataset
+            .into_iter()
+            .map(|(inputs, targets)| (inputs.clone(), self.transform_targets(targets)))
+            .collect();
+
+        Dataset::from(data)
+    }
+
+    /// Scales a single slice of targets.
+    ///
+    /// Columns that were constant are scaled to 0.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of targets differs from the dataset the scaler was
+    /// fitted on.
+    pub fn transform_targets(&self, targets: &[f64]) -> Vec<f64> {
+        self.check_len(targets.len());
+        targets
+            .iter()
+            .enumerate()
+            .map(|(i, &t)| self.scale(i, t))
+            .collect()
+    }
+
+    /// Reverses the scaling of a single slice of targets, such as a network's guesses.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of targets differs from the dataset the scaler was
+    /// fitted on.
+    pub fn inverse_transform_targets(&self, targets: &[f64]) -> Vec<f64> {
+        self.check_len(targets.len());
+        targets
+            .iter()
+            .enumerate()
+            .map(|(i, &t)| self.unscale(i, t))
+            .collect()
+    }
+
+    /// Scales the target of the given column.
+    pub(crate) fn scale(&self, column: usize, target: f64) -> f64 {
+        let std_dev = self.std_dev[column];
+        if std_dev == 0.0 {
+            0.0
+        } else {
+            (target - self.mean[column]) / std_dev
+        }
+    }
+
+    /// Reverses the scaling of the target of the given column.
+    pub(crate) fn unscale(&self, column: usize, target: f64) -> f64 {
+        self.mean[column] + target * self.std_dev[column]
+    }
+
+    /// Reverses the scaling of the target of the given column, in the network's `Float` type.
+    pub(crate) fn unscale_float<T: Float>(&self, column: usize, target: T) -> T {
+        T::convert(self.mean[column]) + target * T::convert(self.std_dev[column])
+    }
+
+    /// Panics if the given number of targets differs from the dataset the scaler was fitted on.
+    fn check_len(&self, len: usize) {
+        if len != self.len() {
+            panic!(
+                "incorrect number of targets supplied (expected {}, found {})",
+                self.len(),
+                len
+            );
+        }
+    }
+}
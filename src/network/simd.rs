@@ -0,0 +1,100 @@
+//! Activations of whole slices of `f32`s and `f64`s, written as branchless loops without any
+//! function calls, so that the compiler turns them into SIMD instructions (on stable Rust, and
+//! without `std`).
+//!
+//! `exp()` is calculated the usual way, by splitting `x` into `n * ln(2) + r` (with `|r|` at most
+//! `ln(2) / 2`) and multiplying `2^n` by a polynomial approximation of `exp(r)`. The results are
+//! within a few ULP of the standard library's.
+
+/// Adding and then subtracting this rounds an `f64` (of magnitude below 2^51) to the nearest
+/// integer, which is also left in the low bits of the sum.
+const ROUND_F64: f64 = 6_755_399_441_055_744.0;
+/// `ln(2)`, split so that multiples of the first part (of up to 2^20) are exact.
+const LN_2_HI_F64: f64 = 6.931_471_803_691_238e-1;
+const LN_2_LO_F64: f64 = 1.908_214_929_270_587_7e-10;
+/// The Taylor series of `exp(r)`, from the 13th power down, which is accurate to within an ULP
+/// when `|r| <= ln(2) / 2`.
+const EXP_COEFFICIENTS_F64: [f64; 14] = [
+    1.0 / 6_227_020_800.0,
+    1.0 / 479_001_600.0,
+    1.0 / 39_916_800.0,
+    1.0 / 3_628_800.0,
+    1.0 / 362_880.0,
+    1.0 / 40_320.0,
+    1.0 / 5_040.0,
+    1.0 / 720.0,
+    1.0 / 120.0,
+    1.0 / 24.0,
+    1.0 / 6.0,
+    1.0 / 2.0,
+    1.0,
+    1.0,
+];
+
+/// The `f32` equivalents of the constants above.
+const ROUND_F32: f32 = 12_582_912.0;
+const LN_2_HI_F32: f32 = 6.931_457_5e-1;
+const LN_2_LO_F32: f32 = 1.428_606_8e-6;
+const EXP_COEFFICIENTS_F32: [f32; 9] = [
+    1.0 / 40_320.0,
+    1.0 / 5_040.0,
+    1.0 / 720.0,
+    1.0 / 120.0,
+    1.0 / 24.0,
+    1.0 / 6.0,
+    1.0 / 2.0,
+    1.0,
+    1.0,
+];
+
+/// Calculates `exp(x)`, clamping `x` to the range in which `2^n` is a normal number (so the
+/// result saturates rather than overflowing to infinity or underflowing to zero).
+#[inline]
+fn exp_f64(x: f64) -> f64 {
+    let x = x.clamp(-708.0, 709.0);
+
+    let rounded = x * core::f64::consts::LOG2_E + ROUND_F64;
+    let n = rounded - ROUND_F64;
+    let r = x - n * LN_2_HI_F64 - n * LN_2_LO_F64;
+
+    let mut exp_r = EXP_COEFFICIENTS_F64[0];
+    for &coefficient in &EXP_COEFFICIENTS_F64[1..] {
+        exp_r = exp_r * r + coefficient;
+    }
+
+    // Builds `2^n` from its exponent bits, the low bits of `rounded` holding `n`
+    let two_to_n = f64::from_bits(rounded.to_bits().wrapping_add(1023) << 52);
+    exp_r * two_to_n
+}
+
+/// The `f32` equivalent of `exp_f64()`.
+#[inline]
+fn exp_f32(x: f32) -> f32 {
+    let x = x.clamp(-87.0, 88.0);
+
+    let rounded = x * core::f32::consts::LOG2_E + ROUND_F32;
+    let n = rounded - ROUND_F32;
+    let r = x - n * LN_2_HI_F32 - n * LN_2_LO_F32;
+
+    let mut exp_r = EXP_COEFFICIENTS_F32[0];
+    for &coefficient in &EXP_COEFFICIENTS_F32[1..] {
+        exp_r = exp_r * r + coefficient;
+    }
+
+    let two_to_n = f32::from_bits(rounded.to_bits().wrapping_add(127) << 23);
+    exp_r * two_to_n
+}
+
+/// Applies the sigmoid function to every value.
+pub(super) fn sigmoid_f64(xs: &mut [f64]) {
+    for x in xs {
+        *x = 1.0 / (1.0 + exp_f64(-*x));
+    }
+}
+
+/// Applies the sigmoid function to every value.
+pub(super) fn sigmoid_f32(xs: &mut [f32]) {
+    for x in xs {
+        *x = 1.0 / (1.0 + exp_f32(-*x));
+    }
+}
@@ -0,0 +1,127 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::{iter::Copied, slice};
+
+/// A vector of input values that only stores the values that aren't zero, as pairs of their
+/// indices and values.
+///
+/// High-dimensional inputs that are mostly zeros (such as bag-of-words vectors) take up a
+/// fraction of the memory as `SparseVector`s, and networks only multiply the weights of their
+/// non-zero values when guessing from them (see
+/// [`NeuralNet::guess_sparse()`](struct.NeuralNet.html#method.guess_sparse)) and training on them
+/// (see [`Trainer::train_sparse()`](struct.Trainer.html#method.train_sparse)).
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::SparseVector;
+///
+/// let inputs = SparseVector::new(5, vec![(3, 2.0), (0, 1.0), (4, 0.0)]);
+///
+/// assert_eq!(inputs.len(), 5);
+/// assert_eq!(inputs.nnz(), 2);
+/// assert_eq!(inputs.iter().collect::<Vec<_>>(), vec![(0, 1.0), (3, 2.0)]);
+/// assert_eq!(inputs.to_dense(), vec![1.0, 0.0, 0.0, 2.0, 0.0]);
+/// assert_eq!(SparseVector::from_dense(&inputs.to_dense()), inputs);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SparseVector {
+    len: usize,
+    /// The indices of the non-zero values, in ascending order.
+    indices: Vec<usize>,
+    values: Vec<f64>,
+}
+
+impl SparseVector {
+    /// Creates a vector of the given length from the indices and values of its non-zero values
+    /// (in any order), ignoring any that are zero.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if an index is out of bounds, or the same index is given twice.
+    pub fn new(len: usize, values: impl IntoIterator<Item = (usize, f64)>) -> Self {
+        let mut values: Vec<_> = values.into_iter().filter(|&(_, x)| x != 0.0).collect();
+        values.sort_unstable_by_key(|&(index, _)| index);
+
+        for pair in values.windows(2) {
+            if pair[0].0 == pair[1].0 {
+                panic!("index {} of a sparse vector was given twice", pair[0].0);
+            }
+        }
+        if let Some(&(index, _)) = values.last() {
+            if index >= len {
+                panic!(
+                    "sparse vector index out of bounds (the length is {} but the index is {})",
+                    len, index
+                );
+            }
+        }
+
+        Self {
+            len,
+            indices: values.iter().map(|&(index, _)| index).collect(),
+            values: values.iter().map(|&(_, x)| x).collect(),
+        }
+    }
+
+    /// Creates a vector from the non-zero values of the given dense slice.
+    pub fn from_dense(values: &[f64]) -> Self {
+        Self::new(values.len(), values.iter().copied().enumerate())
+    }
+
+    /// Returns the length of the vector (including its zeros), which is the number of inputs of
+    /// the networks that it can be fed into.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the length of the vector is zero.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of non-zero values in the vector.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns an iterator over the indices and values of the non-zero values, in order of their
+    /// indices.
+    pub fn iter(&self) -> SparseIter<'_> {
+        SparseIter {
+            indices: self.indices.iter().copied(),
+            values: self.values.iter().copied(),
+        }
+    }
+
+    /// Returns the values of the vector (including its zeros) as a dense `Vec`.
+    pub fn to_dense(&self) -> Vec<f64> {
+        let mut dense = alloc::vec![0.0; self.len];
+        for (index, x) in self.iter() {
+            dense[index] = x;
+        }
+        dense
+    }
+}
+
+/// An iterator over the non-zero values of a `SparseVector`, created by
+/// [`SparseVector::iter()`](struct.SparseVector.html#method.iter).
+#[derive(Clone, Debug)]
+pub struct SparseIter<'a> {
+    indices: Copied<slice::Iter<'a, usize>>,
+    values: Copied<slice::Iter<'a, f64>>,
+}
+
+impl<'a> Iterator for SparseIter<'a> {
+    type Item = (usize, f64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some((self.indices.next()?, self.values.next()?))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.values.size_hint()
+    }
+}
+
+impl ExactSizeIterator for SparseIter<'_> {}
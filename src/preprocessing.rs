@@ -0,0 +1,264 @@
+//! Preprocessing of datasets and inputs before they are given to a network.
+
+use crate::dataset::Dataset;
+#[cfg(feature = "fs")]
+use crate::network::{LoadErr, SaveErr};
+use crate::utils::decode_class;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "fs")]
+use std::{fs, path::Path};
+
+/// Scales each input column to lie between 0 and 1, based on the minimum and maximum values of
+/// the column in the dataset it was fitted on.
+///
+/// The scaler should be fitted on the training data only (so that no statistics leak from the
+/// testing data), and then used to transform every dataset and input given to the network,
+/// including at inference time. It can be saved alongside the network for this purpose.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{preprocessing::MinMaxScaler, Dataset};
+///
+/// let training_data = Dataset::from(vec![
+///     (vec![0.0, 100.0], vec![0.0]),
+///     (vec![5.0, 300.0], vec![1.0]),
+///     (vec![10.0, 200.0], vec![1.0]),
+/// ]);
+///
+/// let scaler = MinMaxScaler::fit(&training_data);
+/// let training_data = scaler.transform(&training_data);
+///
+/// // Inputs at inference time are scaled identically
+/// assert_eq!(scaler.transform_inputs(&[5.0, 250.0]), vec![0.5, 0.75]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MinMaxScaler {
+    min: Vec<f64>,
+    max: Vec<f64>,
+}
+
+impl MinMaxScaler {
+    /// Fits a scaler to the minimum and maximum values of each input column in the `Dataset`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the rows have differing numbers of inputs.
+    pub fn fit(dataset: &Dataset) -> Self {
+        let mut rows = dataset.into_iter();
+        let (min, max) = match rows.next() {
+            Some((inputs, _)) => (inputs.clone(), inputs.clone()),
+            None => (Vec::new(), Vec::new()),
+        };
+
+        rows.fold(Self { min, max }, |mut scaler, (inputs, _)| {
+            scaler.check_len(inputs.len());
+            for (i, x) in inputs.iter().enumerate() {
+                scaler.min[i] = scaler.min[i].min(*x);
+                scaler.max[i] = scaler.max[i].max(*x);
+            }
+            scaler
+        })
+    }
+
+    /// Loads a scaler from a file created using [`MinMaxScaler::save()`](#method.save).
+    #[cfg(feature = "fs")]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, LoadErr> {
+        let file = fs::File::open(path)?;
+        let decoded: MinMaxScaler = bincode::deserialize_from(file)?;
+
+        Ok(decoded)
+    }
+
+    /// Saves the scaler in a binary format to the specified path.
+    #[cfg(feature = "fs")]
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SaveErr> {
+        let encoded = bincode::serialize(&self)?;
+        fs::write(path, encoded)?;
+
+        Ok(())
+    }
+
+    /// Returns the minimum value of each input column that the scaler was fitted on.
+    pub fn min(&self) -> &[f64] {
+        &self.min
+    }
+
+    /// Returns the maximum value of each input column that the scaler was fitted on.
+    pub fn max(&self) -> &[f64] {
+        &self.max
+    }
+
+    /// Creates a new `Dataset` with the inputs of every row scaled, leaving the targets intact.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if any row has a different number of inputs to the dataset the scaler
+    /// was fitted on.
+    pub fn transform(&self, dataset: &Dataset) -> Dataset {
+        let data: Vec<_> = dataset
+            .into_iter()
+            .map(|(inputs, targets)| (self.transform_inputs(inputs), targets.clone()))
+            .collect();
+
+        Dataset::from(data)
+    }
+
+    /// Scales a single slice of inputs.
+    ///
+    /// Values outside of the fitted range are scaled proportionally (and so lie outside of 0 to
+    /// 1), whilst columns that were constant are scaled to 0.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of inputs differs from the dataset the scaler was fitted
+    /// on.
+    pub fn transform_inputs(&self, inputs: &[f64]) -> Vec<f64> {
+        self.check_len(inputs.len());
+        inputs
+            .iter()
+            .zip(self.min.iter().zip(&self.max))
+            .map(|(x, (min, max))| {
+                let range = max - min;
+                if range == 0.0 {
+                    0.0
+                } else {
+                    (x - min) / range
+                }
+            })
+            .collect()
+    }
+
+    /// Reverses the scaling of a single slice of inputs.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of inputs differs from the dataset the scaler was fitted
+    /// on.
+    pub fn inverse_transform_inputs(&self, inputs: &[f64]) -> Vec<f64> {
+        self.check_len(inputs.len());
+        inputs
+            .iter()
+            .zip(self.min.iter().zip(&self.max))
+            .map(|(x, (min, max))| min + x * (max - min))
+            .collect()
+    }
+
+    /// Panics if the given number of inputs differs from the dataset the scaler was fitted on.
+    fn check_len(&self, len: usize) {
+        if len != self.min.len() {
+            panic!(
+                "incorrect number of inputs supplied (expected {}, found {})",
+                self.min.len(),
+                len
+            );
+        }
+    }
+}
+
+/// A mapping between class names and class indices, for classification datasets whose targets
+/// are names (e.g. `"setosa"`) rather than numbers.
+///
+/// Classes are indexed in alphabetical order. An encoder is usually created by
+/// [`Dataset::from_csv_with_labels()`](../struct.Dataset.html#method.from_csv_with_labels), and
+/// can be saved alongside the network so that its guesses can be decoded at inference time.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::preprocessing::LabelEncoder;
+///
+/// let encoder = LabelEncoder::fit(vec!["virginica", "setosa", "versicolor", "setosa"]);
+///
+/// assert_eq!(encoder.len(), 3);
+/// assert_eq!(encoder.encode("versicolor"), Some(1));
+/// assert_eq!(encoder.one_hot("virginica"), Some(vec![0.0, 0.0, 1.0]));
+/// assert_eq!(encoder.decode(0), Some("setosa"));
+/// assert_eq!(encoder.decode_guesses(&[0.1, 0.2, 0.9]), "virginica");
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LabelEncoder {
+    labels: Vec<String>,
+}
+
+impl LabelEncoder {
+    /// Fits an encoder to every distinct class name in the given labels.
+    pub fn fit<S: AsRef<str>>(labels: impl IntoIterator<Item = S>) -> Self {
+        let mut labels: Vec<String> = labels.into_iter().map(|l| l.as_ref().to_string()).collect();
+        labels.sort();
+        labels.dedup();
+
+        Self { labels }
+    }
+
+    /// Loads an encoder from a file created using [`LabelEncoder::save()`](#method.save).
+    #[cfg(feature = "fs")]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, LoadErr> {
+        let file = fs::File::open(path)?;
+        let decoded: LabelEncoder = bincode::deserialize_from(file)?;
+
+        Ok(decoded)
+    }
+
+    /// Saves the encoder in a binary format to the specified path.
+    #[cfg(feature = "fs")]
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SaveErr> {
+        let encoded = bincode::serialize(&self)?;
+        fs::write(path, encoded)?;
+
+        Ok(())
+    }
+
+    /// Returns the number of classes.
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Returns `true` if there are no classes.
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Returns the class names, in order of their indices.
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Returns the index of the given class name, or `None` if it is unknown.
+    pub fn encode(&self, label: &str) -> Option<usize> {
+        self.labels.binary_search_by(|l| l.as_str().cmp(label)).ok()
+    }
+
+    /// Returns the one-hot target vector of the given class name, or `None` if it is unknown.
+    pub fn one_hot(&self, label: &str) -> Option<Vec<f64>> {
+        self.encode(label).map(|index| {
+            let mut targets = vec![0.0; self.len()];
+            targets[index] = 1.0;
+            targets
+        })
+    }
+
+    /// Returns the class name of the given index, or `None` if it is out of bounds.
+    pub fn decode(&self, index: usize) -> Option<&str> {
+        self.labels.get(index).map(String::as_str)
+    }
+
+    /// Returns the class name predicted by a network's guesses (the class whose output is the
+    /// largest).
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of guesses differs from the number of classes.
+    pub fn decode_guesses(&self, guesses: &[f64]) -> &str {
+        if guesses.len() != self.len() {
+            panic!(
+                "incorrect number of guesses supplied (expected {}, found {})",
+                self.len(),
+                guesses.len()
+            );
+        }
+
+        &self.labels[decode_class(guesses, 0.5)]
+    }
+}
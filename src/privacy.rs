@@ -0,0 +1,65 @@
+//! Differentially private training, for learning from sensitive data without the trained network
+//! revealing too much about any individual row.
+//!
+//! Training with [`Trainer::differential_privacy()`](../struct.Trainer.html#method.differential_privacy)
+//! uses DP-SGD: the gradient of each row is clipped to a maximum L2 norm, the clipped gradients of
+//! each batch are summed, and Gaussian noise proportional to the clipping norm is added to the sum
+//! before it is applied. The privacy guaranteed by this is measured by an `(epsilon, delta)` pair,
+//! where smaller values of both mean more privacy, and can be calculated with [`epsilon()`].
+
+use crate::network::{Float, Gradients};
+use crate::utils::standard_normal;
+
+use rand::Rng;
+
+/// Calculates the `epsilon` spent by training with DP-SGD for the given number of epochs (each of
+/// which iterates over the training data once), at the given `delta`.
+///
+/// Since every row belongs to exactly one batch per epoch, each epoch is accounted for as a single
+/// Gaussian mechanism with a standard deviation of `noise_multiplier` times the clipping norm,
+/// and the epochs are composed using Rényi differential privacy. This doesn't take any
+/// amplification from sampling into account, so the bound is conservative.
+///
+/// `delta` should be smaller than the reciprocal of the number of rows in the training data.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::privacy;
+///
+/// let epsilon = privacy::epsilon(10.0, 20, 1e-5);
+/// assert!(epsilon > 2.2 && epsilon < 2.3);
+///
+/// // More noise spends less privacy
+/// assert!(privacy::epsilon(20.0, 20, 1e-5) < epsilon);
+/// ```
+///
+/// # Panics
+///
+/// This function panics if `delta` isn't between 0 and 1 (exclusive).
+pub fn epsilon(noise_multiplier: f64, epochs: u64, delta: f64) -> f64 {
+    if delta <= 0.0 || delta >= 1.0 {
+        panic!("delta must be between 0 and 1 (found {})", delta);
+    }
+
+    // Each epoch has a Rényi divergence of `alpha / (2 * sigma^2)` at every order `alpha`, which
+    // is converted into `(epsilon, delta)` at the order that minimises epsilon
+    let rdp = epochs as f64 / (2.0 * noise_multiplier * noise_multiplier);
+    let log_delta = (1.0 / delta).ln();
+    rdp + 2.0 * (rdp * log_delta).sqrt()
+}
+
+/// Scales the gradients down so that their L2 norm is at most `clip_norm`.
+pub(crate) fn clip<T: Float>(gradients: &mut Gradients<T>, clip_norm: f64) {
+    let norm = gradients.norm();
+    if norm > clip_norm {
+        gradients.scale(clip_norm / norm);
+    }
+}
+
+/// Adds Gaussian noise with the given standard deviation to every gradient.
+pub(crate) fn add_noise<T: Float>(gradients: &mut Gradients<T>, std_dev: f64, rng: &mut impl Rng) {
+    for x in gradients.values_mut() {
+        *x += T::convert(std_dev * standard_normal(rng));
+    }
+}
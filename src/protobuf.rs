@@ -0,0 +1,261 @@
+//! A minimal encoder and decoder for the Protocol Buffers wire format, enough to read and write
+//! the messages of simple ONNX models (and write TensorBoard's events) without generated code.
+
+use std::convert::{TryFrom, TryInto};
+
+/// The wire type of fields encoded as varints.
+const VARINT: u64 = 0;
+/// The wire type of 64-bit fields.
+const FIXED64: u64 = 1;
+/// The wire type of length-delimited fields (strings, bytes, messages and packed values).
+const LENGTH_DELIMITED: u64 = 2;
+/// The wire type of 32-bit fields.
+const FIXED32: u64 = 5;
+
+/// An encoded Protocol Buffers message, built up one field at a time.
+#[derive(Default)]
+pub(crate) struct Message {
+    bytes: Vec<u8>,
+}
+
+impl Message {
+    /// Creates a new empty message.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends an integer field (of type `int32`, `int64`, `uint64` or an enum).
+    pub(crate) fn int(mut self, field: u64, value: i64) -> Self {
+        self.key(field, VARINT);
+        self.varint(value as u64);
+        self
+    }
+
+    /// Appends a packed repeated integer field.
+    pub(crate) fn ints(mut self, field: u64, values: &[i64]) -> Self {
+        let mut packed = Message::new();
+        for &value in values {
+            packed.varint(value as u64);
+        }
+        self.bytes_field(field, &packed.bytes);
+        self
+    }
+
+    /// Appends a `float` field.
+    #[cfg(feature = "tensorboard")]
+    pub(crate) fn float(mut self, field: u64, value: f32) -> Self {
+        self.key(field, FIXED32);
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    /// Appends a `double` field.
+    #[cfg(feature = "tensorboard")]
+    pub(crate) fn double(mut self, field: u64, value: f64) -> Self {
+        self.key(field, FIXED64);
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    /// Appends a packed repeated `double` field.
+    #[cfg(feature = "tensorboard")]
+    pub(crate) fn doubles(mut self, field: u64, values: &[f64]) -> Self {
+        let packed: Vec<u8> = values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+        self.bytes_field(field, &packed);
+        self
+    }
+
+    /// Appends a string field.
+    pub(crate) fn string(self, field: u64, value: &str) -> Self {
+        self.bytes(field, value.as_bytes())
+    }
+
+    /// Appends a bytes field.
+    pub(crate) fn bytes(mut self, field: u64, value: &[u8]) -> Self {
+        self.bytes_field(field, value);
+        self
+    }
+
+    /// Appends an embedded message field.
+    pub(crate) fn message(self, field: u64, value: Message) -> Self {
+        self.bytes(field, &value.bytes)
+    }
+
+    /// Appends an embedded message field for each of the given messages.
+    pub(crate) fn messages(self, field: u64, values: impl IntoIterator<Item = Message>) -> Self {
+        values
+            .into_iter()
+            .fold(self, |message, value| message.message(field, value))
+    }
+
+    /// Returns the encoded bytes of the message.
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    fn bytes_field(&mut self, field: u64, value: &[u8]) {
+        self.key(field, LENGTH_DELIMITED);
+        self.varint(value.len() as u64);
+        self.bytes.extend_from_slice(value);
+    }
+
+    fn key(&mut self, field: u64, wire_type: u64) {
+        self.varint(field << 3 | wire_type);
+    }
+
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.bytes.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        self.bytes.push(value as u8);
+    }
+}
+
+/// The error when decoding malformed Protocol Buffers data.
+#[derive(Debug)]
+pub(crate) struct DecodeError;
+
+/// The value of a decoded field, whose meaning depends on the message's schema.
+#[derive(Clone, Copy)]
+pub(crate) enum Value<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+impl<'a> Value<'a> {
+    /// Interprets the value as an integer field.
+    pub(crate) fn int(self) -> Result<i64, DecodeError> {
+        match self {
+            Value::Varint(value) => Ok(value as i64),
+            _ => Err(DecodeError),
+        }
+    }
+
+    /// Interprets the value as a `float` field.
+    pub(crate) fn float(self) -> Result<f32, DecodeError> {
+        match self {
+            Value::Fixed32(value) => Ok(f32::from_bits(value)),
+            _ => Err(DecodeError),
+        }
+    }
+
+    /// Interprets the value as a bytes (or embedded message) field.
+    pub(crate) fn bytes(self) -> Result<&'a [u8], DecodeError> {
+        match self {
+            Value::Bytes(bytes) => Ok(bytes),
+            _ => Err(DecodeError),
+        }
+    }
+
+    /// Interprets the value as a string field.
+    pub(crate) fn string(self) -> Result<&'a str, DecodeError> {
+        std::str::from_utf8(self.bytes()?).map_err(|_| DecodeError)
+    }
+
+    /// Appends the integers of a repeated integer field, which may be packed or not.
+    pub(crate) fn extend_ints(self, values: &mut Vec<i64>) -> Result<(), DecodeError> {
+        match self {
+            Value::Bytes(mut bytes) => {
+                while !bytes.is_empty() {
+                    values.push(read_varint(&mut bytes)? as i64);
+                }
+            }
+            value => values.push(value.int()?),
+        }
+
+        Ok(())
+    }
+
+    /// Appends the values of a repeated `float` (or, if `double`, a repeated `double`) field,
+    /// which may be packed or not.
+    pub(crate) fn extend_floats(
+        self,
+        values: &mut Vec<f64>,
+        double: bool,
+    ) -> Result<(), DecodeError> {
+        match self {
+            Value::Bytes(bytes) if double => values.extend(
+                bytes
+                    .chunks_exact(8)
+                    .map(|b| f64::from_le_bytes(b.try_into().expect("chunk has 8 bytes"))),
+            ),
+            Value::Bytes(bytes) => {
+                values.extend(bytes.chunks_exact(4).map(|b| {
+                    f64::from(f32::from_le_bytes(b.try_into().expect("chunk has 4 bytes")))
+                }))
+            }
+            Value::Fixed64(value) if double => values.push(f64::from_bits(value)),
+            Value::Fixed32(value) if !double => values.push(f64::from(f32::from_bits(value))),
+            _ => return Err(DecodeError),
+        }
+
+        Ok(())
+    }
+}
+
+/// Decodes the fields of the given message, as pairs of field numbers and values.
+pub(crate) fn fields(
+    mut bytes: &[u8],
+) -> impl Iterator<Item = Result<(u64, Value<'_>), DecodeError>> {
+    std::iter::from_fn(move || {
+        if bytes.is_empty() {
+            None
+        } else {
+            let field = read_field(&mut bytes);
+            if field.is_err() {
+                // Stops at the first error, as the rest of the message can't be found
+                bytes = &[];
+            }
+            Some(field)
+        }
+    })
+}
+
+fn read_field<'a>(bytes: &mut &'a [u8]) -> Result<(u64, Value<'a>), DecodeError> {
+    let key = read_varint(bytes)?;
+    let value = match key & 0x7 {
+        VARINT => Value::Varint(read_varint(bytes)?),
+        FIXED64 => Value::Fixed64(u64::from_le_bytes(
+            take(bytes, 8)?.try_into().map_err(|_| DecodeError)?,
+        )),
+        LENGTH_DELIMITED => {
+            let len = read_varint(bytes)?;
+            Value::Bytes(take(bytes, usize::try_from(len).map_err(|_| DecodeError)?)?)
+        }
+        FIXED32 => Value::Fixed32(u32::from_le_bytes(
+            take(bytes, 4)?.try_into().map_err(|_| DecodeError)?,
+        )),
+        _ => return Err(DecodeError),
+    };
+
+    Ok((key >> 3, value))
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Result<u64, DecodeError> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = bytes.split_first().ok_or(DecodeError)?;
+        *bytes = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte < 0x80 {
+            return Ok(value);
+        }
+    }
+
+    Err(DecodeError)
+}
+
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8], DecodeError> {
+    if bytes.len() < len {
+        return Err(DecodeError);
+    }
+    let (taken, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Ok(taken)
+}
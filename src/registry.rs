@@ -0,0 +1,366 @@
+//! Registration of custom components under names, so that they can be referred to from
+//! configuration files and loaded from saved networks.
+//!
+//! The [`config`](../config/index.html) module refers to a network's activation and loss by name
+//! (e.g. `activation = "relu"` and `loss = "rmse"`), which are looked up in a `Registry`. The
+//! default registry contains the built-in activations and losses, and custom ones can be
+//! registered alongside them. A registry can also load a network saved as JSON (see
+//! [`NeuralNet::save_json()`](../struct.NeuralNet.html#method.save_json)) without knowing its
+//! activation in advance, by looking up the activation named in the file.
+//!
+//! Custom layers (see [`CustomLayer`](../layers/trait.CustomLayer.html)) are saved by name as part
+//! of a [`Sequential`](../layers/struct.Sequential.html) model or
+//! [`Graph`](../layers/struct.Graph.html), and are looked up by that name in a registry when the
+//! model is deserialized (see
+//! [`Registry::deserialize()`](struct.Registry.html#method.deserialize)).
+
+use crate::config::{ConfigErr, ExperimentConfig};
+use crate::layers::{self, CustomLayer, LayerLoader};
+use crate::metrics::{Predictions, Report};
+use crate::network::{json, Activation, LoadErr, NeuralNet, Sigmoid};
+
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+use std::{collections::HashMap, fmt, path::Path};
+
+/// Runs an experiment with a network using a particular activation.
+type Runner = fn(&ExperimentConfig, &Registry) -> Result<Report, ConfigErr>;
+
+/// Loads a network using a particular activation from a JSON file.
+type JsonLoader = fn(&Path) -> Result<AnyNetwork, LoadErr>;
+
+/// Calculates the outputs of a loaded network.
+type Guess = Box<dyn Fn(&[f64]) -> Vec<f64>>;
+
+/// A loss, calculated from a network's guesses.
+pub type Loss = fn(&Predictions) -> f64;
+
+/// An activation registered in a `Registry`.
+#[derive(Clone, Copy, Debug)]
+struct RegisteredActivation {
+    /// The name of the activation in saved networks (see `Activation::name()`).
+    saved_name: fn() -> &'static str,
+    run: Runner,
+    load_json: JsonLoader,
+}
+
+/// A set of components registered under names.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{config::ExperimentConfig, registry::Registry, Activation};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Tanh;
+///
+/// impl Activation for Tanh {
+///     fn activate(x: f64) -> f64 {
+///         x.tanh()
+///     }
+///
+///     fn derivative(x: f64) -> f64 {
+///         1.0 - x * x
+///     }
+/// }
+///
+/// let registry = Registry::default()
+///     .register_activation::<Tanh>("tanh")
+///     .register_loss("max_error", |predictions| {
+///         predictions
+///             .pairs()
+///             .iter()
+///             .flat_map(|(guesses, targets)| guesses.iter().zip(targets))
+///             .map(|(g, t)| (g - t).abs())
+///             .fold(0.0, f64::max)
+///     });
+/// assert!(registry.contains_activation("sigmoid"));
+/// assert!(registry.contains_activation("tanh"));
+/// assert!(registry.contains_loss("max_error"));
+///
+/// // Experiments can now use `activation = "tanh"` and `loss = "max_error"`, when run with
+/// // `ExperimentConfig::run_with_registry()` or `config::run_from_config_with()`
+/// ```
+#[derive(Clone, Debug)]
+pub struct Registry {
+    activations: HashMap<String, RegisteredActivation>,
+    losses: HashMap<String, Loss>,
+    layers: HashMap<&'static str, LayerLoader>,
+}
+
+impl Registry {
+    /// Creates a new, empty registry (without even the built-in components).
+    pub fn new() -> Self {
+        Self {
+            activations: HashMap::new(),
+            losses: HashMap::new(),
+            layers: HashMap::new(),
+        }
+    }
+
+    /// Registers the given activation under the given name, replacing any activation already
+    /// registered under it.
+    pub fn register_activation<A: Activation + Serialize + DeserializeOwned + 'static>(
+        mut self,
+        name: &str,
+    ) -> Self {
+        let activation = RegisteredActivation {
+            saved_name: A::name,
+            run: ExperimentConfig::run_as::<A>,
+            load_json: AnyNetwork::from_json::<A>,
+        };
+        self.activations.insert(name.to_string(), activation);
+        self
+    }
+
+    /// Registers the given loss under the given name, replacing any loss already registered
+    /// under it.
+    pub fn register_loss(mut self, name: &str, loss: Loss) -> Self {
+        self.losses.insert(name.to_string(), loss);
+        self
+    }
+
+    /// Registers the given custom layer under its name (see
+    /// [`CustomLayer::name()`](../layers/trait.CustomLayer.html#tymethod.name)), replacing any
+    /// layer already registered under it, so that models using it can be deserialized.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::layers::{CustomLayer, Sequential};
+    /// use scholar::{registry::Registry, Sigmoid};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// // Scales its inputs by a learned factor
+    /// #[derive(Clone, Debug, Serialize, Deserialize)]
+    /// struct Scale {
+    ///     factor: f64,
+    /// }
+    ///
+    /// impl CustomLayer for Scale {
+    ///     fn name() -> &'static str {
+    ///         "scale"
+    ///     }
+    ///
+    ///     fn output_shape(&self, input_shape: &[usize]) -> Vec<usize> {
+    ///         input_shape.to_vec()
+    ///     }
+    ///
+    ///     fn forward(&self, inputs: &[f64]) -> Vec<f64> {
+    ///         inputs.iter().map(|x| x * self.factor).collect()
+    ///     }
+    ///
+    ///     fn backward(
+    ///         &mut self,
+    ///         inputs: &[f64],
+    ///         gradients: &[f64],
+    ///         learning_rate: f64,
+    ///     ) -> Vec<f64> {
+    ///         let input_gradients = gradients.iter().map(|g| g * self.factor).collect();
+    ///         let factor_gradient: f64 = inputs.iter().zip(gradients).map(|(x, g)| x * g).sum();
+    ///         self.factor -= learning_rate * factor_gradient;
+    ///         input_gradients
+    ///     }
+    /// }
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let model: Sequential<Sigmoid> = Sequential::new(&[2])
+    ///     .dense(3)
+    ///     .custom(Scale { factor: 2.0 })
+    ///     .dense(1);
+    /// let json = serde_json::to_string(&model)?;
+    ///
+    /// // The layer is looked up by name, so the model can't be loaded without it registered
+    /// assert!(serde_json::from_str::<Sequential<Sigmoid>>(&json).is_err());
+    ///
+    /// let registry = Registry::default().register_layer::<Scale>();
+    /// assert!(registry.contains_layer("scale"));
+    /// let loaded: Sequential<Sigmoid> =
+    ///     registry.deserialize(&mut serde_json::Deserializer::from_str(&json))?;
+    /// assert_eq!(loaded.guess(&[1.0, 0.0]), model.guess(&[1.0, 0.0]));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn register_layer<L>(mut self) -> Self
+    where
+        L: CustomLayer + Clone + Serialize + DeserializeOwned + 'static,
+    {
+        self.layers.insert(L::name(), layers::load_layer::<L>);
+        self
+    }
+
+    /// Returns `true` if an activation is registered under the given name.
+    pub fn contains_activation(&self, name: &str) -> bool {
+        self.activations.contains_key(name)
+    }
+
+    /// Returns `true` if a loss is registered under the given name.
+    pub fn contains_loss(&self, name: &str) -> bool {
+        self.losses.contains_key(name)
+    }
+
+    /// Returns `true` if a custom layer is registered under the given name.
+    pub fn contains_layer(&self, name: &str) -> bool {
+        self.layers.contains_key(name)
+    }
+
+    /// Returns the names of every registered activation, in alphabetical order.
+    pub fn activation_names(&self) -> Vec<&str> {
+        sorted_names(&self.activations)
+    }
+
+    /// Returns the names of every registered loss, in alphabetical order.
+    pub fn loss_names(&self) -> Vec<&str> {
+        sorted_names(&self.losses)
+    }
+
+    /// Returns the names of every registered custom layer, in alphabetical order.
+    pub fn layer_names(&self) -> Vec<&str> {
+        let mut names: Vec<_> = self.layers.keys().copied().collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Returns the loss registered under the given name, if any, such as to record it whilst
+    /// training with [`Trainer::metric()`](../struct.Trainer.html#method.metric).
+    pub fn loss(&self, name: &str) -> Option<Loss> {
+        self.losses.get(name).copied()
+    }
+
+    /// Loads a network from a JSON file created using
+    /// [`NeuralNet::save_json()`](../struct.NeuralNet.html#method.save_json), with whichever
+    /// registered activation it was saved with.
+    ///
+    /// The activation is matched by the name it saves networks with (see
+    /// [`Activation::name()`](../trait.Activation.html#method.name)), rather than the name it is
+    /// registered under.
+    ///
+    /// # Errors
+    ///
+    /// Besides the errors of
+    /// [`NeuralNet::from_json()`](../struct.NeuralNet.html#method.from_json), this function
+    /// returns an error if the network's activation isn't registered.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{registry::Registry, NeuralNet, Sigmoid};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 3, 1]);
+    /// let path = std::env::temp_dir().join("scholar_registry_example.json");
+    /// brain.save_json(&path)?;
+    ///
+    /// let loaded = Registry::default().load_json(&path)?;
+    /// assert_eq!(loaded.activation(), "sigmoid");
+    /// assert_eq!(loaded.guess(&[1.0, 0.0]), brain.guess(&[1.0, 0.0]));
+    /// # std::fs::remove_file(path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load_json(&self, path: impl AsRef<Path>) -> Result<AnyNetwork, LoadErr> {
+        let path = path.as_ref();
+        let name = json::read_activation(path)?;
+        let activation = self
+            .activations
+            .values()
+            .find(|activation| (activation.saved_name)() == name)
+            .ok_or(LoadErr::UnknownActivation(name))?;
+        (activation.load_json)(path)
+    }
+
+    /// Deserializes a model, such as a [`Sequential`](../layers/struct.Sequential.html) model or
+    /// a [`Graph`](../layers/struct.Graph.html), from any `serde` format, looking up each of its
+    /// custom layers by name among the registered layers (see
+    /// [`register_layer()`](#method.register_layer)).
+    ///
+    /// # Errors
+    ///
+    /// Besides the errors of the format, this function returns an error if any of the model's
+    /// custom layers isn't registered.
+    pub fn deserialize<'de, M, D>(&self, deserializer: D) -> Result<M, D::Error>
+    where
+        M: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        layers::with_loaders(&self.layers, || M::deserialize(deserializer))
+    }
+
+    /// Runs the experiment with a network using the activation registered under the given name.
+    pub(crate) fn run(
+        &self,
+        activation: &str,
+        config: &ExperimentConfig,
+    ) -> Result<Report, ConfigErr> {
+        let activation = self
+            .activations
+            .get(activation)
+            .ok_or_else(|| ConfigErr::UnknownActivation(activation.to_string()))?;
+        (activation.run)(config, self)
+    }
+}
+
+/// Creates a registry containing the built-in components (the `"sigmoid"` activation, and the
+/// `"mae"` and `"rmse"` losses).
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+            .register_activation::<Sigmoid>("sigmoid")
+            .register_loss("mae", Predictions::mae)
+            .register_loss("rmse", Predictions::rmse)
+    }
+}
+
+/// Returns the keys of the given map, in alphabetical order.
+fn sorted_names<V>(map: &HashMap<String, V>) -> Vec<&str> {
+    let mut names: Vec<_> = map.keys().map(String::as_str).collect();
+    names.sort_unstable();
+    names
+}
+
+/// A network loaded by a [`Registry`](struct.Registry.html), whose activation is only known by
+/// name.
+pub struct AnyNetwork {
+    activation: &'static str,
+    layer_sizes: Vec<usize>,
+    guess: Guess,
+}
+
+impl AnyNetwork {
+    /// Loads a network with the activation `A` from a JSON file.
+    fn from_json<A: Activation + Serialize + DeserializeOwned + 'static>(
+        path: &Path,
+    ) -> Result<Self, LoadErr> {
+        let network: NeuralNet<A> = NeuralNet::from_json(path)?;
+        Ok(Self {
+            activation: A::name(),
+            layer_sizes: network.layer_sizes(),
+            guess: Box::new(move |inputs| network.guess(inputs)),
+        })
+    }
+
+    /// Returns the name of the network's activation, as saved in the file.
+    pub fn activation(&self) -> &str {
+        self.activation
+    }
+
+    /// Returns the number of nodes in each layer of the network, including the input layer.
+    pub fn layer_sizes(&self) -> &[usize] {
+        &self.layer_sizes
+    }
+
+    /// Calculates the network's outputs for the given inputs, like
+    /// [`NeuralNet::guess()`](../struct.NeuralNet.html#method.guess).
+    pub fn guess(&self, inputs: &[f64]) -> Vec<f64> {
+        (self.guess)(inputs)
+    }
+}
+
+impl fmt::Debug for AnyNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyNetwork")
+            .field("activation", &self.activation)
+            .field("layer_sizes", &self.layer_sizes)
+            .finish()
+    }
+}
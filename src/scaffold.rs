@@ -0,0 +1,181 @@
+//! Generation of runnable starter projects, for getting started with the library quickly.
+
+use std::{fmt::Write, fs, path::Path};
+
+/// The type of task that a starter project is set up to solve.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Template {
+    /// Classifying iris flowers into one of three species (one-hot encoded targets).
+    Classification,
+    /// Predicting a continuous value from a single input.
+    Regression,
+}
+
+/// Creates a new Cargo project at the given path, containing a runnable `src/main.rs`, a sample
+/// `data.csv`, and a `config.toml` describing the experiment (see the
+/// [`config`](../config/index.html) module) for the given `Template`.
+///
+/// The project can be run straight away with `cargo run --release` from its directory.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::scaffold::{self, Template};
+///
+/// # fn main() -> Result<(), scholar::scaffold::ScaffoldErr> {
+/// let path = std::env::temp_dir().join("scholar_scaffold_example");
+/// # let _ = std::fs::remove_dir_all(&path);
+/// scaffold::new_project(&path, Template::Classification)?;
+///
+/// assert!(path.join("src/main.rs").exists());
+/// assert!(path.join("data.csv").exists());
+/// # std::fs::remove_dir_all(&path).unwrap();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This function returns an error if the path already exists and isn't an empty directory, or if
+/// writing any of the files fails.
+pub fn new_project(path: impl AsRef<Path>, template: Template) -> Result<(), ScaffoldErr> {
+    let path = path.as_ref();
+    if path.exists() && fs::read_dir(path)?.next().is_some() {
+        return Err(ScaffoldErr::NotEmpty(path.display().to_string()));
+    }
+
+    let name = path
+        .file_name()
+        .map(|n| {
+            n.to_string_lossy()
+                .replace(|c: char| !c.is_alphanumeric(), "_")
+        })
+        .unwrap_or_else(|| "scholar_project".to_string());
+
+    fs::create_dir_all(path.join("src"))?;
+    fs::write(path.join("Cargo.toml"), manifest(&name))?;
+    fs::write(path.join("src/main.rs"), main_rs(template))?;
+    fs::write(path.join("data.csv"), sample_csv(template))?;
+    fs::write(path.join("config.toml"), config_toml(template))?;
+
+    Ok(())
+}
+
+/// Returns the contents of the project's `Cargo.toml`.
+fn manifest(name: &str) -> String {
+    format!(
+        r#"[package]
+name = "{}"
+version = "0.1.0"
+edition = "2018"
+
+[dependencies]
+scholar = "{}"
+"#,
+        name,
+        env!("CARGO_PKG_VERSION")
+    )
+}
+
+/// Returns the contents of the project's `src/main.rs`.
+fn main_rs(template: Template) -> String {
+    let evaluation = match template {
+        Template::Classification => {
+            r#"    // Prints the accuracy, per-class scores and confusion matrix
+    println!("{}", report);"#
+        }
+        Template::Regression => {
+            r#"    println!("R²:   {:.4}", report.r2());
+    println!("RMSE: {:.4}", report.rmse());
+    println!("MAE:  {:.4}", report.mae());"#
+        }
+    };
+
+    format!(
+        r#"fn main() -> Result<(), Box<dyn std::error::Error>> {{
+    // Loads the data, trains a network, and evaluates it as described in 'config.toml'
+    let report = scholar::config::run_from_config("config.toml")?;
+
+{}
+
+    Ok(())
+}}
+"#,
+        evaluation
+    )
+}
+
+/// Returns the contents of the project's `data.csv`.
+fn sample_csv(template: Template) -> String {
+    match template {
+        Template::Classification => {
+            let mut csv = String::from(
+                "sepal_length,sepal_width,petal_length,petal_width,setosa,versicolor,virginica\n",
+            );
+            csv.push_str(include_str!("../examples/iris.csv").trim());
+            csv.push('\n');
+            csv
+        }
+        Template::Regression => {
+            // A smooth curve with a little deterministic 'noise', scaled to lie between 0 and 1
+            let mut csv = String::from("x,y\n");
+            for i in 0..200 {
+                let x = i as f64 / 199.0;
+                let noise = 0.02 * (x * 97.0).sin();
+                let y = 0.5 + 0.4 * (x * std::f64::consts::PI * 2.0).sin() + noise;
+                writeln!(csv, "{:.4},{:.4}", x, y).expect("writing to a string cannot fail");
+            }
+            csv
+        }
+    }
+}
+
+/// Returns the contents of the project's `config.toml`.
+fn config_toml(template: Template) -> String {
+    let (inputs, stratify, node_counts, iterations, learning_rate) = match template {
+        Template::Classification => (4, true, "[4, 10, 10, 3]", 5_000, 0.01),
+        Template::Regression => (1, false, "[1, 16, 16, 1]", 5_000, 0.05),
+    };
+
+    format!(
+        r#"[data]
+# The CSV file to train on, and how to read it
+path = "data.csv"
+headers = true
+# The number of (leading) columns that are inputs; the rest are targets
+inputs = {}
+# The proportion of rows used for training, with the rest used for testing
+train_portion = 0.75
+# Whether both segments keep the same proportion of each class
+stratify = {}
+
+[preprocessing]
+# Scales each input to lie between 0 and 1 ("min_max"), or leaves them as they are ("none")
+scale = "min_max"
+
+[network]
+# The number of nodes in each layer, from the input layer to the output layer
+node_counts = {}
+# The number of times to iterate over the training data
+iterations = {}
+# How quickly the network adapts to the training data
+learning_rate = {}
+
+[output]
+model = "model.network"
+scaler = "model.scaler"
+"#,
+        inputs, stratify, node_counts, iterations, learning_rate
+    )
+}
+
+/// An enumeration over the possible errors when generating a starter project.
+#[derive(thiserror::Error, Debug)]
+pub enum ScaffoldErr {
+    /// When the project's path already contains files.
+    #[error("destination '{0}' already exists and is not empty")]
+    NotEmpty(String),
+    /// When writing the project's files fails.
+    #[error("failed to write project files")]
+    Write(#[from] std::io::Error),
+}
@@ -0,0 +1,411 @@
+//! A minimal HTTP server for running a trained network, so that small models can be deployed
+//! without writing any web plumbing.
+//!
+//! This requires the `serve` feature (and is also available as the `scholar serve` command with
+//! the `cli` feature). The server answers `POST /predict` requests, whose JSON body holds a batch
+//! of rows of inputs:
+//!
+//! ```json
+//! {"inputs": [[5.1, 3.5, 1.4, 0.2], [6.2, 2.9, 4.3, 1.3]]}
+//! ```
+//!
+//! with the network's outputs for each row, and the class that they represent (see
+//! [`NeuralNet::predict_class()`](../struct.NeuralNet.html#method.predict_class)):
+//!
+//! ```json
+//! {"outputs": [[0.98, 0.04, 0.01], [0.02, 0.91, 0.07]], "classes": [0, 1]}
+//! ```
+//!
+//! Invalid requests are answered with a `4xx` status and a body like `{"error": "..."}`.
+//!
+//! Connections are answered by a fixed pool of worker threads (see
+//! [`Server::workers()`](struct.Server.html#method.workers)) and closed after a single request,
+//! with the network shared between the workers so that concurrent batches run in parallel. When
+//! every worker is busy, further connections wait to be accepted rather than each taking up a
+//! thread.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scholar::{serve::Server, NeuralNet, Sigmoid};
+//! use std::io::{Read, Write};
+//! use std::net::{TcpListener, TcpStream};
+//!
+//! # fn main() -> std::io::Result<()> {
+//! let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+//!
+//! // Listens on any free port, rather than a fixed one such as `Server::serve("0.0.0.0:8080")`
+//! let listener = TcpListener::bind("127.0.0.1:0")?;
+//! let address = listener.local_addr()?;
+//! std::thread::spawn(move || Server::new(brain).serve_listener(listener));
+//!
+//! let body = r#"{"inputs": [[0.0, 1.0], [1.0, 1.0]]}"#;
+//! let mut stream = TcpStream::connect(address)?;
+//! write!(
+//!     stream,
+//!     "POST /predict HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+//!     body.len(),
+//!     body
+//! )?;
+//!
+//! let mut response = String::new();
+//! stream.read_to_string(&mut response)?;
+//! assert!(response.starts_with("HTTP/1.1 200 OK"));
+//! assert!(response.contains(r#""classes":["#));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::network::{Activation, Float, NeuralNet};
+use crate::preprocessing::MinMaxScaler;
+use crate::utils::decode_class;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+/// The maximum size of a request's line and headers, in bytes.
+const MAX_HEAD_SIZE: u64 = 16 * 1024;
+
+/// The maximum size of a request's body, in bytes.
+const MAX_BODY_SIZE: usize = 16 * 1024 * 1024;
+
+/// How long the server waits for a client to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The number of threads answering requests by default.
+const DEFAULT_WORKERS: usize = 16;
+
+/// An HTTP server that runs a network on the inputs it is sent.
+///
+/// See the [module-level documentation](index.html) for the requests that it answers.
+pub struct Server<A: Activation, T: Float = f64> {
+    network: NeuralNet<A, T>,
+    scaler: Option<MinMaxScaler>,
+    max_batch_size: usize,
+    workers: usize,
+}
+
+impl<A, T> Server<A, T>
+where
+    A: Activation + Serialize + DeserializeOwned + Send + Sync + 'static,
+    T: Float + Send + Sync,
+{
+    /// Creates a new `Server` that runs the given network, accepting batches of up to 1,024 rows
+    /// and answering up to 16 connections at once.
+    pub fn new(network: NeuralNet<A, T>) -> Self {
+        Self {
+            network,
+            scaler: None,
+            max_batch_size: 1024,
+            workers: DEFAULT_WORKERS,
+        }
+    }
+
+    /// Scales the inputs of every row with the given scaler (fitted on the network's training
+    /// data) before running the network on them.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the scaler was fitted on a different number of inputs to the number
+    /// of nodes in the network's input layer.
+    pub fn scaler(mut self, scaler: MinMaxScaler) -> Self {
+        let input_count = self.network.layer_sizes()[0];
+        if scaler.min().len() != input_count {
+            panic!(
+                "scaler has an incorrect number of inputs (expected {}, found {})",
+                input_count,
+                scaler.min().len()
+            );
+        }
+
+        self.scaler = Some(scaler);
+        self
+    }
+
+    /// Sets the maximum number of rows in a single request, so that clients can't hold up the
+    /// network for too long.
+    pub fn max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size;
+        self
+    }
+
+    /// Sets the number of threads answering requests, which is the most connections that are
+    /// answered at once.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `workers` is 0.
+    pub fn workers(mut self, workers: usize) -> Self {
+        if workers == 0 {
+            panic!("server must have at least one worker");
+        }
+
+        self.workers = workers;
+        self
+    }
+
+    /// Listens for requests on the given address (such as `"127.0.0.1:8080"`), answering them
+    /// forever.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if it fails to listen on the address.
+    pub fn serve(self, address: impl ToSocketAddrs) -> io::Result<()> {
+        self.serve_listener(TcpListener::bind(address)?)
+    }
+
+    /// Answers the requests of the given listener forever.
+    ///
+    /// Failing to accept a connection (or to answer one) doesn't stop the server, so this method
+    /// never returns in practice.
+    pub fn serve_listener(self, listener: TcpListener) -> io::Result<()> {
+        let server = Arc::new(Shared {
+            input_count: self.network.layer_sizes()[0],
+            network: self.network,
+            scaler: self.scaler,
+            max_batch_size: self.max_batch_size,
+        });
+
+        // Accepted connections are queued for the workers, and once the queue is full the rest
+        // wait in the listener's backlog until a worker is free
+        let (sender, receiver) = mpsc::sync_channel::<TcpStream>(self.workers);
+        let receiver = Arc::new(Mutex::new(receiver));
+        for _ in 0..self.workers {
+            let (server, receiver) = (Arc::clone(&server), Arc::clone(&receiver));
+            thread::spawn(move || {
+                // The queue is only locked whilst waiting for a connection, not whilst answering
+                let next = || {
+                    receiver
+                        .lock()
+                        .unwrap_or_else(|err| err.into_inner())
+                        .recv()
+                };
+                while let Ok(stream) = next() {
+                    // The client has disconnected if answering it fails, so there's no one to
+                    // tell, and a panic only loses its own request rather than the worker
+                    let _ = panic::catch_unwind(AssertUnwindSafe(|| server.answer(stream)));
+                }
+            });
+        }
+
+        for stream in listener.incoming().flatten() {
+            sender
+                .send(stream)
+                .expect("workers keep running whilst the server does");
+        }
+
+        Ok(())
+    }
+}
+
+/// The state of a running server, shared between the threads answering its requests.
+struct Shared<A: Activation, T: Float> {
+    network: NeuralNet<A, T>,
+    input_count: usize,
+    scaler: Option<MinMaxScaler>,
+    max_batch_size: usize,
+}
+
+/// The body of a request to `/predict`.
+#[derive(Deserialize)]
+struct PredictRequest {
+    inputs: Vec<Vec<f64>>,
+}
+
+/// The body of a successful response from `/predict`.
+#[derive(Serialize)]
+struct PredictResponse {
+    outputs: Vec<Vec<f64>>,
+    classes: Vec<usize>,
+}
+
+impl<A: Activation + Serialize + DeserializeOwned, T: Float> Shared<A, T> {
+    /// Reads a request from the stream and writes the response to it.
+    fn answer(&self, stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let mut reader = BufReader::new(&stream);
+        let response = match Request::read(&mut reader) {
+            Ok(request) => self.respond(&request),
+            Err(response) => response,
+        };
+
+        response.write(&stream)
+    }
+
+    /// Routes a request to its endpoint.
+    fn respond(&self, request: &Request) -> Response {
+        // Ignores any query string
+        let path = request.path.split('?').next().unwrap_or_default();
+        match (request.method.as_str(), path) {
+            ("POST", "/predict") => self.predict(&request.body).unwrap_or_else(|err| err),
+            (_, "/predict") => Response::error(405, "only POST requests are allowed"),
+            _ => Response::error(404, "not found"),
+        }
+    }
+
+    /// Runs the network on each row of a `/predict` request.
+    fn predict(&self, body: &[u8]) -> Result<Response, Response> {
+        let request: PredictRequest = serde_json::from_slice(body)
+            .map_err(|err| Response::error(400, &format!("invalid request body: {}", err)))?;
+        if request.inputs.len() > self.max_batch_size {
+            return Err(Response::error(
+                413,
+                &format!(
+                    "too many rows (expected at most {}, found {})",
+                    self.max_batch_size,
+                    request.inputs.len()
+                ),
+            ));
+        }
+        if let Some((i, row)) = request
+            .inputs
+            .iter()
+            .enumerate()
+            .find(|(_, row)| row.len() != self.input_count)
+        {
+            return Err(Response::error(
+                400,
+                &format!(
+                    "row {} has an incorrect number of inputs (expected {}, found {})",
+                    i,
+                    self.input_count,
+                    row.len()
+                ),
+            ));
+        }
+
+        let network = &self.network;
+        let outputs: Vec<Vec<f64>> = request
+            .inputs
+            .iter()
+            .map(|inputs| match &self.scaler {
+                Some(scaler) => network.guess(&scaler.transform_inputs(inputs)),
+                None => network.guess(inputs),
+            })
+            .collect();
+        let classes = outputs
+            .iter()
+            .map(|outputs| decode_class(outputs, network.threshold()))
+            .collect();
+
+        let body = serde_json::to_value(PredictResponse { outputs, classes })
+            .expect("outputs can be serialized");
+        Ok(Response { status: 200, body })
+    }
+}
+
+/// An HTTP request, of which only the parts used by the server are kept.
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+impl Request {
+    /// Reads a request, or returns the response to a malformed one.
+    fn read(reader: &mut impl BufRead) -> Result<Self, Response> {
+        let malformed = |_| Response::error(400, "malformed request");
+
+        let mut head = reader.by_ref().take(MAX_HEAD_SIZE);
+        let mut line = String::new();
+        head.read_line(&mut line).map_err(malformed)?;
+        let mut parts = line.split_whitespace();
+        let (method, path) = match (parts.next(), parts.next()) {
+            (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+            _ => return Err(Response::error(400, "malformed request")),
+        };
+
+        let mut content_length = None;
+        loop {
+            line.clear();
+            if head.read_line(&mut line).map_err(malformed)? == 0 && head.limit() > 0 {
+                // The client closed the connection part of the way through the headers
+                return Err(Response::error(400, "malformed request"));
+            }
+            if !line.ends_with('\n') {
+                return Err(Response::error(431, "request headers are too large"));
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+
+            if let Some((name, value)) = line.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    let length = value
+                        .trim()
+                        .parse()
+                        .map_err(|_| Response::error(400, "malformed request"))?;
+                    content_length = Some(length);
+                }
+            }
+        }
+
+        let body = match content_length {
+            Some(length) if length > MAX_BODY_SIZE => {
+                return Err(Response::error(413, "request body is too large"))
+            }
+            Some(length) => {
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).map_err(malformed)?;
+                body
+            }
+            None if method == "POST" => {
+                return Err(Response::error(411, "requests must have a Content-Length"))
+            }
+            None => Vec::new(),
+        };
+
+        Ok(Self { method, path, body })
+    }
+}
+
+/// An HTTP response with a JSON body.
+struct Response {
+    status: u16,
+    body: serde_json::Value,
+}
+
+impl Response {
+    /// Creates a response with the given status, whose body describes an error.
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message }),
+        }
+    }
+
+    /// Writes the response, after which the connection is closed.
+    fn write(&self, mut stream: impl Write) -> io::Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            411 => "Length Required",
+            413 => "Payload Too Large",
+            431 => "Request Header Fields Too Large",
+            _ => "",
+        };
+        let body = self.body.to_string();
+
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+             Connection: close\r\n\r\n{}",
+            self.status,
+            reason,
+            body.len(),
+            body
+        )?;
+        stream.flush()
+    }
+}
@@ -0,0 +1,2013 @@
+#[cfg(feature = "async")]
+mod job;
+
+#[cfg(feature = "async")]
+pub use job::*;
+
+use crate::adversarial;
+#[cfg(feature = "fs")]
+use crate::checkpoint::{Checkpoints, TrainingState};
+#[cfg(any(feature = "matrixmultiply", feature = "gpu"))]
+use crate::dataset::Row;
+use crate::dataset::{DataSource, Dataset, Rows, SequenceDataset, SparseDataset};
+use crate::layers::{Graph, Sequential};
+use crate::metrics::Predictions;
+use crate::network::{Activation, Device, EmbeddingNet, Float, Gradients, NeuralNet, Scratch};
+#[cfg(feature = "fs")]
+use crate::network::{LoadErr, SaveErr};
+use crate::privacy;
+#[cfg(feature = "fs")]
+use crate::training_log::TrainingLog;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{de::DeserializeOwned, Serialize};
+#[cfg(feature = "fs")]
+use std::path::Path;
+use std::{
+    borrow::Cow,
+    convert::Infallible,
+    fmt, mem,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering::Relaxed},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// Trains a `NeuralNet` with a configurable set of training options.
+///
+/// [`NeuralNet::train()`](struct.NeuralNet.html#method.train) is a shorthand for training with
+/// the default options.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{Dataset, NeuralNet, Sigmoid, Trainer};
+///
+/// let dataset = Dataset::from(vec![
+///     (vec![0.0, 0.0], vec![0.0]),
+///     (vec![0.0, 1.0], vec![1.0]),
+///     (vec![1.0, 0.0], vec![1.0]),
+///     (vec![1.0, 1.0], vec![0.0]),
+/// ]);
+///
+/// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+///
+/// // Iterates over the dataset 100 times with a learning rate of 0.1, additionally training on
+/// // an adversarial example of every row
+/// Trainer::new(100, 0.1)
+///     .adversarial(0.05, 1.0)
+///     .train(&mut brain, dataset);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trainer {
+    iterations: u64,
+    learning_rate: f64,
+    adversarial: Option<AdversarialOptions>,
+    privacy: Option<PrivacyOptions>,
+    batch_size: Option<usize>,
+    /// The number of batches whose gradients are accumulated into each update.
+    accumulation_steps: usize,
+    #[cfg(feature = "parallel")]
+    threads: Option<usize>,
+    #[cfg(feature = "parallel")]
+    hogwild: bool,
+    device: Device,
+    profile: bool,
+    seed: Option<u64>,
+    cancellation: Option<CancellationToken>,
+    time_limit: Option<Duration>,
+    /// The named metrics calculated after every epoch (see [`metric()`](#method.metric)).
+    metrics: Vec<(String, Metric)>,
+    validation: Option<Dataset>,
+    /// The state of training to carry on from (see [`resume()`](#method.resume)).
+    #[cfg(feature = "fs")]
+    resumed: Option<TrainingState>,
+    /// Whether to show a progress bar in the terminal, which is hidden when training on a
+    /// background thread.
+    #[cfg(feature = "progress")]
+    progress: bool,
+}
+
+/// A metric of a network's guesses, calculated after every epoch.
+type Metric = fn(&Predictions) -> f64;
+
+/// The options for mixing adversarial examples into training.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct AdversarialOptions {
+    epsilon: f64,
+    ratio: f64,
+}
+
+/// The options for training with DP-SGD.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct PrivacyOptions {
+    clip_norm: f64,
+    noise_multiplier: f64,
+    batch_size: usize,
+}
+
+impl Trainer {
+    /// Creates a new `Trainer` that iterates over the training dataset the given number of
+    /// times, with the given learning rate (which dictates how quickly the network 'adapts to
+    /// the dataset').
+    pub fn new(iterations: u64, learning_rate: f64) -> Self {
+        Self {
+            iterations,
+            learning_rate,
+            adversarial: None,
+            privacy: None,
+            batch_size: None,
+            accumulation_steps: 1,
+            #[cfg(feature = "parallel")]
+            threads: None,
+            #[cfg(feature = "parallel")]
+            hogwild: false,
+            device: Device::Cpu,
+            profile: false,
+            seed: None,
+            cancellation: None,
+            time_limit: None,
+            metrics: Vec::new(),
+            validation: None,
+            #[cfg(feature = "fs")]
+            resumed: None,
+            #[cfg(feature = "progress")]
+            progress: true,
+        }
+    }
+
+    /// Mixes adversarial examples into training to make the network more robust to them.
+    ///
+    /// Each time a row is trained on, the network is (with a probability of `ratio`) additionally
+    /// trained on an adversarial version of the row's inputs, generated against the network's
+    /// current state with [`adversarial::fgsm()`](adversarial/fn.fgsm.html) using the given
+    /// `epsilon`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the given `ratio` isn't between 0 and 1.
+    pub fn adversarial(mut self, epsilon: f64, ratio: f64) -> Self {
+        if !(0.0..=1.0).contains(&ratio) {
+            panic!(
+                "adversarial ratio must be between 0 and 1 (found {})",
+                ratio
+            );
+        }
+
+        self.adversarial = Some(AdversarialOptions { epsilon, ratio });
+        self
+    }
+
+    /// Trains the network with DP-SGD, so that it can be trained on sensitive data with a
+    /// guaranteed level of differential privacy (see the [`privacy`](privacy/index.html) module).
+    ///
+    /// The training data is split into batches of `batch_size` rows each epoch. The gradient of
+    /// each row (including that of its adversarial example, if enabled) is clipped to an L2 norm
+    /// of at most `clip_norm`, and Gaussian noise with a standard deviation of `noise_multiplier`
+    /// times `clip_norm` is added to the sum of each batch's gradients before they are averaged and
+    /// applied. Larger batches need more iterations but are affected less by the noise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{Dataset, NeuralNet, Sigmoid, Trainer};
+    ///
+    /// let dataset = Dataset::from(vec![
+    ///     (vec![0.0, 0.0], vec![0.0]),
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 0.0], vec![1.0]),
+    ///     (vec![1.0, 1.0], vec![0.0]),
+    /// ]);
+    ///
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+    ///
+    /// let trainer = Trainer::new(100, 0.1).differential_privacy(1.0, 1.1, 2);
+    /// trainer.train(&mut brain, dataset);
+    ///
+    /// let epsilon = trainer.epsilon(1e-5).unwrap();
+    /// println!("Trained with (ε = {:.2}, δ = 1e-5)-differential privacy", epsilon);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `clip_norm` isn't positive, if `noise_multiplier` is negative, or if
+    /// `batch_size` is 0.
+    pub fn differential_privacy(
+        mut self,
+        clip_norm: f64,
+        noise_multiplier: f64,
+        batch_size: usize,
+    ) -> Self {
+        if clip_norm <= 0.0 {
+            panic!("clipping norm must be positive (found {})", clip_norm);
+        }
+        if noise_multiplier < 0.0 {
+            panic!(
+                "noise multiplier must not be negative (found {})",
+                noise_multiplier
+            );
+        }
+        if batch_size == 0 {
+            panic!("batch size must be at least 1");
+        }
+
+        self.privacy = Some(PrivacyOptions {
+            clip_norm,
+            noise_multiplier,
+            batch_size,
+        });
+        self
+    }
+
+    /// Trains the network with mini-batch gradient descent, updating it once per batch of
+    /// `batch_size` rows with the average of their gradients, rather than after every row.
+    ///
+    /// With the `parallel` feature, the gradients of each batch's rows are calculated on
+    /// multiple threads (see [`threads()`](#method.threads)), which speeds up training on large
+    /// datasets. With the `matrixmultiply` feature, the rows are fed through the network in
+    /// chunks, as the columns of a single matrix, which is much faster for networks with wide
+    /// layers (unless training with DP-SGD, which needs the gradients of each row separately).
+    /// Averaging the gradients makes each update smaller than when training row by row, so larger
+    /// batches usually need a larger learning rate.
+    ///
+    /// When training with [`differential_privacy()`](#method.differential_privacy), its batch
+    /// size is used instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{Dataset, NeuralNet, Sigmoid, Trainer};
+    ///
+    /// let dataset = Dataset::from(vec![
+    ///     (vec![0.0, 0.0], vec![0.0]),
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 0.0], vec![1.0]),
+    ///     (vec![1.0, 1.0], vec![0.0]),
+    /// ]);
+    ///
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+    /// Trainer::new(100, 0.5).batch_size(2).train(&mut brain, dataset);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `batch_size` is 0.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        if batch_size == 0 {
+            panic!("batch size must be at least 1");
+        }
+
+        self.batch_size = Some(batch_size);
+        self
+    }
+
+    /// Accumulates the gradients of `steps` batches before updating the network with their
+    /// average, rather than updating it after every batch, which trains as though the batches
+    /// were `steps` times larger without holding any more rows in memory at once (such as when
+    /// training on a [`DataSource`](trait.DataSource.html) that streams its rows).
+    ///
+    /// This only affects training in batches (with [`batch_size()`](#method.batch_size) or
+    /// [`differential_privacy()`](#method.differential_privacy), whose noise is then added once
+    /// per update). Any batches left over at the end of an epoch are still applied, whereas an
+    /// [`OnlineTrainer`](struct.OnlineTrainer.html) carries them over to its next batch.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{Dataset, NeuralNet, Sigmoid, Trainer};
+    ///
+    /// let dataset = Dataset::from(vec![
+    ///     (vec![0.0, 0.0], vec![0.0]),
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 0.0], vec![1.0]),
+    ///     (vec![1.0, 1.0], vec![0.0]),
+    /// ]);
+    ///
+    /// let mut a: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+    /// let mut b = a.clone();
+    ///
+    /// // Two batches of 2 rows make the same updates as one batch of 4 rows
+    /// Trainer::new(100, 0.5)
+    ///     .batch_size(2)
+    ///     .accumulate_gradients(2)
+    ///     .train(&mut a, dataset.clone());
+    /// Trainer::new(100, 0.5).batch_size(4).train(&mut b, dataset);
+    /// assert!(a.approx_eq(&b, 1e-9));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `steps` is 0. Training panics if `steps` is more than 1 but the
+    /// trainer doesn't train in batches.
+    pub fn accumulate_gradients(mut self, steps: usize) -> Self {
+        if steps == 0 {
+            panic!("number of accumulation steps must be at least 1");
+        }
+
+        self.accumulation_steps = steps;
+        self
+    }
+
+    /// Sets the number of threads that calculate the gradients of each batch's rows, which is
+    /// the number of logical CPU cores by default.
+    ///
+    /// This requires the `parallel` feature, and only affects training in batches (with
+    /// [`batch_size()`](#method.batch_size) or
+    /// [`differential_privacy()`](#method.differential_privacy)), as otherwise the network is
+    /// updated after every row.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{Dataset, NeuralNet, Sigmoid, Trainer};
+    ///
+    /// let dataset = Dataset::from(vec![
+    ///     (vec![0.0, 0.0], vec![0.0]),
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 0.0], vec![1.0]),
+    ///     (vec![1.0, 1.0], vec![0.0]),
+    /// ]);
+    ///
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+    /// Trainer::new(100, 0.5)
+    ///     .batch_size(4)
+    ///     .threads(2)
+    ///     .train(&mut brain, dataset);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `threads` is 0.
+    #[cfg(feature = "parallel")]
+    pub fn threads(mut self, threads: usize) -> Self {
+        if threads == 0 {
+            panic!("number of threads must be at least 1");
+        }
+
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Trains the network row by row on multiple threads at once (see
+    /// [`threads()`](#method.threads)), which update the network's weights and biases without
+    /// waiting for each other, as in [Hogwild!](https://arxiv.org/abs/1106.5730).
+    ///
+    /// This requires the `parallel` feature. Each thread guesses with its own copy of the
+    /// network, and before every row refreshes only the parameters that the row touches: every
+    /// bias and weight except the first layer's weights from inputs of 0, which neither affect
+    /// the row's guess nor are changed by it. It then adds its updates straight onto the shared
+    /// weights and biases, skipping those whose gradient is 0.
+    ///
+    /// Each update reads a weight and then writes it back, so when another thread writes the
+    /// same weight in between, that thread's update is lost (overwritten), and rows are trained
+    /// on with slightly stale weights. Training still converges when the updates rarely collide
+    /// (as with sparse inputs, where most updates leave most weights unchanged) or the learning
+    /// rate is small, but:
+    ///
+    /// - the result differs between runs, even with a [`seed()`](#method.seed);
+    /// - training may need more iterations to reach the same cost, especially for small or
+    ///   dense networks, where every row updates every weight;
+    /// - each thread holds a copy of the network in memory.
+    ///
+    /// Training in batches (with [`batch_size()`](#method.batch_size) or
+    /// [`differential_privacy()`](#method.differential_privacy)) takes precedence over this, as
+    /// it is parallel without these trade-offs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{Dataset, NeuralNet, Sigmoid, Trainer};
+    ///
+    /// let dataset = Dataset::from(vec![
+    ///     (vec![0.0, 0.0], vec![0.0]),
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 0.0], vec![1.0]),
+    ///     (vec![1.0, 1.0], vec![0.0]),
+    /// ]);
+    ///
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+    /// Trainer::new(100, 0.1)
+    ///     .threads(4)
+    ///     .hogwild()
+    ///     .train(&mut brain, dataset);
+    /// ```
+    #[cfg(feature = "parallel")]
+    pub fn hogwild(mut self) -> Self {
+        self.hogwild = true;
+        self
+    }
+
+    /// Sets the device that the network's matrices are multiplied on whilst training with
+    /// mini-batches (see [`batch_size()`](#method.batch_size)), which is the CPU by default.
+    ///
+    /// Training row by row (or with [`differential_privacy()`](#method.differential_privacy),
+    /// which needs the gradients of each row separately) always happens on the CPU, as a GPU is
+    /// only faster when given many rows to multiply at once. See
+    /// [`Device::gpu()`](enum.Device.html#method.gpu) for an example.
+    pub fn device(mut self, device: Device) -> Self {
+        self.device = device;
+        self
+    }
+
+    /// Seeds the random number generator used during training, so that training a network with
+    /// the same initial weights on the same data gives exactly the same result every time.
+    ///
+    /// The generator determines the order in which the rows are trained on each epoch, as well
+    /// as which adversarial examples are mixed in and the noise added by differential privacy.
+    /// Without a seed, it is seeded from the operating system.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{Dataset, NeuralNet, Sigmoid, Trainer};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let dataset = Dataset::from(vec![
+    ///     (vec![0.0, 0.0], vec![0.0]),
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 0.0], vec![1.0]),
+    ///     (vec![1.0, 1.0], vec![0.0]),
+    /// ]);
+    ///
+    /// // Creates two networks with the same initial weights
+    /// let mut a: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+    /// let path = std::env::temp_dir().join("scholar_seed_example.network");
+    /// a.save(&path)?;
+    /// let mut b: NeuralNet<Sigmoid> = NeuralNet::from_file(&path)?;
+    ///
+    /// let trainer = Trainer::new(100, 0.1).seed(42);
+    /// trainer.train(&mut a, dataset.clone());
+    /// trainer.train(&mut b, dataset);
+    /// assert_eq!(a.guess(&[0.0, 1.0]), b.guess(&[0.0, 1.0]));
+    /// # std::fs::remove_file(path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Times the forward and backward passes of training, recording the time spent in each in
+    /// every epoch's [`EpochRecord`](struct.EpochRecord.html) (alongside the epoch's duration and
+    /// throughput, which are always recorded).
+    ///
+    /// Timing every pass adds a little overhead, so isn't done by default. When training on
+    /// multiple threads, the time spent by each is added together, so may exceed the duration of
+    /// the epoch. Nothing is timed on `wasm32-unknown-unknown`, where there is no clock.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{Dataset, NeuralNet, Sigmoid, Trainer};
+    ///
+    /// let dataset = Dataset::from(vec![
+    ///     (vec![0.0, 0.0], vec![0.0]),
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 0.0], vec![1.0]),
+    ///     (vec![1.0, 1.0], vec![0.0]),
+    /// ]);
+    ///
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+    /// let history = Trainer::new(100, 0.5)
+    ///     .profile()
+    ///     .train(&mut brain, dataset);
+    ///
+    /// let passes = history.passes().unwrap();
+    /// println!("Forward: {:?}, backward: {:?}", passes.forward, passes.backward);
+    /// assert!(passes.forward + passes.backward <= history.duration());
+    /// ```
+    pub fn profile(mut self) -> Self {
+        self.profile = true;
+        self
+    }
+
+    /// Stops training gracefully once the given `CancellationToken` is cancelled, which is
+    /// checked before every epoch, leaving the network as it was after the last full epoch.
+    ///
+    /// Training then returns as usual, with a `History` of only the epochs that were run. See
+    /// [`CancellationToken`](struct.CancellationToken.html) for an example.
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Stops training once the given wall-clock duration has passed since it started, which is
+    /// checked before every epoch, even if the trainer's number of iterations hasn't been
+    /// reached (such as to give every trial of a hyperparameter search the same compute).
+    ///
+    /// The epoch running when the time runs out is finished, so training can take slightly
+    /// longer than the limit. Training returns as usual, with a `History` of only the epochs
+    /// that were run. There is no clock on `wasm32-unknown-unknown`, where the limit is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{Dataset, NeuralNet, Sigmoid, Trainer};
+    /// use std::time::Duration;
+    ///
+    /// let dataset = Dataset::from(vec![
+    ///     (vec![0.0, 0.0], vec![0.0]),
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 0.0], vec![1.0]),
+    ///     (vec![1.0, 1.0], vec![0.0]),
+    /// ]);
+    ///
+    /// // Trains for as many epochs as fit in 50 milliseconds
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+    /// let history = Trainer::new(u64::MAX, 0.1)
+    ///     .time_limit(Duration::from_millis(50))
+    ///     .train(&mut brain, dataset);
+    ///
+    /// println!("Trained for {} epochs", history.epochs().len());
+    /// ```
+    pub fn time_limit(mut self, limit: Duration) -> Self {
+        self.time_limit = Some(limit);
+        self
+    }
+
+    /// Calculates the given metric of the network's guesses (such as
+    /// [`Predictions::accuracy()`](metrics/struct.Predictions.html#method.accuracy)) after every
+    /// epoch, recording it in each [`EpochRecord`](struct.EpochRecord.html) under the given name.
+    ///
+    /// The metric is calculated on the training rows when they're held in memory (as when
+    /// training on a `Dataset`), and on the [`validation()`](#method.validation) dataset under
+    /// the name prefixed with `val_`. Each calculation feeds every row through the network once
+    /// more, which isn't included in the epoch's duration.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{metrics::{Average, Predictions}, Dataset, NeuralNet, Sigmoid, Trainer};
+    ///
+    /// let dataset = Dataset::from(vec![
+    ///     (vec![0.0, 0.0], vec![0.0]),
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 0.0], vec![1.0]),
+    ///     (vec![1.0, 1.0], vec![0.0]),
+    /// ]);
+    ///
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+    /// let history = Trainer::new(100, 0.1)
+    ///     .metric("accuracy", Predictions::accuracy)
+    ///     .metric("f1", |p| p.confusion_matrix().f1(Average::Macro))
+    ///     .validation(dataset.clone())
+    ///     .train(&mut brain, dataset);
+    ///
+    /// let last = &history.epochs()[99];
+    /// assert_eq!(last.metric("accuracy"), last.metric("val_accuracy"));
+    /// assert_eq!(history.metric("f1").len(), 100);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if a metric with the same name has already been added. Training with
+    /// metrics panics unless it trains a `NeuralNet` on dense inputs, as with
+    /// [`train()`](#method.train), rather than with `train_sparse()` or `train_sequential()` etc.
+    pub fn metric(mut self, name: &str, metric: fn(&Predictions) -> f64) -> Self {
+        if self.metrics.iter().any(|(existing, _)| existing == name) {
+            panic!("metric `{}` is already calculated", name);
+        }
+
+        self.metrics.push((name.to_string(), metric));
+        self
+    }
+
+    /// Calculates the trainer's metrics (see [`metric()`](#method.metric)) on the given
+    /// `Dataset` after every epoch, as well as on the training rows, to see how well the network
+    /// generalizes to data it isn't trained on. The loss of each epoch (see
+    /// [`EpochRecord::loss`](struct.EpochRecord.html#structfield.loss)) is calculated on it too.
+    pub fn validation(mut self, dataset: Dataset) -> Self {
+        self.validation = Some(dataset);
+        self
+    }
+
+    /// Carries on training from a checkpoint saved by
+    /// [`train_with_checkpoints()`](#method.train_with_checkpoints), such as after a long run is
+    /// interrupted, by loading the state of training saved alongside the network at the given
+    /// path.
+    ///
+    /// Training then starts from the epoch after the checkpoint (up to the trainer's number of
+    /// iterations, as before), with the random number generator where it was at the checkpoint,
+    /// so that the returned `History` only holds the remaining epochs. The network itself must
+    /// be loaded from the checkpoint with
+    /// [`NeuralNet::from_file()`](struct.NeuralNet.html#method.from_file), and should be
+    /// trained with the same options and data as before.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if reading or deserializing the state of training fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{checkpoint::Checkpoints, Dataset, NeuralNet, Sigmoid, Trainer};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let dataset = Dataset::from(vec![
+    ///     (vec![0.0, 0.0], vec![0.0]),
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 0.0], vec![1.0]),
+    ///     (vec![1.0, 1.0], vec![0.0]),
+    /// ]);
+    ///
+    /// let dir = std::env::temp_dir().join("scholar_resume_example");
+    /// # let _ = std::fs::remove_dir_all(&dir);
+    /// let trainer = Trainer::new(100, 0.1);
+    ///
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+    /// let mut checkpoints = Checkpoints::new(&dir, 30);
+    /// trainer.train_with_checkpoints(&mut brain, dataset.clone(), &mut checkpoints)?;
+    ///
+    /// // Carries on from epoch 60 as though training had stopped there
+    /// let path = &checkpoints.saved()[1].path;
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::from_file(path)?;
+    /// let history = trainer.clone().resume(path)?.train(&mut brain, dataset);
+    ///
+    /// assert_eq!(history.epochs().len(), 40);
+    /// assert_eq!(history.epochs()[0].epoch, 61);
+    /// # std::fs::remove_dir_all(&dir)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn resume(mut self, path: impl AsRef<Path>) -> Result<Self, LoadErr> {
+        self.resumed = Some(TrainingState::from_checkpoint(path)?);
+        Ok(self)
+    }
+
+    /// Returns the `epsilon` spent by training with these options at the given `delta`, or
+    /// `None` if they don't train with differential privacy.
+    ///
+    /// See [`privacy::epsilon()`](privacy/fn.epsilon.html) for details.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `delta` isn't between 0 and 1 (exclusive).
+    pub fn epsilon(&self, delta: f64) -> Option<f64> {
+        self.privacy
+            .map(|options| privacy::epsilon(options.noise_multiplier, self.iterations, delta))
+    }
+
+    /// Trains the network on the given `Dataset`, returning the `History` of training.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{Dataset, NeuralNet, Sigmoid, Trainer};
+    ///
+    /// let dataset = Dataset::from(vec![
+    ///     (vec![0.0, 0.0], vec![0.0]),
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 0.0], vec![1.0]),
+    ///     (vec![1.0, 1.0], vec![0.0]),
+    /// ]);
+    ///
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+    /// let history = Trainer::new(100, 0.1).train(&mut brain, dataset);
+    ///
+    /// assert_eq!(history.epochs().len(), 100);
+    /// if let Some(samples_per_second) = history.samples_per_second() {
+    ///     println!("Trained at {:.0} samples/s", samples_per_second);
+    /// }
+    /// ```
+    pub fn train<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &self,
+        network: &mut NeuralNet<A, T>,
+        mut training_dataset: Dataset,
+    ) -> History {
+        match self.run(network, &mut training_dataset, false, |_, _, _, _| {
+            Ok::<_, Infallible>(())
+        }) {
+            Ok(history) => history,
+            Err(RunErr::Source(never)) | Err(RunErr::AfterEpoch(never)) => match never {},
+        }
+    }
+
+    /// Trains the network on the given `Dataset`, saving checkpoints of it as described by the
+    /// given `Checkpoints` (see the [`checkpoint`](checkpoint/index.html) module).
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if saving or deleting a checkpoint fails, in which case
+    /// training stops early.
+    #[cfg(feature = "fs")]
+    pub fn train_with_checkpoints<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &self,
+        network: &mut NeuralNet<A, T>,
+        mut training_dataset: Dataset,
+        checkpoints: &mut Checkpoints,
+    ) -> Result<History, SaveErr> {
+        self.run(
+            network,
+            &mut training_dataset,
+            false,
+            |network, record, dataset, rng| checkpoints.update(network, record.epoch, dataset, rng),
+        )
+        .map_err(|err| match err {
+            RunErr::Source(never) => match never {},
+            RunErr::AfterEpoch(err) => err,
+        })
+    }
+
+    /// Trains the network on the given `Dataset`, writing the loss, metrics and learning rate of
+    /// every epoch to the given log, such as a `CsvLog` (see the
+    /// [`training_log`](training_log/index.html) module).
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if writing to the log fails, in which case training stops
+    /// early.
+    #[cfg(feature = "fs")]
+    pub fn train_with_log<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &self,
+        network: &mut NeuralNet<A, T>,
+        mut training_dataset: Dataset,
+        log: &mut impl TrainingLog,
+    ) -> Result<History, SaveErr> {
+        self.run(
+            network,
+            &mut training_dataset,
+            true,
+            |network, record, _, _| log.log_epoch(network, record),
+        )
+        .map_err(|err| match err {
+            RunErr::Source(never) => match never {},
+            RunErr::AfterEpoch(err) => err,
+        })
+    }
+
+    /// Trains the network on the rows of the given `DataSource`, such as a
+    /// [`CsvStream`](struct.CsvStream.html) for datasets larger than memory.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if reading a row from the source fails, in which case
+    /// training stops early.
+    pub fn train_from<A: Activation + Serialize + DeserializeOwned, T: Float, S: DataSource>(
+        &self,
+        network: &mut NeuralNet<A, T>,
+        source: &mut S,
+    ) -> Result<History, S::Error> {
+        self.run(network, source, false, |_, _, _, _| Ok::<_, Infallible>(()))
+            .map_err(|err| match err {
+                RunErr::Source(err) => err,
+                RunErr::AfterEpoch(never) => match never {},
+            })
+    }
+
+    /// Trains the network on the given `SparseDataset`, returning the `History` of training.
+    ///
+    /// Only the weights of each row's non-zero inputs are multiplied and updated, so training on
+    /// high-dimensional inputs that are mostly zeros (such as bag-of-words vectors) takes a
+    /// fraction of the time that training on their dense equivalents would, with the same
+    /// results.
+    ///
+    /// Sparse rows are trained on one by one, on a single thread.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{NeuralNet, Sigmoid, SparseDataset, SparseVector, Trainer};
+    ///
+    /// // Whether a "document" contains word 3 or word 40,000, but not both
+    /// let dataset = SparseDataset::from(vec![
+    ///     (SparseVector::new(50_000, vec![(3, 1.0), (40_000, 1.0)]), vec![0.0]),
+    ///     (SparseVector::new(50_000, vec![(3, 1.0), (17, 1.0)]), vec![1.0]),
+    ///     (SparseVector::new(50_000, vec![(40_000, 1.0)]), vec![1.0]),
+    ///     (SparseVector::new(50_000, vec![(17, 1.0)]), vec![0.0]),
+    /// ]);
+    ///
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[50_000, 8, 1]);
+    /// let history = Trainer::new(100, 0.5).train_sparse(&mut brain, dataset);
+    ///
+    /// assert_eq!(history.epochs().len(), 100);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the trainer trains with mini-batches, adversarial examples or
+    /// differential privacy, none of which are supported with sparse inputs.
+    pub fn train_sparse<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &self,
+        network: &mut NeuralNet<A, T>,
+        mut training_dataset: SparseDataset,
+    ) -> History {
+        let mut scratch = Scratch::new();
+        self.run_row_by_row("sparse inputs", |rng, profiler| {
+            training_dataset.shuffle_with(rng);
+            for (inputs, targets) in training_dataset.as_slice() {
+                profiler.forward(|| network.feedforward_sparse_into(inputs, &mut scratch));
+                profiler.backward(|| {
+                    network.backpropagate_sparse(inputs, targets, self.learning_rate, &mut scratch)
+                });
+            }
+
+            training_dataset.rows()
+        })
+    }
+
+    /// Trains an `EmbeddingNet`'s embeddings and network jointly on the given `Dataset`, whose
+    /// inputs start with the category IDs of each embedding (see
+    /// [`EmbeddingNet`](struct.EmbeddingNet.html)), returning the `History` of training.
+    ///
+    /// Rows are trained on one by one, on a single thread.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the trainer trains with mini-batches, adversarial examples or
+    /// differential privacy, none of which are supported with embeddings, or if a row has an
+    /// invalid category ID.
+    pub fn train_embedding<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &self,
+        model: &mut EmbeddingNet<A, T>,
+        mut training_dataset: Dataset,
+    ) -> History {
+        let mut scratch = Scratch::new();
+        self.run_row_by_row("embeddings", |rng, profiler| {
+            training_dataset.shuffle_with(rng);
+            for (inputs, targets) in &training_dataset {
+                profiler.forward(|| model.feedforward_into(inputs, &mut scratch));
+                profiler.backward(|| {
+                    model.backpropagate(inputs, targets, self.learning_rate, &mut scratch)
+                });
+            }
+
+            training_dataset.rows()
+        })
+    }
+
+    /// Trains a [`Sequential`](layers/struct.Sequential.html) model on the given `Dataset`,
+    /// returning the `History` of training.
+    ///
+    /// Rows are trained on one by one, on a single thread.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the trainer trains with mini-batches, adversarial examples or
+    /// differential privacy, none of which are supported with `Sequential` models.
+    pub fn train_sequential<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &self,
+        model: &mut Sequential<A, T>,
+        mut training_dataset: Dataset,
+    ) -> History {
+        self.run_row_by_row("sequential models", |rng, profiler| {
+            training_dataset.shuffle_with(rng);
+            for (inputs, targets) in &training_dataset {
+                let values = profiler.forward(|| model.feedforward(inputs));
+                profiler.backward(|| model.backpropagate(&values, targets, self.learning_rate));
+            }
+
+            training_dataset.rows()
+        })
+    }
+
+    /// Trains a [`Sequential`](layers/struct.Sequential.html) model on the sequences of the given
+    /// `SequenceDataset`, backpropagating through every step of each, and returns the `History`
+    /// of training.
+    ///
+    /// Rows are trained on one by one, on a single thread.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the model doesn't take sequences (see
+    /// [`Sequential::sequences()`](layers/struct.Sequential.html#method.sequences)), their steps
+    /// have a different number of values to the dataset's, or the trainer trains with
+    /// mini-batches, adversarial examples or differential privacy, none of which are supported
+    /// with sequences.
+    pub fn train_sequences<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &self,
+        model: &mut Sequential<A, T>,
+        mut training_dataset: SequenceDataset,
+    ) -> History {
+        if !model.takes_sequences() {
+            panic!("model doesn't take sequences (create it with `Sequential::sequences()`)");
+        }
+        let step_len = model.input_shape()[0];
+        if !training_dataset.is_empty() && training_dataset.step_len() != step_len {
+            panic!(
+                "incorrect number of values in each step (expected {}, found {})",
+                step_len,
+                training_dataset.step_len()
+            );
+        }
+
+        self.run_row_by_row("sequences", |rng, profiler| {
+            training_dataset.shuffle_with(rng);
+            for (inputs, targets) in training_dataset.as_slice() {
+                let values = profiler.forward(|| model.feedforward(inputs));
+                profiler.backward(|| model.backpropagate(&values, targets, self.learning_rate));
+            }
+
+            training_dataset.rows()
+        })
+    }
+
+    /// Trains a [`Graph`](layers/struct.Graph.html) model on the given `Dataset`, returning the
+    /// `History` of training.
+    ///
+    /// Each row's inputs are the values of each of the graph's inputs in turn, and its targets
+    /// are the values of each of the graph's outputs in turn. Rows are trained on one by one, on
+    /// a single thread.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the graph has no outputs, or the trainer trains with mini-batches,
+    /// adversarial examples or differential privacy, none of which are supported with `Graph`
+    /// models.
+    pub fn train_graph<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &self,
+        model: &mut Graph<A, T>,
+        mut training_dataset: Dataset,
+    ) -> History {
+        self.run_row_by_row("graph models", |rng, profiler| {
+            training_dataset.shuffle_with(rng);
+            for (inputs, targets) in &training_dataset {
+                let values = profiler.forward(|| model.feedforward(&model.split_inputs(inputs)));
+                profiler.backward(|| model.backpropagate(&values, targets, self.learning_rate));
+            }
+
+            training_dataset.rows()
+        })
+    }
+
+    /// Creates an [`OnlineTrainer`](struct.OnlineTrainer.html) that trains networks on batches of
+    /// rows as they arrive with these options, rather than iterating over a whole dataset.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the trainer accumulates gradients (see
+    /// [`accumulate_gradients()`](#method.accumulate_gradients)) without training in batches.
+    pub fn online<T: Float>(&self) -> OnlineTrainer<T> {
+        self.check_accumulation();
+        OnlineTrainer {
+            trainer: self.clone(),
+            rng: self.rng(),
+            threads: Threads::new(self),
+            accumulated: Accumulated::new(),
+            batches: 0,
+            history: History::default(),
+            history_limit: ONLINE_HISTORY_LIMIT,
+        }
+    }
+
+    /// Runs every epoch of training with `epoch`, which trains on each row of the data one by
+    /// one and returns the number of rows trained on.
+    ///
+    /// Only the simplest training is supported this way, so this panics (saying that it isn't
+    /// supported with `what`) if the trainer trains with anything else.
+    fn run_row_by_row(
+        &self,
+        what: &str,
+        mut epoch: impl FnMut(&mut StdRng, &Profiler) -> usize,
+    ) -> History {
+        if self.batch_size.is_some()
+            || self.accumulation_steps > 1
+            || self.adversarial.is_some()
+            || self.privacy.is_some()
+        {
+            panic!(
+                "mini-batches, adversarial examples and differential privacy aren't supported \
+                 with {}",
+                what
+            );
+        }
+        if !self.metrics.is_empty() {
+            panic!("metrics aren't supported with {}", what);
+        }
+
+        let started = start_timer();
+        let (first, mut rng) = self.start();
+        let mut history = History::default();
+        for i in first..=self.iterations {
+            if self.should_stop(started) {
+                break;
+            }
+
+            let start = start_timer();
+            let profiler = Profiler::new(self.profile && start.is_some());
+            let samples = epoch(&mut rng, &profiler);
+            history.epochs.push(EpochRecord {
+                epoch: i,
+                samples,
+                duration: start.map_or(Duration::ZERO, |start| start.elapsed()),
+                passes: profiler.durations(),
+                learning_rate: self.learning_rate,
+                loss: None,
+                metrics: Vec::new(),
+            });
+        }
+
+        history
+    }
+
+    /// Trains the network on the rows of the given `DataSource`, calling `after_epoch` with the
+    /// network, the record of the epoch, the source and the random number generator after every
+    /// epoch.
+    ///
+    /// The loss of every epoch is recorded if `with_loss` is set, or if it comes for free with the
+    /// trainer's metrics.
+    fn run<A, T, S, E>(
+        &self,
+        network: &mut NeuralNet<A, T>,
+        source: &mut S,
+        with_loss: bool,
+        mut after_epoch: impl FnMut(
+            &mut NeuralNet<A, T>,
+            &EpochRecord,
+            &S,
+            &mut StdRng,
+        ) -> Result<(), E>,
+    ) -> Result<History, RunErr<S::Error, E>>
+    where
+        A: Activation + Serialize + DeserializeOwned,
+        T: Float,
+        S: DataSource,
+    {
+        #[cfg(feature = "progress")]
+        let progress_bar = if self.progress {
+            indicatif::ProgressBar::new(self.iterations)
+        } else {
+            indicatif::ProgressBar::hidden()
+        };
+        #[cfg(feature = "progress")]
+        progress_bar.set_style(
+            indicatif::ProgressStyle::default_bar()
+                .template("Training [{bar:30}] {percent:>3}% ETA: {eta} {msg}")
+                .progress_chars("=> "),
+        );
+
+        // The progress bar is only updated every percentage progressed so as not to significantly
+        // impact the speed of training
+        #[cfg(feature = "progress")]
+        let percentile = (self.iterations / 100).max(1);
+
+        self.check_accumulation();
+        let started = start_timer();
+        let (first, mut rng) = self.start();
+        #[cfg(feature = "progress")]
+        progress_bar.set_position(first - 1);
+        let threads = Threads::new(self);
+        let mut history = History::default();
+        for i in first..=self.iterations {
+            if self.should_stop(started) {
+                break;
+            }
+
+            let start = start_timer();
+            let profiler = Profiler::new(self.profile && start.is_some());
+            let rows = source.epoch(&mut rng);
+            let mut accumulated = Accumulated::new();
+            let samples = self
+                .train_rows(
+                    network,
+                    rows,
+                    &mut accumulated,
+                    &threads,
+                    &profiler,
+                    &mut rng,
+                )
+                .map_err(RunErr::Source)?;
+            // Each epoch ends with an update, even if fewer batches than usual have been
+            // accumulated since the last
+            self.apply_accumulated(network, &mut accumulated, &mut rng);
+            let duration = start.map_or(Duration::ZERO, |start| start.elapsed());
+            let (loss, metrics) = self.evaluate(network, source.as_dataset(), with_loss);
+            let record = EpochRecord {
+                epoch: i,
+                samples,
+                duration,
+                passes: profiler.durations(),
+                learning_rate: self.learning_rate,
+                loss,
+                metrics,
+            };
+
+            after_epoch(network, &record, source, &mut rng).map_err(RunErr::AfterEpoch)?;
+            history.epochs.push(record);
+
+            #[cfg(feature = "progress")]
+            if i % percentile == 0 {
+                let recent = history.epochs.len().saturating_sub(percentile as usize);
+                let recent = History {
+                    epochs: history.epochs[recent..].to_vec(),
+                };
+                if let Some(samples_per_second) = recent.samples_per_second() {
+                    progress_bar.set_message(&format!("({:.0} samples/s)", samples_per_second));
+                }
+                progress_bar.inc(percentile);
+            }
+        }
+
+        #[cfg(feature = "progress")]
+        progress_bar.finish_and_clear();
+        Ok(history)
+    }
+
+    /// Trains the network on the given rows once, in batches if the trainer trains with them, and
+    /// returns the number of rows trained on.
+    ///
+    /// The gradients of the batches are added to `accumulated`, which is left holding those that
+    /// haven't been applied yet.
+    fn train_rows<A: Activation + Serialize + DeserializeOwned, T: Float, E>(
+        &self,
+        network: &mut NeuralNet<A, T>,
+        rows: Rows<'_, E>,
+        accumulated: &mut Accumulated<T>,
+        threads: &Threads,
+        profiler: &Profiler,
+        rng: &mut impl Rng,
+    ) -> Result<usize, E> {
+        match self.batches() {
+            Some(_) => self.batch_epoch(network, rows, accumulated, threads, profiler, rng),
+            #[cfg(feature = "parallel")]
+            None if self.hogwild => self.hogwild_epoch(network, rows, threads, profiler, rng),
+            None => self.epoch(network, rows, profiler, rng),
+        }
+    }
+
+    /// Calculates the trainer's metrics of the network on the given training rows (if they're
+    /// held in memory) and the validation dataset (if any), along with its loss on the latter
+    /// (or the former without one) if `with_loss` is set or there are metrics.
+    ///
+    /// Each dataset is only fed through the network once, for both the metrics and the loss.
+    fn evaluate<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &self,
+        network: &NeuralNet<A, T>,
+        training: Option<&Dataset>,
+        with_loss: bool,
+    ) -> (Option<f64>, Vec<(String, f64)>) {
+        if self.metrics.is_empty() && !with_loss {
+            return (None, Vec::new());
+        }
+
+        let datasets = training
+            .map(|dataset| ("", dataset))
+            .into_iter()
+            .chain(self.validation.as_ref().map(|dataset| ("val_", dataset)));
+        let (mut loss, mut metrics) = (None, Vec::new());
+        for (prefix, dataset) in datasets {
+            // The training rows only need feeding through the network for the loss if there's
+            // no validation data to calculate it on instead
+            if self.metrics.is_empty() && prefix.is_empty() && self.validation.is_some() {
+                continue;
+            }
+
+            let predictions = Predictions::new(network, dataset);
+            loss = Some(predictions.mae());
+            for (name, metric) in &self.metrics {
+                metrics.push((format!("{}{}", prefix, name), metric(&predictions)));
+            }
+        }
+
+        (loss, metrics)
+    }
+
+    /// Returns the size of the batches that the network is trained in, if it is (as it always is
+    /// with DP-SGD).
+    fn batches(&self) -> Option<usize> {
+        self.privacy
+            .map(|options| options.batch_size)
+            .or(self.batch_size)
+    }
+
+    /// Panics if the trainer accumulates gradients without training in batches.
+    fn check_accumulation(&self) {
+        if self.batches().is_none() && self.accumulation_steps > 1 {
+            panic!("gradients can only be accumulated when training with mini-batches");
+        }
+    }
+
+    /// Returns whether training (which started at the given time, if there is a clock) should
+    /// stop before its next epoch, as it has been cancelled (see
+    /// [`cancellation()`](#method.cancellation)) or has run out of time (see
+    /// [`time_limit()`](#method.time_limit)).
+    fn should_stop(&self, started: Option<Instant>) -> bool {
+        let cancelled = self
+            .cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled);
+        let out_of_time = self
+            .time_limit
+            .zip(started)
+            .is_some_and(|(limit, started)| started.elapsed() >= limit);
+
+        cancelled || out_of_time
+    }
+
+    /// Returns the number of the first epoch of training, and the random number generator to
+    /// train with, which both carry on from the state of training if resuming.
+    fn start(&self) -> (u64, StdRng) {
+        #[cfg(feature = "fs")]
+        if let Some(state) = self.resumed {
+            return (state.epoch + 1, StdRng::seed_from_u64(state.seed));
+        }
+
+        (1, self.rng())
+    }
+
+    /// Creates the random number generator for training, seeded with the trainer's seed if it has
+    /// one.
+    fn rng(&self) -> StdRng {
+        match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        }
+    }
+
+    /// Iterates over the training data once, updating the network after every row, and returns
+    /// the number of rows trained on.
+    fn epoch<A: Activation + Serialize + DeserializeOwned, T: Float, E>(
+        &self,
+        network: &mut NeuralNet<A, T>,
+        rows: Rows<'_, E>,
+        profiler: &Profiler,
+        rng: &mut impl Rng,
+    ) -> Result<usize, E> {
+        let mut scratch = Scratch::new();
+        let mut train_row = |network: &mut NeuralNet<A, T>, inputs: &[f64], targets: &[f64]| {
+            profiler.forward(|| network.feedforward_into(inputs, &mut scratch));
+            profiler.backward(|| network.backpropagate(targets, self.learning_rate, &mut scratch));
+        };
+
+        let mut samples = 0;
+        for row in rows {
+            let (inputs, targets) = &*row?;
+            train_row(network, inputs, targets);
+
+            if let Some(options) = self.adversarial {
+                if rng.gen::<f64>() < options.ratio {
+                    let inputs = adversarial::fgsm(network, inputs, targets, options.epsilon);
+                    train_row(network, &inputs, targets);
+                }
+            }
+            samples += 1;
+        }
+
+        Ok(samples)
+    }
+
+    /// Iterates over the training data once in batches, updating the network after every batch
+    /// (or every [`accumulate_gradients()`](#method.accumulate_gradients) batches) with the
+    /// average of their rows' gradients (clipped and noised if training with DP-SGD), and returns
+    /// the number of rows trained on.
+    ///
+    /// The gradients of any batches since the last update are left in `accumulated`, to be
+    /// carried over to the next call or applied by the caller.
+    fn batch_epoch<A: Activation + Serialize + DeserializeOwned, T: Float, E>(
+        &self,
+        network: &mut NeuralNet<A, T>,
+        mut rows: Rows<'_, E>,
+        accumulated: &mut Accumulated<T>,
+        threads: &Threads,
+        profiler: &Profiler,
+        rng: &mut impl Rng,
+    ) -> Result<usize, E> {
+        let batch_size = self.batches().expect("trainer trains in batches");
+        let mut samples = 0;
+        loop {
+            let batch = rows
+                .by_ref()
+                .take(batch_size)
+                .collect::<Result<Vec<_>, _>>()?;
+            if batch.is_empty() {
+                break;
+            }
+
+            // Chooses the rows with adversarial examples up front, so that the random number
+            // generator is used in the same order however the rows are spread between threads
+            let batch: Vec<_> = batch
+                .into_iter()
+                .map(|row| {
+                    let adversarial = self
+                        .adversarial
+                        .filter(|options| rng.gen::<f64>() < options.ratio);
+                    (row, adversarial)
+                })
+                .collect();
+
+            let network_ref = &*network;
+            let per_row = || {
+                threads.map(&batch, |(row, adversarial)| {
+                    let (inputs, targets) = &**row;
+                    self.row_gradients(network_ref, inputs, targets, *adversarial, profiler)
+                })
+            };
+            // DP-SGD clips the gradients of each row, so rows can only be fed through the network
+            // together without it. The chunks are the same size however many threads there are,
+            // so that seeded training still gives the same result on any number of them
+            #[cfg(any(feature = "matrixmultiply", feature = "gpu"))]
+            let gradients = if self.privacy.is_none() && self.device.multiplies_batches() {
+                // A GPU is given the whole batch at once
+                let chunk_size = match self.device {
+                    Device::Cpu => GEMM_CHUNK_SIZE,
+                    #[cfg(feature = "gpu")]
+                    Device::Gpu(_) => batch.len(),
+                };
+                let chunks: Vec<_> = batch.chunks(chunk_size).collect();
+                threads.map(&chunks, |chunk| {
+                    self.chunk_gradients(network_ref, chunk, profiler)
+                })
+            } else {
+                per_row()
+            };
+            #[cfg(not(any(feature = "matrixmultiply", feature = "gpu")))]
+            let gradients = per_row();
+
+            // Summed in order, so that seeded training gives the same result on any number of
+            // threads
+            let sum = gradients.into_iter().reduce(|mut sum, gradients| {
+                sum.add(&gradients);
+                sum
+            });
+            accumulated.add(sum, batch.len());
+            samples += batch.len();
+
+            if accumulated.batches == self.accumulation_steps {
+                self.apply_accumulated(network, accumulated, rng);
+            }
+        }
+
+        Ok(samples)
+    }
+
+    /// Updates the network with the average of the accumulated gradients (if any), after
+    /// noising their sum if training with DP-SGD, and starts accumulating afresh.
+    fn apply_accumulated<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &self,
+        network: &mut NeuralNet<A, T>,
+        accumulated: &mut Accumulated<T>,
+        rng: &mut impl Rng,
+    ) {
+        let Accumulated { sum, rows, .. } = mem::replace(accumulated, Accumulated::new());
+        if let Some(mut sum) = sum {
+            if let Some(options) = self.privacy {
+                let std_dev = options.noise_multiplier * options.clip_norm;
+                privacy::add_noise(&mut sum, std_dev, rng);
+            }
+            sum.scale(1.0 / rows as f64);
+            network.apply_gradients(&sum, self.learning_rate);
+        }
+    }
+
+    /// Iterates over the training data once on every thread at once, with each thread updating
+    /// the network after every row without any synchronization (see
+    /// [`hogwild()`](#method.hogwild)), and returns the number of rows trained on.
+    #[cfg(feature = "parallel")]
+    fn hogwild_epoch<A: Activation + Serialize + DeserializeOwned, T: Float, E>(
+        &self,
+        network: &mut NeuralNet<A, T>,
+        mut rows: Rows<'_, E>,
+        threads: &Threads,
+        profiler: &Profiler,
+        rng: &mut impl Rng,
+    ) -> Result<usize, E> {
+        use rayon::prelude::*;
+
+        // The bits of each parameter as an `f64`, which the threads can read and write whilst
+        // others are too (as relaxed atomics, so that racing updates are lost rather than being
+        // undefined behaviour)
+        let parameters: Vec<AtomicU64> = network
+            .parameters()
+            .map(|x| AtomicU64::new(x.as_f64().to_bits()))
+            .collect();
+        let frozen = network.frozen_parameters();
+        let learning_rate = self.learning_rate;
+
+        // The first layer's weights are stored column by column, so each input's weights are a
+        // run of as many weights as there are nodes in the first hidden layer
+        let layer_sizes = network.layer_sizes();
+        let (first_layer_nodes, first_layer_weights) =
+            (layer_sizes[1], layer_sizes[0] * layer_sizes[1]);
+
+        let mut samples = 0;
+        loop {
+            let chunk = rows
+                .by_ref()
+                .take(HOGWILD_CHUNK_SIZE)
+                .collect::<Result<Vec<_>, _>>()?;
+            if chunk.is_empty() {
+                break;
+            }
+
+            let chunk: Vec<_> = chunk
+                .into_iter()
+                .map(|row| {
+                    let adversarial = self
+                        .adversarial
+                        .filter(|options| rng.gen::<f64>() < options.ratio);
+                    (row, adversarial)
+                })
+                .collect();
+
+            let network_ref = &*network;
+            threads.install(|| {
+                chunk.par_iter().for_each_init(
+                    || network_ref.clone(),
+                    |local, (row, adversarial)| {
+                        let (inputs, targets) = &**row;
+
+                        // An adversarial example perturbs every input, so touches every weight
+                        let touches = |i: usize| {
+                            i >= first_layer_weights
+                                || adversarial.is_some()
+                                || inputs[i / first_layer_nodes] != 0.0
+                        };
+                        let shared = local.parameters_mut().zip(&parameters).enumerate();
+                        for (_, (x, shared)) in shared.filter(|(i, _)| touches(*i)) {
+                            *x = T::convert(f64::from_bits(shared.load(Relaxed)));
+                        }
+
+                        let gradients =
+                            self.row_gradients(local, inputs, targets, *adversarial, profiler);
+                        let updates = gradients.values().zip(&parameters).zip(&frozen);
+                        for ((gradient, shared), frozen) in updates {
+                            if !frozen && !gradient.is_zero() {
+                                let x = f64::from_bits(shared.load(Relaxed));
+                                let updated = x + learning_rate * gradient.as_f64();
+                                shared.store(updated.to_bits(), Relaxed);
+                            }
+                        }
+                    },
+                );
+            });
+            samples += chunk.len();
+        }
+
+        for (x, shared) in network.parameters_mut().zip(parameters) {
+            *x = T::convert(f64::from_bits(shared.into_inner()));
+        }
+        Ok(samples)
+    }
+
+    /// Calculates the gradients of a single row, plus those of its adversarial example if given
+    /// the options to generate one with, clipped if training with DP-SGD.
+    fn row_gradients<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &self,
+        network: &NeuralNet<A, T>,
+        inputs: &[f64],
+        targets: &[f64],
+        adversarial: Option<AdversarialOptions>,
+        profiler: &Profiler,
+    ) -> Gradients<T> {
+        let gradients_of = |inputs: &[f64]| {
+            let layers = profiler.forward(|| network.feedforward(inputs));
+            profiler.backward(|| network.backward(&layers, targets))
+        };
+
+        let mut gradients = gradients_of(inputs);
+        if let Some(options) = adversarial {
+            let inputs = adversarial::fgsm(network, inputs, targets, options.epsilon);
+            gradients.add(&gradients_of(&inputs));
+        }
+
+        if let Some(options) = self.privacy {
+            // Bounds the influence of any single row on the update
+            privacy::clip(&mut gradients, options.clip_norm);
+        }
+        gradients
+    }
+
+    /// Calculates the sum of the gradients of a chunk of rows, plus those of the adversarial
+    /// examples of the rows given options to generate them with, by feeding them through the
+    /// network together.
+    #[cfg(any(feature = "matrixmultiply", feature = "gpu"))]
+    fn chunk_gradients<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &self,
+        network: &NeuralNet<A, T>,
+        chunk: &[(Cow<'_, Row>, Option<AdversarialOptions>)],
+        profiler: &Profiler,
+    ) -> Gradients<T> {
+        let adversarial_rows: Vec<_> = chunk
+            .iter()
+            .filter_map(|(row, adversarial)| {
+                let (inputs, targets) = &**row;
+                adversarial.map(|options| {
+                    let inputs = adversarial::fgsm(network, inputs, targets, options.epsilon);
+                    (inputs, targets)
+                })
+            })
+            .collect();
+
+        let rows: Vec<(&[f64], &[f64])> = chunk
+            .iter()
+            .map(|(row, _)| (&row.0[..], &row.1[..]))
+            .chain(
+                adversarial_rows
+                    .iter()
+                    .map(|(inputs, targets)| (&inputs[..], &targets[..])),
+            )
+            .collect();
+        let layers = profiler.forward(|| network.batch_feedforward(&rows, &self.device));
+        profiler.backward(|| network.batch_backward(&layers, &rows, &self.device))
+    }
+}
+
+/// A flag that stops training early when cancelled, such as when the user presses Ctrl-C or a
+/// stop button (see [`Trainer::cancellation()`](struct.Trainer.html#method.cancellation)).
+///
+/// Clones of a token share the same flag, so a token can be given to a `Trainer` whilst a clone
+/// of it is cancelled from another thread.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{CancellationToken, Dataset, NeuralNet, Sigmoid, Trainer};
+/// use std::{thread, time::Duration};
+///
+/// let dataset = Dataset::from(vec![
+///     (vec![0.0, 0.0], vec![0.0]),
+///     (vec![0.0, 1.0], vec![1.0]),
+///     (vec![1.0, 0.0], vec![1.0]),
+///     (vec![1.0, 1.0], vec![0.0]),
+/// ]);
+///
+/// let token = CancellationToken::new();
+/// let stop = token.clone();
+/// thread::spawn(move || {
+///     thread::sleep(Duration::from_millis(10));
+///     stop.cancel();
+/// });
+///
+/// // Trains until cancelled, and then keeps the partially trained network
+/// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+/// let history = Trainer::new(u64::MAX, 0.1)
+///     .cancellation(token)
+///     .train(&mut brain, dataset);
+///
+/// println!("Stopped after {} epochs", history.epochs().len());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a token that hasn't been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the token (and every clone of it), so that training stops before its next
+    /// epoch.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Relaxed);
+    }
+
+    /// Returns whether the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Relaxed)
+    }
+}
+
+/// Tokens are equal if they are clones of each other, and so share the same flag.
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.cancelled, &other.cancelled)
+    }
+}
+
+/// Trains a `NeuralNet` incrementally, on each batch of rows that it is given, such as to keep a
+/// model up to date with a live stream of data without retraining it from scratch.
+///
+/// An `OnlineTrainer` is created from a [`Trainer`](struct.Trainer.html) with
+/// [`Trainer::online()`](struct.Trainer.html#method.online), and trains with its options (except
+/// for its number of iterations, as each batch is trained on once). The state of training is
+/// kept between batches: the random number generator (so that a seeded trainer never repeats
+/// the same adversarial examples or noise), the gradients accumulated towards the next update
+/// (see [`Trainer::accumulate_gradients()`](struct.Trainer.html#method.accumulate_gradients)),
+/// and the `History` of the most recent batches (see [`history_limit()`](#method.history_limit)).
+/// Training uses plain stochastic gradient descent, so there is no other optimizer state to
+/// keep.
+///
+/// As the accumulated gradients are carried over to the next batch, every batch should be of
+/// the same network.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{Dataset, NeuralNet, Sigmoid, Trainer};
+///
+/// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+/// let mut trainer = Trainer::new(1, 0.1).batch_size(2).online().history_limit(2);
+///
+/// // Each batch of rows from the stream updates the network as it arrives
+/// for _ in 0..3 {
+///     let batch = Dataset::from(vec![
+///         (vec![0.0, 1.0], vec![1.0]),
+///         (vec![1.0, 1.0], vec![0.0]),
+///     ]);
+///     trainer.train_on_batch(&mut brain, &batch);
+/// }
+///
+/// // Only the records of the last 2 batches are kept
+/// assert_eq!(trainer.history().epochs().len(), 2);
+/// assert_eq!(trainer.history().epochs()[1].epoch, 3);
+/// ```
+pub struct OnlineTrainer<T: Float = f64> {
+    trainer: Trainer,
+    rng: StdRng,
+    threads: Threads,
+    /// The gradients of the mini-batches since the network was last updated.
+    accumulated: Accumulated<T>,
+    /// The number of batches trained on so far.
+    batches: u64,
+    /// The records of the most recent batches trained on, as an epoch each.
+    history: History,
+    history_limit: usize,
+}
+
+impl<T: Float> OnlineTrainer<T> {
+    /// Sets the number of the most recent batches whose records are kept in the
+    /// [`history()`](#method.history), so that it doesn't grow forever on an endless stream
+    /// (`100` by default).
+    pub fn history_limit(mut self, limit: usize) -> Self {
+        self.history_limit = limit;
+        self.trim_history();
+        self
+    }
+
+    /// Trains the network on every row of the given batch once, in the order they are given, and
+    /// returns the record of training on it (numbered from 1 for the first batch).
+    ///
+    /// If the trainer trains with mini-batches (see
+    /// [`Trainer::batch_size()`](struct.Trainer.html#method.batch_size)), the rows are split
+    /// into mini-batches of that size, and otherwise the network is updated after every row.
+    /// Any metrics (see [`Trainer::metric()`](struct.Trainer.html#method.metric)) are calculated
+    /// on the batch afterwards.
+    ///
+    /// When accumulating gradients, the network is only updated once enough mini-batches have
+    /// been trained on, which may take several calls; the gradients of any mini-batches since the
+    /// last update can be applied early with [`flush()`](#method.flush).
+    pub fn train_on_batch<A: Activation + Serialize + DeserializeOwned>(
+        &mut self,
+        network: &mut NeuralNet<A, T>,
+        rows: &Dataset,
+    ) -> EpochRecord {
+        let start = start_timer();
+        let profiler = Profiler::new(self.trainer.profile && start.is_some());
+        let batch: Rows<'_, Infallible> =
+            Box::new(rows.into_iter().map(|row| Ok(Cow::Borrowed(row))));
+        let trained = self.trainer.train_rows(
+            network,
+            batch,
+            &mut self.accumulated,
+            &self.threads,
+            &profiler,
+            &mut self.rng,
+        );
+        let samples = match trained {
+            Ok(samples) => samples,
+            Err(never) => match never {},
+        };
+
+        let duration = start.map_or(Duration::ZERO, |start| start.elapsed());
+        let (loss, metrics) = self.trainer.evaluate(network, Some(rows), false);
+        self.batches += 1;
+        let record = EpochRecord {
+            epoch: self.batches,
+            samples,
+            duration,
+            passes: profiler.durations(),
+            learning_rate: self.trainer.learning_rate,
+            loss,
+            metrics,
+        };
+        self.history.epochs.push(record.clone());
+        self.trim_history();
+        record
+    }
+
+    /// Updates the network with the gradients accumulated since it was last updated, if any,
+    /// such as before saving it at the end of a stream.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scholar::{Dataset, NeuralNet, Sigmoid, Trainer};
+    ///
+    /// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+    /// let original = brain.clone();
+    /// let batch = Dataset::from(vec![
+    ///     (vec![0.0, 1.0], vec![1.0]),
+    ///     (vec![1.0, 1.0], vec![0.0]),
+    /// ]);
+    ///
+    /// // The gradients of two mini-batches are applied together, even across calls
+    /// let mut trainer = Trainer::new(1, 0.5).batch_size(2).accumulate_gradients(2).online();
+    /// trainer.train_on_batch(&mut brain, &batch);
+    /// assert!(brain.approx_eq(&original, 1e-9));
+    ///
+    /// // Unless they are flushed first
+    /// trainer.flush(&mut brain);
+    /// assert!(!brain.approx_eq(&original, 1e-9));
+    /// ```
+    pub fn flush<A: Activation + Serialize + DeserializeOwned>(
+        &mut self,
+        network: &mut NeuralNet<A, T>,
+    ) {
+        self.trainer
+            .apply_accumulated(network, &mut self.accumulated, &mut self.rng);
+    }
+
+    /// Returns the options that the network is trained with.
+    pub fn trainer(&self) -> &Trainer {
+        &self.trainer
+    }
+
+    /// Returns the `History` of the most recent batches trained on, with an epoch for each.
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// Removes the oldest records from the history, down to the limit.
+    fn trim_history(&mut self) {
+        let excess = self.history.epochs.len().saturating_sub(self.history_limit);
+        self.history.epochs.drain(..excess);
+    }
+}
+
+impl<T: Float> fmt::Debug for OnlineTrainer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OnlineTrainer")
+            .field("trainer", &self.trainer)
+            .field("history", &self.history)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An error that stops training early.
+enum RunErr<S, E> {
+    /// When reading a row from the data source fails.
+    Source(S),
+    /// When the work done after an epoch fails.
+    AfterEpoch(E),
+}
+
+/// Starts timing an epoch, unless there's no clock (as `Instant::now()` panics on
+/// `wasm32-unknown-unknown`).
+fn start_timer() -> Option<Instant> {
+    if cfg!(all(target_arch = "wasm32", target_os = "unknown")) {
+        None
+    } else {
+        Some(Instant::now())
+    }
+}
+
+/// Times the forward and backward passes of training when profiling (see
+/// [`Trainer::profile()`](struct.Trainer.html#method.profile)), from any number of threads at
+/// once.
+struct Profiler {
+    enabled: bool,
+    /// The total number of nanoseconds spent in each pass.
+    forward: AtomicU64,
+    backward: AtomicU64,
+}
+
+impl Profiler {
+    /// Creates a `Profiler`, which only times anything if enabled.
+    fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            forward: AtomicU64::new(0),
+            backward: AtomicU64::new(0),
+        }
+    }
+
+    /// Runs a forward pass, timing it if enabled.
+    fn forward<R>(&self, pass: impl FnOnce() -> R) -> R {
+        self.time(&self.forward, pass)
+    }
+
+    /// Runs a backward pass, timing it if enabled.
+    fn backward<R>(&self, pass: impl FnOnce() -> R) -> R {
+        self.time(&self.backward, pass)
+    }
+
+    fn time<R>(&self, total: &AtomicU64, pass: impl FnOnce() -> R) -> R {
+        if !self.enabled {
+            return pass();
+        }
+
+        let start = Instant::now();
+        let result = pass();
+        total.fetch_add(start.elapsed().as_nanos() as u64, Relaxed);
+        result
+    }
+
+    /// Returns the total time spent in each pass, or `None` if not enabled.
+    fn durations(&self) -> Option<PassDurations> {
+        self.enabled.then(|| PassDurations {
+            forward: Duration::from_nanos(self.forward.load(Relaxed)),
+            backward: Duration::from_nanos(self.backward.load(Relaxed)),
+        })
+    }
+}
+
+/// The number of the most recent batches whose records an `OnlineTrainer` keeps by default.
+const ONLINE_HISTORY_LIMIT: usize = 100;
+
+/// The sum of the gradients of the batches trained on since the network was last updated, with
+/// the number of batches and rows they came from.
+#[derive(Debug)]
+struct Accumulated<T: Float> {
+    sum: Option<Gradients<T>>,
+    batches: usize,
+    rows: usize,
+}
+
+impl<T: Float> Accumulated<T> {
+    /// Creates an empty `Accumulated`, without any batches.
+    fn new() -> Self {
+        Self {
+            sum: None,
+            batches: 0,
+            rows: 0,
+        }
+    }
+
+    /// Adds the sum of the gradients of a batch of the given number of rows.
+    fn add(&mut self, sum: Option<Gradients<T>>, rows: usize) {
+        if let Some(sum) = sum {
+            match self.sum.as_mut() {
+                Some(accumulated) => accumulated.add(&sum),
+                None => self.sum = Some(sum),
+            }
+        }
+        self.batches += 1;
+        self.rows += rows;
+    }
+}
+
+/// The number of rows of a batch that are fed through the network together with the
+/// `matrixmultiply` feature, with each chunk's gradients being calculated on a different thread.
+#[cfg(any(feature = "matrixmultiply", feature = "gpu"))]
+const GEMM_CHUNK_SIZE: usize = 64;
+
+/// The number of rows read from the data source at a time when training with Hogwild!, which the
+/// threads then share between them.
+#[cfg(feature = "parallel")]
+const HOGWILD_CHUNK_SIZE: usize = 4096;
+
+/// The threads that calculate the gradients of a batch's rows, or that train with Hogwild!.
+#[cfg(feature = "parallel")]
+struct Threads {
+    /// The pool of the number of threads given to the `Trainer`, or `None` to use rayon's global
+    /// pool (with a thread per logical CPU core).
+    pool: Option<rayon::ThreadPool>,
+}
+
+/// The threads that calculate the gradients of a batch's rows, which is only the current thread
+/// without the `parallel` feature.
+#[cfg(not(feature = "parallel"))]
+struct Threads;
+
+impl Threads {
+    #[cfg(feature = "parallel")]
+    fn new(trainer: &Trainer) -> Self {
+        let pool = trainer.threads.map(|threads| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .expect("failed to start training threads")
+        });
+        Threads { pool }
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn new(_trainer: &Trainer) -> Self {
+        Threads
+    }
+
+    /// Runs the function, during which rayon's parallel iterators use these threads.
+    #[cfg(feature = "parallel")]
+    fn install<R: Send>(&self, f: impl FnOnce() -> R + Send) -> R {
+        match &self.pool {
+            Some(pool) => pool.install(f),
+            None => f(),
+        }
+    }
+
+    /// Applies the function to every item, returning the results in order.
+    #[cfg(feature = "parallel")]
+    fn map<I: Sync, R: Send>(&self, items: &[I], f: impl Fn(&I) -> R + Send + Sync) -> Vec<R> {
+        use rayon::prelude::*;
+
+        self.install(|| items.par_iter().map(f).collect())
+    }
+
+    /// Applies the function to every item, returning the results in order.
+    #[cfg(not(feature = "parallel"))]
+    fn map<I, R>(&self, items: &[I], f: impl Fn(&I) -> R) -> Vec<R> {
+        items.iter().map(f).collect()
+    }
+}
+
+/// The record of a network's training, returned by
+/// [`Trainer::train()`](struct.Trainer.html#method.train).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct History {
+    epochs: Vec<EpochRecord>,
+}
+
+/// The record of a single epoch (iteration over the training data) of training.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EpochRecord {
+    /// The number of the epoch, starting at 1.
+    pub epoch: u64,
+    /// The number of rows trained on.
+    pub samples: usize,
+    /// The wall-clock duration of the epoch (which is zero on `wasm32-unknown-unknown`, where
+    /// there is no clock to time it with).
+    pub duration: Duration,
+    /// The time spent in the forward and backward passes, when profiling (see
+    /// [`Trainer::profile()`](struct.Trainer.html#method.profile)).
+    pub passes: Option<PassDurations>,
+    /// The learning rate that the network was trained with.
+    pub learning_rate: f64,
+    /// The network's mean absolute error after the epoch, on the validation data (see
+    /// [`Trainer::validation()`](struct.Trainer.html#method.validation)) or else the training
+    /// data, which is calculated alongside any metrics and whilst training with a log (see
+    /// [`Trainer::train_with_log()`](struct.Trainer.html#method.train_with_log)).
+    pub loss: Option<f64>,
+    /// The value of each metric calculated after the epoch (see
+    /// [`Trainer::metric()`](struct.Trainer.html#method.metric)), in order, by name.
+    pub metrics: Vec<(String, f64)>,
+}
+
+/// The time spent in the forward and backward passes of training, when profiling (see
+/// [`Trainer::profile()`](struct.Trainer.html#method.profile)).
+///
+/// The rest of an epoch is spent reading the training data, generating adversarial examples,
+/// and (when training with mini-batches) combining and applying the gradients.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PassDurations {
+    /// The time spent feeding rows forward through the network.
+    pub forward: Duration,
+    /// The time spent propagating the errors back through the network and calculating the
+    /// gradients (and, when training row by row, applying them).
+    pub backward: Duration,
+}
+
+impl History {
+    /// Returns the record of every epoch, in order.
+    pub fn epochs(&self) -> &[EpochRecord] {
+        &self.epochs
+    }
+
+    /// Returns the total wall-clock duration of training (excluding any checkpointing).
+    pub fn duration(&self) -> Duration {
+        self.epochs.iter().map(|e| e.duration).sum()
+    }
+
+    /// Returns the average number of rows trained on per second, or `None` if no time was
+    /// measured (as when no epochs were run, or on `wasm32-unknown-unknown`, where there is no
+    /// clock).
+    pub fn samples_per_second(&self) -> Option<f64> {
+        let samples: usize = self.epochs.iter().map(|e| e.samples).sum();
+        throughput(samples, self.duration())
+    }
+
+    /// Returns the value of the metric with the given name after each epoch, skipping any epochs
+    /// where it wasn't calculated (see [`Trainer::metric()`](struct.Trainer.html#method.metric)).
+    pub fn metric(&self, name: &str) -> Vec<f64> {
+        self.epochs.iter().filter_map(|e| e.metric(name)).collect()
+    }
+
+    /// Returns the total time spent in the forward and backward passes, or `None` if training
+    /// wasn't profiled (see [`Trainer::profile()`](struct.Trainer.html#method.profile)).
+    pub fn passes(&self) -> Option<PassDurations> {
+        self.epochs
+            .iter()
+            .try_fold(PassDurations::default(), |total, e| {
+                e.passes.map(|passes| PassDurations {
+                    forward: total.forward + passes.forward,
+                    backward: total.backward + passes.backward,
+                })
+            })
+    }
+}
+
+impl EpochRecord {
+    /// Returns the number of rows trained on per second during the epoch, or `None` if its
+    /// duration wasn't measured (as on `wasm32-unknown-unknown`, where there is no clock).
+    pub fn samples_per_second(&self) -> Option<f64> {
+        throughput(self.samples, self.duration)
+    }
+
+    /// Returns the value of the metric with the given name after the epoch, or `None` if it
+    /// wasn't calculated.
+    pub fn metric(&self, name: &str) -> Option<f64> {
+        self.metrics
+            .iter()
+            .find(|(metric, _)| metric == name)
+            .map(|&(_, value)| value)
+    }
+}
+
+/// Returns the number of samples trained on per second over the given duration, or `None` if it
+/// is zero.
+fn throughput(samples: usize, duration: Duration) -> Option<f64> {
+    (!duration.is_zero()).then(|| samples as f64 / duration.as_secs_f64())
+}
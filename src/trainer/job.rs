@@ -0,0 +1,216 @@
+use super::{CancellationToken, EpochRecord, History, RunErr, Trainer};
+use crate::dataset::Dataset;
+use crate::network::{Activation, Float, NeuralNet};
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::VecDeque,
+    convert::Infallible,
+    fmt,
+    future::poll_fn,
+    panic,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Poll, Waker},
+    thread::{self, JoinHandle},
+};
+
+/// A network being trained on a background thread, started with
+/// [`Trainer::spawn()`](struct.Trainer.html#method.spawn), whose progress can be awaited from
+/// async code without blocking it.
+///
+/// The record of every epoch is sent to the job as soon as the epoch ends, to be read with
+/// [`next_epoch()`](#method.next_epoch), and the trained network is returned by
+/// [`finish()`](#method.finish). Neither depends on an async runtime, so they can be awaited
+/// from any (such as Tokio). Dropping the job before training finishes cancels it, and no progress
+/// bar is shown whilst training in the background.
+///
+/// This requires the `async` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{Dataset, NeuralNet, Sigmoid, Trainer};
+///
+/// // E.g. in a web server's request handler
+/// async fn train(dataset: Dataset) -> NeuralNet<Sigmoid> {
+///     let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+///     let mut job = Trainer::new(1_000, 0.1).spawn(brain, dataset);
+///
+///     while let Some(record) = job.next_epoch().await {
+///         println!("Epoch {} took {:?}", record.epoch, record.duration);
+///     }
+///
+///     let (brain, history) = job.finish().await;
+///     brain
+/// }
+/// ```
+pub struct TrainingJob<A: Activation, T: Float = f64> {
+    shared: Arc<Mutex<Shared<A, T>>>,
+    cancellation: CancellationToken,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// The state shared between a `TrainingJob` and its thread.
+struct Shared<A: Activation, T: Float> {
+    /// The records of the epochs that haven't been read yet.
+    records: VecDeque<EpochRecord>,
+    /// The trained network and its history, once training has finished.
+    result: Option<(NeuralNet<A, T>, History)>,
+    /// Whether the thread has stopped, after finishing training or panicking.
+    stopped: bool,
+    /// The waker of the task awaiting the job, if any.
+    waker: Option<Waker>,
+}
+
+impl<A: Activation, T: Float> Shared<A, T> {
+    /// Wakes the task awaiting the job, if any.
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Marks the job as stopped when the thread ends, even if training panics, so that it is never
+/// awaited forever.
+struct StopGuard<A: Activation, T: Float>(Arc<Mutex<Shared<A, T>>>);
+
+impl<A: Activation, T: Float> Drop for StopGuard<A, T> {
+    fn drop(&mut self) {
+        let mut shared = lock(&self.0);
+        shared.stopped = true;
+        shared.wake();
+    }
+}
+
+impl Trainer {
+    /// Starts training the network on the given `Dataset` on a background thread, returning a
+    /// [`TrainingJob`](struct.TrainingJob.html) that reports each epoch and returns the trained
+    /// network once training finishes.
+    ///
+    /// This requires the `async` feature. The job can be stopped early with
+    /// [`TrainingJob::cancel()`](struct.TrainingJob.html#method.cancel) or by dropping it, which
+    /// cancels the trainer's [`cancellation()`](#method.cancellation) token if it has one.
+    pub fn spawn<A, T>(&self, network: NeuralNet<A, T>, dataset: Dataset) -> TrainingJob<A, T>
+    where
+        A: Activation + Serialize + DeserializeOwned + 'static,
+        T: Float,
+    {
+        let cancellation = self.cancellation.clone().unwrap_or_default();
+        let trainer = Trainer {
+            #[cfg(feature = "progress")]
+            progress: false,
+            ..self.clone().cancellation(cancellation.clone())
+        };
+        let shared = Arc::new(Mutex::new(Shared {
+            records: VecDeque::new(),
+            result: None,
+            stopped: false,
+            waker: None,
+        }));
+
+        let guard = StopGuard(Arc::clone(&shared));
+        let thread = thread::spawn(move || {
+            let (mut network, mut dataset) = (network, dataset);
+            let run = trainer.run(&mut network, &mut dataset, false, |_, record, _, _| {
+                let mut shared = lock(&guard.0);
+                shared.records.push_back(record.clone());
+                shared.wake();
+                Ok::<_, Infallible>(())
+            });
+            let history = match run {
+                Ok(history) => history,
+                Err(RunErr::Source(never)) | Err(RunErr::AfterEpoch(never)) => match never {},
+            };
+            lock(&guard.0).result = Some((network, history));
+        });
+
+        TrainingJob {
+            shared,
+            cancellation,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl<A: Activation, T: Float> TrainingJob<A, T> {
+    /// Waits for the record of the next epoch, returning `None` once training has finished and
+    /// every record has been read.
+    pub async fn next_epoch(&mut self) -> Option<EpochRecord> {
+        poll_fn(|cx| {
+            let mut shared = lock(&self.shared);
+            match shared.records.pop_front() {
+                Some(record) => Poll::Ready(Some(record)),
+                None if shared.stopped => Poll::Ready(None),
+                None => {
+                    shared.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+
+    /// Waits for training to finish, returning the trained network and the `History` of
+    /// training (including any epochs that weren't read with
+    /// [`next_epoch()`](#method.next_epoch)).
+    ///
+    /// # Panics
+    ///
+    /// This method panics if training panicked, with the same payload.
+    pub async fn finish(mut self) -> (NeuralNet<A, T>, History) {
+        poll_fn(|cx| {
+            let mut shared = lock(&self.shared);
+            if shared.stopped {
+                Poll::Ready(())
+            } else {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        })
+        .await;
+
+        let thread = self.thread.take().expect("job has a thread until finished");
+        if let Err(payload) = thread.join() {
+            panic::resume_unwind(payload);
+        }
+        lock(&self.shared)
+            .result
+            .take()
+            .expect("training has finished")
+    }
+
+    /// Stops training gracefully before its next epoch, after which
+    /// [`finish()`](#method.finish) returns the partially trained network.
+    pub fn cancel(&self) {
+        self.cancellation.cancel();
+    }
+
+    /// Returns whether training has stopped, either after finishing or panicking.
+    pub fn is_finished(&self) -> bool {
+        lock(&self.shared).stopped
+    }
+}
+
+/// Cancels training if the job is dropped before it finishes, so that the thread doesn't keep
+/// training a network that can no longer be retrieved.
+impl<A: Activation, T: Float> Drop for TrainingJob<A, T> {
+    fn drop(&mut self) {
+        if self.thread.is_some() && !self.is_finished() {
+            self.cancel();
+        }
+    }
+}
+
+impl<A: Activation, T: Float> fmt::Debug for TrainingJob<A, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrainingJob")
+            .field("finished", &self.is_finished())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Locks the shared state, which is never left inconsistent by a panic.
+fn lock<A: Activation, T: Float>(shared: &Mutex<Shared<A, T>>) -> MutexGuard<'_, Shared<A, T>> {
+    shared.lock().unwrap_or_else(|err| err.into_inner())
+}
@@ -0,0 +1,374 @@
+//! Logging of each epoch of training as it runs, to a CSV file or TensorBoard's event files, so
+//! that long runs can be monitored and plotted with other tools.
+
+use crate::network::{Activation, Float, NeuralNet, SaveErr};
+#[cfg(feature = "tensorboard")]
+use crate::protobuf::Message;
+use crate::trainer::EpochRecord;
+use crate::utils::csv_field;
+
+use serde::{de::DeserializeOwned, Serialize};
+#[cfg(feature = "tensorboard")]
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    iter,
+    path::{Path, PathBuf},
+};
+
+/// A log that training writes to after every epoch, with
+/// [`Trainer::train_with_log()`](../struct.Trainer.html#method.train_with_log).
+///
+/// A pair of logs is a log too, which writes to both, so that (for example) training can be
+/// logged to a CSV file and TensorBoard at once.
+pub trait TrainingLog {
+    /// Logs the given epoch of training (whose record includes its loss), after which the
+    /// network is in the given state.
+    ///
+    /// # Errors
+    ///
+    /// Training stops early with the error if writing the log fails.
+    fn log_epoch<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &mut self,
+        network: &NeuralNet<A, T>,
+        record: &EpochRecord,
+    ) -> Result<(), SaveErr>;
+}
+
+impl<L: TrainingLog, M: TrainingLog> TrainingLog for (L, M) {
+    fn log_epoch<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &mut self,
+        network: &NeuralNet<A, T>,
+        record: &EpochRecord,
+    ) -> Result<(), SaveErr> {
+        self.0.log_epoch(network, record)?;
+        self.1.log_epoch(network, record)
+    }
+}
+
+impl<L: TrainingLog> TrainingLog for &mut L {
+    fn log_epoch<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &mut self,
+        network: &NeuralNet<A, T>,
+        record: &EpochRecord,
+    ) -> Result<(), SaveErr> {
+        (**self).log_epoch(network, record)
+    }
+}
+
+/// Appends a row to a CSV file after every epoch whilst training with
+/// [`Trainer::train_with_log()`](../struct.Trainer.html#method.train_with_log).
+///
+/// Each row holds the number of the epoch, the loss of the network, the value of each of the
+/// trainer's metrics (see [`Trainer::metric()`](../struct.Trainer.html#method.metric)) and the
+/// learning rate, under a header row naming the columns. The loss is the network's mean absolute
+/// error on the trainer's validation data (see
+/// [`Trainer::validation()`](../struct.Trainer.html#method.validation)), or on the training data
+/// if it has none.
+///
+/// The file is opened and closed for every row, so the log is complete up to the last epoch even
+/// if the process dies. Logging to an existing file appends to it without repeating the header,
+/// such as when carrying on training with
+/// [`Trainer::resume()`](../struct.Trainer.html#method.resume).
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{metrics::Predictions, training_log::CsvLog};
+/// use scholar::{Dataset, NeuralNet, Sigmoid, Trainer};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let dataset = Dataset::from(vec![
+///     (vec![0.0, 0.0], vec![0.0]),
+///     (vec![0.0, 1.0], vec![1.0]),
+///     (vec![1.0, 0.0], vec![1.0]),
+///     (vec![1.0, 1.0], vec![0.0]),
+/// ]);
+///
+/// let path = std::env::temp_dir().join("scholar_training_log_example.csv");
+/// # let _ = std::fs::remove_file(&path);
+/// let mut log = CsvLog::new(&path);
+///
+/// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 4, 1]);
+/// let history = Trainer::new(100, 0.1)
+///     .metric("accuracy", Predictions::accuracy)
+///     .train_with_log(&mut brain, dataset, &mut log)?;
+///
+/// let csv = std::fs::read_to_string(&path)?;
+/// assert!(csv.starts_with("epoch,loss,accuracy,learning_rate\n1,"));
+/// assert_eq!(csv.lines().count(), 101);
+///
+/// // The loss is the same as recorded in the history
+/// let loss = history.epochs()[99].loss.unwrap();
+/// assert!(csv.lines().last().unwrap().starts_with(&format!("100,{},", loss)));
+/// # std::fs::remove_file(&path)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct CsvLog {
+    path: PathBuf,
+}
+
+impl CsvLog {
+    /// Creates a log that appends to the CSV file at the given path, which is created (along
+    /// with its directory) if it doesn't exist.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Returns the path of the CSV file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Appends the row of the epoch to the file, preceded by the header if the file is empty.
+impl TrainingLog for CsvLog {
+    fn log_epoch<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &mut self,
+        _network: &NeuralNet<A, T>,
+        record: &EpochRecord,
+    ) -> Result<(), SaveErr> {
+        let mut lines = String::new();
+        if fs::metadata(&self.path).map_or(true, |metadata| metadata.len() == 0) {
+            if let Some(dir) = self.path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let names = record.metrics.iter().map(|(name, _)| csv_field(name));
+            let header: Vec<_> = vec!["epoch".to_string(), "loss".to_string()]
+                .into_iter()
+                .chain(names)
+                .chain(iter::once("learning_rate".to_string()))
+                .collect();
+            lines += &header.join(",");
+            lines.push('\n');
+        }
+
+        let values = record.metrics.iter().map(|(_, value)| value.to_string());
+        // The loss is left empty if it wasn't calculated, such as when streaming the rows
+        let loss = record.loss.map_or(String::new(), |loss| loss.to_string());
+        let row: Vec<_> = vec![record.epoch.to_string(), loss]
+            .into_iter()
+            .chain(values)
+            .chain(iter::once(record.learning_rate.to_string()))
+            .collect();
+        lines += &row.join(",");
+        lines.push('\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(lines.as_bytes())?;
+
+        Ok(())
+    }
+}
+
+/// Writes the loss, metrics and learning rate of every epoch (and optionally histograms of the
+/// network's weights and biases) to an event file in a directory, in the format that
+/// [TensorBoard](https://www.tensorflow.org/tensorboard) reads, whilst training with
+/// [`Trainer::train_with_log()`](../struct.Trainer.html#method.train_with_log).
+///
+/// Each value is a scalar summary tagged with its name (`loss`, the name of each of the trainer's
+/// metrics, and `learning_rate`) at the epoch's step, and the histograms are tagged
+/// `weights/N` and `biases/N` for the parameters feeding into each layer `N` after the input
+/// layer. The loss is the network's mean absolute error on the trainer's validation data, or on
+/// the training data if it has none. Every event is written as soon as its epoch
+/// ends, so running `tensorboard --logdir` on the parent directory of several runs' directories
+/// compares them whilst they train.
+///
+/// This requires the `tensorboard` feature.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use scholar::{metrics::Predictions, training_log::TensorBoardLog};
+/// use scholar::{Dataset, NeuralNet, Sigmoid, Trainer};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let dataset = Dataset::from_csv("iris.csv", false, 4)?;
+/// let (training_data, testing_data) = dataset.split(0.75);
+///
+/// let mut log = TensorBoardLog::new("runs/iris").histograms();
+///
+/// let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[4, 10, 10, 3]);
+/// Trainer::new(1_000, 0.01)
+///     .metric("accuracy", Predictions::accuracy)
+///     .validation(testing_data)
+///     .train_with_log(&mut brain, training_data, &mut log)?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "tensorboard")]
+#[derive(Clone, Debug)]
+pub struct TensorBoardLog {
+    path: PathBuf,
+    histograms: bool,
+}
+
+/// The number of equal-width buckets in each histogram.
+#[cfg(feature = "tensorboard")]
+const HISTOGRAM_BUCKETS: usize = 30;
+
+#[cfg(feature = "tensorboard")]
+impl TensorBoardLog {
+    /// Creates a log that writes to a new event file in the given directory (which is created if
+    /// it doesn't exist), named after the current time as TensorBoard expects.
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        let created = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs());
+
+        Self {
+            path: dir
+                .as_ref()
+                .join(format!("events.out.tfevents.{}.scholar", created)),
+            histograms: false,
+        }
+    }
+
+    /// Writes histograms of the network's weights and biases after every epoch, to see how they
+    /// change during training.
+    pub fn histograms(mut self) -> Self {
+        self.histograms = true;
+        self
+    }
+
+    /// Returns the path of the event file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Appends an event with a summary of the epoch to the file, preceded by the event of the file's
+/// version if the file is empty.
+#[cfg(feature = "tensorboard")]
+impl TrainingLog for TensorBoardLog {
+    fn log_epoch<A: Activation + Serialize + DeserializeOwned, T: Float>(
+        &mut self,
+        network: &NeuralNet<A, T>,
+        record: &EpochRecord,
+    ) -> Result<(), SaveErr> {
+        let wall_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0.0, |time| time.as_secs_f64());
+        // Events are messages of TensorFlow's `Event`, whose `Summary` holds each value
+        let event = || Message::new().double(1, wall_time);
+
+        let mut records = Vec::new();
+        if fs::metadata(&self.path).map_or(true, |metadata| metadata.len() == 0) {
+            if let Some(dir) = self.path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            append_record(&mut records, event().string(3, "brain.Event:2"));
+        }
+
+        let scalar = |tag: &str, value: f64| Message::new().string(1, tag).float(2, value as f32);
+        let mut summary = Vec::new();
+        if let Some(loss) = record.loss {
+            summary.push(scalar("loss", loss));
+        }
+        summary.extend(
+            record
+                .metrics
+                .iter()
+                .map(|(name, value)| scalar(name, *value)),
+        );
+        summary.push(scalar("learning_rate", record.learning_rate));
+        if self.histograms {
+            let parameters = network.weights().iter().zip(network.biases());
+            for (i, (weights, biases)) in parameters.enumerate() {
+                for (name, matrix) in [("weights", weights), ("biases", biases)] {
+                    let values: Vec<f64> = matrix.iter().map(|x| x.as_f64()).collect();
+                    let tag = format!("{}/{}", name, i + 1);
+                    summary.push(
+                        Message::new()
+                            .string(1, &tag)
+                            .message(5, histogram(&values)),
+                    );
+                }
+            }
+        }
+        let summary = Message::new().messages(1, summary);
+        append_record(
+            &mut records,
+            event().int(2, record.epoch as i64).message(5, summary),
+        );
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(&records)?;
+
+        Ok(())
+    }
+}
+
+/// Encodes the given values as a TensorFlow `HistogramProto` message, with equal-width buckets
+/// between the smallest and largest value (or a single bucket if they're all equal).
+#[cfg(feature = "tensorboard")]
+fn histogram(values: &[f64]) -> Message {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let num_buckets = if max > min { HISTOGRAM_BUCKETS } else { 1 };
+    let width = (max - min) / num_buckets as f64;
+
+    let mut buckets = vec![0.0; num_buckets];
+    for &value in values {
+        let bucket = if width > 0.0 {
+            ((value - min) / width) as usize
+        } else {
+            0
+        };
+        buckets[bucket.min(num_buckets - 1)] += 1.0;
+    }
+    // The upper limit of each bucket, with the last being the largest value
+    let mut limits: Vec<f64> = (1..num_buckets).map(|i| min + width * i as f64).collect();
+    limits.push(max);
+
+    Message::new()
+        .double(1, min)
+        .double(2, max)
+        .double(3, values.len() as f64)
+        .double(4, values.iter().sum())
+        .double(5, values.iter().map(|x| x * x).sum())
+        .doubles(6, &limits)
+        .doubles(7, &buckets)
+}
+
+/// Appends the given message as a TFRecord: its length and the length's checksum, followed by the
+/// message and its checksum.
+#[cfg(feature = "tensorboard")]
+fn append_record(records: &mut Vec<u8>, message: Message) {
+    let data = message.into_bytes();
+    let len = (data.len() as u64).to_le_bytes();
+    records.extend_from_slice(&len);
+    records.extend_from_slice(&masked_crc32c(&len).to_le_bytes());
+    records.extend_from_slice(&data);
+    records.extend_from_slice(&masked_crc32c(&data).to_le_bytes());
+}
+
+/// Calculates the CRC-32C checksum of the given bytes, masked as TFRecords require.
+#[cfg(feature = "tensorboard")]
+fn masked_crc32c(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0x82f6_3b78
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    let crc = !crc;
+
+    crc.rotate_right(15).wrapping_add(0xa282_ead8)
+}
@@ -0,0 +1,344 @@
+//! Hyperparameter tuning of network configurations.
+
+use crate::dataset::Dataset;
+use crate::metrics::Predictions;
+use crate::network::{Activation, NetworkConfig, NeuralNet};
+use crate::validation::{cross_validate, CrossValidation};
+
+use rand::Rng;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// An exhaustive search over every combination of the given hyperparameter values, each of which
+/// is evaluated with k-fold cross-validation.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{metrics::Predictions, tuning::GridSearch, Dataset, Sigmoid};
+///
+/// let dataset = Dataset::from(vec![
+///     (vec![0.0, 0.0], vec![0.0]),
+///     (vec![0.0, 1.0], vec![1.0]),
+///     (vec![1.0, 0.0], vec![1.0]),
+///     (vec![1.0, 1.0], vec![0.0]),
+/// ]);
+///
+/// let search = GridSearch::new()
+///     .node_counts(&[&[2, 2, 1], &[2, 4, 1]])
+///     .learning_rates(&[0.1, 0.5])
+///     .iterations(&[50])
+///     .folds(2);
+/// assert_eq!(search.configs().len(), 4);
+///
+/// let result = search.run::<Sigmoid>(&dataset, Predictions::accuracy);
+/// println!("Best configuration: {:?}", result.best_config());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridSearch {
+    node_counts: Vec<Vec<usize>>,
+    learning_rates: Vec<f64>,
+    iterations: Vec<u64>,
+    folds: usize,
+}
+
+impl GridSearch {
+    /// Creates a new `GridSearch` with no hyperparameter values and 5 cross-validation folds.
+    ///
+    /// At least one value must be given for each hyperparameter before running the search.
+    pub fn new() -> Self {
+        Self {
+            node_counts: Vec::new(),
+            learning_rates: Vec::new(),
+            iterations: Vec::new(),
+            folds: 5,
+        }
+    }
+
+    /// Sets the network architectures (node counts of each layer) to try.
+    pub fn node_counts(mut self, node_counts: &[&[usize]]) -> Self {
+        self.node_counts = node_counts.iter().map(|c| c.to_vec()).collect();
+        self
+    }
+
+    /// Sets the learning rates to try.
+    pub fn learning_rates(mut self, learning_rates: &[f64]) -> Self {
+        self.learning_rates = learning_rates.to_vec();
+        self
+    }
+
+    /// Sets the numbers of training iterations to try.
+    pub fn iterations(mut self, iterations: &[u64]) -> Self {
+        self.iterations = iterations.to_vec();
+        self
+    }
+
+    /// Sets the number of folds each configuration is cross-validated with.
+    pub fn folds(mut self, folds: usize) -> Self {
+        self.folds = folds;
+        self
+    }
+
+    /// Returns every combination of the hyperparameter values.
+    pub fn configs(&self) -> Vec<NetworkConfig> {
+        let mut configs = Vec::new();
+        for node_counts in &self.node_counts {
+            for &learning_rate in &self.learning_rates {
+                for &iterations in &self.iterations {
+                    configs.push(NetworkConfig::new(node_counts, iterations, learning_rate));
+                }
+            }
+        }
+
+        configs
+    }
+
+    /// Cross-validates every configuration on the `Dataset` using the given `metric` (where a
+    /// higher score is better), then trains the best configuration on the whole dataset.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if no value has been given for any of the hyperparameters, or if the
+    /// number of folds is less than 2 or greater than the number of rows.
+    pub fn run<A: Activation + Serialize + DeserializeOwned>(
+        &self,
+        dataset: &Dataset,
+        metric: impl Fn(&Predictions) -> f64,
+    ) -> SearchResult<A> {
+        let configs = self.configs();
+        if configs.is_empty() {
+            panic!("every hyperparameter must be given at least one value");
+        }
+
+        SearchResult::new(configs, dataset, self.folds, metric)
+    }
+}
+
+impl Default for GridSearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A random search over hyperparameter values drawn from the given distributions, with a fixed
+/// budget of trials, each of which is evaluated with k-fold cross-validation. This is useful when
+/// the search space is too large to enumerate with a [`GridSearch`](struct.GridSearch.html).
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{metrics::Predictions, tuning::{Param, RandomSearch}, Dataset, Sigmoid};
+///
+/// let dataset = Dataset::from(vec![
+///     (vec![0.0, 0.0], vec![0.0]),
+///     (vec![0.0, 1.0], vec![1.0]),
+///     (vec![1.0, 0.0], vec![1.0]),
+///     (vec![1.0, 1.0], vec![0.0]),
+/// ]);
+///
+/// let search = RandomSearch::new(3)
+///     .node_counts(&[&[2, 2, 1], &[2, 4, 1], &[2, 8, 1]])
+///     .learning_rate(Param::LogUniform(0.01, 1.0))
+///     .iterations(Param::Choice(vec![20.0, 50.0]))
+///     .folds(2);
+///
+/// let result = search.run::<Sigmoid>(&dataset, Predictions::accuracy);
+/// assert_eq!(result.trials().len(), 3);
+/// for trial in result.trials() {
+///     println!("{:?}: {:.2}", trial.config, trial.scores.mean());
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct RandomSearch {
+    trials: usize,
+    node_counts: Vec<Vec<usize>>,
+    learning_rate: Option<Param>,
+    iterations: Option<Param>,
+    folds: usize,
+}
+
+impl RandomSearch {
+    /// Creates a new `RandomSearch` that evaluates the given number of trials, with 5
+    /// cross-validation folds.
+    ///
+    /// Every hyperparameter must be given before running the search.
+    pub fn new(trials: usize) -> Self {
+        Self {
+            trials,
+            node_counts: Vec::new(),
+            learning_rate: None,
+            iterations: None,
+            folds: 5,
+        }
+    }
+
+    /// Sets the network architectures (node counts of each layer) to choose between.
+    pub fn node_counts(mut self, node_counts: &[&[usize]]) -> Self {
+        self.node_counts = node_counts.iter().map(|c| c.to_vec()).collect();
+        self
+    }
+
+    /// Sets the distribution that learning rates are drawn from.
+    pub fn learning_rate(mut self, learning_rate: Param) -> Self {
+        self.learning_rate = Some(learning_rate);
+        self
+    }
+
+    /// Sets the distribution that numbers of training iterations are drawn from (rounded to the
+    /// nearest whole number).
+    pub fn iterations(mut self, iterations: Param) -> Self {
+        self.iterations = Some(iterations);
+        self
+    }
+
+    /// Sets the number of folds each configuration is cross-validated with.
+    pub fn folds(mut self, folds: usize) -> Self {
+        self.folds = folds;
+        self
+    }
+
+    /// Draws a configuration for each trial.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if any of the hyperparameters haven't been given.
+    pub fn sample_configs(&self) -> Vec<NetworkConfig> {
+        let (learning_rate, iterations) = match (&self.learning_rate, &self.iterations) {
+            (Some(l), Some(i)) if !self.node_counts.is_empty() => (l, i),
+            _ => panic!("every hyperparameter must be given before sampling"),
+        };
+
+        let mut rng = rand::thread_rng();
+        (0..self.trials)
+            .map(|_| {
+                let node_counts = &self.node_counts[rng.gen_range(0, self.node_counts.len())];
+                let iterations = iterations.sample(&mut rng).round().max(1.0) as u64;
+                NetworkConfig::new(node_counts, iterations, learning_rate.sample(&mut rng))
+            })
+            .collect()
+    }
+
+    /// Cross-validates a randomly drawn configuration for each trial on the `Dataset` using the
+    /// given `metric` (where a higher score is better), then trains the best configuration on
+    /// the whole dataset.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if any of the hyperparameters haven't been given, if the number of
+    /// trials is 0, or if the number of folds is less than 2 or greater than the number of rows.
+    pub fn run<A: Activation + Serialize + DeserializeOwned>(
+        &self,
+        dataset: &Dataset,
+        metric: impl Fn(&Predictions) -> f64,
+    ) -> SearchResult<A> {
+        if self.trials == 0 {
+            panic!("at least one trial is required");
+        }
+
+        SearchResult::new(self.sample_configs(), dataset, self.folds, metric)
+    }
+}
+
+/// A distribution that a hyperparameter's values are drawn from during a
+/// [`RandomSearch`](struct.RandomSearch.html).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Param {
+    /// One of the given values, each equally likely.
+    Choice(Vec<f64>),
+    /// Any value between the given bounds (inclusive), each equally likely.
+    Uniform(f64, f64),
+    /// Any value between the given (positive) bounds, with each order of magnitude equally
+    /// likely. This suits hyperparameters like learning rates, which span several orders of
+    /// magnitude.
+    LogUniform(f64, f64),
+}
+
+impl Param {
+    /// Draws a value from the distribution.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if a `Choice` has no values, or if a `LogUniform`'s bounds aren't
+    /// positive.
+    pub fn sample(&self, rng: &mut impl Rng) -> f64 {
+        match self {
+            Param::Choice(values) => match values.len() {
+                0 => panic!("choice must have at least one value"),
+                len => values[rng.gen_range(0, len)],
+            },
+            Param::Uniform(low, high) => low + (high - low) * rng.gen::<f64>(),
+            Param::LogUniform(low, high) => {
+                if *low <= 0.0 || *high <= 0.0 {
+                    panic!(
+                        "log-uniform bounds must be positive (found {} and {})",
+                        low, high
+                    );
+                }
+                (low.ln() + (high.ln() - low.ln()) * rng.gen::<f64>()).exp()
+            }
+        }
+    }
+}
+
+/// A configuration evaluated during a search, alongside its cross-validation scores.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trial {
+    /// The configuration evaluated.
+    pub config: NetworkConfig,
+    /// The configuration's cross-validation scores.
+    pub scores: CrossValidation,
+}
+
+/// The outcome of a hyperparameter search.
+pub struct SearchResult<A: Activation> {
+    trials: Vec<Trial>,
+    best_model: NeuralNet<A>,
+}
+
+impl<A: Activation + Serialize + DeserializeOwned> SearchResult<A> {
+    /// Cross-validates each configuration, ranks them by their mean score, and trains the best on
+    /// the whole dataset.
+    fn new(
+        configs: Vec<NetworkConfig>,
+        dataset: &Dataset,
+        folds: usize,
+        metric: impl Fn(&Predictions) -> f64,
+    ) -> Self {
+        let mut trials: Vec<Trial> = configs
+            .into_iter()
+            .map(|config| Trial {
+                scores: cross_validate::<A>(&config, dataset, folds, &metric),
+                config,
+            })
+            .collect();
+        trials.sort_by(|a, b| {
+            b.scores
+                .mean()
+                .partial_cmp(&a.scores.mean())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let best_model = trials[0].config.train(dataset.clone());
+
+        Self { trials, best_model }
+    }
+
+    /// Returns every trial, ranked from the best mean score to the worst.
+    pub fn trials(&self) -> &[Trial] {
+        &self.trials
+    }
+
+    /// Returns the configuration with the best mean score.
+    pub fn best_config(&self) -> &NetworkConfig {
+        &self.trials[0].config
+    }
+
+    /// Returns the best configuration's network, trained on the whole dataset.
+    pub fn best_model(&self) -> &NeuralNet<A> {
+        &self.best_model
+    }
+
+    /// Consumes the result, returning the best configuration's network.
+    pub fn into_best_model(self) -> NeuralNet<A> {
+        self.best_model
+    }
+}
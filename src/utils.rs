@@ -1,19 +1,65 @@
+use crate::network::Float;
 
 use nalgebra::DMatrix;
+#[cfg(feature = "std")]
 use rand::distributions::{Distribution, Uniform};
+#[cfg(feature = "std")]
+use rand::Rng;
 
 /// Generates a matrix with the specified dimensions and random values between -1 and 1.
-pub(crate) fn gen_random_matrix(rows: usize, cols: usize) -> DMatrix<f64> {
+#[cfg(feature = "std")]
+pub(crate) fn gen_random_matrix<T: Float>(rows: usize, cols: usize) -> DMatrix<T> {
     let elements = rows * cols;
     let range = Uniform::new_inclusive(-1.0, 1.0);
     DMatrix::from_iterator(
         rows,
         cols,
-        (0..elements).map(|_| range.sample(&mut rand::thread_rng())),
+        (0..elements).map(|_| T::convert(range.sample(&mut rand::thread_rng()))),
     )
 }
 
+/// Samples from the standard normal distribution using the Box-Muller transform.
+#[cfg(feature = "std")]
+pub(crate) fn standard_normal(rng: &mut impl Rng) -> f64 {
+    // Avoids taking the logarithm of 0
+    let u1 = 1.0 - rng.gen::<f64>();
+    let u2 = rng.gen::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
 /// Converts a slice to a one-column matrix.
-pub(crate) fn convert_slice_to_matrix(slice: &[f64]) -> DMatrix<f64> {
-    DMatrix::from_row_slice(slice.len(), 1, slice)
-}
\ No newline at end of file
+pub(crate) fn convert_slice_to_matrix<T: Float>(slice: &[f64]) -> DMatrix<T> {
+    DMatrix::from_iterator(slice.len(), 1, slice.iter().map(|&x| T::convert(x)))
+}
+/// Decodes the class index represented by a vector of output (or target) values.
+///
+/// A single value is treated as a binary class (positive if it is at least the given `threshold`),
+/// whereas multiple values are treated as a one-hot encoding (the index of the largest value
+/// being the class).
+pub(crate) fn decode_class(values: &[f64], threshold: f64) -> usize {
+    if values.len() == 1 {
+        (values[0] >= threshold) as usize
+    } else {
+        values
+            .iter()
+            .enumerate()
+            .fold((0, f64::NEG_INFINITY), |(best, max), (i, &v)| {
+                if v > max {
+                    (i, v)
+                } else {
+                    (best, max)
+                }
+            })
+            .0
+    }
+}
+
+/// Quotes a field of a CSV file if it contains a comma, quote or line break.
+#[cfg(feature = "std")]
+pub(crate) fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
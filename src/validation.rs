@@ -0,0 +1,306 @@
+//! Cross-validation of network configurations, and checking of networks' gradients.
+
+use crate::dataset::Dataset;
+use crate::layers::Sequential;
+use crate::metrics::Predictions;
+use crate::network::{Activation, Float, NetworkConfig, NeuralNet};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// The scores achieved on each fold of a cross-validation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CrossValidation {
+    /// The score achieved on each fold, in order.
+    pub scores: Vec<f64>,
+}
+
+impl CrossValidation {
+    /// Returns the mean score across all folds.
+    pub fn mean(&self) -> f64 {
+        self.scores.iter().sum::<f64>() / self.scores.len() as f64
+    }
+
+    /// Returns the (population) standard deviation of the scores across all folds.
+    pub fn std_dev(&self) -> f64 {
+        let mean = self.mean();
+        let variance = self
+            .scores
+            .iter()
+            .map(|score| (score - mean).powi(2))
+            .sum::<f64>()
+            / self.scores.len() as f64;
+
+        variance.sqrt()
+    }
+}
+
+/// Performs k-fold cross-validation: the `Dataset` is partitioned into `k` folds, and for each
+/// fold a fresh network is built from the given `NetworkConfig`, trained on the other folds, and
+/// scored on that fold using the given `metric`.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{metrics::Predictions, validation, Dataset, NetworkConfig, Sigmoid};
+///
+/// let dataset = Dataset::from(vec![
+///     (vec![0.0, 0.0], vec![0.0]),
+///     (vec![0.0, 1.0], vec![1.0]),
+///     (vec![1.0, 0.0], vec![1.0]),
+///     (vec![1.0, 1.0], vec![0.0]),
+/// ]);
+///
+/// let config = NetworkConfig::new(&[2, 4, 1], 100, 0.1);
+/// let results = validation::cross_validate::<Sigmoid>(&config, &dataset, 2, Predictions::accuracy);
+///
+/// assert_eq!(results.scores.len(), 2);
+/// println!("Accuracy: {:.2} ± {:.2}", results.mean(), results.std_dev());
+/// ```
+///
+/// # Panics
+///
+/// This function panics if `k` is less than 2 or greater than the number of rows, or if fewer
+/// than 2 layers are configured.
+pub fn cross_validate<A: Activation + Serialize + DeserializeOwned>(
+    config: &NetworkConfig,
+    dataset: &Dataset,
+    k: usize,
+    metric: impl Fn(&Predictions) -> f64,
+) -> CrossValidation {
+    let scores = dataset
+        .k_folds(k)
+        .into_iter()
+        .map(|(training_data, testing_data)| {
+            let network: NeuralNet<A> = config.train(training_data);
+            metric(&Predictions::new(&network, &testing_data))
+        })
+        .collect();
+
+    CrossValidation { scores }
+}
+
+/// The result of [`grad_check()`](fn.grad_check.html) (or
+/// [`grad_check_sequential()`](fn.grad_check_sequential.html)): how far the gradients calculated by
+/// backpropagation are from their finite-difference estimates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GradCheck {
+    /// The largest difference between a gradient and its estimate, relative to the larger of
+    /// their magnitudes.
+    pub max_relative_error: f64,
+    /// The largest absolute difference between a gradient and its estimate.
+    pub max_absolute_error: f64,
+    /// The number of weights and biases checked.
+    pub parameters: usize,
+}
+
+impl GradCheck {
+    /// Creates a check of no parameters.
+    fn new() -> Self {
+        Self {
+            max_relative_error: 0.0,
+            max_absolute_error: 0.0,
+            parameters: 0,
+        }
+    }
+
+    /// Checks the gradient of another parameter against its estimate.
+    fn compare(&mut self, gradient: f64, estimate: f64) {
+        let error = (gradient - estimate).abs();
+        let scale = gradient.abs().max(estimate.abs()).max(1e-8);
+        self.max_relative_error = self.max_relative_error.max(error / scale);
+        self.max_absolute_error = self.max_absolute_error.max(error);
+        self.parameters += 1;
+    }
+}
+
+/// Checks the gradients of the cost with respect to every weight and bias that backpropagation
+/// calculates for the given row, against estimates from the change in the cost when each
+/// parameter is nudged by `epsilon` either way.
+///
+/// Relative errors below about `1e-6` mean that the gradients are correct, whilst errors near
+/// `1` mean that they are wrong. As the estimates aren't exact, small gradients (below about
+/// `1e-8`) are compared relative to `1e-8` instead, and networks whose calculations aren't in
+/// `f64` precision need a larger `epsilon` and tolerance. Every parameter is checked, so this
+/// is only practical for small networks.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::{validation, NeuralNet, Sigmoid};
+///
+/// let brain: NeuralNet<Sigmoid> = NeuralNet::new(&[3, 4, 2]);
+/// let check = validation::grad_check(&brain, &[0.5, -1.0, 0.2], &[1.0, 0.0], 1e-5);
+///
+/// assert_eq!(check.parameters, 3 * 4 + 4 * 2 + 4 + 2);
+/// assert!(check.max_relative_error < 1e-5);
+/// ```
+///
+/// # Panics
+///
+/// This function panics if the number of given input (or target) values is not equal to the
+/// number of nodes in the network's input (or output) layer.
+pub fn grad_check<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    network: &NeuralNet<A, T>,
+    inputs: &[f64],
+    targets: &[f64],
+    epsilon: f64,
+) -> GradCheck {
+    let cost = |network: &NeuralNet<A, T>| -> f64 {
+        // The network's outputs before any target scaler reverses their scaling, as it trains on
+        // scaled targets
+        let layers = network.feedforward(inputs);
+        let outputs = &layers[layers.len() - 1];
+        if outputs.len() != targets.len() {
+            panic!(
+                "incorrect number of targets supplied (expected {}, found {})",
+                outputs.len(),
+                targets.len()
+            );
+        }
+        let scaler = network.target_scaler();
+        outputs
+            .iter()
+            .zip(targets)
+            .enumerate()
+            .map(|(i, (o, &t))| {
+                let t = scaler.map_or(t, |scaler| scaler.scale(i, t));
+                0.5 * (o.as_f64() - t) * (o.as_f64() - t)
+            })
+            .sum()
+    };
+    let cost_when = |biases: bool, matrix: usize, index: usize, delta: f64| -> f64 {
+        let mut network = network.clone();
+        let mut parameters = if biases {
+            network.biases().to_vec()
+        } else {
+            network.weights().to_vec()
+        };
+        let x = &mut parameters[matrix].as_mut_slice()[index];
+        *x = T::convert(x.as_f64() + delta);
+        if biases {
+            network.set_biases(parameters).unwrap();
+        } else {
+            network.set_weights(parameters).unwrap();
+        }
+        cost(&network)
+    };
+
+    // Checks the number of targets before backpropagating them
+    cost(network);
+
+    // Backpropagation gives the gradients that decrease the cost, in the order of every weight
+    // and then every bias
+    let gradients = network.backward(&network.feedforward(inputs), targets);
+    let mut analytic = gradients.values();
+
+    let mut check = GradCheck::new();
+    for (biases, matrices) in [(false, network.weights()), (true, network.biases())] {
+        for (matrix, values) in matrices.iter().enumerate() {
+            for index in 0..values.len() {
+                let estimate = (cost_when(biases, matrix, index, epsilon)
+                    - cost_when(biases, matrix, index, -epsilon))
+                    / (2.0 * epsilon);
+                let gradient = -analytic.next().unwrap().as_f64();
+                check.compare(gradient, estimate);
+            }
+        }
+    }
+
+    check
+}
+
+/// Checks the gradients that backpropagation through a
+/// [`Sequential`](../layers/struct.Sequential.html) model calculates for the given row, like
+/// [`grad_check()`](fn.grad_check.html), perturbing every parameter of each of its layers in
+/// turn.
+///
+/// Layers update their parameters as they backpropagate, so the gradients are taken to be how
+/// far the parameters move in a single step with a learning rate of `1`. The parameters of
+/// custom layers aren't checked, though the gradients that they pass back to the layers before
+/// them are, and layers that truncate backpropagation through time (see
+/// [`RecurrentOptions::truncate()`](../layers/struct.RecurrentOptions.html#method.truncate))
+/// only estimate their gradients.
+///
+/// # Examples
+///
+/// ```rust
+/// use scholar::layers::{ConvOptions, RecurrentOptions, Sequential};
+/// use scholar::{validation, Sigmoid};
+///
+/// let check = |model: Sequential<Sigmoid>, steps: usize| {
+///     let len = steps * model.input_shape().iter().product::<usize>();
+///     let inputs: Vec<f64> = (0..len).map(|i| (i as f64 * 0.7).sin()).collect();
+///     let targets = vec![0.5; model.output_shape().iter().product()];
+///     let check = validation::grad_check_sequential(&model, &inputs, &targets, 1e-5);
+///     assert!(check.max_relative_error < 1e-4, "{:?}", check);
+///     check.parameters
+/// };
+///
+/// // Fully-connected layers
+/// let dense = Sequential::new(&[3]).dense(4).dense(2);
+/// assert_eq!(check(dense, 1), 3 * 4 + 4 + 4 * 2 + 2);
+///
+/// // Convolutional and pooling layers
+/// check(Sequential::new(&[2, 8]).conv1d(3, 3).max_pool1d(2).dense(1), 1);
+/// check(Sequential::new(&[2, 8]).conv1d(3, 3).avg_pool1d(2).dense(1), 1);
+/// let padded = ConvOptions::new().stride(2).padding(1);
+/// check(Sequential::new(&[1, 6, 6]).conv2d_with(2, (3, 3), padded).max_pool2d((3, 3)), 1);
+/// check(Sequential::new(&[1, 6, 6]).conv2d(2, (3, 3)).avg_pool2d((2, 2)).dense(1), 1);
+///
+/// // Recurrent layers, through every step of sequences of a fixed or any number of steps
+/// let sequences = RecurrentOptions::new().return_sequences(true);
+/// check(Sequential::new(&[4, 2]).rnn_with(3, sequences).dense(1), 1);
+/// check(Sequential::new(&[4, 2]).lstm_with(3, sequences).lstm(2), 1);
+/// check(Sequential::sequences(2).gru_with(3, sequences).gru(2).dense(1), 5);
+///
+/// // Attention layers and positional encodings
+/// check(Sequential::sequences(4).positional_encoding().self_attention(2).rnn(2), 3);
+///
+/// // Residual blocks
+/// check(Sequential::new(&[4]).residual(|block| block.dense(3).dense(4)).dense(1), 1);
+/// check(Sequential::sequences(4).residual(|block| block.self_attention(2)).gru(2), 3);
+/// ```
+///
+/// # Panics
+///
+/// This function panics if the number of given input (or target) values doesn't match the
+/// model's input (or output) shape.
+pub fn grad_check_sequential<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    model: &Sequential<A, T>,
+    inputs: &[f64],
+    targets: &[f64],
+    epsilon: f64,
+) -> GradCheck {
+    let cost = |model: &Sequential<A, T>| -> f64 {
+        model
+            .guess(inputs)
+            .iter()
+            .zip(targets)
+            .map(|(o, t)| 0.5 * (o - t) * (o - t))
+            .sum()
+    };
+    let cost_when = |index: usize, delta: f64| -> f64 {
+        let mut model = model.clone();
+        let x = model.parameters_mut().nth(index).unwrap();
+        *x = T::convert(x.as_f64() + delta);
+        cost(&model)
+    };
+    let parameters = |mut model: Sequential<A, T>| -> Vec<f64> {
+        model.parameters_mut().map(|x| x.as_f64()).collect()
+    };
+
+    // Checks the number of targets as it backpropagates them, taking a step of the gradients
+    // that decrease the cost
+    let mut stepped = model.clone();
+    stepped.backpropagate(&model.feedforward(inputs), targets, 1.0);
+
+    let mut check = GradCheck::new();
+    let before = parameters(model.clone());
+    let after = parameters(stepped);
+    for (index, (before, after)) in before.into_iter().zip(after).enumerate() {
+        let estimate = (cost_when(index, epsilon) - cost_when(index, -epsilon)) / (2.0 * epsilon);
+        check.compare(before - after, estimate);
+    }
+
+    check
+}
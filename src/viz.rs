@@ -0,0 +1,285 @@
+//! Data for plotting how a network learns, in simple CSV and JSON forms that any plotting tool
+//! can read: the decision boundary of a network with 2 inputs, sampled over a grid, and the
+//! learning curves of the metrics recorded whilst training.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scholar::{datasets, metrics::Predictions, viz, NeuralNet, Sigmoid, Trainer};
+//!
+//! let dataset = datasets::moons(200, 0.1, 42);
+//! let (training_data, testing_data) = dataset.split(0.75);
+//!
+//! let mut brain: NeuralNet<Sigmoid> = NeuralNet::new(&[2, 8, 1]);
+//! let history = Trainer::new(100, 0.1)
+//!     .metric("loss", Predictions::mae)
+//!     .validation(testing_data)
+//!     .train(&mut brain, training_data.clone());
+//!
+//! // Plot the network's guesses as a heatmap underneath the points of the dataset...
+//! let (x_range, y_range) = viz::input_ranges(&training_data, 0.1);
+//! let boundary = viz::decision_boundary(&brain, x_range, y_range, 50);
+//! let csv = boundary.to_csv();
+//! assert!(csv.starts_with("x,y,class,output_0\n"));
+//!
+//! // ...and the training and validation loss against the epoch
+//! let curves = viz::LearningCurves::from_history(&history);
+//! assert!(curves.to_csv().starts_with("epoch,loss,val_loss\n1,"));
+//! ```
+
+use crate::dataset::Dataset;
+#[cfg(feature = "fs")]
+use crate::network::SaveErr;
+use crate::network::{Activation, Float, NeuralNet};
+use crate::trainer::History;
+use crate::utils::{csv_field, decode_class};
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::Write;
+#[cfg(feature = "fs")]
+use std::{fs, path::Path};
+
+/// A network's guesses at every point of an evenly spaced grid over its 2 inputs, returned by
+/// [`decision_boundary()`](fn.decision_boundary.html).
+///
+/// Plotted as a heatmap (of the outputs) or contour (of the classes), the grid shows the regions
+/// of the input space that the network assigns to each class.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DecisionBoundary {
+    resolution: usize,
+    points: Vec<GridPoint>,
+}
+
+/// A point of a [`DecisionBoundary`](struct.DecisionBoundary.html)'s grid.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GridPoint {
+    /// The value of the first input.
+    pub x: f64,
+    /// The value of the second input.
+    pub y: f64,
+    /// The class that the network's guesses decode to (see
+    /// [`NeuralNet::threshold()`](../struct.NeuralNet.html#method.threshold)).
+    pub class: usize,
+    /// The network's guesses.
+    pub outputs: Vec<f64>,
+}
+
+/// Samples the given network's guesses over a grid of `resolution` by `resolution` points,
+/// evenly spaced across the given ranges of its first (`x`) and second (`y`) inputs.
+///
+/// The points are in rows of increasing `y`, each of increasing `x`.
+///
+/// # Panics
+///
+/// This function panics if the network doesn't have 2 inputs, or `resolution` is less than 2.
+pub fn decision_boundary<A: Activation + Serialize + DeserializeOwned, T: Float>(
+    network: &NeuralNet<A, T>,
+    x_range: (f64, f64),
+    y_range: (f64, f64),
+    resolution: usize,
+) -> DecisionBoundary {
+    let num_inputs = network.layer_sizes()[0];
+    if num_inputs != 2 {
+        panic!(
+            "decision boundaries can only be sampled from networks with 2 inputs (found {})",
+            num_inputs
+        );
+    }
+    if resolution < 2 {
+        panic!("grid resolution must be at least 2 points");
+    }
+
+    let step = |(start, end): (f64, f64), i: usize| {
+        start + (end - start) * i as f64 / (resolution - 1) as f64
+    };
+    let mut points = Vec::with_capacity(resolution * resolution);
+    for j in 0..resolution {
+        for i in 0..resolution {
+            let (x, y) = (step(x_range, i), step(y_range, j));
+            let outputs = network.guess(&[x, y]);
+            points.push(GridPoint {
+                x,
+                y,
+                class: decode_class(&outputs, network.threshold()),
+                outputs,
+            });
+        }
+    }
+
+    DecisionBoundary { resolution, points }
+}
+
+/// Returns the ranges of the first and second inputs of the `Dataset`'s rows, each widened by
+/// the given fraction of its width on both sides, to sample a decision boundary over.
+///
+/// # Panics
+///
+/// This function panics if the dataset is empty, or its rows have fewer than 2 inputs.
+pub fn input_ranges(dataset: &Dataset, margin: f64) -> ((f64, f64), (f64, f64)) {
+    if dataset.rows() == 0 {
+        panic!("cannot find the input ranges of an empty dataset");
+    }
+
+    let range = |column: usize| {
+        let (min, max) = dataset.into_iter().fold(
+            (f64::INFINITY, f64::NEG_INFINITY),
+            |(min, max), (inputs, _)| (min.min(inputs[column]), max.max(inputs[column])),
+        );
+        let padding = (max - min) * margin;
+        (min - padding, max + padding)
+    };
+
+    (range(0), range(1))
+}
+
+impl DecisionBoundary {
+    /// Returns the number of points along each side of the grid.
+    pub fn resolution(&self) -> usize {
+        self.resolution
+    }
+
+    /// Returns every point of the grid, in rows of increasing `y`, each of increasing `x`.
+    pub fn points(&self) -> &[GridPoint] {
+        &self.points
+    }
+
+    /// Formats the grid as CSV, with a row for each point holding its `x`, `y`, `class` and
+    /// each output (`output_0`, `output_1`, ...).
+    pub fn to_csv(&self) -> String {
+        let num_outputs = self.points.first().map_or(0, |point| point.outputs.len());
+        let mut csv = "x,y,class".to_string();
+        for i in 0..num_outputs {
+            write!(csv, ",output_{}", i).expect("writing to a string cannot fail");
+        }
+        csv.push('\n');
+
+        for point in &self.points {
+            write!(csv, "{},{},{}", point.x, point.y, point.class)
+                .expect("writing to a string cannot fail");
+            for output in &point.outputs {
+                write!(csv, ",{}", output).expect("writing to a string cannot fail");
+            }
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    /// Formats the grid as JSON, as an object holding the `resolution` and a list of `points`,
+    /// each with its `x`, `y`, `class` and `outputs`.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("grids always serialize")
+    }
+
+    /// Saves the grid to the specified path as CSV (see [`to_csv()`](#method.to_csv)).
+    #[cfg(feature = "fs")]
+    pub fn save_csv(&self, path: impl AsRef<Path>) -> Result<(), SaveErr> {
+        fs::write(path, self.to_csv())?;
+        Ok(())
+    }
+
+    /// Saves the grid to the specified path as JSON (see [`to_json()`](#method.to_json)).
+    #[cfg(feature = "fs")]
+    pub fn save_json(&self, path: impl AsRef<Path>) -> Result<(), SaveErr> {
+        fs::write(path, self.to_json())?;
+        Ok(())
+    }
+}
+
+/// The value of each metric recorded whilst training (see
+/// [`Trainer::metric()`](../struct.Trainer.html#method.metric)) after every epoch, as series to
+/// plot against the epoch.
+///
+/// To plot the loss, record it as a metric, such as with `.metric("loss", Predictions::mae)`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LearningCurves {
+    epochs: Vec<u64>,
+    series: Vec<Series>,
+}
+
+/// The values of a metric in [`LearningCurves`](struct.LearningCurves.html).
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Series {
+    /// The name of the metric.
+    pub name: String,
+    /// The value of the metric after each epoch, or `None` where it wasn't calculated.
+    pub values: Vec<Option<f64>>,
+}
+
+impl LearningCurves {
+    /// Collects a series for every metric in the `History` of training, in the order they were
+    /// first recorded.
+    pub fn from_history(history: &History) -> Self {
+        let epochs = history.epochs();
+        let mut series: Vec<Series> = Vec::new();
+        for record in epochs {
+            for (name, _) in &record.metrics {
+                if series.iter().all(|series| &series.name != name) {
+                    series.push(Series {
+                        name: name.clone(),
+                        values: epochs.iter().map(|record| record.metric(name)).collect(),
+                    });
+                }
+            }
+        }
+
+        Self {
+            epochs: epochs.iter().map(|record| record.epoch).collect(),
+            series,
+        }
+    }
+
+    /// Returns the number of each epoch.
+    pub fn epochs(&self) -> &[u64] {
+        &self.epochs
+    }
+
+    /// Returns the series of every metric.
+    pub fn series(&self) -> &[Series] {
+        &self.series
+    }
+
+    /// Formats the curves as CSV, with a row for each epoch holding its number and the value of
+    /// each metric (which is empty where it wasn't calculated).
+    pub fn to_csv(&self) -> String {
+        let mut csv = "epoch".to_string();
+        for series in &self.series {
+            write!(csv, ",{}", csv_field(&series.name)).expect("writing to a string cannot fail");
+        }
+        csv.push('\n');
+
+        for (i, epoch) in self.epochs.iter().enumerate() {
+            csv += &epoch.to_string();
+            for series in &self.series {
+                csv.push(',');
+                if let Some(value) = series.values[i] {
+                    csv += &value.to_string();
+                }
+            }
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    /// Formats the curves as JSON, as an object holding the list of `epochs` and a list of
+    /// `series`, each with its `name` and `values` (which are `null` where they weren't
+    /// calculated).
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("learning curves always serialize")
+    }
+
+    /// Saves the curves to the specified path as CSV (see [`to_csv()`](#method.to_csv)).
+    #[cfg(feature = "fs")]
+    pub fn save_csv(&self, path: impl AsRef<Path>) -> Result<(), SaveErr> {
+        fs::write(path, self.to_csv())?;
+        Ok(())
+    }
+
+    /// Saves the curves to the specified path as JSON (see [`to_json()`](#method.to_json)).
+    #[cfg(feature = "fs")]
+    pub fn save_json(&self, path: impl AsRef<Path>) -> Result<(), SaveErr> {
+        fs::write(path, self.to_json())?;
+        Ok(())
+    }
+}